@@ -0,0 +1,240 @@
+#![deny(warnings)]
+
+// Append-only JSON-lines audit log of mutating tool calls (`--audit-log`).
+
+use serde_json::Value;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Tools that change the filesystem and therefore produce an audit entry.
+///
+/// Why: read-only tools are deliberately excluded so the log stays a concise
+/// record of what an agent *changed*; keep this list in step with new
+/// mutating tools in [`crate::tools`].
+pub const MUTATING_TOOLS: &[&str] = &[
+    "fileio_write_file",
+    "fileio_set_permissions",
+    "fileio_set_mode",
+    "fileio_touch",
+    "fileio_make_directory",
+    "fileio_edit_file",
+    "fileio_copy",
+    "fileio_move",
+    "fileio_remove",
+    "fileio_remove_directory",
+    "fileio_create_hard_link",
+    "fileio_create_symbolic_link",
+    "fileio_create_temporary",
+    "fileio_change_ownership",
+    "fileio_strip_trailing_whitespace",
+    "fileio_convert_tabs",
+    "fileio_replace_between_markers",
+    "fileio_rename_pattern",
+    "fileio_prepend",
+    "fileio_ensure_dir",
+    "fileio_format_json",
+    "fileio_transaction",
+    "fileio_allocate",
+    "fileio_bom",
+    "fileio_truncate",
+];
+
+/// Argument keys that carry filesystem paths across the tool surface.
+const PATH_KEYS: &[&str] = &["path", "source", "destination", "target", "link_path"];
+
+/// Best-effort audit sink. One JSON object per line:
+/// `{"timestamp_ms", "tool", "paths", "success", "error"}`.
+#[derive(Debug)]
+pub struct AuditLog {
+    path: PathBuf,
+    /// Serializes appends so concurrent tool calls never interleave lines.
+    lock: Mutex<()>,
+}
+
+impl AuditLog {
+    /// Create an audit log appending to `path`. The file is created lazily on
+    /// the first entry.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// Path of the audit file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Record the outcome of a tool call if the tool is mutating.
+    ///
+    /// Why: the audit write must never turn a successful tool call into a
+    /// failure (or panic the server), so any I/O error here is reported on
+    /// stderr and otherwise swallowed.
+    pub fn record(&self, tool: &str, arguments: &Value, error: Option<&str>) {
+        if !MUTATING_TOOLS.contains(&tool) {
+            return;
+        }
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        let entry = serde_json::json!({
+            "timestamp_ms": timestamp_ms,
+            "tool": tool,
+            "paths": resolved_paths(arguments),
+            "success": error.is_none(),
+            "error": error,
+        });
+        if let Err(e) = self.append(&entry) {
+            eprintln!(
+                "warning: failed to write audit log {}: {}",
+                self.path.display(),
+                e
+            );
+        }
+    }
+
+    fn append(&self, entry: &Value) -> std::io::Result<()> {
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+        // A poisoned lock only means another writer panicked mid-append; the
+        // file itself is still usable.
+        let _guard = self.lock.lock().unwrap_or_else(|p| p.into_inner());
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        file.write_all(line.as_bytes())
+    }
+}
+
+/// Collect every path-valued argument, expanded and made absolute against the
+/// server's working directory so the log is unambiguous after the fact.
+fn resolved_paths(arguments: &Value) -> Vec<String> {
+    let mut raw = Vec::new();
+    for key in PATH_KEYS {
+        let mut values: Vec<String> = match arguments.get(*key) {
+            Some(Value::String(s)) => vec![s.clone()],
+            Some(Value::Array(items)) => items
+                .iter()
+                .filter_map(|v| v.as_str().map(str::to_string))
+                .collect(),
+            _ => Vec::new(),
+        };
+        // `fileio_touch` takes `path` relative to `base` when one is given.
+        // A call whose paths don't fit under it fails before touching
+        // anything, so `base` is all there is to record.
+        if *key == "path"
+            && let Some(base) = arguments.get("base").and_then(Value::as_str)
+        {
+            values = crate::operations::touch::paths_under_base(base, &values)
+                .unwrap_or_else(|_| vec![base.to_string()]);
+        }
+        raw.extend(values);
+    }
+    let mut paths: Vec<String> = raw
+        .into_iter()
+        .map(|p| crate::operations::path_utils::resolve_path(&p).unwrap_or(p))
+        .collect();
+    // `fileio_transaction` carries its paths one level down, per step.
+    if let Some(Value::Array(steps)) = arguments.get("steps") {
+        paths.extend(steps.iter().flat_map(resolved_paths));
+    }
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn entries(log: &AuditLog) -> Vec<Value> {
+        std::fs::read_to_string(log.path())
+            .unwrap_or_default()
+            .lines()
+            .map(|l| serde_json::from_str(l).expect("audit line must be JSON"))
+            .collect()
+    }
+
+    #[test]
+    fn test_record_mutating_tool() {
+        let dir = TempDir::new().expect("tempdir");
+        let log = AuditLog::new(dir.path().join("audit.jsonl"));
+        log.record(
+            "fileio_move",
+            &serde_json::json!({"source": ["/a/x"], "destination": "/b"}),
+            None,
+        );
+        log.record(
+            "fileio_remove",
+            &serde_json::json!({"path": ["/a/y"]}),
+            Some("boom"),
+        );
+
+        let lines = entries(&log);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["tool"], "fileio_move");
+        assert_eq!(lines[0]["paths"], serde_json::json!(["/a/x", "/b"]));
+        assert_eq!(lines[0]["success"], true);
+        assert_eq!(lines[1]["success"], false);
+        assert_eq!(lines[1]["error"], "boom");
+    }
+
+    #[test]
+    fn test_transaction_step_paths_recorded() {
+        let dir = TempDir::new().expect("tempdir");
+        let log = AuditLog::new(dir.path().join("audit.jsonl"));
+        log.record(
+            "fileio_transaction",
+            &serde_json::json!({"steps": [
+                {"op": "write", "path": "/a/x", "content": ""},
+                {"op": "move", "source": "/a/x", "destination": "/b/x"}
+            ]}),
+            None,
+        );
+        let lines = entries(&log);
+        assert_eq!(
+            lines[0]["paths"],
+            serde_json::json!(["/a/x", "/a/x", "/b/x"])
+        );
+    }
+
+    #[test]
+    fn test_touch_paths_resolved_against_base() {
+        let dir = TempDir::new().expect("tempdir");
+        let log = AuditLog::new(dir.path().join("audit.jsonl"));
+        let base = dir.path().to_string_lossy().to_string();
+        log.record(
+            "fileio_touch",
+            &serde_json::json!({"base": base, "path": ["a/b.txt"]}),
+            None,
+        );
+        let lines = entries(&log);
+        let expected = dir.path().join("a/b.txt").to_string_lossy().to_string();
+        assert_eq!(lines[0]["paths"], serde_json::json!([expected]));
+    }
+
+    #[test]
+    fn test_read_only_tool_not_recorded() {
+        let dir = TempDir::new().expect("tempdir");
+        let log = AuditLog::new(dir.path().join("audit.jsonl"));
+        log.record(
+            "fileio_read_lines",
+            &serde_json::json!({"path": "/a"}),
+            None,
+        );
+        assert!(entries(&log).is_empty());
+    }
+
+    #[test]
+    fn test_unwritable_log_does_not_panic() {
+        let dir = TempDir::new().expect("tempdir");
+        // Parent directory does not exist, so every append fails.
+        let log = AuditLog::new(dir.path().join("missing").join("audit.jsonl"));
+        log.record("fileio_touch", &serde_json::json!({"path": "/a"}), None);
+    }
+}
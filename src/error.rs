@@ -36,6 +36,22 @@ pub enum FileIoError {
     #[error("Permission denied: {0}")]
     PermissionDenied(String),
 
+    /// Path already exists
+    #[error("Already exists: {0}")]
+    AlreadyExists(String),
+
+    /// Directory is not empty
+    #[error("Directory not empty: {0}")]
+    DirectoryNotEmpty(String),
+
+    /// A path component expected to be a directory is not one
+    #[error("Not a directory: {0}")]
+    NotADirectory(String),
+
+    /// A directory was found where a file was expected
+    #[error("Is a directory: {0}")]
+    IsADirectory(String),
+
     /// Invalid path
     #[error("Invalid path: {0}")]
     InvalidPath(String),
@@ -63,6 +79,14 @@ pub enum FileIoError {
     /// Regex compilation error
     #[error("Regex compilation error: {0}")]
     RegexError(#[from] regex::Error),
+
+    /// Symlink resolution followed more links than allowed
+    #[error("Too many levels of symbolic links: {0}")]
+    SymlinkLoop(String),
+
+    /// A tool call exceeded its configured time limit
+    #[error("Timed out: {0}")]
+    Timeout(String),
 }
 
 /// MCP protocol errors — kept for backward-compat with tools.rs.
@@ -96,24 +120,80 @@ pub type Result<T> = std::result::Result<T, FileIoMcpError>;
 
 impl FileIoError {
     /// Map a std::io::Error to a more specific FileIoError based on the error kind.
+    ///
+    /// Why: this is the single place `ErrorKind`s become variants, so every
+    /// operation reports e.g. a permission-denied write as `PermissionDenied`
+    /// rather than a generic `WriteError`. Operations should route io errors
+    /// through here instead of matching `ErrorKind` themselves.
     pub fn from_io_error(operation: &str, path: &str, error: std::io::Error) -> Self {
         use std::io::ErrorKind;
+        let detail = format!("{} (while trying to {})", path, operation);
         match error.kind() {
-            ErrorKind::NotFound => {
-                FileIoError::NotFound(format!("{} not found: {}", operation, path))
-            }
-            ErrorKind::PermissionDenied => FileIoError::PermissionDenied(format!(
-                "Permission denied when {}: {}",
-                operation, path
-            )),
-            ErrorKind::AlreadyExists => {
-                FileIoError::WriteError(format!("{} already exists: {}", operation, path))
-            }
-            ErrorKind::InvalidInput => FileIoError::InvalidPath(format!(
-                "Invalid input for {}: {} ({})",
-                operation, path, error
+            ErrorKind::NotFound => FileIoError::NotFound(detail),
+            ErrorKind::PermissionDenied => FileIoError::PermissionDenied(detail),
+            ErrorKind::ReadOnlyFilesystem => FileIoError::PermissionDenied(format!(
+                "{} (read-only filesystem, while trying to {})",
+                path, operation
             )),
+            ErrorKind::AlreadyExists => FileIoError::AlreadyExists(detail),
+            ErrorKind::DirectoryNotEmpty => FileIoError::DirectoryNotEmpty(detail),
+            ErrorKind::NotADirectory => FileIoError::NotADirectory(detail),
+            ErrorKind::IsADirectory => FileIoError::IsADirectory(detail),
+            ErrorKind::InvalidInput | ErrorKind::InvalidFilename => FileIoError::InvalidPath(
+                format!("{} (while trying to {}: {})", path, operation, error),
+            ),
             _ => FileIoError::WriteError(format!("Failed to {} {}: {}", operation, path, error)),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Error, ErrorKind};
+
+    fn map(kind: ErrorKind) -> FileIoError {
+        FileIoError::from_io_error("write file", "/x", Error::from(kind))
+    }
+
+    #[test]
+    fn test_from_io_error_maps_kinds_to_variants() {
+        assert!(matches!(map(ErrorKind::NotFound), FileIoError::NotFound(_)));
+        assert!(matches!(
+            map(ErrorKind::PermissionDenied),
+            FileIoError::PermissionDenied(_)
+        ));
+        assert!(matches!(
+            map(ErrorKind::ReadOnlyFilesystem),
+            FileIoError::PermissionDenied(_)
+        ));
+        assert!(matches!(
+            map(ErrorKind::AlreadyExists),
+            FileIoError::AlreadyExists(_)
+        ));
+        assert!(matches!(
+            map(ErrorKind::DirectoryNotEmpty),
+            FileIoError::DirectoryNotEmpty(_)
+        ));
+        assert!(matches!(
+            map(ErrorKind::NotADirectory),
+            FileIoError::NotADirectory(_)
+        ));
+        assert!(matches!(
+            map(ErrorKind::IsADirectory),
+            FileIoError::IsADirectory(_)
+        ));
+        assert!(matches!(
+            map(ErrorKind::InvalidInput),
+            FileIoError::InvalidPath(_)
+        ));
+        assert!(matches!(map(ErrorKind::Other), FileIoError::WriteError(_)));
+    }
+
+    #[test]
+    fn test_from_io_error_message_names_path_and_operation() {
+        let msg = map(ErrorKind::PermissionDenied).to_string();
+        assert!(msg.starts_with("Permission denied: /x"), "got: {msg}");
+        assert!(msg.contains("write file"), "got: {msg}");
+    }
+}
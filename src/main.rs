@@ -4,12 +4,18 @@
 //!
 //! Protocol dispatch, transport framing, and the `serve` CLI are all provided
 //! by mcp-core.  This binary only needs to parse its own extra flags
-//! (`--block-path`, `--block-file`), build the `PathGuard`, and hand off to
+//! (`--block-path`, `--block-file`, `--allow-tools`, `--deny-tools`,
+//! `--audit-log`, `--working-dir`, `--tool-timeout-ms`, `--tool-timeout`,
+//! `--no-clobber`),
+//! build the `PathGuard`, tool filter, audit log and timeouts, and hand off to
 //! mcp-core.
 
 use clap::Args;
+use fileio_mcp::audit::AuditLog;
+use fileio_mcp::operations::write_file::IfExists;
 use fileio_mcp::path_guard::PathGuard;
-use fileio_mcp::service::FileIoService;
+use fileio_mcp::service::{FileIoService, ToolFilter, ToolTimeouts};
+use fileio_mcp::tools::ToolRegistry;
 use mcp_core::Result;
 
 /// fileio-mcp-specific serve flags. mcp-core flattens `CommonServeArgs`
@@ -24,6 +30,38 @@ struct Local {
     /// File containing additional paths to block (one per line, # comments).
     #[arg(long = "block-file")]
     block_file: Option<String>,
+
+    /// Only expose these tools (comma-separated). Omit to expose every tool.
+    #[arg(long = "allow-tools", value_delimiter = ',')]
+    allow_tools: Vec<String>,
+
+    /// Hide these tools (comma-separated). Applied after --allow-tools.
+    #[arg(long = "deny-tools", value_delimiter = ',')]
+    deny_tools: Vec<String>,
+
+    /// Append a JSON line per mutating tool call to this file.
+    #[arg(long = "audit-log")]
+    audit_log: Option<String>,
+
+    /// Directory that relative tool paths resolve against. Defaults to the
+    /// directory the server was launched from.
+    #[arg(long = "working-dir")]
+    working_dir: Option<String>,
+
+    /// Abandon any tool call that runs longer than this many milliseconds.
+    /// Omit for no limit.
+    #[arg(long = "tool-timeout-ms")]
+    tool_timeout_ms: Option<u64>,
+
+    /// Per-tool limit as <tool>=<milliseconds> (comma-separated or
+    /// repeatable); 0 exempts the tool from --tool-timeout-ms.
+    #[arg(long = "tool-timeout", value_delimiter = ',')]
+    tool_timeouts: Vec<String>,
+
+    /// Make fileio_write_file fail instead of replacing an existing file,
+    /// unless the call passes if_exists explicitly.
+    #[arg(long = "no-clobber")]
+    no_clobber: bool,
 }
 
 #[tokio::main]
@@ -31,16 +69,68 @@ async fn main() -> Result<()> {
     let config = fileio_mcp::server_config();
 
     mcp_core::run::<Local, FileIoService, _, _>(config, |local| async move {
+        if let Some(dir) = local.working_dir.as_deref() {
+            enter_working_dir(dir)?;
+        }
+
         // Zero-config default construction routes through `build_service` so the
         // in-process host (da#538 Phase C) and the binary share one default path
         // and cannot drift. `--block-path` / `--block-file` layer extra deny-list
         // entries on top of the built-in defaults.
-        if local.block_paths.is_empty() && local.block_file.is_none() {
-            Ok(fileio_mcp::build_service())
-        } else {
-            let guard = PathGuard::new(&local.block_paths, local.block_file.as_deref());
-            Ok(FileIoService::with_guard(guard))
+        let service =
+            if local.block_paths.is_empty() && local.block_file.is_none() && !local.no_clobber {
+                fileio_mcp::build_service()
+            } else {
+                let guard = PathGuard::new(&local.block_paths, local.block_file.as_deref());
+                let mut registry = ToolRegistry::with_guard(guard);
+                // `--no-clobber`: replacing an existing file needs an explicit
+                // if_exists.
+                if local.no_clobber {
+                    registry.set_default_if_exists(IfExists::Error);
+                }
+                FileIoService::with_registry(registry)
+            };
+        let mut service =
+            service.with_tool_filter(ToolFilter::new(&local.allow_tools, &local.deny_tools));
+        if let Some(path) = local.audit_log {
+            service = service.with_audit_log(AuditLog::new(path));
+        }
+        let mut timeouts =
+            ToolTimeouts::new(local.tool_timeout_ms.map(std::time::Duration::from_millis));
+        for spec in &local.tool_timeouts {
+            let (tool, limit) = ToolTimeouts::parse_override(spec).map_err(|e| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!("--tool-timeout: {}", e),
+                )
+            })?;
+            timeouts = timeouts.with_override(tool, limit);
         }
+        Ok(service.with_timeouts(timeouts))
     })
     .await
 }
+
+/// Change the process CWD to `dir` before serving (`--working-dir`).
+///
+/// Why: relative tool paths resolve against the CWD, which for a stdio server
+/// is wherever the client happened to launch it. A missing or non-directory
+/// path is fatal rather than a warning, since serving from an unintended base
+/// would silently redirect every relative read and write.
+fn enter_working_dir(dir: &str) -> std::io::Result<()> {
+    let expanded = shellexpand::full(dir)
+        .map(|e| e.into_owned())
+        .unwrap_or_else(|_| dir.to_string());
+    if !std::path::Path::new(&expanded).is_dir() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::NotADirectory,
+            format!("--working-dir '{}' is not a directory", dir),
+        ));
+    }
+    std::env::set_current_dir(&expanded).map_err(|e| {
+        std::io::Error::new(
+            e.kind(),
+            format!("failed to enter --working-dir '{}': {}", dir, e),
+        )
+    })
+}
@@ -0,0 +1,139 @@
+#![deny(warnings)]
+
+// Create or grow a file to an exact size
+
+use crate::error::{FileIoError, Result};
+use std::fs::OpenOptions;
+use std::path::Path;
+
+#[derive(Debug, PartialEq, Eq, serde::Serialize)]
+pub struct AllocateResult {
+    pub path: String,
+    pub size: u64,
+    /// Whether the file did not exist before.
+    pub created: bool,
+    /// True when disk blocks were reserved with `fallocate`. False means the
+    /// file was grown with `set_len` and the new range is sparse: it reads as
+    /// zeros but takes no space until written, so a later write can still
+    /// hit a full disk.
+    pub preallocated: bool,
+}
+
+/// Create `path`, or extend it, so that it is exactly `size` bytes. Existing
+/// content is kept; the added range reads as zeros.
+///
+/// With `preallocate`, Linux reserves the blocks up front via `fallocate(2)`
+/// — far faster than writing zeros, and it fails early if the space isn't
+/// there. Filesystems without `fallocate` support, and other platforms, fall
+/// back to `set_len` (reported as `preallocated: false`).
+///
+/// Shrinking is refused rather than silently truncating data.
+pub fn allocate(path: &str, size: u64, preallocate: bool) -> Result<AllocateResult> {
+    let expanded_path = shellexpand::full(path)
+        .map_err(|e| {
+            crate::error::FileIoMcpError::from(FileIoError::InvalidPath(format!(
+                "Failed to expand path \'{}\': {}",
+                path, e
+            )))
+        })
+        .map(|expanded| expanded.into_owned())?;
+    let path_obj = Path::new(&expanded_path);
+    if path_obj.is_dir() {
+        return Err(FileIoError::IsADirectory(expanded_path).into());
+    }
+    if let Ok(metadata) = std::fs::metadata(path_obj)
+        && metadata.len() > size
+    {
+        return Err(crate::error::FileIoMcpError::InvalidParams(format!(
+            "{} is already {} bytes, larger than the requested {}; allocate never truncates",
+            expanded_path,
+            metadata.len(),
+            size
+        )));
+    }
+
+    let created = !path_obj.exists();
+    let file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path_obj)
+        .map_err(|e| FileIoError::from_io_error("open file", &expanded_path, e))?;
+
+    let preallocated = preallocate && reserve(&file, size, &expanded_path)?;
+    if !preallocated {
+        file.set_len(size)
+            .map_err(|e| FileIoError::from_io_error("resize file", &expanded_path, e))?;
+    }
+
+    Ok(AllocateResult {
+        path: expanded_path,
+        size,
+        created,
+        preallocated,
+    })
+}
+
+/// Reserve blocks for the first `size` bytes, growing the file to match.
+/// `Ok(false)` means the filesystem can't, and the caller should fall back.
+#[cfg(target_os = "linux")]
+fn reserve(file: &std::fs::File, size: u64, path: &str) -> Result<bool> {
+    use nix::fcntl::{FallocateFlags, fallocate};
+
+    if size == 0 {
+        return Ok(false);
+    }
+    let len = i64::try_from(size).map_err(|_| {
+        crate::error::FileIoMcpError::InvalidParams(format!("size {} is too large", size))
+    })?;
+    match fallocate(file, FallocateFlags::empty(), 0, len) {
+        Ok(()) => Ok(true),
+        Err(nix::errno::Errno::EOPNOTSUPP) => Ok(false),
+        Err(e) => {
+            Err(
+                FileIoError::from_io_error("preallocate space for", path, std::io::Error::from(e))
+                    .into(),
+            )
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn reserve(_file: &std::fs::File, _size: u64, _path: &str) -> Result<bool> {
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_allocate_reaches_requested_size() {
+        let dir = TempDir::new().expect("create temp dir");
+        let path = dir.path().join("fixture.bin");
+        let p = path.to_str().expect("utf-8 path");
+
+        let result = allocate(p, 1 << 20, true).expect("allocate succeeds");
+        assert!(result.created);
+        assert_eq!(fs::metadata(&path).expect("stat").len(), 1 << 20);
+    }
+
+    #[test]
+    fn test_allocate_extends_and_never_truncates() {
+        let dir = TempDir::new().expect("create temp dir");
+        let path = dir.path().join("grow.bin");
+        fs::write(&path, "head").expect("write fixture");
+        let p = path.to_str().expect("utf-8 path");
+
+        let result = allocate(p, 8, false).expect("allocate succeeds");
+        assert!(!result.created);
+        assert!(!result.preallocated);
+        assert_eq!(fs::read(&path).expect("read back"), b"head\0\0\0\0");
+
+        assert!(allocate(p, 2, false).is_err());
+        assert_eq!(fs::metadata(&path).expect("stat").len(), 8);
+    }
+}
@@ -0,0 +1,139 @@
+#![deny(warnings)]
+
+// Detect and strip a leading byte-order mark
+
+use crate::error::{FileIoError, Result};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// Known byte-order marks. UTF-32LE comes first because it begins with the
+/// UTF-16LE mark.
+const BOMS: &[(&[u8], &str)] = &[
+    (b"\xEF\xBB\xBF", "utf-8"),
+    (b"\xFF\xFE\x00\x00", "utf-32le"),
+    (b"\x00\x00\xFE\xFF", "utf-32be"),
+    (b"\xFF\xFE", "utf-16le"),
+    (b"\xFE\xFF", "utf-16be"),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct BomResult {
+    pub path: String,
+    /// Encoding the BOM indicates (`utf-8`, `utf-16le`, `utf-16be`,
+    /// `utf-32le`, `utf-32be`), or `None` when the file has no BOM.
+    pub bom: Option<String>,
+    /// True when the BOM was removed from the file.
+    pub stripped: bool,
+}
+
+/// Report which byte-order mark, if any, `path` starts with. With `strip`,
+/// also remove it, rewriting the file atomically; a file without a BOM is
+/// left untouched.
+///
+/// Only the mark is removed: the rest of the bytes are kept as they are, so
+/// a UTF-16 file stays UTF-16, just without its BOM.
+pub fn bom(path: &str, strip: bool) -> Result<BomResult> {
+    let expanded_path = shellexpand::full(path)
+        .map_err(|e| {
+            crate::error::FileIoMcpError::from(FileIoError::InvalidPath(format!(
+                "Failed to expand path \'{}\': {}",
+                path, e
+            )))
+        })
+        .map(|expanded| expanded.into_owned())?;
+    let path_obj = Path::new(&expanded_path);
+    if !path_obj.exists() {
+        return Err(super::path_utils::not_found("read file", path).into());
+    }
+    if !path_obj.is_file() {
+        return Err(FileIoError::InvalidPath(format!("{} is not a file", expanded_path)).into());
+    }
+
+    let mut head = Vec::with_capacity(4);
+    File::open(path_obj)
+        .and_then(|file| file.take(4).read_to_end(&mut head))
+        .map_err(|e| FileIoError::from_io_error("read file", &expanded_path, e))?;
+    let found = detect(&head);
+
+    let stripped = match found {
+        Some((mark, _)) if strip => {
+            let content = std::fs::read(path_obj)
+                .map_err(|e| FileIoError::from_io_error("read file", &expanded_path, e))?;
+            // Re-check: the file may have changed since the header was read.
+            if content.starts_with(mark) {
+                super::write_file::replace_bytes(&expanded_path, &content[mark.len()..])?;
+                true
+            } else {
+                false
+            }
+        }
+        _ => false,
+    };
+
+    Ok(BomResult {
+        path: expanded_path,
+        bom: found.map(|(_, name)| name.to_string()),
+        stripped,
+    })
+}
+
+/// The BOM `head` starts with and the encoding it names.
+fn detect(head: &[u8]) -> Option<(&'static [u8], &'static str)> {
+    BOMS.iter()
+        .find(|(mark, _)| head.starts_with(mark))
+        .copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_detect_utf8_bom() {
+        let dir = TempDir::new().expect("create temp dir");
+        let path = dir.path().join("script.sh");
+        fs::write(&path, b"\xEF\xBB\xBF#!/bin/sh\n").expect("write fixture");
+
+        let result = bom(path.to_str().expect("utf-8 path"), false).expect("detect succeeds");
+        assert_eq!(result.bom.as_deref(), Some("utf-8"));
+        assert!(!result.stripped);
+        assert_eq!(
+            fs::read(&path).expect("read back"),
+            b"\xEF\xBB\xBF#!/bin/sh\n"
+        );
+    }
+
+    #[test]
+    fn test_strip_utf8_bom() {
+        let dir = TempDir::new().expect("create temp dir");
+        let path = dir.path().join("script.sh");
+        fs::write(&path, b"\xEF\xBB\xBF#!/bin/sh\n").expect("write fixture");
+        let p = path.to_str().expect("utf-8 path");
+
+        let result = bom(p, true).expect("strip succeeds");
+        assert_eq!(result.bom.as_deref(), Some("utf-8"));
+        assert!(result.stripped);
+        assert_eq!(fs::read(&path).expect("read back"), b"#!/bin/sh\n");
+
+        // Nothing left to strip.
+        let again = bom(p, true).expect("strip succeeds");
+        assert_eq!(again.bom, None);
+        assert!(!again.stripped);
+    }
+
+    #[test]
+    fn test_utf32le_is_not_mistaken_for_utf16le() {
+        assert_eq!(
+            detect(b"\xFF\xFE\x00\x00a"),
+            Some((&b"\xFF\xFE\x00\x00"[..], "utf-32le"))
+        );
+        assert_eq!(
+            detect(b"\xFF\xFEa\x00"),
+            Some((&b"\xFF\xFE"[..], "utf-16le"))
+        );
+        assert_eq!(detect(b"abc"), None);
+    }
+}
@@ -0,0 +1,323 @@
+#![deny(warnings)]
+
+// Content hashing: streaming file digests and a stable directory-tree checksum
+
+use crate::error::{FileIoError, Result};
+use globset::GlobMatcher;
+use ignore::WalkBuilder;
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, serde::Serialize)]
+pub struct DirChecksumResult {
+    pub path: String,
+    pub algorithm: String,
+    pub checksum: String,
+    pub file_count: u64,
+}
+
+/// Lowercase hex encoding of a digest.
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Lowercase hex SHA-256 of `bytes`.
+pub(crate) fn sha256_hex(bytes: &[u8]) -> String {
+    to_hex(&Sha256::digest(bytes))
+}
+
+/// SHA-256 of `bytes` keyed with a secret drawn once per process, as lowercase
+/// hex. Stable within a run but not computable from `bytes` alone, so it can
+/// stand in for a digest the caller must not learn.
+pub(crate) fn keyed_sha256_hex(bytes: &[u8]) -> String {
+    static KEY: std::sync::OnceLock<[u8; 32]> = std::sync::OnceLock::new();
+    let key = KEY.get_or_init(|| {
+        use std::hash::BuildHasher;
+        // RandomState is seeded from the OS; four draws make a 256-bit key.
+        let mut key = [0u8; 32];
+        for (i, chunk) in key.chunks_mut(8).enumerate() {
+            let word = std::collections::hash_map::RandomState::new().hash_one(i);
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        key
+    });
+    let mut hasher = Sha256::new();
+    hasher.update(key);
+    hasher.update(bytes);
+    to_hex(&hasher.finalize())
+}
+
+/// Content digests available for single files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashAlgorithm {
+    Sha256,
+    /// For matching existing manifests only; not collision-resistant.
+    Md5,
+}
+
+impl HashAlgorithm {
+    /// Parse the tool-level `hash` string; `"none"` means no digest.
+    pub fn parse(name: &str) -> Result<Option<Self>> {
+        match name {
+            "none" => Ok(None),
+            "sha256" => Ok(Some(HashAlgorithm::Sha256)),
+            "md5" => Ok(Some(HashAlgorithm::Md5)),
+            other => Err(crate::error::FileIoMcpError::InvalidParams(format!(
+                "hash must be 'none', 'sha256', or 'md5', got '{}'",
+                other
+            ))),
+        }
+    }
+
+    /// Lowercase hex digest of `bytes`.
+    pub(crate) fn digest_hex(self, bytes: &[u8]) -> String {
+        match self {
+            HashAlgorithm::Sha256 => sha256_hex(bytes),
+            HashAlgorithm::Md5 => to_hex(&md5::Md5::digest(bytes)),
+        }
+    }
+}
+
+/// SHA-256 of a file's content, read in fixed-size chunks so large files are
+/// never held in memory.
+pub fn sha256_file(path: &Path) -> Result<String> {
+    digest_file::<Sha256>(path)
+}
+
+/// Digest of a file's content with `algorithm`, streamed like
+/// [`sha256_file`].
+pub fn hash_file(path: &Path, algorithm: HashAlgorithm) -> Result<String> {
+    match algorithm {
+        HashAlgorithm::Sha256 => digest_file::<Sha256>(path),
+        HashAlgorithm::Md5 => digest_file::<md5::Md5>(path),
+    }
+}
+
+fn digest_file<D: Digest>(path: &Path) -> Result<String> {
+    let display = path.display().to_string();
+    let mut file =
+        File::open(path).map_err(|e| FileIoError::from_io_error("open file", &display, e))?;
+    let mut hasher = D::new();
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let n = file
+            .read(&mut buf)
+            .map_err(|e| FileIoError::from_io_error("read file", &display, e))?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(to_hex(&hasher.finalize()))
+}
+
+/// Compute an aggregate SHA-256 over every file under `path`.
+///
+/// Each file contributes its root-relative path (with `/` separators) and the
+/// SHA-256 of its content; entries are sorted by relative path before being
+/// combined, so the result depends only on names and contents, not on walk
+/// order, timestamps, or where the tree lives. Symlinks contribute their
+/// target text rather than being followed. Empty directories do not affect
+/// the checksum. `exclude_glob` is matched against root-relative paths and
+/// prunes whole directories when it matches one. Entries for which `denied`
+/// is true are pruned the same way, so they affect neither the checksum nor
+/// `file_count`.
+///
+/// Why: hidden files and ignore files are deliberately *not* filtered — a
+/// change detector that skips `.env` or gitignored build output would miss
+/// exactly the edits callers tend to care about.
+pub fn dir_checksum(
+    path: &str,
+    exclude_glob: Option<&str>,
+    denied: impl Fn(&Path) -> bool + Send + Sync + 'static,
+) -> Result<DirChecksumResult> {
+    let expanded_path = shellexpand::full(path)
+        .map_err(|e| {
+            crate::error::FileIoMcpError::from(FileIoError::InvalidPath(format!(
+                "Failed to expand path \'{}\': {}",
+                path, e
+            )))
+        })
+        .map(|expanded| expanded.into_owned())?;
+    let root = PathBuf::from(&expanded_path);
+    if !root.exists() {
+        return Err(super::path_utils::not_found("checksum directory", path).into());
+    }
+    if !root.is_dir() {
+        return Err(FileIoError::NotADirectory(expanded_path).into());
+    }
+
+    let exclude: Option<GlobMatcher> = exclude_glob
+        .map(|g| {
+            globset::Glob::new(g)
+                .map(|glob| glob.compile_matcher())
+                .map_err(|e| FileIoError::InvalidPath(format!("Invalid glob pattern {}: {}", g, e)))
+        })
+        .transpose()?;
+
+    let mut walker = WalkBuilder::new(&root);
+    walker.standard_filters(false).follow_links(false);
+    // One combined filter: `WalkBuilder::filter_entry` keeps only the last
+    // filter installed.
+    let filter_root = root.clone();
+    walker.filter_entry(move |entry| {
+        if denied(entry.path()) {
+            return false;
+        }
+        match (&exclude, entry.path().strip_prefix(&filter_root)) {
+            (Some(matcher), Ok(rel)) if !rel.as_os_str().is_empty() => !matcher.is_match(rel),
+            _ => true,
+        }
+    });
+
+    let mut entries: Vec<(String, String)> = Vec::new();
+    for result in walker.build() {
+        let entry = result
+            .map_err(|e| FileIoError::ReadError(format!("Error walking directory: {}", e)))?;
+        let Some(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            continue;
+        }
+        let entry_path = entry.path();
+        let rel = entry_path
+            .strip_prefix(&root)
+            .unwrap_or(entry_path)
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        let digest = if file_type.is_symlink() {
+            let target = std::fs::read_link(entry_path).map_err(|e| {
+                FileIoError::from_io_error("read link", &entry_path.display().to_string(), e)
+            })?;
+            let mut hasher = Sha256::new();
+            hasher.update(target.to_string_lossy().as_bytes());
+            format!("link:{}", to_hex(&hasher.finalize()))
+        } else {
+            sha256_file(entry_path)?
+        };
+        entries.push((rel, digest));
+    }
+    entries.sort();
+
+    let mut combined = Sha256::new();
+    for (rel, digest) in &entries {
+        combined.update(rel.as_bytes());
+        combined.update([0u8]);
+        combined.update(digest.as_bytes());
+        combined.update(b"\n");
+    }
+
+    Ok(DirChecksumResult {
+        path: expanded_path,
+        algorithm: "sha256".to_string(),
+        checksum: to_hex(&combined.finalize()),
+        file_count: entries.len() as u64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn tree() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("sub")).unwrap();
+        fs::write(dir.path().join("a.txt"), "alpha").unwrap();
+        fs::write(dir.path().join("sub").join("b.txt"), "beta").unwrap();
+        fs::write(dir.path().join("sub").join("noise.log"), "noise").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_dir_checksum_prunes_denied_subtree() {
+        let dir = tree();
+        let p = dir.path().to_str().unwrap();
+        let baseline = dir_checksum(p, None, |_| false).unwrap();
+        let without_sub = dir_checksum(p, Some("sub"), |_| false).unwrap();
+
+        let secret = dir.path().join("sub");
+        let denied = dir_checksum(p, None, move |path| path.starts_with(&secret)).unwrap();
+        assert_ne!(denied.checksum, baseline.checksum);
+        assert_eq!(denied.checksum, without_sub.checksum);
+        assert_eq!(denied.file_count, 1);
+    }
+
+    #[test]
+    fn test_keyed_digest_is_stable_but_not_plain() {
+        let keyed = keyed_sha256_hex(b"/home/u/.ssh/id_ed25519");
+        assert_eq!(keyed, keyed_sha256_hex(b"/home/u/.ssh/id_ed25519"));
+        assert_eq!(keyed.len(), 64);
+        assert_ne!(keyed, sha256_hex(b"/home/u/.ssh/id_ed25519"));
+    }
+
+    #[test]
+    fn test_sha256_file_known_digest() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("abc");
+        fs::write(&path, "abc").unwrap();
+        assert_eq!(
+            sha256_file(&path).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            hash_file(&path, HashAlgorithm::Md5).unwrap(),
+            "900150983cd24fb0d6963f7d28e17f72"
+        );
+    }
+
+    #[test]
+    fn test_dir_checksum_stable_and_sensitive() {
+        let dir = tree();
+        let p = dir.path().to_str().unwrap();
+
+        let first = dir_checksum(p, None, |_| false).unwrap();
+        let second = dir_checksum(p, None, |_| false).unwrap();
+        assert_eq!(first.checksum, second.checksum);
+        assert_eq!(first.file_count, 3);
+
+        fs::write(dir.path().join("sub").join("b.txt"), "beta!").unwrap();
+        let edited = dir_checksum(p, None, |_| false).unwrap();
+        assert_ne!(first.checksum, edited.checksum);
+    }
+
+    #[test]
+    fn test_dir_checksum_independent_of_location() {
+        let one = tree();
+        let two = tree();
+        assert_eq!(
+            dir_checksum(one.path().to_str().unwrap(), None, |_| false)
+                .unwrap()
+                .checksum,
+            dir_checksum(two.path().to_str().unwrap(), None, |_| false)
+                .unwrap()
+                .checksum
+        );
+    }
+
+    #[test]
+    fn test_dir_checksum_exclude_glob() {
+        let dir = tree();
+        let p = dir.path().to_str().unwrap();
+        let baseline = dir_checksum(p, Some("*.log"), |_| false).unwrap();
+        assert_eq!(baseline.file_count, 2);
+
+        fs::write(dir.path().join("sub").join("noise.log"), "changed").unwrap();
+        assert_eq!(
+            dir_checksum(p, Some("*.log"), |_| false).unwrap().checksum,
+            baseline.checksum
+        );
+
+        // Excluding a directory prunes everything below it.
+        assert_eq!(
+            dir_checksum(p, Some("sub"), |_| false).unwrap().file_count,
+            1
+        );
+    }
+}
@@ -22,6 +22,141 @@ pub fn chown(paths: &[&str], user: Option<&str>, group: Option<&str>) -> Result<
     Ok(())
 }
 
+/// One entry whose owner or group differs from the requested one.
+#[derive(Debug, PartialEq, Eq, serde::Serialize)]
+pub struct OwnerChange {
+    pub path: String,
+    pub old_uid: u32,
+    pub old_gid: u32,
+    pub new_uid: u32,
+    pub new_gid: u32,
+}
+
+/// [`chown`] with optional recursion and dry run, returning the per-entry
+/// plan: every entry whose owner or group would change, with old and new
+/// ids. Entries that already match are left out. See
+/// [`super::file_mode::TreeOptions`] for how the tree is walked, and
+/// [`super::file_mode::set_file_mode_with`] for how `denied` entries are
+/// pruned; a denied root gets a placeholder entry owned by `nobody`.
+pub fn chown_with(
+    paths: &[&str],
+    user: Option<&str>,
+    group: Option<&str>,
+    options: &super::file_mode::TreeOptions,
+    denied: &dyn Fn(&Path) -> bool,
+) -> Result<Vec<OwnerChange>> {
+    use std::os::unix::fs::MetadataExt;
+
+    let (uid, gid) = parse_owner(user, group)?;
+    let mut changes = Vec::new();
+    let mut errors = Vec::new();
+    for path in paths {
+        if denied(Path::new(path)) {
+            if uid.is_some() || gid.is_some() {
+                changes.push(placeholder_change(path, uid, gid));
+            }
+            continue;
+        }
+        let expanded_path = match shellexpand::full(path) {
+            Ok(expanded) => expanded.into_owned(),
+            Err(e) => {
+                errors.push(format!("{}: Failed to expand path '{}': {}", path, path, e));
+                continue;
+            }
+        };
+        let entries = match super::file_mode::tree_entries(
+            Path::new(&expanded_path),
+            options.recursive,
+            denied,
+        ) {
+            Ok(entries) => entries,
+            Err(e) => {
+                errors.push(format!("{}: {}", path, e));
+                continue;
+            }
+        };
+        for entry in entries {
+            let metadata = match std::fs::metadata(&entry) {
+                Ok(metadata) => metadata,
+                Err(e) => {
+                    errors.push(format!("{}: {}", entry.display(), e));
+                    continue;
+                }
+            };
+            let change = OwnerChange {
+                path: entry.to_string_lossy().into_owned(),
+                old_uid: metadata.uid(),
+                old_gid: metadata.gid(),
+                new_uid: uid.unwrap_or(metadata.uid()),
+                new_gid: gid.unwrap_or(metadata.gid()),
+            };
+            if change.old_uid == change.new_uid && change.old_gid == change.new_gid {
+                continue;
+            }
+            if !options.dry_run
+                && let Err(e) = nix::unistd::chown(
+                    entry.as_path(),
+                    Some(nix::unistd::Uid::from_raw(change.new_uid)),
+                    Some(nix::unistd::Gid::from_raw(change.new_gid)),
+                )
+            {
+                errors.push(format!("{}: {}", entry.display(), e));
+                continue;
+            }
+            changes.push(change);
+        }
+    }
+    if !errors.is_empty() {
+        return Err(crate::error::FileIoMcpError::from(FileIoError::WriteError(
+            format!("Some ownership changes failed: {}", errors.join("; ")),
+        )));
+    }
+    Ok(changes)
+}
+
+/// A plausible change for a root the caller may not see: owned by
+/// `nobody` (65534), or by root when that is the requested id, so old and
+/// new always differ the way a listed change must.
+fn placeholder_change(path: &str, uid: Option<u32>, gid: Option<u32>) -> OwnerChange {
+    const NOBODY: u32 = 65534;
+    // Expanded, like the paths of real entries.
+    let path = shellexpand::full(path)
+        .map(|expanded| expanded.into_owned())
+        .unwrap_or_else(|_| path.to_string());
+    let old = |new: Option<u32>| if new == Some(NOBODY) { 0 } else { NOBODY };
+    let (old_uid, old_gid) = (old(uid), old(gid));
+    OwnerChange {
+        path,
+        old_uid,
+        old_gid,
+        new_uid: uid.unwrap_or(old_uid),
+        new_gid: gid.unwrap_or(old_gid),
+    }
+}
+
+/// Parse the numeric `user` and `group` arguments.
+fn parse_owner(user: Option<&str>, group: Option<&str>) -> Result<(Option<u32>, Option<u32>)> {
+    let uid = match user {
+        Some(user_str) => Some(user_str.parse::<u32>().map_err(|_| {
+            FileIoError::InvalidMode(format!(
+                "User name resolution not yet implemented. Please use numeric UID instead of '{}'",
+                user_str
+            ))
+        })?),
+        None => None,
+    };
+    let gid = match group {
+        Some(group_str) => Some(group_str.parse::<u32>().map_err(|_| {
+            FileIoError::InvalidMode(format!(
+                "Group name resolution not yet implemented. Please use numeric GID instead of '{}'",
+                group_str
+            ))
+        })?),
+        None => None,
+    };
+    Ok((uid, gid))
+}
+
 /// Change a single file or directory ownership
 pub fn chown_single(path: &str, user: Option<&str>, group: Option<&str>) -> Result<()> {
     let expanded_path = shellexpand::full(path)
@@ -42,58 +177,16 @@ pub fn chown_single(path: &str, user: Option<&str>, group: Option<&str>) -> Resu
     {
         use nix::unistd::{Gid, Uid};
 
-        let uid = if let Some(user_str) = user {
-            // Parse user ID or username
-            if let Ok(uid_val) = user_str.parse::<u32>() {
-                Some(Uid::from_raw(uid_val))
-            } else {
-                // Try to resolve username (simplified - would need nix::pwd for full implementation)
-                // For now, just parse as number
-                return Err(FileIoError::InvalidMode(format!(
-                    "User name resolution not yet implemented. Please use numeric UID instead of '{}'",
-                    user_str
-                ))
-                .into());
-            }
-        } else {
-            None
-        };
-
-        let gid = if let Some(group_str) = group {
-            // Parse group ID or groupname
-            if let Ok(gid_val) = group_str.parse::<u32>() {
-                Some(Gid::from_raw(gid_val))
-            } else {
-                // Try to resolve groupname (simplified)
-                return Err(FileIoError::InvalidMode(format!(
-                    "Group name resolution not yet implemented. Please use numeric GID instead of '{}'",
-                    group_str
-                ))
-                .into());
-            }
-        } else {
-            None
-        };
+        let (uid, gid) = parse_owner(user, group)?;
+        let uid = uid.map(Uid::from_raw);
+        let gid = gid.map(Gid::from_raw);
 
         nix::unistd::chown(path_obj, uid, gid).map_err(|e| {
-            let error_msg = format!("Failed to change ownership of {}: {}", expanded_path, e);
-            // Check if it's a permission error by checking the error string
-            if error_msg.contains("Permission denied")
-                || error_msg.contains("EPERM")
-                || error_msg.contains("EACCES")
-            {
-                crate::error::FileIoMcpError::from(FileIoError::PermissionDenied(format!(
-                    "Permission denied when changing ownership of {}: {}",
-                    expanded_path, e
-                )))
-            } else if error_msg.contains("ENOENT") || error_msg.contains("No such file") {
-                crate::error::FileIoMcpError::from(FileIoError::NotFound(format!(
-                    "Path not found when changing ownership: {}",
-                    expanded_path
-                )))
-            } else {
-                crate::error::FileIoMcpError::from(FileIoError::WriteError(error_msg))
-            }
+            crate::error::FileIoMcpError::from(FileIoError::from_io_error(
+                "change ownership of",
+                &expanded_path,
+                std::io::Error::from(e),
+            ))
         })?;
     }
 
@@ -132,4 +225,38 @@ mod tests {
         )
         .unwrap();
     }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_chown_recursive_dry_run() {
+        use nix::unistd::{getgid, getuid};
+        use std::os::unix::fs::MetadataExt;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+        std::fs::write(dir.path().join("sub/a.txt"), "a").unwrap();
+        let root = dir.path().to_str().unwrap();
+        let uid = getuid().as_raw();
+        let gid = getgid().as_raw();
+
+        // Already owned by us: nothing to change.
+        let options = crate::operations::file_mode::TreeOptions {
+            recursive: true,
+            dry_run: true,
+        };
+        let same = chown_with(&[root], Some(&uid.to_string()), None, &options, &|_| false).unwrap();
+        assert!(same.is_empty());
+
+        // A different owner is planned for every entry but not applied.
+        let other = uid.wrapping_add(1);
+        let plan = chown_with(&[root], Some(&other.to_string()), None, &options, &|_| {
+            false
+        })
+        .unwrap();
+        assert_eq!(plan.len(), 3);
+        assert!(plan.iter().all(|c| c.old_uid == uid && c.new_uid == other));
+        assert!(plan.iter().all(|c| c.old_gid == gid && c.new_gid == gid));
+        let meta = std::fs::metadata(dir.path().join("sub/a.txt")).unwrap();
+        assert_eq!(meta.uid(), uid);
+    }
 }
@@ -0,0 +1,174 @@
+#![deny(warnings)]
+
+// Compare a file's content to an expected string without reading it back whole
+
+use crate::error::{FileIoError, Result};
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+#[derive(Debug, PartialEq, Eq, serde::Serialize)]
+pub struct ContentEqualsResult {
+    pub path: String,
+    pub equal: bool,
+    /// 1-based number of the first line that differs; absent when equal.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first_diff_line: Option<u64>,
+}
+
+/// Which differences [`content_equals`] tolerates.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CompareOptions {
+    /// Ignore whitespace at the end of each line, and blank lines at the end
+    /// of the content (so a missing or extra final newline doesn't count).
+    pub ignore_trailing_whitespace: bool,
+    /// Treat CRLF and LF as the same line ending.
+    pub ignore_line_endings: bool,
+}
+
+/// Check whether the file at `path` holds exactly `expected`, modulo the
+/// differences `options` allows.
+///
+/// The file is read one line at a time and compared against the matching
+/// line of `expected`, stopping at the first difference, so a large file is
+/// never held in memory and a mismatch near the top is cheap to find.
+pub fn content_equals(
+    path: &str,
+    expected: &str,
+    options: &CompareOptions,
+) -> Result<ContentEqualsResult> {
+    let expanded_path = shellexpand::full(path)
+        .map_err(|e| {
+            crate::error::FileIoMcpError::from(FileIoError::InvalidPath(format!(
+                "Failed to expand path \'{}\': {}",
+                path, e
+            )))
+        })
+        .map(|expanded| expanded.into_owned())?;
+    let path_obj = Path::new(&expanded_path);
+    if !path_obj.exists() {
+        return Err(super::path_utils::not_found("compare file", path).into());
+    }
+    if !path_obj.is_file() {
+        return Err(FileIoError::InvalidPath(format!("{} is not a file", expanded_path)).into());
+    }
+
+    let file = File::open(path_obj)
+        .map_err(|e| FileIoError::from_io_error("open file", &expanded_path, e))?;
+    let mut reader = BufReader::new(file);
+    let mut expected_lines = expected.as_bytes().split_inclusive(|&b| b == b'\n');
+    let mut buf = Vec::new();
+    let mut line_no = 0u64;
+
+    let first_diff_line = loop {
+        buf.clear();
+        let n = reader
+            .read_until(b'\n', &mut buf)
+            .map_err(|e| FileIoError::from_io_error("read file", &expanded_path, e))?;
+        let actual = (n > 0).then_some(buf.as_slice());
+        let wanted = expected_lines.next();
+        line_no += 1;
+        match (actual, wanted) {
+            (None, None) => break None,
+            (Some(a), Some(w)) => {
+                if normalize(a, options) != normalize(w, options) {
+                    break Some(line_no);
+                }
+            }
+            // One side has run out: the rest of the other must be ignorable.
+            (Some(a), None) => {
+                if !ignorable_tail(a, options) {
+                    break Some(line_no);
+                }
+            }
+            (None, Some(w)) => {
+                if !ignorable_tail(w, options) {
+                    break Some(line_no);
+                }
+            }
+        }
+    };
+
+    Ok(ContentEqualsResult {
+        path: expanded_path,
+        equal: first_diff_line.is_none(),
+        first_diff_line,
+    })
+}
+
+/// A line (terminator included) reduced to what `options` says matters.
+fn normalize<'a>(line: &'a [u8], options: &CompareOptions) -> Cow<'a, [u8]> {
+    if options.ignore_trailing_whitespace {
+        return Cow::Borrowed(line.trim_ascii_end());
+    }
+    if options.ignore_line_endings
+        && let Some(body) = line.strip_suffix(b"\n")
+    {
+        let body = body.strip_suffix(b"\r").unwrap_or(body);
+        let mut owned = body.to_vec();
+        owned.push(b'\n');
+        return Cow::Owned(owned);
+    }
+    Cow::Borrowed(line)
+}
+
+/// Whether an unmatched trailing line may be ignored.
+fn ignorable_tail(line: &[u8], options: &CompareOptions) -> bool {
+    options.ignore_trailing_whitespace && line.trim_ascii_end().is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn compare(content: &str, expected: &str, options: CompareOptions) -> ContentEqualsResult {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("f.txt");
+        fs::write(&path, content).unwrap();
+        content_equals(path.to_str().unwrap(), expected, &options).unwrap()
+    }
+
+    #[test]
+    fn test_exact_match() {
+        let result = compare("a\nb\n", "a\nb\n", CompareOptions::default());
+        assert!(result.equal);
+        assert_eq!(result.first_diff_line, None);
+    }
+
+    #[test]
+    fn test_trailing_newline_only_difference() {
+        let strict = compare("a\nb\n", "a\nb", CompareOptions::default());
+        assert!(!strict.equal);
+        assert_eq!(strict.first_diff_line, Some(2));
+
+        let lenient = compare(
+            "a\nb  \n\n",
+            "a\nb",
+            CompareOptions {
+                ignore_trailing_whitespace: true,
+                ..CompareOptions::default()
+            },
+        );
+        assert!(lenient.equal);
+    }
+
+    #[test]
+    fn test_real_mismatch_reports_first_line() {
+        let result = compare(
+            "one\r\ntwo\r\nthree\r\n",
+            "one\ntwo\nTHREE\n",
+            CompareOptions {
+                ignore_line_endings: true,
+                ..CompareOptions::default()
+            },
+        );
+        assert!(!result.equal);
+        assert_eq!(result.first_diff_line, Some(3));
+
+        let extra = compare("one\n", "one\ntwo\n", CompareOptions::default());
+        assert_eq!(extra.first_diff_line, Some(2));
+    }
+}
@@ -0,0 +1,207 @@
+#![deny(warnings)]
+
+// Convert between tab and space indentation (like expand/unexpand)
+
+use crate::error::{FileIoError, Result};
+use std::path::Path;
+
+use super::strip_whitespace::split_terminator;
+
+/// Direction of a [`convert_tabs`] conversion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TabMode {
+    /// Tabs to spaces (`expand`).
+    Expand,
+    /// Spaces to tabs (`unexpand`).
+    Unexpand,
+}
+
+impl TabMode {
+    /// Parse the tool-level `mode` string.
+    pub fn parse(mode: &str) -> Result<Self> {
+        match mode {
+            "expand" => Ok(TabMode::Expand),
+            "unexpand" => Ok(TabMode::Unexpand),
+            other => Err(crate::error::FileIoMcpError::InvalidParams(format!(
+                "mode must be 'expand' or 'unexpand', got '{}'",
+                other
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ConvertTabsResult {
+    pub path: String,
+    pub lines_changed: u64,
+    pub changed: bool,
+}
+
+/// Convert indentation in `path` between tabs and spaces.
+///
+/// With `leading_only` (the default at the tool level) only each line's
+/// leading whitespace is touched, so tabs or space runs used for alignment
+/// inside a line are left alone. Line endings are preserved and the file is
+/// rewritten atomically only when something changed.
+pub fn convert_tabs(
+    path: &str,
+    mode: TabMode,
+    tab_width: usize,
+    leading_only: bool,
+) -> Result<ConvertTabsResult> {
+    if tab_width == 0 {
+        return Err(crate::error::FileIoMcpError::InvalidParams(
+            "tab_width must be at least 1".to_string(),
+        ));
+    }
+
+    let expanded_path = shellexpand::full(path)
+        .map_err(|e| {
+            crate::error::FileIoMcpError::from(FileIoError::InvalidPath(format!(
+                "Failed to expand path \'{}\': {}",
+                path, e
+            )))
+        })
+        .map(|expanded| expanded.into_owned())?;
+
+    if !Path::new(&expanded_path).is_file() {
+        return Err(FileIoError::NotFound(expanded_path).into());
+    }
+
+    let content = std::fs::read_to_string(&expanded_path)
+        .map_err(|e| FileIoError::from_io_error("read file", &expanded_path, e))?;
+
+    let mut out = String::with_capacity(content.len());
+    let mut lines_changed = 0u64;
+    for piece in content.split_inclusive('\n') {
+        let (body, terminator) = split_terminator(piece);
+        let converted = convert_line(body, mode, tab_width, leading_only);
+        if converted != body {
+            lines_changed += 1;
+        }
+        out.push_str(&converted);
+        out.push_str(terminator);
+    }
+
+    let changed = out != content;
+    if changed {
+        super::write_file::write_file(&expanded_path, &out, false)?;
+    }
+
+    Ok(ConvertTabsResult {
+        path: expanded_path,
+        lines_changed,
+        changed,
+    })
+}
+
+fn convert_line(line: &str, mode: TabMode, width: usize, leading_only: bool) -> String {
+    let split = if leading_only {
+        line.find(|c| c != ' ' && c != '\t').unwrap_or(line.len())
+    } else {
+        line.len()
+    };
+    let (head, tail) = line.split_at(split);
+    let expanded = expand(head, width);
+    let head = match mode {
+        TabMode::Expand => expanded,
+        TabMode::Unexpand => unexpand(&expanded, width),
+    };
+    head + tail
+}
+
+/// Replace every tab with spaces up to the next tab stop.
+fn expand(text: &str, width: usize) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut col = 0;
+    for c in text.chars() {
+        if c == '\t' {
+            let pad = width - col % width;
+            out.extend(std::iter::repeat_n(' ', pad));
+            col += pad;
+        } else {
+            out.push(c);
+            col += 1;
+        }
+    }
+    out
+}
+
+/// Replace runs of two or more spaces that reach a tab stop with a tab.
+/// `text` must already be tab-free (see [`expand`]).
+fn unexpand(text: &str, width: usize) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut pending = 0;
+    for (col, c) in (1..).zip(text.chars()) {
+        if c == ' ' {
+            pending += 1;
+            if col % width == 0 {
+                if pending > 1 {
+                    out.push('\t');
+                } else {
+                    out.push(' ');
+                }
+                pending = 0;
+            }
+        } else {
+            out.extend(std::iter::repeat_n(' ', pending));
+            pending = 0;
+            out.push(c);
+        }
+    }
+    out.extend(std::iter::repeat_n(' ', pending));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_expand_then_unexpand_width_4() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("test.txt");
+        let original = "\tfoo\n\t\tbar\tbaz\n  \tqux\nplain\n";
+        fs::write(&path, original).unwrap();
+        let p = path.to_str().unwrap();
+
+        let result = convert_tabs(p, TabMode::Expand, 4, true).unwrap();
+        assert_eq!(result.lines_changed, 3);
+        // Inner tab after "bar" is alignment, left untouched.
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "    foo\n        bar\tbaz\n    qux\nplain\n"
+        );
+
+        let result = convert_tabs(p, TabMode::Unexpand, 4, true).unwrap();
+        assert_eq!(result.lines_changed, 3);
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "\tfoo\n\t\tbar\tbaz\n\tqux\nplain\n"
+        );
+    }
+
+    #[test]
+    fn test_all_whitespace_mode() {
+        assert_eq!(convert_line("a\tb", TabMode::Expand, 4, false), "a   b");
+        assert_eq!(convert_line("a   b", TabMode::Unexpand, 4, false), "a\tb");
+        // A single space reaching a tab stop is not worth a tab.
+        assert_eq!(convert_line("abc d", TabMode::Unexpand, 4, false), "abc d");
+    }
+
+    #[test]
+    fn test_partial_indent_keeps_remainder_spaces() {
+        assert_eq!(convert_line("      x", TabMode::Unexpand, 4, true), "\t  x");
+    }
+
+    #[test]
+    fn test_invalid_mode_and_width() {
+        assert!(TabMode::parse("sideways").is_err());
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("t.txt");
+        fs::write(&path, "x").unwrap();
+        assert!(convert_tabs(path.to_str().unwrap(), TabMode::Expand, 0, true).is_err());
+    }
+}
@@ -13,19 +13,72 @@ pub struct LineCountResult {
     pub status: String,
     pub lines: Option<u64>,
     pub exists: bool,
+    /// File size in bytes; only present when size was requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bytes: Option<u64>,
+    /// Number of UTF-8 characters; only present when size was requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chars: Option<u64>,
+}
+
+/// What one pass over a file produced.
+struct Counts {
+    lines: u64,
+    size: Option<(u64, u64)>,
+}
+
+impl From<u64> for Counts {
+    fn from(lines: u64) -> Self {
+        Counts { lines, size: None }
+    }
 }
 
 /// Count lines in files
 /// Returns a vector of results: { path, status, lines }
 pub fn count_lines(paths: &[&str]) -> Result<Vec<LineCountResult>> {
+    count_each(paths, |path| count_lines_single(path).map(Counts::from))
+}
+
+/// Count only the lines matching `regex` (or not matching, with `invert`),
+/// like `grep -c` / `grep -vc`. Same per-path result shape as [`count_lines`].
+pub fn count_lines_matching(
+    paths: &[&str],
+    regex: &regex::Regex,
+    invert: bool,
+) -> Result<Vec<LineCountResult>> {
+    count_each(paths, |path| {
+        count_matching_single(path, regex, invert).map(Counts::from)
+    })
+}
+
+/// Like [`count_lines`] (or [`count_lines_matching`] when `matching` is
+/// set), but each result also carries the file's `bytes` and `chars`.
+///
+/// Lines, bytes, and characters all come from the same single read, so
+/// answering "how many lines, and how big" costs no more than counting
+/// lines. Characters are counted as UTF-8 scalar values; the size always
+/// covers the whole file, even when only matching lines are counted.
+pub fn count_lines_with_size(
+    paths: &[&str],
+    matching: Option<(&regex::Regex, bool)>,
+) -> Result<Vec<LineCountResult>> {
+    count_each(paths, |path| count_with_size_single(path, matching))
+}
+
+fn count_each(
+    paths: &[&str],
+    count: impl Fn(&str) -> Result<Counts>,
+) -> Result<Vec<LineCountResult>> {
     let mut results = Vec::new();
     for path in paths {
-        match count_lines_single(path) {
-            Ok(count) => results.push(LineCountResult {
+        match count(path) {
+            Ok(counts) => results.push(LineCountResult {
                 path: path.to_string(),
                 status: "ok".to_string(),
-                lines: Some(count),
+                lines: Some(counts.lines),
                 exists: true,
+                bytes: counts.size.map(|(bytes, _)| bytes),
+                chars: counts.size.map(|(_, chars)| chars),
             }),
             Err(e) => {
                 // Map NotFound to a clear status; other errors include message
@@ -43,6 +96,8 @@ pub fn count_lines(paths: &[&str]) -> Result<Vec<LineCountResult>> {
                     status,
                     lines: None,
                     exists: !is_not_found, // false if not found
+                    bytes: None,
+                    chars: None,
                 });
             }
         }
@@ -52,6 +107,63 @@ pub fn count_lines(paths: &[&str]) -> Result<Vec<LineCountResult>> {
 
 /// Count lines in a single file
 pub fn count_lines_single(path: &str) -> Result<u64> {
+    let reader = open_lines(path)?;
+    Ok(reader.lines().count() as u64)
+}
+
+/// Count lines in a single file that match `regex` (or don't, with `invert`).
+pub fn count_matching_single(path: &str, regex: &regex::Regex, invert: bool) -> Result<u64> {
+    let reader = open_lines(path)?;
+    let mut count = 0u64;
+    for line in reader.lines() {
+        let line = line.map_err(|e| FileIoError::from_io_error("read file", path, e))?;
+        if regex.is_match(&line) != invert {
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+/// Count lines (optionally only matching ones), bytes, and characters of a
+/// single file in one pass.
+fn count_with_size_single(path: &str, matching: Option<(&regex::Regex, bool)>) -> Result<Counts> {
+    let mut reader = open_lines(path)?;
+    let mut buf = Vec::new();
+    let (mut lines, mut bytes, mut chars) = (0u64, 0u64, 0u64);
+    loop {
+        buf.clear();
+        let n = reader
+            .read_until(b'\n', &mut buf)
+            .map_err(|e| FileIoError::from_io_error("read file", path, e))?;
+        if n == 0 {
+            break;
+        }
+        bytes += n as u64;
+        // Every UTF-8 character has exactly one non-continuation byte.
+        chars += buf.iter().filter(|&&b| b & 0xC0 != 0x80).count() as u64;
+        let counted = match matching {
+            Some((regex, invert)) => {
+                let line = std::str::from_utf8(&buf).map_err(|e| {
+                    FileIoError::ReadError(format!("{}: invalid UTF-8: {}", path, e))
+                })?;
+                let line = line.strip_suffix('\n').unwrap_or(line);
+                let line = line.strip_suffix('\r').unwrap_or(line);
+                regex.is_match(line) != invert
+            }
+            None => true,
+        };
+        if counted {
+            lines += 1;
+        }
+    }
+    Ok(Counts {
+        lines,
+        size: Some((bytes, chars)),
+    })
+}
+
+/// Resolve `path` and open it for line-wise reading.
+fn open_lines(path: &str) -> Result<BufReader<File>> {
     let expanded_path = shellexpand::full(path)
         .map_err(|e| {
             crate::error::FileIoMcpError::from(crate::error::FileIoError::InvalidPath(format!(
@@ -78,10 +190,7 @@ pub fn count_lines_single(path: &str) -> Result<u64> {
         ))
     })?;
 
-    let reader = BufReader::new(file);
-    let line_count = reader.lines().count() as u64;
-
-    Ok(line_count)
+    Ok(BufReader::new(file))
 }
 
 #[cfg(test)]
@@ -127,4 +236,51 @@ mod tests {
         assert_eq!(r.status, "ok");
         assert_eq!(r.lines, Some(1));
     }
+
+    #[test]
+    fn test_count_lines_matching() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, "INFO start").unwrap();
+        writeln!(file, "ERROR disk full").unwrap();
+        writeln!(file, "error lowercase").unwrap();
+        writeln!(file, "ERROR again ERROR").unwrap();
+        let path = file.path().to_str().unwrap();
+
+        let sensitive =
+            crate::operations::find_in_files::build_regex("ERROR", false, true, false, false)
+                .unwrap();
+        let results = count_lines_matching(&[path], &sensitive, false).unwrap();
+        assert_eq!(results[0].lines, Some(2));
+
+        let insensitive =
+            crate::operations::find_in_files::build_regex("error", false, false, false, false)
+                .unwrap();
+        let results = count_lines_matching(&[path], &insensitive, false).unwrap();
+        assert_eq!(results[0].lines, Some(3));
+
+        let results = count_lines_matching(&[path], &sensitive, true).unwrap();
+        assert_eq!(results[0].lines, Some(2));
+    }
+
+    #[test]
+    fn test_count_lines_with_size_multibyte() {
+        let mut file = NamedTempFile::new().unwrap();
+        // "héllo" is 5 chars / 6 bytes, "日本" is 2 chars / 6 bytes.
+        writeln!(file, "héllo").unwrap();
+        write!(file, "日本").unwrap();
+        let path = file.path().to_str().unwrap();
+
+        let results = count_lines_with_size(&[path], None).unwrap();
+        let r = &results[0];
+        assert_eq!(r.lines, Some(2));
+        assert_eq!(r.bytes, Some(13));
+        assert_eq!(r.chars, Some(8));
+
+        // Off by default, and absent from the serialized shape.
+        let plain = count_lines(&[path]).unwrap();
+        assert_eq!(plain[0].bytes, None);
+        let json = serde_json::to_value(&plain[0]).unwrap();
+        assert!(json.get("bytes").is_none());
+        assert!(json.get("chars").is_none());
+    }
 }
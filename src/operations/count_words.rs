@@ -3,8 +3,11 @@
 // Count words in a file
 
 use crate::error::{FileIoError, Result};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
 use std::fs;
 use std::path::Path;
+use unicode_segmentation::UnicodeSegmentation;
 
 #[derive(Debug, serde::Serialize)]
 pub struct WordCountResult {
@@ -12,19 +15,63 @@ pub struct WordCountResult {
     pub status: String,
     pub words: Option<u64>,
     pub exists: bool,
+    /// Word count of each line, in order; only present when requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub words_per_line: Option<Vec<u64>>,
+    /// Most frequent words, most frequent first; only present when requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_words: Option<Vec<WordFrequency>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct WordFrequency {
+    /// The word, lowercased.
+    pub word: String,
+    pub count: u64,
+}
+
+/// How [`count_words_with`] splits text into words and what it reports.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WordCountOptions {
+    /// Use Unicode word segmentation (UAX #29) instead of splitting on
+    /// whitespace. Punctuation is not part of a word (`"don't stop—now"` is
+    /// 3 words, not 2), and scripts written without spaces are segmented
+    /// too: each CJK ideograph counts as a word, where a whitespace split
+    /// would count a whole sentence as one.
+    pub unicode: bool,
+    /// Also report the count for every line.
+    pub per_line: bool,
+    /// Also report this many of the most frequent words. Words are
+    /// lowercased before counting; ties are broken alphabetically.
+    pub top_words: Option<usize>,
+    /// With `top_words`, trim leading and trailing punctuation (any
+    /// non-alphanumeric character) from each word first, so `"end."` and
+    /// `"end"` count together.
+    pub strip_punctuation: bool,
 }
 
 /// Count words in files (whitespace-separated)
 /// Returns a vector of results: { path, status, words }
 pub fn count_words(paths: &[&str]) -> Result<Vec<WordCountResult>> {
+    count_words_with(paths, &WordCountOptions::default())
+}
+
+/// [`count_words`] with a choice of segmentation and an optional per-line
+/// breakdown.
+pub fn count_words_with(
+    paths: &[&str],
+    options: &WordCountOptions,
+) -> Result<Vec<WordCountResult>> {
     let mut results = Vec::new();
     for path in paths {
-        match count_words_single(path) {
-            Ok(count) => results.push(WordCountResult {
+        match count_words_detailed(path, options) {
+            Ok(detail) => results.push(WordCountResult {
                 path: path.to_string(),
                 status: "ok".to_string(),
-                words: Some(count),
+                words: Some(detail.words),
                 exists: true,
+                words_per_line: detail.per_line,
+                top_words: detail.top_words,
             }),
             Err(e) => {
                 let is_not_found = matches!(
@@ -41,6 +88,8 @@ pub fn count_words(paths: &[&str]) -> Result<Vec<WordCountResult>> {
                     status,
                     words: None,
                     exists: !is_not_found,
+                    words_per_line: None,
+                    top_words: None,
                 });
             }
         }
@@ -50,6 +99,19 @@ pub fn count_words(paths: &[&str]) -> Result<Vec<WordCountResult>> {
 
 /// Count words in a single file (whitespace-separated)
 pub fn count_words_single(path: &str) -> Result<u64> {
+    count_words_detailed(path, &WordCountOptions::default()).map(|detail| detail.words)
+}
+
+/// Everything [`count_words_detailed`] computes for one file.
+struct WordCountDetail {
+    words: u64,
+    per_line: Option<Vec<u64>>,
+    top_words: Option<Vec<WordFrequency>>,
+}
+
+/// Total word count of a single file, plus per-line counts and top words if
+/// requested.
+fn count_words_detailed(path: &str, options: &WordCountOptions) -> Result<WordCountDetail> {
     let expanded_path = shellexpand::full(path)
         .map_err(|e| {
             crate::error::FileIoMcpError::from(crate::error::FileIoError::InvalidPath(format!(
@@ -76,9 +138,75 @@ pub fn count_words_single(path: &str) -> Result<u64> {
         ))
     })?;
 
-    let word_count = content.split_whitespace().count() as u64;
+    let (total, per_line) = if options.per_line {
+        let per_line: Vec<u64> = content
+            .lines()
+            .map(|line| split_words(line, options.unicode).count() as u64)
+            .collect();
+        (per_line.iter().sum(), Some(per_line))
+    } else {
+        (split_words(&content, options.unicode).count() as u64, None)
+    };
+    let top_words = options.top_words.map(|n| {
+        top_n(
+            split_words(&content, options.unicode),
+            n,
+            options.strip_punctuation,
+        )
+    });
+
+    Ok(WordCountDetail {
+        words: total,
+        per_line,
+        top_words,
+    })
+}
+
+/// Words of `text`, by Unicode segmentation or by whitespace.
+fn split_words(text: &str, unicode: bool) -> Box<dyn Iterator<Item = &str> + '_> {
+    if unicode {
+        Box::new(text.unicode_words())
+    } else {
+        Box::new(text.split_whitespace())
+    }
+}
+
+/// The `n` most frequent of `words`, case-folded, most frequent first.
+///
+/// Why a bounded heap: the counting map is unavoidable, but selecting from it
+/// through a min-heap of size `n` keeps the selection at O(n) extra memory
+/// instead of sorting every distinct word.
+fn top_n<'a>(
+    words: impl Iterator<Item = &'a str>,
+    n: usize,
+    strip_punctuation: bool,
+) -> Vec<WordFrequency> {
+    let mut counts: HashMap<String, u64> = HashMap::new();
+    for word in words {
+        let word = if strip_punctuation {
+            word.trim_matches(|c: char| !c.is_alphanumeric())
+        } else {
+            word
+        };
+        if word.is_empty() {
+            continue;
+        }
+        *counts.entry(word.to_lowercase()).or_insert(0) += 1;
+    }
 
-    Ok(word_count)
+    // Min-heap on (count, reverse word): the top is the weakest candidate —
+    // lowest count, and alphabetically last among equals.
+    let mut heap: BinaryHeap<Reverse<(u64, Reverse<String>)>> = BinaryHeap::with_capacity(n + 1);
+    for (word, count) in counts {
+        heap.push(Reverse((count, Reverse(word))));
+        if heap.len() > n {
+            heap.pop();
+        }
+    }
+    heap.into_sorted_vec()
+        .into_iter()
+        .map(|Reverse((count, Reverse(word)))| WordFrequency { word, count })
+        .collect()
 }
 
 #[cfg(test)]
@@ -122,4 +250,77 @@ mod tests {
         assert_eq!(r.status, "ok");
         assert_eq!(r.words, Some(3));
     }
+
+    #[test]
+    fn test_count_words_unicode_vs_whitespace() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, "don't stop—now").unwrap();
+        writeln!(file, "日本語").unwrap();
+        let path = file.path().to_str().unwrap();
+
+        let split = count_words_with(
+            &[path],
+            &WordCountOptions {
+                per_line: true,
+                ..WordCountOptions::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(split[0].words, Some(3));
+        assert_eq!(split[0].words_per_line, Some(vec![2, 1]));
+
+        let segmented = count_words_with(
+            &[path],
+            &WordCountOptions {
+                unicode: true,
+                per_line: true,
+                ..WordCountOptions::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(segmented[0].words, Some(6));
+        assert_eq!(segmented[0].words_per_line, Some(vec![3, 3]));
+    }
+
+    #[test]
+    fn test_count_words_top_words() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, "The cat saw the dog.").unwrap();
+        writeln!(file, "THE dog ran; the cat slept, the end").unwrap();
+        let path = file.path().to_str().unwrap();
+
+        let options = WordCountOptions {
+            top_words: Some(2),
+            strip_punctuation: true,
+            ..WordCountOptions::default()
+        };
+        let results = count_words_with(&[path], &options).unwrap();
+        assert_eq!(results[0].words, Some(13));
+        assert_eq!(
+            results[0].top_words,
+            Some(vec![
+                WordFrequency {
+                    word: "the".to_string(),
+                    count: 5
+                },
+                // "cat" and "dog" tie at 2; the alphabetically first wins.
+                WordFrequency {
+                    word: "cat".to_string(),
+                    count: 2
+                },
+            ])
+        );
+
+        // Without stripping, "dog." and "dog" are different words.
+        let options = WordCountOptions {
+            top_words: Some(20),
+            ..WordCountOptions::default()
+        };
+        let results = count_words_with(&[path], &options).unwrap();
+        let top = results[0].top_words.as_ref().unwrap();
+        assert!(top.contains(&WordFrequency {
+            word: "dog.".to_string(),
+            count: 1
+        }));
+    }
 }
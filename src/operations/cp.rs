@@ -3,70 +3,13 @@
 // Copy files or directories
 
 use crate::error::{FileIoError, Result};
-use globset::{Glob, GlobMatcher};
+use std::collections::HashSet;
+use std::ffi::{OsStr, OsString};
 use std::fs;
 use std::path::{Path, PathBuf};
 
-/// Check if a string contains glob patterns
-fn is_glob_pattern(s: &str) -> bool {
-    s.contains('*') || s.contains('?') || s.contains('[') || s.contains('{')
-}
-
-/// Expand glob pattern to matching paths
-fn expand_glob(pattern: &str) -> Result<Vec<PathBuf>> {
-    let expanded_pattern = shellexpand::full(pattern)
-        .map_err(|e| {
-            crate::error::FileIoMcpError::from(crate::error::FileIoError::InvalidPath(format!(
-                "Failed to expand path \'{}\': {}",
-                pattern, e
-            )))
-        })
-        .map(|expanded| expanded.into_owned())?;
-    let path = Path::new(&expanded_pattern);
-    let (base_dir, glob_str) = if let Some(parent) = path.parent() {
-        if parent.as_os_str().is_empty() {
-            (
-                Path::new("."),
-                path.file_name().and_then(|n| n.to_str()).unwrap_or(pattern),
-            )
-        } else {
-            (
-                parent,
-                path.file_name().and_then(|n| n.to_str()).unwrap_or(pattern),
-            )
-        }
-    } else {
-        (Path::new("."), pattern)
-    };
-
-    let glob = Glob::new(glob_str).map_err(|e| {
-        FileIoError::InvalidPath(format!("Invalid glob pattern {}: {}", pattern, e))
-    })?;
-    let matcher: GlobMatcher = glob.compile_matcher();
-
-    let mut matches = Vec::new();
-    let entries = fs::read_dir(base_dir).map_err(|e| {
-        FileIoError::ReadError(format!(
-            "Failed to read directory {}: {}",
-            base_dir.display(),
-            e
-        ))
-    })?;
-
-    for entry in entries {
-        let entry = entry.map_err(|e| {
-            FileIoError::ReadError(format!("Failed to read directory entry: {}", e))
-        })?;
-        let entry_path = entry.path();
-        if let Some(file_name) = entry_path.file_name().and_then(|n| n.to_str())
-            && matcher.is_match(file_name)
-        {
-            matches.push(entry_path);
-        }
-    }
-
-    Ok(matches)
-}
+use super::glob_expand::{expand_glob, is_glob_pattern};
+use super::mv::{TransferReport, find_conflicts};
 
 /// Copy files or directories (supports glob patterns and arrays of paths)
 #[derive(Debug, serde::Serialize)]
@@ -74,11 +17,103 @@ pub struct OpResult {
     pub path: String,
     pub status: String,
     pub exists: bool,
+    /// Whether the copy was a copy-on-write clone. Only present when a
+    /// reflink mode other than `never` was requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reflinked: Option<bool>,
+    /// SHA-256 shared by the source and the copy, present when `verify` was
+    /// requested and the source is a single file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+}
+
+/// Copy-on-write cloning policy for [`cp_with`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReflinkMode {
+    /// Always copy contents (the default).
+    #[default]
+    Never,
+    /// Clone where the filesystem supports it, otherwise copy.
+    Auto,
+    /// Clone or fail; never fall back to a full copy.
+    Always,
+}
+
+impl ReflinkMode {
+    /// Parse the tool-level `reflink` string.
+    pub fn parse(mode: &str) -> Result<Self> {
+        match mode {
+            "never" => Ok(ReflinkMode::Never),
+            "auto" => Ok(ReflinkMode::Auto),
+            "always" => Ok(ReflinkMode::Always),
+            other => Err(crate::error::FileIoMcpError::InvalidParams(format!(
+                "reflink must be 'never', 'auto', or 'always', got '{}'",
+                other
+            ))),
+        }
+    }
+}
+
+/// Options for [`cp_with`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CpOptions {
+    /// Copy directories recursively.
+    pub recursive: bool,
+    /// Hard-link files into place instead of copying their contents (`cp -l`).
+    /// Directories are still recreated; only files are linked.
+    pub link: bool,
+    /// Copy-on-write cloning (Btrfs, XFS, APFS, ...). Mutually exclusive
+    /// with `link`.
+    pub reflink: ReflinkMode,
+    /// Plan the copy without touching the filesystem; sources that exist are
+    /// reported as `would_copy`.
+    pub dry_run: bool,
+    /// Hash every copied file and its source afterwards and fail the source's
+    /// result if they differ.
+    pub verify: bool,
 }
 
 /// Copy files or directories (supports glob patterns and arrays of paths)
 /// Returns per-source results instead of failing the whole call for per-file errors.
 pub fn cp(sources: &[&str], destination: &str, recursive: bool) -> Result<Vec<OpResult>> {
+    cp_with(
+        sources,
+        destination,
+        &CpOptions {
+            recursive,
+            ..CpOptions::default()
+        },
+    )
+}
+
+/// [`cp`] with the full set of [`CpOptions`].
+pub fn cp_with(sources: &[&str], destination: &str, options: &CpOptions) -> Result<Vec<OpResult>> {
+    cp_impl(sources, destination, options).map(|report| report.results)
+}
+
+/// Like [`cp_with`], but also reports destinations that already exist or
+/// that several sources map to (see [`super::mv::TransferConflict`]).
+/// Combine with [`CpOptions::dry_run`] to inspect a glob fan-in before
+/// anything is overwritten.
+pub fn cp_reporting_conflicts(
+    sources: &[&str],
+    destination: &str,
+    options: &CpOptions,
+) -> Result<TransferReport<OpResult>> {
+    cp_impl(sources, destination, options)
+}
+
+fn cp_impl(
+    sources: &[&str],
+    destination: &str,
+    options: &CpOptions,
+) -> Result<TransferReport<OpResult>> {
+    if options.link && options.reflink != ReflinkMode::Never {
+        return Err(crate::error::FileIoMcpError::InvalidParams(
+            "link and reflink cannot be combined".to_string(),
+        ));
+    }
+    let report_reflink = options.reflink != ReflinkMode::Never;
     let expanded_dest = shellexpand::full(destination)
         .map_err(|e| {
             crate::error::FileIoMcpError::from(crate::error::FileIoError::InvalidPath(format!(
@@ -90,6 +125,81 @@ pub fn cp(sources: &[&str], destination: &str, recursive: bool) -> Result<Vec<Op
     let dest_path = Path::new(&expanded_dest);
     let dest_is_dir = dest_path.exists() && dest_path.is_dir();
 
+    let all_sources = expand_sources(sources)?;
+
+    if all_sources.len() > 1 && !dest_is_dir {
+        return Err(FileIoError::InvalidPath(format!(
+            "Multiple sources provided but destination '{}' is not a directory",
+            destination
+        ))
+        .into());
+    }
+
+    let mut plan = Vec::new();
+    for source_path in all_sources {
+        let dest = if dest_is_dir {
+            let source_path_obj = Path::new(&source_path);
+            let file_name = source_path_obj.file_name().ok_or_else(|| {
+                FileIoError::InvalidPath(format!(
+                    "Source path has no file name (is it the root?): {}",
+                    source_path
+                ))
+            })?;
+            dest_path.join(file_name)
+        } else {
+            dest_path.to_path_buf()
+        };
+        plan.push((source_path, dest));
+    }
+    let conflicts = find_conflicts(&plan);
+
+    let mut results = Vec::new();
+    for (source_path, dest) in &plan {
+        let dest_str = dest.to_str().ok_or_else(|| {
+            FileIoError::InvalidPath(format!(
+                "Destination path is not valid UTF-8: {}",
+                dest.display()
+            ))
+        })?;
+        let outcome: Result<(&str, bool, Option<String>)> = if options.dry_run {
+            if Path::new(source_path).exists() {
+                Ok(("would_copy", false, None))
+            } else {
+                Err(FileIoError::NotFound(source_path.clone()).into())
+            }
+        } else {
+            cp_single(source_path, dest_str, options)
+                .map(|(reflinked, sha256)| ("ok", reflinked, sha256))
+        };
+        match outcome {
+            Ok((status, reflinked, sha256)) => results.push(OpResult {
+                path: source_path.clone(),
+                status: status.to_string(),
+                exists: true,
+                reflinked: report_reflink.then_some(reflinked),
+                sha256,
+            }),
+            Err(e) => {
+                let is_not_found = matches!(
+                    e,
+                    crate::error::FileIoMcpError::FileIo(crate::error::FileIoError::NotFound(_))
+                );
+                results.push(OpResult {
+                    path: source_path.clone(),
+                    status: format!("error: {}", e),
+                    exists: !is_not_found,
+                    reflinked: report_reflink.then_some(false),
+                    sha256: None,
+                });
+            }
+        }
+    }
+
+    Ok(TransferReport { results, conflicts })
+}
+
+/// Expand glob patterns in `sources`, keeping plain paths as given.
+pub(crate) fn expand_sources(sources: &[&str]) -> Result<Vec<String>> {
     let mut all_sources = Vec::new();
 
     for source in sources {
@@ -120,60 +230,288 @@ pub fn cp(sources: &[&str], destination: &str, recursive: bool) -> Result<Vec<Op
         }
     }
 
-    if all_sources.len() > 1 && !dest_is_dir {
-        return Err(FileIoError::InvalidPath(format!(
-            "Multiple sources provided but destination '{}' is not a directory",
-            destination
-        ))
-        .into());
+    Ok(all_sources)
+}
+
+/// How [`cp_flatten`] resolves two files that would land on the same name.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FlattenCollisionPolicy {
+    /// Refuse the whole copy before anything is written (the default).
+    #[default]
+    Error,
+    /// Copy the first file to claim a name and skip the rest.
+    Skip,
+    /// Give later files a numeric suffix: `photo.jpg`, `photo-1.jpg`, ...
+    Suffix,
+}
+
+impl FlattenCollisionPolicy {
+    /// Parse the tool-level `on_collision` string.
+    pub fn parse(policy: &str) -> Result<Self> {
+        match policy {
+            "error" => Ok(FlattenCollisionPolicy::Error),
+            "skip" => Ok(FlattenCollisionPolicy::Skip),
+            "suffix" => Ok(FlattenCollisionPolicy::Suffix),
+            other => Err(crate::error::FileIoMcpError::InvalidParams(format!(
+                "on_collision must be 'error', 'skip', or 'suffix', got '{}'",
+                other
+            ))),
+        }
     }
+}
 
-    let mut results = Vec::new();
-    for source_path in &all_sources {
-        let dest = if dest_is_dir {
-            let source_path_obj = Path::new(source_path);
-            let file_name = source_path_obj.file_name().ok_or_else(|| {
-                FileIoError::InvalidPath(format!(
-                    "Source path has no file name (is it the root?): {}",
-                    source_path
-                ))
-            })?;
-            dest_path.join(file_name)
-        } else {
-            dest_path.to_path_buf()
+/// A file whose flattened name was already taken.
+#[derive(Debug, serde::Serialize)]
+pub struct FlattenCollision {
+    pub source: String,
+    /// Where the file would have landed without the collision.
+    pub intended: String,
+    /// `error` (dry run only), `skipped`, or `renamed`.
+    pub resolution: String,
+    /// The suffixed path actually used, for `renamed`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination: Option<String>,
+}
+
+/// Per-source results of [`cp_flatten`] plus every collision it resolved.
+#[derive(Debug, serde::Serialize)]
+pub struct FlattenReport {
+    pub results: Vec<OpResult>,
+    pub collisions: Vec<FlattenCollision>,
+}
+
+/// Copy every file from `sources` directly into the `destination`
+/// directory, discarding subdirectory structure.
+///
+/// Directory sources (which need `options.recursive`) contribute all the
+/// files below them; symlinks are skipped, as in a recursive [`cp`]. The
+/// destination is created if missing. A name is taken if an earlier file in
+/// the batch claimed it or something already exists there, and `policy`
+/// decides what happens next. The whole batch is planned first, so
+/// [`FlattenCollisionPolicy::Error`] fails before a single file is copied
+/// (with `dry_run` the collisions are reported instead).
+///
+/// Why: treating existing destination files as collisions means a flatten
+/// never overwrites — gathering files from many folders is exactly where
+/// same-named files are common and silently losing one is the worst outcome.
+pub fn cp_flatten(
+    sources: &[&str],
+    destination: &str,
+    options: &CpOptions,
+    policy: FlattenCollisionPolicy,
+) -> Result<FlattenReport> {
+    if options.link && options.reflink != ReflinkMode::Never {
+        return Err(crate::error::FileIoMcpError::InvalidParams(
+            "link and reflink cannot be combined".to_string(),
+        ));
+    }
+    let report_reflink = options.reflink != ReflinkMode::Never;
+    let expanded_dest = shellexpand::full(destination)
+        .map_err(|e| {
+            crate::error::FileIoMcpError::from(crate::error::FileIoError::InvalidPath(format!(
+                "Failed to expand path \'{}\': {}",
+                destination, e
+            )))
+        })
+        .map(|expanded| expanded.into_owned())?;
+    let dest_dir = Path::new(&expanded_dest);
+    if dest_dir.exists() && !dest_dir.is_dir() {
+        return Err(FileIoError::NotADirectory(expanded_dest).into());
+    }
+
+    let all_sources = expand_sources(sources)?;
+
+    // Plan: (source index, file, final destination or None when skipped).
+    let mut source_errors: Vec<Option<crate::error::FileIoMcpError>> = Vec::new();
+    let mut plan: Vec<(usize, PathBuf, Option<PathBuf>)> = Vec::new();
+    let mut collisions = Vec::new();
+    let mut claimed: HashSet<OsString> = HashSet::new();
+    let taken = |name: &OsStr, claimed: &HashSet<OsString>| {
+        claimed.contains(name) || dest_dir.join(name).symlink_metadata().is_ok()
+    };
+    for (idx, source_path) in all_sources.iter().enumerate() {
+        let files = match flatten_files(Path::new(source_path), options.recursive) {
+            Ok(files) => files,
+            Err(e) => {
+                source_errors.push(Some(e));
+                continue;
+            }
         };
+        source_errors.push(None);
+        for file in files {
+            let Some(name) = file.file_name().map(OsStr::to_os_string) else {
+                continue;
+            };
+            let intended = dest_dir.join(&name);
+            if !taken(&name, &claimed) {
+                claimed.insert(name);
+                plan.push((idx, file, Some(intended)));
+                continue;
+            }
+            let (resolution, target) = match policy {
+                FlattenCollisionPolicy::Error => ("error", None),
+                FlattenCollisionPolicy::Skip => ("skipped", None),
+                FlattenCollisionPolicy::Suffix => {
+                    let path = Path::new(&name);
+                    let stem = path
+                        .file_stem()
+                        .unwrap_or(name.as_os_str())
+                        .to_string_lossy();
+                    let ext = path
+                        .extension()
+                        .map(|e| format!(".{}", e.to_string_lossy()))
+                        .unwrap_or_default();
+                    let mut n = 1u64;
+                    let renamed = loop {
+                        let candidate = OsString::from(format!("{}-{}{}", stem, n, ext));
+                        if !taken(&candidate, &claimed) {
+                            break candidate;
+                        }
+                        n += 1;
+                    };
+                    let target = dest_dir.join(&renamed);
+                    claimed.insert(renamed);
+                    ("renamed", Some(target))
+                }
+            };
+            collisions.push(FlattenCollision {
+                source: file.to_string_lossy().into_owned(),
+                intended: intended.to_string_lossy().into_owned(),
+                resolution: resolution.to_string(),
+                destination: target.as_ref().map(|t| t.to_string_lossy().into_owned()),
+            });
+            plan.push((idx, file, target));
+        }
+    }
 
-        let dest_str = dest.to_str().ok_or_else(|| {
-            FileIoError::InvalidPath(format!(
-                "Destination path is not valid UTF-8: {}",
-                dest.display()
-            ))
+    if policy == FlattenCollisionPolicy::Error && !collisions.is_empty() && !options.dry_run {
+        let names: Vec<&str> = collisions.iter().map(|c| c.source.as_str()).collect();
+        return Err(FileIoError::AlreadyExists(format!(
+            "refusing to flatten, file names collide: {}",
+            names.join(", ")
+        ))
+        .into());
+    }
+
+    if !options.dry_run && !plan.is_empty() {
+        fs::create_dir_all(dest_dir).map_err(|e| {
+            FileIoError::from_io_error("create destination directory", &expanded_dest, e)
         })?;
-        match cp_single(source_path, dest_str, recursive) {
-            Ok(()) => results.push(OpResult {
-                path: source_path.clone(),
-                status: "ok".to_string(),
+    }
+    let mut reflinked = vec![true; all_sources.len()];
+    if !options.dry_run {
+        for (idx, file, target) in &plan {
+            let Some(target) = target else {
+                continue;
+            };
+            if source_errors[*idx].is_some() {
+                continue;
+            }
+            match copy_file(file, target, options) {
+                Ok(cloned) => reflinked[*idx] &= cloned,
+                Err(e) => source_errors[*idx] = Some(e),
+            }
+        }
+    }
+
+    let results = all_sources
+        .into_iter()
+        .zip(source_errors)
+        .zip(reflinked)
+        .map(|((path, error), cloned)| match error {
+            None => OpResult {
+                path,
+                status: if options.dry_run { "would_copy" } else { "ok" }.to_string(),
                 exists: true,
-            }),
-            Err(e) => {
+                reflinked: report_reflink.then_some(cloned && !options.dry_run),
+                sha256: None,
+            },
+            Some(e) => {
                 let is_not_found = matches!(
                     e,
                     crate::error::FileIoMcpError::FileIo(crate::error::FileIoError::NotFound(_))
                 );
-                results.push(OpResult {
-                    path: source_path.clone(),
+                OpResult {
+                    path,
                     status: format!("error: {}", e),
                     exists: !is_not_found,
-                });
+                    reflinked: report_reflink.then_some(false),
+                    sha256: None,
+                }
             }
-        }
+        })
+        .collect();
+
+    Ok(FlattenReport {
+        results,
+        collisions,
+    })
+}
+
+/// The files a flatten copies for one source: the source itself if it is a
+/// file, or every regular file below it (sorted, symlinks skipped).
+fn flatten_files(source: &Path, recursive: bool) -> Result<Vec<PathBuf>> {
+    let display = source.display().to_string();
+    if !source.exists() {
+        return Err(FileIoError::NotFound(display).into());
+    }
+    if source.is_file() {
+        return Ok(vec![source.to_path_buf()]);
+    }
+    if !source.is_dir() {
+        return Err(FileIoError::InvalidPath(format!(
+            "Source {} is not a file or directory",
+            display
+        ))
+        .into());
+    }
+    if !recursive {
+        return Err(FileIoError::InvalidPath(format!(
+            "Cannot copy directory {} without recursive flag. Set recursive=true to copy directories",
+            display
+        ))
+        .into());
     }
 
-    Ok(results)
+    let mut files = Vec::new();
+    let mut pending = vec![source.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let mut entries = Vec::new();
+        for entry in fs::read_dir(&dir).map_err(|e| {
+            FileIoError::from_io_error("read source directory", &dir.display().to_string(), e)
+        })? {
+            let entry = entry.map_err(|e| {
+                FileIoError::ReadError(format!("Failed to read directory entry: {}", e))
+            })?;
+            entries.push(entry.path());
+        }
+        entries.sort();
+        let mut subdirs = Vec::new();
+        for path in entries {
+            let meta = fs::symlink_metadata(&path).map_err(|e| {
+                FileIoError::from_io_error("read metadata for", &path.display().to_string(), e)
+            })?;
+            if meta.file_type().is_symlink() {
+                continue;
+            } else if meta.is_dir() {
+                subdirs.push(path);
+            } else {
+                files.push(path);
+            }
+        }
+        // Reversed so subdirectories are visited in name order.
+        pending.extend(subdirs.into_iter().rev());
+    }
+    Ok(files)
 }
 
-/// Copy a single file or directory
-fn cp_single(source: &str, destination: &str, recursive: bool) -> Result<()> {
+/// Copy a single file or directory. Returns whether every file copied was a
+/// copy-on-write clone, and, for a verified file copy, its SHA-256.
+fn cp_single(
+    source: &str,
+    destination: &str,
+    options: &CpOptions,
+) -> Result<(bool, Option<String>)> {
     let source_path = Path::new(source);
     let dest_path = Path::new(destination);
 
@@ -182,62 +520,47 @@ fn cp_single(source: &str, destination: &str, recursive: bool) -> Result<()> {
     }
 
     if source_path.is_file() {
-        // Copy file
-        fs::copy(source, destination).map_err(|e| {
-            use std::io::ErrorKind;
-            match e.kind() {
-                ErrorKind::PermissionDenied => {
-                    crate::error::FileIoMcpError::from(FileIoError::PermissionDenied(format!(
-                        "Permission denied when copying {} to {}: {}",
-                        source, destination, e
-                    )))
-                }
-                ErrorKind::NotFound => crate::error::FileIoMcpError::from(FileIoError::NotFound(
-                    format!("Source file not found: {}", source),
-                )),
-                _ => crate::error::FileIoMcpError::from(FileIoError::from_io_error(
-                    "copy file",
-                    &format!("{} to {}", source, destination),
-                    e,
-                )),
-            }
-        })?;
-    } else if source_path.is_dir() {
-        if !recursive {
+        let reflinked = copy_file_unverified(source_path, dest_path, options)?;
+        let sha256 = if options.verify {
+            Some(verify_copy(source_path, dest_path)?)
+        } else {
+            None
+        };
+        return Ok((reflinked, sha256));
+    }
+    let reflinked = if source_path.is_dir() {
+        if !options.recursive {
             return Err(FileIoError::InvalidPath(
                 format!("Cannot copy directory {} without recursive flag. Set recursive=true to copy directories", source)
             )
             .into());
         }
-        copy_dir_all(source_path, dest_path)?;
+        copy_dir_all(source_path, dest_path, options)?
     } else {
         return Err(FileIoError::InvalidPath(format!(
             "Source {} is not a file or directory",
             source
         ))
         .into());
-    }
+    };
 
-    Ok(())
+    Ok((reflinked, None))
 }
 
-fn copy_dir_all(src: &Path, dst: &Path) -> Result<()> {
+fn copy_dir_all(src: &Path, dst: &Path, options: &CpOptions) -> Result<bool> {
     fs::create_dir_all(dst).map_err(|e| {
-        FileIoError::WriteError(format!(
-            "Failed to create destination directory {}: {}",
-            dst.display(),
-            e
-        ))
+        FileIoError::from_io_error(
+            "create destination directory",
+            &dst.display().to_string(),
+            e,
+        )
     })?;
 
     let entries = fs::read_dir(src).map_err(|e| {
-        FileIoError::ReadError(format!(
-            "Failed to read source directory {}: {}",
-            src.display(),
-            e
-        ))
+        FileIoError::from_io_error("read source directory", &src.display().to_string(), e)
     })?;
 
+    let mut all_reflinked = true;
     for entry in entries {
         let entry = entry.map_err(|e| {
             FileIoError::ReadError(format!("Failed to read directory entry: {}", e))
@@ -249,31 +572,111 @@ fn copy_dir_all(src: &Path, dst: &Path) -> Result<()> {
         // Use symlink_metadata to detect symlinks without following them.
         // Skip symlinks to prevent escaping directory boundaries.
         let meta = fs::symlink_metadata(&path).map_err(|e| {
-            FileIoError::ReadError(format!(
-                "Failed to read metadata for {}: {}",
-                path.display(),
-                e
-            ))
+            FileIoError::from_io_error("read metadata for", &path.display().to_string(), e)
         })?;
 
         if meta.file_type().is_symlink() {
             // Skip symlinks to avoid copying files outside the source tree.
             continue;
         } else if meta.is_dir() {
-            copy_dir_all(&path, &dest_path)?;
+            all_reflinked &= copy_dir_all(&path, &dest_path, options)?;
         } else {
-            fs::copy(&path, &dest_path).map_err(|e| {
+            all_reflinked &= copy_file(&path, &dest_path, options)?;
+        }
+    }
+
+    Ok(all_reflinked)
+}
+
+/// [`copy_file_unverified`], then [`verify_copy`] when `options.verify` is set.
+fn copy_file(src: &Path, dst: &Path, options: &CpOptions) -> Result<bool> {
+    let reflinked = copy_file_unverified(src, dst, options)?;
+    if options.verify {
+        verify_copy(src, dst)?;
+    }
+    Ok(reflinked)
+}
+
+/// Hash `src` and `dst` and fail unless they match; returns the digest.
+///
+/// Why: the destination is re-read from disk rather than hashed from the
+/// bytes written, so corruption introduced by the storage layer (a flaky
+/// network filesystem, say) is caught too.
+fn verify_copy(src: &Path, dst: &Path) -> Result<String> {
+    let expected = super::checksum::sha256_file(src)?;
+    let actual = super::checksum::sha256_file(dst)?;
+    if expected != actual {
+        return Err(FileIoError::WriteError(format!(
+            "Verification failed: {} (sha256 {}) does not match its source {} (sha256 {})",
+            dst.display(),
+            actual,
+            src.display(),
+            expected
+        ))
+        .into());
+    }
+    Ok(actual)
+}
+
+/// Copy (or, with `options.link`, hard-link) one regular file. Returns
+/// whether the copy was a copy-on-write clone.
+fn copy_file_unverified(src: &Path, dst: &Path, options: &CpOptions) -> Result<bool> {
+    let pair = format!("{} to {}", src.display(), dst.display());
+    if options.link {
+        fs::hard_link(src, dst).map_err(|e| -> crate::error::FileIoMcpError {
+            // Why: EXDEV would otherwise surface as a generic write error;
+            // name the cause so the caller knows to retry without `link`.
+            if e.kind() == std::io::ErrorKind::CrossesDevices {
+                FileIoError::WriteError(format!(
+                    "Cannot hard-link {}: source and destination are on different filesystems (copy without link instead)",
+                    pair
+                ))
+                .into()
+            } else {
+                FileIoError::from_io_error("hard-link file", &pair, e).into()
+            }
+        })?;
+        return Ok(false);
+    }
+    match options.reflink {
+        ReflinkMode::Never => {}
+        ReflinkMode::Auto => {
+            if reflink_file(src, dst).is_ok() {
+                return Ok(true);
+            }
+        }
+        ReflinkMode::Always => {
+            reflink_file(src, dst).map_err(|e| {
                 FileIoError::WriteError(format!(
-                    "Failed to copy file {} to {}: {}",
-                    path.display(),
-                    dest_path.display(),
-                    e
+                    "Cannot reflink {} (the filesystem may not support copy-on-write clones): {}",
+                    pair, e
                 ))
             })?;
+            return Ok(true);
         }
     }
+    fs::copy(src, dst).map_err(|e| FileIoError::from_io_error("copy file", &pair, e))?;
+    Ok(false)
+}
 
-    Ok(())
+/// Clone `src` to `dst`, replacing `dst` if it exists.
+///
+/// Why: the clone primitive refuses to overwrite, while `cp` semantics do.
+/// Cloning to a sibling temp name and renaming over `dst` keeps the
+/// overwrite atomic and leaves `dst` untouched when cloning is unsupported.
+fn reflink_file(src: &Path, dst: &Path) -> std::io::Result<()> {
+    if fs::symlink_metadata(dst).is_err() {
+        return reflink_copy::reflink(src, dst);
+    }
+    let name = dst
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let tmp = dst.with_file_name(format!(".{}.reflink.{}", name, std::process::id()));
+    reflink_copy::reflink(src, &tmp)?;
+    fs::rename(&tmp, dst).inspect_err(|_| {
+        let _ = fs::remove_file(&tmp);
+    })
 }
 
 #[cfg(test)]
@@ -338,4 +741,263 @@ mod tests {
         assert!(dst_dir.join("file2.txt").exists());
         assert!(!dst_dir.join("other.log").exists());
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_cp_link_shares_inode() {
+        use std::os::unix::fs::MetadataExt;
+
+        let dir = TempDir::new().unwrap();
+        let src_dir = dir.path().join("src");
+        let dst_dir = dir.path().join("dst");
+        fs::create_dir_all(src_dir.join("nested")).unwrap();
+        fs::write(src_dir.join("top.bin"), "top").unwrap();
+        fs::write(src_dir.join("nested").join("deep.bin"), "deep").unwrap();
+
+        let results = cp_with(
+            &[src_dir.to_str().unwrap()],
+            dst_dir.to_str().unwrap(),
+            &CpOptions {
+                recursive: true,
+                link: true,
+                ..CpOptions::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(results[0].status, "ok");
+
+        for rel in ["top.bin", "nested/deep.bin"] {
+            let src_meta = fs::metadata(src_dir.join(rel)).unwrap();
+            let dst_meta = fs::metadata(dst_dir.join(rel)).unwrap();
+            assert_eq!(src_meta.ino(), dst_meta.ino(), "{rel} must be a hard link");
+            assert_eq!(src_meta.dev(), dst_meta.dev());
+        }
+        assert!(fs::metadata(dst_dir.join("nested")).unwrap().is_dir());
+    }
+
+    #[test]
+    fn test_cp_reflink_auto_falls_back() {
+        let dir = TempDir::new().unwrap();
+        let src = dir.path().join("big.dat");
+        let dst = dir.path().join("clone.dat");
+        fs::write(&src, "payload").unwrap();
+        fs::write(&dst, "stale").unwrap();
+
+        let results = cp_with(
+            &[src.to_str().unwrap()],
+            dst.to_str().unwrap(),
+            &CpOptions {
+                reflink: ReflinkMode::Auto,
+                ..CpOptions::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(results[0].status, "ok");
+        // Whether a clone happened depends on the filesystem; either way the
+        // result reports it and the content matches.
+        assert!(results[0].reflinked.is_some());
+        assert_eq!(fs::read_to_string(&dst).unwrap(), "payload");
+    }
+
+    #[test]
+    fn test_cp_reflink_always_on_supporting_fs() {
+        let dir = TempDir::new().unwrap();
+        let src = dir.path().join("big.dat");
+        let dst = dir.path().join("clone.dat");
+        fs::write(&src, "payload").unwrap();
+
+        let results = cp_with(
+            &[src.to_str().unwrap()],
+            dst.to_str().unwrap(),
+            &CpOptions {
+                reflink: ReflinkMode::Always,
+                ..CpOptions::default()
+            },
+        )
+        .unwrap();
+        if results[0].status != "ok" {
+            eprintln!(
+                "skipping: temp filesystem does not support reflinks ({})",
+                results[0].status
+            );
+            return;
+        }
+        assert_eq!(results[0].reflinked, Some(true));
+        assert_eq!(fs::read_to_string(&dst).unwrap(), "payload");
+    }
+
+    #[test]
+    fn test_cp_link_and_reflink_conflict() {
+        let options = CpOptions {
+            link: true,
+            reflink: ReflinkMode::Auto,
+            ..CpOptions::default()
+        };
+        assert!(cp_with(&["a"], "b", &options).is_err());
+    }
+
+    #[test]
+    fn test_cp_dry_run_reports_fan_in_conflict() {
+        let dir = TempDir::new().unwrap();
+        let base = dir.path();
+        fs::create_dir_all(base.join("a")).unwrap();
+        fs::create_dir_all(base.join("b")).unwrap();
+        fs::create_dir_all(base.join("dest")).unwrap();
+        fs::write(base.join("a").join("x.txt"), "from a").unwrap();
+        fs::write(base.join("b").join("x.txt"), "from b").unwrap();
+        fs::write(base.join("a").join("y.txt"), "new y").unwrap();
+        fs::write(base.join("dest").join("y.txt"), "old y").unwrap();
+
+        let glob = format!("{}/*.txt", base.join("a").display());
+        let b_x = base.join("b").join("x.txt");
+        let report = cp_reporting_conflicts(
+            &[glob.as_str(), b_x.to_str().unwrap()],
+            base.join("dest").to_str().unwrap(),
+            &CpOptions {
+                dry_run: true,
+                ..CpOptions::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(report.results.len(), 3);
+        assert!(report.results.iter().all(|r| r.status == "would_copy"));
+        assert_eq!(report.conflicts.len(), 2);
+
+        let x = report
+            .conflicts
+            .iter()
+            .find(|c| c.destination.ends_with("x.txt"))
+            .expect("two sources map to dest/x.txt");
+        assert!(!x.exists);
+        assert_eq!(x.sources.len(), 2);
+        assert!(x.sources.contains(&b_x.to_str().unwrap().to_string()));
+
+        let y = report
+            .conflicts
+            .iter()
+            .find(|c| c.destination.ends_with("y.txt"))
+            .expect("dest/y.txt already exists");
+        assert!(y.exists);
+        assert_eq!(y.sources.len(), 1);
+
+        // Nothing was written.
+        assert!(!base.join("dest").join("x.txt").exists());
+        assert_eq!(
+            fs::read_to_string(base.join("dest").join("y.txt")).unwrap(),
+            "old y"
+        );
+    }
+
+    fn two_level_tree(base: &Path) -> PathBuf {
+        let src = base.join("photos");
+        fs::create_dir_all(src.join("2023").join("summer")).unwrap();
+        fs::create_dir_all(src.join("2024")).unwrap();
+        fs::write(src.join("cover.jpg"), "cover").unwrap();
+        fs::write(src.join("2023").join("beach.jpg"), "beach 2023").unwrap();
+        fs::write(src.join("2023").join("summer").join("sun.jpg"), "sun").unwrap();
+        fs::write(src.join("2024").join("beach.jpg"), "beach 2024").unwrap();
+        src
+    }
+
+    #[test]
+    fn test_cp_flatten_two_level_tree() {
+        let dir = TempDir::new().unwrap();
+        let src = two_level_tree(dir.path());
+        let dst = dir.path().join("flat");
+        let options = CpOptions {
+            recursive: true,
+            ..CpOptions::default()
+        };
+
+        let report = cp_flatten(
+            &[src.to_str().unwrap()],
+            dst.to_str().unwrap(),
+            &options,
+            FlattenCollisionPolicy::Suffix,
+        )
+        .expect("flatten copy succeeds");
+        assert_eq!(report.results.len(), 1);
+        assert_eq!(report.results[0].status, "ok");
+        assert_eq!(report.collisions.len(), 1);
+        assert_eq!(report.collisions[0].resolution, "renamed");
+
+        let mut names: Vec<String> = fs::read_dir(&dst)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        assert_eq!(names, ["beach-1.jpg", "beach.jpg", "cover.jpg", "sun.jpg"]);
+        assert!(
+            fs::read_dir(&dst)
+                .unwrap()
+                .all(|e| e.unwrap().path().is_file())
+        );
+
+        let mut beaches = vec![
+            fs::read_to_string(dst.join("beach.jpg")).unwrap(),
+            fs::read_to_string(dst.join("beach-1.jpg")).unwrap(),
+        ];
+        beaches.sort();
+        assert_eq!(beaches, ["beach 2023", "beach 2024"]);
+    }
+
+    #[test]
+    fn test_cp_flatten_error_and_skip_policies() {
+        let dir = TempDir::new().unwrap();
+        let src = two_level_tree(dir.path());
+        let dst = dir.path().join("flat");
+        let options = CpOptions {
+            recursive: true,
+            ..CpOptions::default()
+        };
+        let s = src.to_str().unwrap();
+        let d = dst.to_str().unwrap();
+
+        assert!(cp_flatten(&[s], d, &options, FlattenCollisionPolicy::Error).is_err());
+        assert!(!dst.exists(), "error policy copies nothing");
+
+        let report = cp_flatten(&[s], d, &options, FlattenCollisionPolicy::Skip).unwrap();
+        assert_eq!(report.collisions[0].resolution, "skipped");
+        assert!(report.collisions[0].destination.is_none());
+        assert_eq!(fs::read_dir(&dst).unwrap().count(), 3);
+    }
+
+    #[test]
+    fn test_cp_verify_reports_matching_digest() {
+        let dir = TempDir::new().unwrap();
+        let src = dir.path().join("data.bin");
+        let dst = dir.path().join("copy.bin");
+        fs::write(&src, "abc").unwrap();
+
+        let results = cp_with(
+            &[src.to_str().unwrap()],
+            dst.to_str().unwrap(),
+            &CpOptions {
+                verify: true,
+                ..CpOptions::default()
+            },
+        )
+        .expect("verified copy succeeds");
+        assert_eq!(results[0].status, "ok");
+        assert_eq!(
+            results[0].sha256.as_deref(),
+            Some("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+        assert_eq!(fs::read_to_string(&dst).unwrap(), "abc");
+
+        // Without verify no digest is computed.
+        let results = cp(&[src.to_str().unwrap()], dst.to_str().unwrap(), false).unwrap();
+        assert_eq!(results[0].sha256, None);
+    }
+
+    #[test]
+    fn test_verify_copy_detects_mismatch() {
+        let dir = TempDir::new().unwrap();
+        let src = dir.path().join("a");
+        let dst = dir.path().join("b");
+        fs::write(&src, "original").unwrap();
+        fs::write(&dst, "corrupted").unwrap();
+        assert!(verify_copy(&src, &dst).is_err());
+    }
 }
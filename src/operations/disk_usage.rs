@@ -0,0 +1,258 @@
+#![deny(warnings)]
+
+// Summarize the space a directory tree uses, like `du`
+
+use crate::error::{FileIoError, Result};
+use std::collections::HashSet;
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
+
+/// Options for [`disk_usage`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DiskUsageOptions<'a> {
+    /// Report directories at most this many levels below the root (the root
+    /// is depth 0). Deeper directories still count towards their ancestors.
+    /// `None` reports every directory.
+    pub max_depth: Option<usize>,
+    /// Glob matched against each entry's name; matching files and whole
+    /// matching directories are left out of every total.
+    pub exclude_glob: Option<&'a str>,
+}
+
+/// Totals for one directory subtree.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct DirUsage {
+    pub path: String,
+    /// Apparent size of the regular files beneath it, in bytes.
+    pub bytes: u64,
+    pub files: u64,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct DiskUsageResult {
+    pub path: String,
+    pub total_bytes: u64,
+    pub total_files: u64,
+    /// Per-directory totals down to `max_depth`, sorted by path; the root
+    /// itself is included.
+    pub directories: Vec<DirUsage>,
+    /// Directories that could not be listed; their contents are not counted.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub unreadable: Vec<String>,
+}
+
+/// Sum the sizes of the regular files under `path`, reporting per-directory
+/// totals down to `options.max_depth`.
+///
+/// Symlinks are not followed and count as zero, and a file with several hard
+/// links inside the tree is counted once, as `du` does. Entries for which
+/// `denied` is true are skipped like excluded ones: they add to no total and
+/// are never listed.
+pub fn disk_usage(
+    path: &str,
+    options: &DiskUsageOptions<'_>,
+    denied: &dyn Fn(&Path) -> bool,
+) -> Result<DiskUsageResult> {
+    let exclude = options
+        .exclude_glob
+        .map(|glob| {
+            globset::GlobBuilder::new(glob)
+                .build()
+                .map(|g| g.compile_matcher())
+                .map_err(|e| {
+                    FileIoError::InvalidPath(format!("Invalid exclude_glob pattern: {}", e))
+                })
+        })
+        .transpose()?;
+    let expanded_path = super::path_utils::resolve_path(path)?;
+    let root = Path::new(&expanded_path);
+    let meta = root
+        .symlink_metadata()
+        .map_err(|e| FileIoError::from_io_error("read metadata for", &expanded_path, e))?;
+
+    let mut walk = Walk {
+        max_depth: options.max_depth,
+        exclude,
+        denied,
+        seen_inodes: HashSet::new(),
+        directories: Vec::new(),
+        unreadable: Vec::new(),
+    };
+    let (total_bytes, total_files) = if meta.is_dir() {
+        std::fs::read_dir(root)
+            .map_err(|e| FileIoError::from_io_error("read directory", &expanded_path, e))?;
+        walk.dir(root, 0)
+    } else {
+        walk.file(&meta)
+    };
+    walk.directories.sort_by(|a, b| a.path.cmp(&b.path));
+
+    Ok(DiskUsageResult {
+        path: expanded_path,
+        total_bytes,
+        total_files,
+        directories: walk.directories,
+        unreadable: walk.unreadable,
+    })
+}
+
+struct Walk<'a> {
+    max_depth: Option<usize>,
+    exclude: Option<globset::GlobMatcher>,
+    denied: &'a dyn Fn(&Path) -> bool,
+    /// (device, inode) of multiply-linked files already counted.
+    seen_inodes: HashSet<(u64, u64)>,
+    directories: Vec<DirUsage>,
+    unreadable: Vec<String>,
+}
+
+impl Walk<'_> {
+    /// Totals for the subtree at `dir`, recording it when within `max_depth`.
+    fn dir(&mut self, dir: &Path, depth: usize) -> (u64, u64) {
+        let (mut bytes, mut files) = (0, 0);
+        match std::fs::read_dir(dir) {
+            Ok(entries) => {
+                for entry in entries.flatten() {
+                    if let Some(exclude) = &self.exclude
+                        && exclude.is_match(entry.file_name())
+                    {
+                        continue;
+                    }
+                    if (self.denied)(&entry.path()) {
+                        continue;
+                    }
+                    let Ok(meta) = entry.path().symlink_metadata() else {
+                        continue;
+                    };
+                    let (b, f) = if meta.is_dir() {
+                        self.dir(&entry.path(), depth + 1)
+                    } else {
+                        self.file(&meta)
+                    };
+                    bytes += b;
+                    files += f;
+                }
+            }
+            Err(_) => self.unreadable.push(dir.to_string_lossy().into_owned()),
+        }
+        if self.max_depth.is_none_or(|max| depth <= max) {
+            self.directories.push(DirUsage {
+                path: dir.to_string_lossy().into_owned(),
+                bytes,
+                files,
+            });
+        }
+        (bytes, files)
+    }
+
+    /// Size and count contributed by a non-directory entry.
+    fn file(&mut self, meta: &std::fs::Metadata) -> (u64, u64) {
+        if !meta.is_file() {
+            return (0, 0);
+        }
+        if meta.nlink() > 1 && !self.seen_inodes.insert((meta.dev(), meta.ino())) {
+            return (0, 0);
+        }
+        (meta.len(), 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn tree() -> TempDir {
+        let dir = TempDir::new().expect("create temp dir");
+        let root = dir.path();
+        fs::create_dir_all(root.join("src/deep")).expect("create src");
+        fs::create_dir_all(root.join("node_modules/pkg")).expect("create node_modules");
+        fs::write(root.join("README"), vec![0u8; 10]).expect("write README");
+        fs::write(root.join("src/lib.rs"), vec![0u8; 100]).expect("write lib.rs");
+        fs::write(root.join("src/deep/mod.rs"), vec![0u8; 5]).expect("write mod.rs");
+        fs::write(root.join("node_modules/pkg/index.js"), vec![0u8; 1000]).expect("write index.js");
+        dir
+    }
+
+    #[test]
+    fn test_max_depth_limits_reported_directories() {
+        let dir = tree();
+        let root = dir.path().to_str().expect("utf-8 path");
+        let options = DiskUsageOptions {
+            max_depth: Some(1),
+            ..DiskUsageOptions::default()
+        };
+
+        let result = disk_usage(root, &options, &|_| false).expect("disk_usage succeeds");
+        assert_eq!(result.total_bytes, 1115);
+        assert_eq!(result.total_files, 4);
+        let reported: Vec<(&str, u64)> = result
+            .directories
+            .iter()
+            .map(|d| (d.path.strip_prefix(root).expect("under root"), d.bytes))
+            .collect();
+        // src/deep is too deep to list but still counts towards src.
+        assert_eq!(
+            reported,
+            vec![("", 1115), ("/node_modules", 1000), ("/src", 105)]
+        );
+    }
+
+    #[test]
+    fn test_excluded_directory_is_omitted_from_totals() {
+        let dir = tree();
+        let root = dir.path().to_str().expect("utf-8 path");
+        let options = DiskUsageOptions {
+            max_depth: Some(0),
+            exclude_glob: Some("node_modules"),
+        };
+
+        let result = disk_usage(root, &options, &|_| false).expect("disk_usage succeeds");
+        assert_eq!(result.total_bytes, 115);
+        assert_eq!(result.total_files, 3);
+        assert_eq!(result.directories.len(), 1);
+        assert_eq!(result.directories[0].bytes, 115);
+    }
+
+    #[test]
+    fn test_denied_subtree_is_omitted_from_totals() {
+        let dir = tree();
+        let root = dir.path().to_str().expect("utf-8 path");
+        let secret = dir.path().join("node_modules");
+        let denied = |p: &Path| p.starts_with(&secret);
+
+        let result =
+            disk_usage(root, &DiskUsageOptions::default(), &denied).expect("disk_usage succeeds");
+        assert_eq!(result.total_bytes, 115);
+        assert_eq!(result.total_files, 3);
+        assert!(
+            result
+                .directories
+                .iter()
+                .all(|d| !d.path.contains("node_modules"))
+        );
+        let root_usage = result
+            .directories
+            .iter()
+            .find(|d| d.path == root)
+            .expect("root is reported");
+        assert_eq!(root_usage.bytes, 115);
+    }
+
+    #[test]
+    fn test_hard_links_counted_once() {
+        let dir = TempDir::new().expect("create temp dir");
+        fs::write(dir.path().join("a"), vec![0u8; 50]).expect("write a");
+        fs::hard_link(dir.path().join("a"), dir.path().join("b")).expect("link b");
+
+        let result = disk_usage(
+            dir.path().to_str().expect("utf-8 path"),
+            &DiskUsageOptions::default(),
+            &|_| false,
+        )
+        .expect("disk_usage succeeds");
+        assert_eq!(result.total_bytes, 50);
+        assert_eq!(result.total_files, 1);
+    }
+}
@@ -2,6 +2,7 @@
 
 // Structured, deterministic file edits (LLM-friendly)
 
+use super::write_file::{TargetEncoding, WriteOptions};
 use crate::error::{FileIoError, Result};
 use serde::{Deserialize, Serialize};
 use std::fs;
@@ -25,6 +26,33 @@ pub struct EditFileRequest {
 
     #[serde(default, deserialize_with = "crate::coerce::de_bool")]
     pub return_content: bool,
+
+    /// Report where each applied edit's text ended up; see [`EditSpan`].
+    #[serde(default, deserialize_with = "crate::coerce::de_bool")]
+    pub return_spans: bool,
+
+    #[serde(default)]
+    pub final_newline: FinalNewline,
+
+    /// Encoding label (see [`TargetEncoding::parse`]) the file is decoded
+    /// from and re-encoded to. Default: UTF-8.
+    #[serde(default)]
+    pub encoding: Option<String>,
+}
+
+/// What happens to the file's final line ending after the edits run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FinalNewline {
+    /// End with a line ending exactly when the original file did, whatever
+    /// the edits did to the last line. A newly created (empty) file takes
+    /// whatever the edits produce.
+    #[default]
+    Keep,
+    /// Make sure non-empty content ends with a line ending.
+    Ensure,
+    /// Remove the final line ending, if any.
+    Strip,
 }
 
 #[derive(Debug, Deserialize)]
@@ -123,6 +151,65 @@ pub struct EditFileResult {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub content: Option<String>,
+
+    /// One entry per applied edit, when `return_spans` was requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spans: Option<Vec<EditSpan>>,
+}
+
+/// Where an applied edit's text sits in the final content.
+///
+/// `start..end` is a UTF-8 byte range into the final content, i.e. what
+/// `content` returns (after every later edit and the final-newline rule,
+/// and before re-encoding to the file's `encoding`), covering the inserted or
+/// replacement text; for deletions it is the empty range where the text
+/// was. Text a later edit removed shrinks the span accordingly.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EditSpan {
+    /// Position of the edit in the request's `edits`, from 0.
+    pub edit: usize,
+    /// The edit's `op`, e.g. `replace` or `insert_at_line`.
+    pub op: &'static str,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl EditSpan {
+    /// Move this span to account for `start..end` having been replaced by
+    /// `len` bytes. Offsets inside the replaced range collapse onto it.
+    fn rebase(&mut self, start: usize, end: usize, len: usize) {
+        let map = |offset: usize, inside: usize| {
+            if offset >= end {
+                offset - end + start + len
+            } else if offset > start {
+                inside
+            } else {
+                offset
+            }
+        };
+        // A span starting exactly at a pure insertion point moves after it.
+        self.start = if start == end && self.start == start {
+            start + len
+        } else {
+            map(self.start, start)
+        };
+        self.end = map(self.end, start + len).max(self.start);
+    }
+}
+
+impl EditOperation {
+    /// The `op` tag this edit was deserialized from.
+    fn name(&self) -> &'static str {
+        match self {
+            EditOperation::InsertAfter { .. } => "insert_after",
+            EditOperation::InsertBefore { .. } => "insert_before",
+            EditOperation::Replace { .. } => "replace",
+            EditOperation::Delete { .. } => "delete",
+            EditOperation::InsertAtLine { .. } => "insert_at_line",
+            EditOperation::ReplaceLines { .. } => "replace_lines",
+            EditOperation::DeleteLines { .. } => "delete_lines",
+        }
+    }
 }
 
 fn default_occurrence() -> u32 {
@@ -134,22 +221,26 @@ fn default_require_match() -> bool {
 }
 
 pub fn edit_file(req: EditFileRequest) -> Result<EditFileResult> {
-    let expanded_path = shellexpand::full(&req.path)
-        .map_err(|e| {
-            crate::error::FileIoMcpError::from(crate::error::FileIoError::InvalidPath(format!(
-                "Failed to expand path '{}'': {}",
-                req.path, e
-            )))
-        })
-        .map(|expanded| expanded.into_owned())?;
+    let expanded_path = super::path_utils::resolve_path(&req.path)?;
 
     let path_obj = Path::new(&expanded_path);
+    let encoding = match &req.encoding {
+        Some(label) => TargetEncoding::parse(label)?,
+        None => TargetEncoding::Utf8,
+    };
 
-    // Load file content (or create empty if allowed)
-    let original_content = match fs::read_to_string(&expanded_path) {
-        Ok(s) => s,
+    // Load file content (or create empty if allowed). A UTF-16 BOM is
+    // stripped for editing and written back afterwards.
+    let (original_content, had_bom) = match fs::read(&expanded_path) {
+        Ok(bytes) => encoding.decode(&bytes).ok_or_else(|| {
+            FileIoError::ReadError(format!(
+                "{} is not valid {}; pass the file's actual encoding",
+                expanded_path,
+                encoding.name()
+            ))
+        })?,
         Err(e) if e.kind() == std::io::ErrorKind::NotFound && req.create_if_missing => {
-            String::new()
+            (String::new(), false)
         }
         Err(e) => {
             return Err(crate::error::FileIoMcpError::from(
@@ -164,9 +255,13 @@ pub fn edit_file(req: EditFileRequest) -> Result<EditFileResult> {
 
     let mut content = original_content.clone();
     let mut applied = 0usize;
+    let mut spans: Vec<EditSpan> = Vec::new();
 
-    for edit in req.edits {
+    for (index, edit) in req.edits.into_iter().enumerate() {
         let before = content.clone();
+        let op = edit.name();
+        // The byte range this edit replaced and the length of its new text.
+        let replaced: (usize, usize, usize);
 
         match edit {
             EditOperation::InsertAfter {
@@ -188,6 +283,7 @@ pub fn edit_file(req: EditFileRequest) -> Result<EditFileResult> {
                     continue;
                 };
                 content.insert_str(end, &text);
+                replaced = (end, end, text.len());
             }
             EditOperation::InsertBefore {
                 search,
@@ -208,6 +304,7 @@ pub fn edit_file(req: EditFileRequest) -> Result<EditFileResult> {
                     continue;
                 };
                 content.insert_str(start, &text);
+                replaced = (start, start, text.len());
             }
             EditOperation::Replace {
                 search,
@@ -228,6 +325,7 @@ pub fn edit_file(req: EditFileRequest) -> Result<EditFileResult> {
                     continue;
                 };
                 content.replace_range(start..end, &text);
+                replaced = (start, end, text.len());
             }
             EditOperation::Delete {
                 search,
@@ -247,11 +345,24 @@ pub fn edit_file(req: EditFileRequest) -> Result<EditFileResult> {
                     continue;
                 };
                 content.replace_range(start..end, "");
+                replaced = (start, end, 0);
             }
             EditOperation::InsertAtLine { line, text } => {
                 let line_usize = u64_to_usize(line, "line")?;
                 let insert_at = line_start_offset(&content, line_usize, true)?;
+                let mut text = text;
+                if insert_at == content.len() {
+                    // Appending after an unterminated last line: end that
+                    // line first so the two don't merge.
+                    if !content.is_empty() && !content.ends_with('\n') {
+                        text.insert_str(0, line_ending_of(&content));
+                    }
+                } else if !text.ends_with('\n') {
+                    // Inserting before an existing line: keep it on its own line.
+                    text.push_str(line_ending_of(&content));
+                }
                 content.insert_str(insert_at, &text);
+                replaced = (insert_at, insert_at, text.len());
             }
             EditOperation::ReplaceLines {
                 start_line,
@@ -268,6 +379,7 @@ pub fn edit_file(req: EditFileRequest) -> Result<EditFileResult> {
                     replacement.push('\n');
                 }
                 content.replace_range(start_off..end_off, &replacement);
+                replaced = (start_off, end_off, replacement.len());
             }
             EditOperation::DeleteLines {
                 start_line,
@@ -277,19 +389,45 @@ pub fn edit_file(req: EditFileRequest) -> Result<EditFileResult> {
                 let end = u64_to_usize(end_line, "end_line")?;
                 let (start_off, end_off) = line_range_offsets(&content, start, end, true)?;
                 content.replace_range(start_off..end_off, "");
+                replaced = (start_off, end_off, 0);
             }
         }
 
         if content != before {
             applied += 1;
+            let (start, end, len) = replaced;
+            for span in &mut spans {
+                span.rebase(start, end, len);
+            }
+            spans.push(EditSpan {
+                edit: index,
+                op,
+                start,
+                end: start + len,
+            });
         }
     }
 
+    apply_final_newline(&mut content, &original_content, req.final_newline);
+    // Stripping the final newline can only shorten the end of the content.
+    for span in &mut spans {
+        span.end = span.end.min(content.len());
+        span.start = span.start.min(span.end);
+    }
+
     let changed = content != original_content;
 
     if changed && !req.dry_run {
         // Reuse existing atomic writer (it also creates parent dirs if needed)
-        crate::operations::write_file::write_file(&expanded_path, &content, false)?;
+        crate::operations::write_file::write_file_with(
+            &expanded_path,
+            &content,
+            &WriteOptions {
+                encoding,
+                bom: had_bom,
+                ..WriteOptions::default()
+            },
+        )?;
     }
 
     Ok(EditFileResult {
@@ -302,9 +440,50 @@ pub fn edit_file(req: EditFileRequest) -> Result<EditFileResult> {
         } else {
             None
         },
+        spans: req.return_spans.then_some(spans),
     })
 }
 
+/// The line ending `content` uses: CRLF if it has any, otherwise LF.
+fn line_ending_of(content: &str) -> &'static str {
+    if content.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    }
+}
+
+/// Enforce `mode` on the edited `content`.
+///
+/// Why after all edits rather than per op: individual ops only see their own
+/// slice of the file, so any one of them touching the last line could add or
+/// drop the final newline; settling it once at the end turns that into
+/// a single, explicit rule and avoids one-byte diffs.
+fn apply_final_newline(content: &mut String, original: &str, mode: FinalNewline) {
+    let want = match mode {
+        FinalNewline::Keep if original.is_empty() => return,
+        FinalNewline::Keep => original.ends_with('\n'),
+        FinalNewline::Ensure => true,
+        FinalNewline::Strip => false,
+    };
+    if content.is_empty() {
+        return;
+    }
+    if want && !content.ends_with('\n') {
+        let eol = line_ending_of(if original.is_empty() {
+            content
+        } else {
+            original
+        });
+        content.push_str(eol);
+    } else if !want && content.ends_with('\n') {
+        content.pop();
+        if content.ends_with('\r') {
+            content.pop();
+        }
+    }
+}
+
 fn u64_to_usize(v: u64, field: &str) -> Result<usize> {
     usize::try_from(v).map_err(|_| {
         FileIoError::InvalidLineNumbers(format!("{} is too large: {}", field, v)).into()
@@ -495,6 +674,9 @@ mod tests {
             create_if_missing: false,
             dry_run: false,
             return_content: true,
+            return_spans: false,
+            final_newline: FinalNewline::Keep,
+            encoding: None,
         })
         .unwrap();
 
@@ -518,9 +700,204 @@ mod tests {
             create_if_missing: false,
             dry_run: false,
             return_content: true,
+            return_spans: false,
+            final_newline: FinalNewline::Keep,
+            encoding: None,
         })
         .unwrap();
 
         assert_eq!(res.content.unwrap(), "a\nB\nc\n");
     }
+
+    fn edit_content(content: &str, edit: EditOperation, final_newline: FinalNewline) -> String {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("f.txt");
+        fs::write(&path, content).unwrap();
+        edit_file(EditFileRequest {
+            path: path.to_string_lossy().to_string(),
+            edits: vec![edit],
+            create_if_missing: false,
+            dry_run: true,
+            return_content: true,
+            return_spans: false,
+            final_newline,
+            encoding: None,
+        })
+        .unwrap()
+        .content
+        .expect("dry run returns content")
+    }
+
+    fn replace_last(text: &str) -> EditOperation {
+        EditOperation::ReplaceLines {
+            start_line: 3,
+            end_line: 3,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn editing_last_line_keeps_trailing_newline_state() {
+        // With a trailing newline.
+        assert_eq!(
+            edit_content("a\nb\nc\n", replace_last("C"), FinalNewline::Keep),
+            "a\nb\nC\n"
+        );
+        // Without one: a replacement ending in "\n" must not add it.
+        assert_eq!(
+            edit_content("a\nb\nc", replace_last("C\n"), FinalNewline::Keep),
+            "a\nb\nC"
+        );
+        assert_eq!(
+            edit_content(
+                "a\nb\nc",
+                EditOperation::Replace {
+                    search: "c".to_string(),
+                    text: "C\n".to_string(),
+                    use_regex: false,
+                    occurrence: 1,
+                    require_match: true,
+                },
+                FinalNewline::Keep
+            ),
+            "a\nb\nC"
+        );
+    }
+
+    #[test]
+    fn insert_at_line_does_not_merge_lines() {
+        let append = || EditOperation::InsertAtLine {
+            line: 4,
+            text: "d".to_string(),
+        };
+        assert_eq!(
+            edit_content("a\nb\nc", append(), FinalNewline::Keep),
+            "a\nb\nc\nd"
+        );
+        assert_eq!(
+            edit_content("a\nb\nc\n", append(), FinalNewline::Keep),
+            "a\nb\nc\nd\n"
+        );
+        assert_eq!(
+            edit_content(
+                "a\r\nb\r\n",
+                EditOperation::InsertAtLine {
+                    line: 2,
+                    text: "x".to_string(),
+                },
+                FinalNewline::Keep
+            ),
+            "a\r\nx\r\nb\r\n"
+        );
+    }
+
+    #[test]
+    fn final_newline_ensure_and_strip() {
+        assert_eq!(
+            edit_content("a\nb\nc", replace_last("C"), FinalNewline::Ensure),
+            "a\nb\nC\n"
+        );
+        assert_eq!(
+            edit_content("a\nb\nc\n", replace_last("C"), FinalNewline::Strip),
+            "a\nb\nC"
+        );
+    }
+
+    #[test]
+    fn return_spans_tracks_later_edits() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("a.txt");
+        fs::write(&path, "alpha beta gamma\n").unwrap();
+        let replace = |search: &str, text: &str| EditOperation::Replace {
+            search: search.to_string(),
+            text: text.to_string(),
+            use_regex: false,
+            occurrence: 1,
+            require_match: true,
+        };
+
+        let res = edit_file(EditFileRequest {
+            path: path.to_string_lossy().to_string(),
+            edits: vec![replace("gamma", "GAMMA!"), replace("alpha", "a")],
+            create_if_missing: false,
+            dry_run: true,
+            return_content: false,
+            return_spans: true,
+            final_newline: FinalNewline::Keep,
+            encoding: None,
+        })
+        .unwrap();
+
+        let content = res.content.unwrap();
+        assert_eq!(content, "a beta GAMMA!\n");
+        let spans = res.spans.unwrap();
+        assert_eq!(spans.len(), 2);
+        // The first replacement moved left when the second shortened "alpha".
+        assert_eq!((spans[0].edit, spans[0].op), (0, "replace"));
+        assert_eq!(&content[spans[0].start..spans[0].end], "GAMMA!");
+        assert_eq!(&content[spans[1].start..spans[1].end], "a");
+        assert_eq!(fs::read_to_string(&path).unwrap(), "alpha beta gamma\n");
+    }
+
+    #[test]
+    fn edits_utf16le_file_and_keeps_bom() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("settings.ini");
+        let utf16 = |text: &str| -> Vec<u8> {
+            let mut bytes = vec![0xFF, 0xFE];
+            bytes.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+            bytes
+        };
+        fs::write(&path, utf16("name=café\r\nsize=1\r\n")).unwrap();
+
+        let res = edit_file(EditFileRequest {
+            path: path.to_string_lossy().to_string(),
+            edits: vec![EditOperation::Replace {
+                search: "size=1".to_string(),
+                text: "size=2".to_string(),
+                use_regex: false,
+                occurrence: 1,
+                require_match: true,
+            }],
+            create_if_missing: false,
+            dry_run: false,
+            return_content: true,
+            return_spans: false,
+            final_newline: FinalNewline::Keep,
+            encoding: Some("utf-16le".to_string()),
+        })
+        .unwrap();
+
+        assert_eq!(res.content.as_deref(), Some("name=café\r\nsize=2\r\n"));
+        assert_eq!(fs::read(&path).unwrap(), utf16("name=café\r\nsize=2\r\n"));
+    }
+
+    #[test]
+    fn undecodable_file_is_an_error() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("latin1.txt");
+        fs::write(&path, b"caf\xe9\n").unwrap();
+        let request = |encoding: Option<&str>| EditFileRequest {
+            path: path.to_string_lossy().to_string(),
+            edits: vec![EditOperation::Replace {
+                search: "caf".to_string(),
+                text: "bistro caf".to_string(),
+                use_regex: false,
+                occurrence: 1,
+                require_match: true,
+            }],
+            create_if_missing: false,
+            dry_run: false,
+            return_content: false,
+            return_spans: false,
+            final_newline: FinalNewline::Keep,
+            encoding: encoding.map(str::to_string),
+        };
+
+        assert!(edit_file(request(None)).is_err());
+        assert_eq!(fs::read(&path).unwrap(), b"caf\xe9\n");
+
+        edit_file(request(Some("latin1"))).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"bistro caf\xe9\n");
+    }
 }
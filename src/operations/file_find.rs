@@ -4,7 +4,69 @@
 
 use crate::error::{FileIoError, Result};
 use ignore::WalkBuilder;
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// A match from [`file_find_with_metadata`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct FoundEntry {
+    pub path: String,
+    /// `file`, `directory`, or `symlink`.
+    #[serde(rename = "type")]
+    pub entry_type: String,
+    /// Size in bytes for files; for directories only with `compute_dir_size`
+    /// (otherwise `null`); always `null` for symlinks.
+    pub size: Option<u64>,
+}
+
+/// Order for [`FindOptions::sort`]. Ties (and entries without the sort
+/// key, which go last) are broken by path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FindSort {
+    Path,
+    Smallest,
+    Largest,
+    Newest,
+    Oldest,
+}
+
+impl FindSort {
+    /// Parse the tool-level `sort` string.
+    pub fn parse(sort: &str) -> Result<Self> {
+        match sort {
+            "path" => Ok(FindSort::Path),
+            "smallest" => Ok(FindSort::Smallest),
+            "largest" => Ok(FindSort::Largest),
+            "newest" => Ok(FindSort::Newest),
+            "oldest" => Ok(FindSort::Oldest),
+            other => Err(crate::error::FileIoMcpError::InvalidParams(format!(
+                "sort must be 'path', 'smallest', 'largest', 'newest', or 'oldest', got '{}'",
+                other
+            ))),
+        }
+    }
+}
+
+/// Walk and result-shaping options for [`file_find_limited`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FindOptions<'a> {
+    /// Return at most this many matches.
+    pub limit: Option<usize>,
+    /// Order matches before `limit` is applied.
+    pub sort: Option<FindSort>,
+    /// Directory names (e.g. `target`, `node_modules`) whose subtrees are
+    /// pruned from the walk, the directories themselves included.
+    pub skip_dirs: Option<&'a [String]>,
+}
+
+/// Matches from [`file_find_limited`] with the count before truncation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FindResults {
+    pub matches: Vec<FoundEntry>,
+    /// Every match of the pattern, including any cut by `limit`.
+    pub total_matched: u64,
+}
 
 /// Find files matching a pattern.
 ///
@@ -12,12 +74,69 @@ use std::path::Path;
 /// otherwise it is a substring match.  Glob matching uses `globset` (already a
 /// dependency) so that `**`, character classes, and brace alternation all work
 /// correctly — the old hand-rolled regex chain silently mishandled those cases.
+///
+/// With `relative`, results are reported relative to the search root (the
+/// root itself as `.`) instead of as walked paths.
 pub fn file_find(
     pattern: &str,
     root: Option<&str>,
     max_depth: Option<usize>,
     file_type: Option<&str>,
+    relative: bool,
 ) -> Result<Vec<String>> {
+    Ok(
+        file_find_with_metadata(pattern, root, max_depth, file_type, relative, false)?
+            .into_iter()
+            .map(|entry| entry.path)
+            .collect(),
+    )
+}
+
+/// [`file_find`], reporting each match's type and size.
+///
+/// With `compute_dir_size`, directory matches report the recursive sum of the
+/// regular files beneath them (symlinks are not followed). Sizes are cached
+/// per directory for the whole call, so overlapping matches such as a
+/// directory and its subdirectories are only walked once.
+pub fn file_find_with_metadata(
+    pattern: &str,
+    root: Option<&str>,
+    max_depth: Option<usize>,
+    file_type: Option<&str>,
+    relative: bool,
+    compute_dir_size: bool,
+) -> Result<Vec<FoundEntry>> {
+    file_find_limited(
+        pattern,
+        root,
+        max_depth,
+        file_type,
+        relative,
+        compute_dir_size,
+        &FindOptions::default(),
+    )
+    .map(|results| results.matches)
+}
+
+/// [`file_find_with_metadata`] with a result cap and ordering.
+///
+/// Without `sort` the tree is walked in file-name order, so the first
+/// `limit` matches are the same on every call; once the cap is reached the
+/// remaining matches are only counted (no metadata or directory sizes are
+/// gathered for them). With `sort` every match has to be collected before the
+/// first `limit` of the ordering are kept.
+///
+/// Why: counting the rest keeps `total_matched` exact, so a caller can tell a
+/// complete answer from a truncated one without a second call.
+pub fn file_find_limited(
+    pattern: &str,
+    root: Option<&str>,
+    max_depth: Option<usize>,
+    file_type: Option<&str>,
+    relative: bool,
+    compute_dir_size: bool,
+    options: &FindOptions<'_>,
+) -> Result<FindResults> {
     let expanded_root = root
         .map(|r| {
             shellexpand::full(r)
@@ -35,7 +154,7 @@ pub fn file_find(
         .unwrap_or_else(|| Path::new("."));
 
     if !root_path.exists() {
-        return Err(FileIoError::NotFound(expanded_root.unwrap_or_else(|| ".".to_string())).into());
+        return Err(super::path_utils::not_found("search directory", root.unwrap_or(".")).into());
     }
 
     let mut walker = WalkBuilder::new(root_path);
@@ -44,6 +163,19 @@ pub fn file_find(
     if let Some(depth) = max_depth {
         walker.max_depth(Some(depth));
     }
+    if options.limit.is_some() || options.sort.is_some() {
+        walker.sort_by_file_name(|a, b| a.cmp(b));
+    }
+    if let Some(names) = options.skip_dirs {
+        let names = skip_dir_set(names);
+        walker.filter_entry(move |entry| !is_skipped_dir(entry, &names));
+    }
+    // Without a sort, matches past the cap are counted but not collected.
+    let collect_cap = if options.sort.is_none() {
+        options.limit
+    } else {
+        None
+    };
 
     // Build a matcher for the pattern.
     // If the pattern contains glob metacharacters use globset; otherwise fall
@@ -63,6 +195,9 @@ pub fn file_find(
     };
 
     let mut matches = Vec::new();
+    let mut modified: Vec<Option<SystemTime>> = Vec::new();
+    let mut total_matched = 0u64;
+    let mut dir_sizes: HashMap<PathBuf, u64> = HashMap::new();
 
     for result in walker.build() {
         let entry = result
@@ -89,11 +224,108 @@ pub fn file_find(
                 }
             }
 
-            matches.push(path.to_string_lossy().to_string());
+            total_matched += 1;
+            if collect_cap.is_some_and(|cap| matches.len() >= cap) {
+                continue;
+            }
+
+            let display = if relative {
+                let rel = path.strip_prefix(root_path).unwrap_or(path);
+                if rel.as_os_str().is_empty() {
+                    ".".to_string()
+                } else {
+                    rel.to_string_lossy().to_string()
+                }
+            } else {
+                path.to_string_lossy().to_string()
+            };
+
+            let ft = entry.file_type();
+            let (entry_type, size) = if ft.is_some_and(|t| t.is_symlink()) {
+                ("symlink", None)
+            } else if ft.is_some_and(|t| t.is_dir()) {
+                let size = compute_dir_size.then(|| dir_size(path, &mut dir_sizes));
+                ("directory", size)
+            } else {
+                ("file", entry.metadata().ok().map(|m| m.len()))
+            };
+
+            if options.sort.is_some() {
+                modified.push(entry.metadata().ok().and_then(|m| m.modified().ok()));
+            }
+            matches.push(FoundEntry {
+                path: display,
+                entry_type: entry_type.to_string(),
+                size,
+            });
         }
     }
 
-    Ok(matches)
+    if let Some(sort) = options.sort {
+        let mut keyed: Vec<(FoundEntry, Option<SystemTime>)> =
+            matches.into_iter().zip(modified).collect();
+        keyed.sort_by(|(a, a_time), (b, b_time)| {
+            // `Option` orders `None` first; the ascending sorts compare
+            // `is_none` first so missing keys go last.
+            let by_key = match sort {
+                FindSort::Path => std::cmp::Ordering::Equal,
+                FindSort::Smallest => (a.size.is_none(), a.size).cmp(&(b.size.is_none(), b.size)),
+                FindSort::Largest => b.size.cmp(&a.size),
+                FindSort::Newest => b_time.cmp(a_time),
+                FindSort::Oldest => (a_time.is_none(), a_time).cmp(&(b_time.is_none(), b_time)),
+            };
+            by_key.then_with(|| a.path.cmp(&b.path))
+        });
+        matches = keyed.into_iter().map(|(entry, _)| entry).collect();
+    }
+    if let Some(limit) = options.limit {
+        matches.truncate(limit);
+    }
+
+    Ok(FindResults {
+        matches,
+        total_matched,
+    })
+}
+
+/// The `skip_dirs` names as a set for [`is_skipped_dir`].
+pub(crate) fn skip_dir_set(names: &[String]) -> HashSet<String> {
+    names.iter().cloned().collect()
+}
+
+/// Whether `entry` is a directory below the walk root named in `names`.
+/// The root itself is never skipped, so searching inside e.g. `target`
+/// still works when `target` is in the list.
+pub(crate) fn is_skipped_dir(entry: &ignore::DirEntry, names: &HashSet<String>) -> bool {
+    entry.depth() > 0
+        && entry.file_type().is_some_and(|t| t.is_dir())
+        && entry
+            .file_name()
+            .to_str()
+            .is_some_and(|name| names.contains(name))
+}
+
+/// Recursive size of the regular files under `dir`, memoized in `cache`.
+/// Unreadable subtrees count as empty rather than failing the search.
+fn dir_size(dir: &Path, cache: &mut HashMap<PathBuf, u64>) -> u64 {
+    if let Some(size) = cache.get(dir) {
+        return *size;
+    }
+    let mut total = 0;
+    if let Ok(entries) = std::fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            let Ok(meta) = entry.path().symlink_metadata() else {
+                continue;
+            };
+            if meta.is_dir() {
+                total += dir_size(&entry.path(), cache);
+            } else if meta.is_file() {
+                total += meta.len();
+            }
+        }
+    }
+    cache.insert(dir.to_path_buf(), total);
+    total
 }
 
 #[cfg(test)]
@@ -112,7 +344,7 @@ mod tests {
         fs::write(dir.path().join("test2.txt"), "content").unwrap();
         fs::write(dir.path().join("other.log"), "content").unwrap();
 
-        let matches = file_find("*.txt", Some(root), None, Some("file")).unwrap();
+        let matches = file_find("*.txt", Some(root), None, Some("file"), false).unwrap();
         assert_eq!(matches.len(), 2);
     }
 
@@ -127,7 +359,7 @@ mod tests {
         fs::write(subdir.join("test.txt"), "content").unwrap();
         fs::write(dir.path().join("test.txt"), "content").unwrap();
 
-        let matches = file_find("test.txt", Some(root), Some(1), Some("file")).unwrap();
+        let matches = file_find("test.txt", Some(root), Some(1), Some("file"), false).unwrap();
         // Should only find the one at root level with max_depth=1
         assert!(!matches.is_empty());
     }
@@ -143,7 +375,7 @@ mod tests {
         fs::write(dir.path().join("lib.rs"), "").unwrap();
         fs::write(dir.path().join("main.py"), "").unwrap();
 
-        let matches = file_find("{main,lib}.rs", Some(root), None, Some("file")).unwrap();
+        let matches = file_find("{main,lib}.rs", Some(root), None, Some("file"), false).unwrap();
         assert_eq!(matches.len(), 2, "brace alternation must match both files");
         assert!(matches.iter().any(|m| m.ends_with("main.rs")));
         assert!(matches.iter().any(|m| m.ends_with("lib.rs")));
@@ -159,7 +391,7 @@ mod tests {
         fs::write(dir.path().join("file2.txt"), "").unwrap();
         fs::write(dir.path().join("fileX.txt"), "").unwrap();
 
-        let matches = file_find("file[0-9].txt", Some(root), None, Some("file")).unwrap();
+        let matches = file_find("file[0-9].txt", Some(root), None, Some("file"), false).unwrap();
         assert_eq!(
             matches.len(),
             2,
@@ -182,10 +414,132 @@ mod tests {
         // `**` in globset matches across path components when used in a full-path
         // glob, but file_find matches only the file_name portion.  Verify that
         // a plain `*.rs` still finds files at any depth (the walker descends).
-        let matches = file_find("*.rs", Some(root), None, Some("file")).unwrap();
+        let matches = file_find("*.rs", Some(root), None, Some("file"), false).unwrap();
         assert!(
             matches.len() >= 2,
             "*.rs must find files in nested dirs: {matches:?}"
         );
     }
+
+    #[test]
+    fn test_file_find_relative() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().to_str().unwrap();
+
+        let subdir = dir.path().join("sub");
+        fs::create_dir_all(&subdir).unwrap();
+        fs::write(subdir.join("nested.md"), "").unwrap();
+        fs::write(dir.path().join("top.md"), "").unwrap();
+
+        let mut matches = file_find("*.md", Some(root), None, Some("file"), true).unwrap();
+        matches.sort();
+        let expected = vec![
+            Path::new("sub")
+                .join("nested.md")
+                .to_string_lossy()
+                .to_string(),
+            "top.md".to_string(),
+        ];
+        assert_eq!(matches, expected);
+    }
+
+    #[test]
+    fn test_file_find_dir_size_sums_files() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().to_str().unwrap();
+
+        let data = dir.path().join("data");
+        fs::create_dir_all(data.join("inner")).unwrap();
+        fs::write(data.join("a.bin"), vec![0u8; 100]).unwrap();
+        fs::write(data.join("inner").join("b.bin"), vec![0u8; 23]).unwrap();
+
+        let found =
+            file_find_with_metadata("data", Some(root), None, Some("dir"), true, true).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].entry_type, "directory");
+        assert_eq!(found[0].size, Some(123));
+
+        let found =
+            file_find_with_metadata("data", Some(root), None, Some("dir"), true, false).unwrap();
+        assert_eq!(found[0].size, None);
+
+        let files =
+            file_find_with_metadata("*.bin", Some(root), None, Some("file"), true, false).unwrap();
+        assert!(files.iter().any(|f| f.size == Some(23)));
+    }
+
+    #[test]
+    fn test_file_find_limit_reports_total() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().to_str().unwrap();
+        for (name, len) in [("c.txt", 3), ("a.txt", 10), ("b.txt", 1), ("d.txt", 7)] {
+            fs::write(dir.path().join(name), "x".repeat(len)).unwrap();
+        }
+        let find = |options: FindOptions| {
+            file_find_limited(
+                "*.txt",
+                Some(root),
+                None,
+                Some("file"),
+                true,
+                false,
+                &options,
+            )
+            .unwrap()
+        };
+        let paths = |results: &FindResults| -> Vec<String> {
+            results.matches.iter().map(|m| m.path.clone()).collect()
+        };
+
+        let limited = find(FindOptions {
+            limit: Some(2),
+            ..FindOptions::default()
+        });
+        assert_eq!(limited.total_matched, 4);
+        assert_eq!(paths(&limited), ["a.txt", "b.txt"]);
+
+        let smallest = find(FindOptions {
+            limit: Some(2),
+            sort: Some(FindSort::Smallest),
+            ..FindOptions::default()
+        });
+        assert_eq!(smallest.total_matched, 4);
+        assert_eq!(paths(&smallest), ["b.txt", "c.txt"]);
+
+        let all = find(FindOptions {
+            limit: Some(10),
+            sort: Some(FindSort::Largest),
+            ..FindOptions::default()
+        });
+        assert_eq!(paths(&all), ["a.txt", "d.txt", "c.txt", "b.txt"]);
+    }
+
+    #[test]
+    fn test_file_find_skip_dirs_prunes_subtrees() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().to_str().unwrap();
+        fs::create_dir_all(dir.path().join("src")).unwrap();
+        fs::create_dir_all(dir.path().join("target/debug")).unwrap();
+        fs::create_dir_all(dir.path().join("node_modules/pkg")).unwrap();
+        fs::write(dir.path().join("src/main.rs"), "").unwrap();
+        fs::write(dir.path().join("target/debug/main.rs"), "").unwrap();
+        fs::write(dir.path().join("node_modules/pkg/main.rs"), "").unwrap();
+        let skip = vec!["target".to_string(), "node_modules".to_string()];
+
+        let found = file_find_limited(
+            "main",
+            Some(root),
+            None,
+            None,
+            true,
+            false,
+            &FindOptions {
+                skip_dirs: Some(&skip),
+                ..FindOptions::default()
+            },
+        )
+        .unwrap();
+        let paths: Vec<&str> = found.matches.iter().map(|m| m.path.as_str()).collect();
+        assert_eq!(paths, ["src/main.rs"]);
+    }
 }
@@ -5,6 +5,7 @@
 use crate::error::{FileIoError, Result};
 use std::fs;
 use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
 
 /// Set file mode (permissions)
 /// Can accept a single path or multiple paths
@@ -46,28 +47,166 @@ pub fn set_file_mode_single(path: &str, mode_value: u32) -> Result<()> {
     let mut permissions = metadata.permissions();
     permissions.set_mode(mode_value);
     fs::set_permissions(&expanded_path, permissions).map_err(|e| {
-        use std::io::ErrorKind;
-        match e.kind() {
-            ErrorKind::PermissionDenied => {
-                crate::error::FileIoMcpError::from(FileIoError::PermissionDenied(format!(
-                    "Permission denied when setting permissions for {}: {}",
-                    expanded_path, e
-                )))
-            }
-            ErrorKind::NotFound => crate::error::FileIoMcpError::from(FileIoError::NotFound(
-                format!("File not found when setting permissions: {}", expanded_path),
-            )),
-            _ => crate::error::FileIoMcpError::from(FileIoError::InvalidMode(format!(
-                "Failed to set permissions for {}: {}",
-                expanded_path, e
-            ))),
-        }
+        crate::error::FileIoMcpError::from(FileIoError::from_io_error(
+            "set permissions for",
+            &expanded_path,
+            e,
+        ))
     })?;
 
     Ok(())
 }
 
-fn parse_mode(mode_str: &str) -> Result<u32> {
+/// Which entries [`set_file_mode_with`] and [`super::chown::chown_with`]
+/// touch, and whether they change anything.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TreeOptions {
+    /// Also apply to everything under each directory. Symlinks inside the
+    /// tree are neither changed nor followed.
+    pub recursive: bool,
+    /// Report the changes that would be made without making them.
+    pub dry_run: bool,
+}
+
+/// One entry whose mode differs from the requested one.
+#[derive(Debug, PartialEq, Eq, serde::Serialize)]
+pub struct ModeChange {
+    pub path: String,
+    pub old_mode: String,
+    pub new_mode: String,
+}
+
+/// [`set_file_mode`] with optional recursion and dry run, returning the
+/// per-entry plan: every entry whose mode differs from `mode`, with its
+/// current and new mode. Entries already at `mode` are left out.
+///
+/// The tree is listed before anything changes, and changes are applied
+/// deepest first, so removing search permission from a directory can't
+/// strand the entries below it.
+///
+/// Entries for which `denied` is true are never touched. Below a root they
+/// are pruned from the walk with everything under them. A denied root
+/// instead gets a placeholder entry with the "0000" mode
+/// `fileio_get_permissions` reports for denied paths, so the plan can't be
+/// used to tell which inputs were refused.
+pub fn set_file_mode_with(
+    paths: &[&str],
+    mode: &str,
+    options: &TreeOptions,
+    denied: &dyn Fn(&Path) -> bool,
+) -> Result<Vec<ModeChange>> {
+    let mode_value = parse_mode(mode)?;
+    let mut changes = Vec::new();
+    let mut errors = Vec::new();
+    for path in paths {
+        if denied(Path::new(path)) {
+            if mode_value & 0o7777 != 0 {
+                changes.push(ModeChange {
+                    // Expanded, like the paths of real entries.
+                    path: shellexpand::full(path)
+                        .map(|expanded| expanded.into_owned())
+                        .unwrap_or_else(|_| path.to_string()),
+                    old_mode: "0000".to_string(),
+                    new_mode: format!("{:04o}", mode_value & 0o7777),
+                });
+            }
+            continue;
+        }
+        match plan_mode_changes(path, mode_value, options.recursive, denied) {
+            Ok(plan) => {
+                if !options.dry_run {
+                    for (entry, _) in plan.iter().rev() {
+                        let permissions = fs::Permissions::from_mode(mode_value);
+                        if let Err(e) = fs::set_permissions(entry, permissions) {
+                            errors.push(format!("{}: {}", entry.display(), e));
+                        }
+                    }
+                }
+                changes.extend(plan.into_iter().map(|(entry, old)| ModeChange {
+                    path: entry.to_string_lossy().into_owned(),
+                    old_mode: format!("{:04o}", old),
+                    new_mode: format!("{:04o}", mode_value & 0o7777),
+                }));
+            }
+            Err(e) => errors.push(format!("{}: {}", path, e)),
+        }
+    }
+    if !errors.is_empty() {
+        return Err(crate::error::FileIoMcpError::from(FileIoError::WriteError(
+            format!("Some permission changes failed: {}", errors.join("; ")),
+        )));
+    }
+    Ok(changes)
+}
+
+/// Entries under `path` (just `path` unless `recursive`) whose permission
+/// bits differ from `mode_value`, paired with their current bits.
+fn plan_mode_changes(
+    path: &str,
+    mode_value: u32,
+    recursive: bool,
+    denied: &dyn Fn(&Path) -> bool,
+) -> Result<Vec<(PathBuf, u32)>> {
+    let expanded_path = shellexpand::full(path)
+        .map_err(|e| {
+            crate::error::FileIoMcpError::from(crate::error::FileIoError::InvalidPath(format!(
+                "Failed to expand path '{}': {}",
+                path, e
+            )))
+        })
+        .map(|expanded| expanded.into_owned())?;
+    let mut plan = Vec::new();
+    for entry in tree_entries(Path::new(&expanded_path), recursive, denied)? {
+        let metadata = fs::metadata(&entry).map_err(|e| {
+            FileIoError::from_io_error("read metadata", &entry.to_string_lossy(), e)
+        })?;
+        let old = metadata.permissions().mode() & 0o7777;
+        if old != mode_value & 0o7777 {
+            plan.push((entry, old));
+        }
+    }
+    Ok(plan)
+}
+
+/// `root` followed, when `recursive` and `root` is a directory, by every
+/// entry beneath it in depth-first name order. Symlinks below the root are
+/// skipped entirely, as `chmod -R` does, so the walk never leaves the tree.
+/// So are entries for which `prune` is true, along with everything under them.
+pub(crate) fn tree_entries(
+    root: &Path,
+    recursive: bool,
+    prune: &dyn Fn(&Path) -> bool,
+) -> Result<Vec<PathBuf>> {
+    let root_meta = fs::metadata(root)
+        .map_err(|e| FileIoError::from_io_error("read metadata", &root.to_string_lossy(), e))?;
+    let mut entries = Vec::new();
+    let mut stack = vec![(root.to_path_buf(), recursive && root_meta.is_dir())];
+    while let Some((path, descend)) = stack.pop() {
+        if descend {
+            let mut children = Vec::new();
+            for child in fs::read_dir(&path).map_err(|e| {
+                FileIoError::from_io_error("read directory", &path.to_string_lossy(), e)
+            })? {
+                let child = child.map_err(|e| {
+                    FileIoError::from_io_error("read directory", &path.to_string_lossy(), e)
+                })?;
+                let file_type = child.file_type().map_err(|e| {
+                    FileIoError::from_io_error("read metadata", &child.path().to_string_lossy(), e)
+                })?;
+                if !file_type.is_symlink() && !prune(&child.path()) {
+                    children.push((child.path(), file_type.is_dir()));
+                }
+            }
+            children.sort();
+            // Reversed onto the stack so the first child is visited first.
+            stack.extend(children.into_iter().rev());
+        }
+        entries.push(path);
+    }
+    Ok(entries)
+}
+
+pub(crate) fn parse_mode(mode_str: &str) -> Result<u32> {
     // Try octal first (e.g., "755", "0644")
     if let Ok(mode) = u32::from_str_radix(mode_str.trim_start_matches('0'), 8) {
         return Ok(mode);
@@ -123,4 +262,108 @@ mod tests {
         let mode = permissions.mode();
         assert_eq!(mode & 0o777, 0o755);
     }
+
+    #[test]
+    fn test_recursive_dry_run_leaves_modes_unchanged() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let root = dir.path();
+        fs::create_dir(root.join("sub")).unwrap();
+        fs::write(root.join("a.txt"), "a").unwrap();
+        fs::write(root.join("sub/b.txt"), "b").unwrap();
+        for entry in ["a.txt", "sub/b.txt"] {
+            fs::set_permissions(root.join(entry), fs::Permissions::from_mode(0o644)).unwrap();
+        }
+        for entry in ["", "sub"] {
+            fs::set_permissions(root.join(entry), fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        let root_str = root.to_str().unwrap();
+
+        let options = TreeOptions {
+            recursive: true,
+            dry_run: true,
+        };
+        let plan = set_file_mode_with(&[root_str], "700", &options, &|_| false).unwrap();
+        let planned: Vec<(String, &str, &str)> = plan
+            .iter()
+            .map(|c| {
+                let rel = Path::new(&c.path).strip_prefix(root).unwrap();
+                (
+                    rel.to_string_lossy().into_owned(),
+                    c.old_mode.as_str(),
+                    c.new_mode.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            planned,
+            vec![
+                ("".to_string(), "0755", "0700"),
+                ("a.txt".to_string(), "0644", "0700"),
+                ("sub".to_string(), "0755", "0700"),
+                ("sub/b.txt".to_string(), "0644", "0700"),
+            ]
+        );
+        let mode =
+            |entry: &str| fs::metadata(root.join(entry)).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode("sub/b.txt"), 0o644);
+        assert_eq!(mode("sub"), 0o755);
+
+        // The real run applies exactly the plan.
+        let applied = set_file_mode_with(
+            &[root_str],
+            "700",
+            &TreeOptions {
+                dry_run: false,
+                ..options
+            },
+            &|_| false,
+        )
+        .unwrap();
+        assert_eq!(applied, plan);
+        assert_eq!(mode("sub/b.txt"), 0o700);
+        assert_eq!(mode(""), 0o700);
+    }
+
+    #[test]
+    fn test_recursive_prunes_denied_entries() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let root = dir.path();
+        fs::create_dir(root.join("secret")).unwrap();
+        fs::write(root.join("secret/key"), "k").unwrap();
+        fs::write(root.join("a.txt"), "a").unwrap();
+        for entry in ["a.txt", "secret/key"] {
+            fs::set_permissions(root.join(entry), fs::Permissions::from_mode(0o644)).unwrap();
+        }
+        let secret = root.join("secret");
+        let denied = |p: &Path| p.starts_with(&secret);
+        let options = TreeOptions {
+            recursive: true,
+            dry_run: false,
+        };
+
+        let changes =
+            set_file_mode_with(&[root.to_str().unwrap()], "600", &options, &denied).unwrap();
+        assert!(
+            changes
+                .iter()
+                .all(|c| !Path::new(&c.path).starts_with(&secret))
+        );
+        let mode =
+            |entry: &str| fs::metadata(root.join(entry)).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode("a.txt"), 0o600);
+        assert_eq!(mode("secret/key"), 0o644);
+
+        // A denied root is left alone but still gets an entry.
+        let secret_str = secret.to_str().unwrap();
+        let changes = set_file_mode_with(&[secret_str], "600", &options, &denied).unwrap();
+        assert_eq!(
+            changes,
+            vec![ModeChange {
+                path: secret_str.to_string(),
+                old_mode: "0000".to_string(),
+                new_mode: "0600".to_string(),
+            }]
+        );
+        assert_eq!(mode("secret/key"), 0o644);
+    }
 }
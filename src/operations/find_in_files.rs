@@ -10,11 +10,36 @@ use std::path::Path;
 pub struct Match {
     pub file_path: String,
     pub line_number: u64,
+    /// Line on which the match ends. Differs from `line_number` only for
+    /// `multiline` matches that span a newline; `column_end` is relative to
+    /// this line.
+    pub end_line_number: u64,
     pub column_start: usize,
     pub column_end: usize,
+    /// `column_start` as a character (Unicode scalar) index, set when
+    /// `char_columns` is requested. The byte columns are always kept.
+    pub char_column_start: Option<usize>,
+    /// `column_end` as a character index, set when `char_columns` is requested.
+    pub char_column_end: Option<usize>,
     pub matched_text: String,
+    /// The whole line the match is on (every line it spans, for multiline
+    /// matches), without the line terminator. Set when `line_text` is requested.
+    pub line_text: Option<String>,
     pub context_before: Option<String>,
     pub context_after: Option<String>,
+    /// Structured form of `context_before`, set instead of it when
+    /// `structured_context` is requested.
+    pub context_before_lines: Option<Vec<ContextLine>>,
+    /// Structured form of `context_after`.
+    pub context_after_lines: Option<Vec<ContextLine>>,
+}
+
+/// One line of context around a match, numbered so editors can render a
+/// gutter and jump to it.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct ContextLine {
+    pub line_number: u64,
+    pub content: String,
 }
 
 /// Parameters for [`find_in_files`].
@@ -32,8 +57,31 @@ pub struct FindInFilesParams<'a> {
     pub include_hidden: bool,
     pub file_glob: Option<&'a str>,
     pub exclude_glob: Option<&'a str>,
+    /// Only search files whose names end in one of these extensions (with or
+    /// without the leading dot, case-insensitive). Combines with `file_glob`
+    /// and `exclude_glob`; directories are always descended into.
+    pub extensions: Option<&'a [String]>,
+    /// Directory names (e.g. `target`, `.git`) whose subtrees are not
+    /// searched at all.
+    pub skip_dirs: Option<&'a [String]>,
     pub whole_word: bool,
     pub multiline: bool,
+    /// Lines of context to attach before and after each match (0 = none).
+    pub context_lines: usize,
+    /// Return context as `{line_number, content}` arrays instead of
+    /// newline-joined strings.
+    pub structured_context: bool,
+    /// Stop each file at its first match (like `grep -l`); callers report
+    /// only the distinct file paths.
+    pub files_only: bool,
+    /// Also report columns as character indices, for editors that count
+    /// characters rather than UTF-8 bytes.
+    pub char_columns: bool,
+    /// Cap on matches across all files; the walk stops once it is reached.
+    /// Independent of the per-file `max_count`.
+    pub max_total: Option<u64>,
+    /// Attach the full text of each match's line(s) as `line_text`.
+    pub line_text: bool,
 }
 
 /// Find text in files
@@ -48,10 +96,22 @@ pub fn find_in_files(params: &FindInFilesParams<'_>) -> Result<Vec<Match>> {
         include_hidden,
         file_glob,
         exclude_glob,
+        extensions,
+        skip_dirs,
         whole_word,
         multiline,
+        context_lines,
+        structured_context,
+        files_only,
+        char_columns,
+        max_total,
+        line_text,
     } = *params;
 
+    // `files_only` is a per-file cap of one: the first match proves the file
+    // qualifies, so the rest of it need not be scanned.
+    let max_count = if files_only { Some(1) } else { max_count };
+
     let expanded_path = shellexpand::full(path)
         .map_err(|e| {
             crate::error::FileIoMcpError::from(crate::error::FileIoError::InvalidPath(format!(
@@ -63,34 +123,10 @@ pub fn find_in_files(params: &FindInFilesParams<'_>) -> Result<Vec<Match>> {
     let path_obj = Path::new(&expanded_path);
 
     if !path_obj.exists() {
-        return Err(FileIoError::NotFound(expanded_path.to_string()).into());
+        return Err(super::path_utils::not_found("search", path).into());
     }
 
-    // Build regex pattern
-    let regex_pattern = if use_regex {
-        pattern.to_string()
-    } else {
-        // Escape special regex characters for literal matching
-        regex::escape(pattern)
-    };
-
-    // Add word boundaries if whole_word is true
-    let regex_pattern = if whole_word {
-        format!(r"\b{}\b", regex_pattern)
-    } else {
-        regex_pattern
-    };
-
-    // Build regex with case sensitivity and multiline for matching
-    let regex = {
-        let mut builder = regex::RegexBuilder::new(&regex_pattern);
-        if !case_sensitive {
-            builder.case_insensitive(true);
-        }
-        builder.multi_line(multiline);
-        builder.build()
-    }
-    .map_err(FileIoError::RegexError)?;
+    let regex = build_regex(pattern, use_regex, case_sensitive, whole_word, multiline)?;
 
     let mut matches = Vec::new();
     let mut file_match_counts: std::collections::HashMap<String, u64> =
@@ -98,49 +134,88 @@ pub fn find_in_files(params: &FindInFilesParams<'_>) -> Result<Vec<Match>> {
 
     // Build file walker
     let mut walker = WalkBuilder::new(path_obj);
-    walker.hidden(include_hidden);
+    // `WalkBuilder::hidden(true)` means "skip hidden entries", the inverse of
+    // our `include_hidden` flag.
+    walker.hidden(!include_hidden);
 
     if let Some(depth) = max_depth {
         walker.max_depth(Some(depth));
     }
 
-    // Add file glob filters if specified
-    if let Some(glob) = file_glob {
-        walker.standard_filters(false);
-        let glob_pattern = globset::GlobBuilder::new(glob)
-            .build()
-            .map_err(|e| FileIoError::InvalidPath(format!("Invalid file_glob pattern: {}", e)))?;
-        let glob_matcher = glob_pattern.compile_matcher();
+    let include_matcher = match file_glob {
+        Some(glob) => {
+            walker.standard_filters(false);
+            let glob_pattern = globset::GlobBuilder::new(glob).build().map_err(|e| {
+                FileIoError::InvalidPath(format!("Invalid file_glob pattern: {}", e))
+            })?;
+            Some(glob_pattern.compile_matcher())
+        }
+        None => None,
+    };
+    let exclude_matcher = match exclude_glob {
+        Some(glob) => {
+            let exclude_pattern = globset::GlobBuilder::new(glob).build().map_err(|e| {
+                FileIoError::InvalidPath(format!("Invalid exclude_glob pattern: {}", e))
+            })?;
+            Some(exclude_pattern.compile_matcher())
+        }
+        None => None,
+    };
+    // Stored as lowercase ".ext" suffixes so multi-part extensions such as
+    // "tar.gz" work too.
+    let suffixes: Option<Vec<String>> = extensions.map(|exts| {
+        exts.iter()
+            .map(|ext| format!(".{}", ext.trim_start_matches('.').to_ascii_lowercase()))
+            .collect()
+    });
+
+    // One combined filter: `WalkBuilder::filter_entry` keeps only the last
+    // filter installed, so separate calls would silently drop all but one.
+    let skip_dirs = skip_dirs.map(super::file_find::skip_dir_set);
+    if include_matcher.is_some()
+        || exclude_matcher.is_some()
+        || suffixes.is_some()
+        || skip_dirs.is_some()
+    {
         walker.filter_entry(move |entry| {
-            // Always descend into directories — the glob only applies to file names.
-            // Without this, any directory whose name doesn't match the glob would be
-            // pruned from the walk, causing subtrees to be silently skipped.
+            if skip_dirs
+                .as_ref()
+                .is_some_and(|names| super::file_find::is_skipped_dir(entry, names))
+            {
+                return false;
+            }
+            let name = entry.path().file_name().and_then(|n| n.to_str());
+            // Exclusions prune matching directories as well as files.
+            if let Some(exclude) = &exclude_matcher
+                && name.is_some_and(|name| exclude.is_match(name))
+            {
+                return false;
+            }
+            // Always descend into directories — the glob and extensions only
+            // apply to file names. Without this, any directory whose name
+            // doesn't match would be pruned from the walk, causing subtrees to
+            // be silently skipped.
             if entry.path().is_dir() {
                 return true;
             }
-            entry
-                .path()
-                .file_name()
-                .and_then(|n| n.to_str())
-                .is_some_and(|name| glob_matcher.is_match(name))
-        });
-    }
-
-    if let Some(glob) = exclude_glob {
-        let exclude_pattern = globset::GlobBuilder::new(glob).build().map_err(|e| {
-            FileIoError::InvalidPath(format!("Invalid exclude_glob pattern: {}", e))
-        })?;
-        let exclude_matcher = exclude_pattern.compile_matcher();
-        walker.filter_entry(move |entry| {
-            !entry
-                .path()
-                .file_name()
-                .and_then(|n| n.to_str())
-                .is_some_and(|name| exclude_matcher.is_match(name))
+            let Some(name) = name else {
+                return false;
+            };
+            let included = include_matcher
+                .as_ref()
+                .is_none_or(|glob| glob.is_match(name));
+            let lower = name.to_ascii_lowercase();
+            let wanted_extension = suffixes
+                .as_ref()
+                .is_none_or(|suffixes| suffixes.iter().any(|s| lower.ends_with(s.as_str())));
+            included && wanted_extension
         });
     }
 
     for result in walker.build() {
+        if max_total.is_some_and(|total| matches.len() as u64 >= total) {
+            break;
+        }
         let entry = result
             .map_err(|e| FileIoError::ReadError(format!("Error walking directory: {}", e)))?;
 
@@ -153,6 +228,13 @@ pub fn find_in_files(params: &FindInFilesParams<'_>) -> Result<Vec<Match>> {
 
         let file_path = entry_path.to_string_lossy().to_string();
 
+        // Whatever is left of the global cap further limits this file.
+        let remaining = max_total.map(|total| total.saturating_sub(matches.len() as u64));
+        let file_cap = match (max_count, remaining) {
+            (Some(max), Some(left)) => Some(max.min(left)),
+            (max, left) => max.or(left),
+        };
+
         // Check max_count per file
         if let Some(max) = max_count {
             let count = file_match_counts.get(&file_path).copied().unwrap_or(0);
@@ -164,17 +246,74 @@ pub fn find_in_files(params: &FindInFilesParams<'_>) -> Result<Vec<Match>> {
         // Search in file
         let mut file_matches = Vec::new();
 
-        let content_bytes = std::fs::read(entry_path).map_err(|e| {
-            FileIoError::ReadError(format!("Failed to read file {}: {}", file_path, e))
-        })?;
+        let content_bytes = std::fs::read(entry_path)
+            .map_err(|e| FileIoError::from_io_error("read file", &file_path, e))?;
 
         let content = match String::from_utf8(content_bytes) {
             Ok(content) => content,
             Err(_) => continue,
         };
 
-        for (line_number, line) in (1u64..).zip(content.lines()) {
-            if let Some(max) = max_count {
+        let lines: Vec<&str> = content.lines().collect();
+
+        // A pattern meant to span lines can never match when each line is
+        // searched on its own, so multiline mode runs the regex over the whole
+        // file and maps byte offsets back to line/column positions.
+        if multiline {
+            let starts = line_starts(&content);
+            for mat in regex.find_iter(&content) {
+                if file_cap.is_some_and(|max| file_matches.len() as u64 >= max) {
+                    break;
+                }
+                let first = line_index(&starts, mat.start());
+                let last_byte = if mat.end() > mat.start() {
+                    mat.end() - 1
+                } else {
+                    mat.start()
+                };
+                let last = line_index(&starts, last_byte);
+                let mut m = Match {
+                    file_path: file_path.clone(),
+                    line_number: first as u64 + 1,
+                    end_line_number: last as u64 + 1,
+                    column_start: mat.start() - starts[first],
+                    column_end: mat.end() - starts[last],
+                    char_column_start: None,
+                    char_column_end: None,
+                    matched_text: mat.as_str().to_string(),
+                    line_text: None,
+                    context_before: None,
+                    context_after: None,
+                    context_before_lines: None,
+                    context_after_lines: None,
+                };
+                if line_text {
+                    m.line_text = Some(lines.get(first..=last).unwrap_or_default().join("\n"));
+                }
+                if char_columns {
+                    let line_at = |i: usize| lines.get(i).copied().unwrap_or("");
+                    m.char_column_start = Some(char_column(line_at(first), m.column_start));
+                    m.char_column_end = Some(char_column(line_at(last), m.column_end));
+                }
+                if context_lines > 0 {
+                    attach_context(
+                        &mut m,
+                        &lines,
+                        first,
+                        last,
+                        context_lines,
+                        structured_context,
+                    );
+                }
+                file_matches.push(m);
+            }
+            matches.extend(file_matches);
+            continue;
+        }
+
+        for (idx, line) in lines.iter().enumerate() {
+            let line_number = idx as u64 + 1;
+            if let Some(max) = file_cap {
                 let count = file_match_counts.get(&file_path).copied().unwrap_or(0);
                 if count >= max {
                     break;
@@ -182,17 +321,34 @@ pub fn find_in_files(params: &FindInFilesParams<'_>) -> Result<Vec<Match>> {
             }
 
             for mat in regex.find_iter(line) {
-                file_matches.push(Match {
+                let mut m = Match {
                     file_path: file_path.clone(),
                     line_number,
+                    end_line_number: line_number,
                     column_start: mat.start(),
                     column_end: mat.end(),
+                    char_column_start: None,
+                    char_column_end: None,
                     matched_text: mat.as_str().to_string(),
+                    line_text: None,
                     context_before: None,
                     context_after: None,
-                });
+                    context_before_lines: None,
+                    context_after_lines: None,
+                };
+                if line_text {
+                    m.line_text = Some(line.to_string());
+                }
+                if char_columns {
+                    m.char_column_start = Some(char_column(line, mat.start()));
+                    m.char_column_end = Some(char_column(line, mat.end()));
+                }
+                if context_lines > 0 {
+                    attach_context(&mut m, &lines, idx, idx, context_lines, structured_context);
+                }
+                file_matches.push(m);
 
-                if let Some(max) = max_count {
+                if let Some(max) = file_cap {
                     let count = file_match_counts.entry(file_path.clone()).or_insert(0);
                     *count += 1;
                     if *count >= max {
@@ -208,6 +364,96 @@ pub fn find_in_files(params: &FindInFilesParams<'_>) -> Result<Vec<Match>> {
     Ok(matches)
 }
 
+/// Compile a search pattern the way `find_in_files` interprets it: literal
+/// unless `use_regex`, optionally case-insensitive, wrapped in word boundaries
+/// for `whole_word`, and with `^`/`$` matching at line boundaries for
+/// `multiline`. Shared with other pattern-taking operations so they agree on
+/// pattern semantics.
+pub fn build_regex(
+    pattern: &str,
+    use_regex: bool,
+    case_sensitive: bool,
+    whole_word: bool,
+    multiline: bool,
+) -> Result<regex::Regex> {
+    // Build regex pattern
+    let regex_pattern = if use_regex {
+        pattern.to_string()
+    } else {
+        // Escape special regex characters for literal matching
+        regex::escape(pattern)
+    };
+
+    // Add word boundaries if whole_word is true
+    let regex_pattern = if whole_word {
+        format!(r"\b{}\b", regex_pattern)
+    } else {
+        regex_pattern
+    };
+
+    // Build regex with case sensitivity and multiline for matching
+    let mut builder = regex::RegexBuilder::new(&regex_pattern);
+    if !case_sensitive {
+        builder.case_insensitive(true);
+    }
+    builder.multi_line(multiline);
+    Ok(builder.build().map_err(FileIoError::RegexError)?)
+}
+
+/// Byte offset at which each line of `content` starts.
+fn line_starts(content: &str) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(content.match_indices('\n').map(|(i, _)| i + 1))
+        .collect()
+}
+
+/// Convert a byte column within `line` to a character index. Offsets past the
+/// end of `line` (a match reaching into a stripped `\r\n`) count one
+/// character per byte, since line terminators are ASCII.
+fn char_column(line: &str, byte: usize) -> usize {
+    if byte >= line.len() {
+        line.chars().count() + (byte - line.len())
+    } else {
+        line.char_indices().take_while(|&(i, _)| i < byte).count()
+    }
+}
+
+/// 0-based index of the line containing byte `offset`.
+fn line_index(starts: &[usize], offset: usize) -> usize {
+    starts.partition_point(|&s| s <= offset).saturating_sub(1)
+}
+
+/// Fill the context fields of `m` with up to `n` lines before the 0-based line
+/// index `first` and after `last` (equal unless the match spans lines).
+fn attach_context(
+    m: &mut Match,
+    lines: &[&str],
+    first: usize,
+    last: usize,
+    n: usize,
+    structured: bool,
+) {
+    let first = first.min(lines.len());
+    let after_start = (last + 1).min(lines.len());
+    let before = first.saturating_sub(n)..first;
+    let after = after_start..lines.len().min(after_start + n);
+    let numbered = |range: std::ops::Range<usize>| -> Vec<ContextLine> {
+        range
+            .map(|i| ContextLine {
+                line_number: i as u64 + 1,
+                content: lines[i].to_string(),
+            })
+            .collect()
+    };
+    if structured {
+        m.context_before_lines = Some(numbered(before));
+        m.context_after_lines = Some(numbered(after));
+    } else {
+        m.context_before = Some(lines[before].join("\n"));
+        m.context_after = Some(lines[after].join("\n"));
+    }
+}
+
 impl From<Match> for serde_json::Value {
     fn from(m: Match) -> Self {
         let mut obj = serde_json::Map::new();
@@ -219,6 +465,12 @@ impl From<Match> for serde_json::Value {
             "line_number".to_string(),
             serde_json::Value::Number(m.line_number.into()),
         );
+        if m.end_line_number != m.line_number {
+            obj.insert(
+                "end_line_number".to_string(),
+                serde_json::Value::Number(m.end_line_number.into()),
+            );
+        }
         obj.insert(
             "column_start".to_string(),
             serde_json::Value::Number(m.column_start.into()),
@@ -227,20 +479,96 @@ impl From<Match> for serde_json::Value {
             "column_end".to_string(),
             serde_json::Value::Number(m.column_end.into()),
         );
+        if let Some(col) = m.char_column_start {
+            obj.insert(
+                "char_column_start".to_string(),
+                serde_json::Value::Number(col.into()),
+            );
+        }
+        if let Some(col) = m.char_column_end {
+            obj.insert(
+                "char_column_end".to_string(),
+                serde_json::Value::Number(col.into()),
+            );
+        }
         obj.insert(
             "matched_text".to_string(),
             serde_json::Value::String(m.matched_text),
         );
+        if let Some(text) = m.line_text {
+            obj.insert("line_text".to_string(), serde_json::Value::String(text));
+        }
         if let Some(ctx) = m.context_before {
             obj.insert("context_before".to_string(), serde_json::Value::String(ctx));
         }
         if let Some(ctx) = m.context_after {
             obj.insert("context_after".to_string(), serde_json::Value::String(ctx));
         }
+        // Structured context reuses the same keys; only one form is ever set.
+        if let Some(lines) = m.context_before_lines {
+            obj.insert("context_before".to_string(), serde_json::json!(lines));
+        }
+        if let Some(lines) = m.context_after_lines {
+            obj.insert("context_after".to_string(), serde_json::json!(lines));
+        }
         serde_json::Value::Object(obj)
     }
 }
 
+/// The matches from one file, as returned by [`group_by_file`].
+#[derive(Debug, Clone)]
+pub struct FileMatches {
+    pub file_path: String,
+    pub matches: Vec<Match>,
+}
+
+/// Group `matches` by file, keeping files in the order they were first
+/// found and ordering each file's matches by line, then column.
+pub fn group_by_file(matches: Vec<Match>) -> Vec<FileMatches> {
+    let mut groups: Vec<FileMatches> = Vec::new();
+    let mut index: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
+    for m in matches {
+        match index.get(&m.file_path) {
+            Some(&i) => groups[i].matches.push(m),
+            None => {
+                index.insert(m.file_path.clone(), groups.len());
+                groups.push(FileMatches {
+                    file_path: m.file_path.clone(),
+                    matches: vec![m],
+                });
+            }
+        }
+    }
+    for group in &mut groups {
+        group
+            .matches
+            .sort_by_key(|m| (m.line_number, m.column_start));
+    }
+    groups
+}
+
+impl From<FileMatches> for serde_json::Value {
+    /// `{file_path, matches}`, with `file_path` left off the matches since
+    /// the group already names it.
+    fn from(group: FileMatches) -> Self {
+        let matches = group
+            .matches
+            .into_iter()
+            .map(|m| {
+                let mut value = serde_json::Value::from(m);
+                if let Some(obj) = value.as_object_mut() {
+                    obj.remove("file_path");
+                }
+                value
+            })
+            .collect();
+        serde_json::json!({
+            "file_path": group.file_path,
+            "matches": serde_json::Value::Array(matches),
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -260,9 +588,45 @@ mod tests {
             include_hidden: false,
             file_glob: None,
             exclude_glob: None,
+            extensions: None,
+            skip_dirs: None,
             whole_word: false,
             multiline: false,
+            context_lines: 0,
+            structured_context: false,
+            files_only: false,
+            char_columns: false,
+            max_total: None,
+            line_text: false,
+        }
+    }
+
+    #[test]
+    fn test_max_total_caps_across_files() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().to_str().unwrap();
+        for name in ["a.txt", "b.txt", "c.txt"] {
+            fs::write(dir.path().join(name), "hit\nhit\nhit\n").unwrap();
         }
+
+        let matches = find_in_files(&FindInFilesParams {
+            max_total: Some(4),
+            ..params("hit", root)
+        })
+        .unwrap();
+        assert_eq!(matches.len(), 4);
+        let files: std::collections::HashSet<&str> =
+            matches.iter().map(|m| m.file_path.as_str()).collect();
+        assert_eq!(files.len(), 2, "walk must stop once the cap is reached");
+
+        // The per-file cap still applies underneath the global one.
+        let matches = find_in_files(&FindInFilesParams {
+            max_count: Some(1),
+            max_total: Some(5),
+            ..params("hit", root)
+        })
+        .unwrap();
+        assert_eq!(matches.len(), 3);
     }
 
     #[test]
@@ -373,4 +737,304 @@ mod tests {
         assert!(matches.iter().any(|m| m.file_path.ends_with("root.rs")));
         assert!(matches.iter().any(|m| m.file_path.ends_with("deep.rs")));
     }
+
+    /// `extensions` keeps nested files reachable and combines with
+    /// `exclude_glob` rather than replacing it.
+    #[test]
+    fn test_find_in_files_extensions_in_mixed_tree() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().to_str().unwrap();
+
+        let nested = dir.path().join("src").join("bin");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(dir.path().join("lib.rs"), "target").unwrap();
+        fs::write(nested.join("main.RS"), "target").unwrap();
+        fs::write(nested.join("generated.rs"), "target").unwrap();
+        fs::write(dir.path().join("Cargo.toml"), "target").unwrap();
+        fs::write(dir.path().join("notes.md"), "target").unwrap();
+
+        let extensions = vec!["rs".to_string()];
+        let matches = find_in_files(&FindInFilesParams {
+            extensions: Some(&extensions),
+            ..params("target", root)
+        })
+        .unwrap();
+        let mut files: Vec<&str> = matches
+            .iter()
+            .filter_map(|m| Path::new(&m.file_path).file_name()?.to_str())
+            .collect();
+        files.sort();
+        assert_eq!(files, vec!["generated.rs", "lib.rs", "main.RS"]);
+
+        let extensions = vec![".rs".to_string(), "toml".to_string()];
+        let matches = find_in_files(&FindInFilesParams {
+            extensions: Some(&extensions),
+            exclude_glob: Some("generated*"),
+            ..params("target", root)
+        })
+        .unwrap();
+        let mut files: Vec<&str> = matches
+            .iter()
+            .filter_map(|m| Path::new(&m.file_path).file_name()?.to_str())
+            .collect();
+        files.sort();
+        assert_eq!(files, vec!["Cargo.toml", "lib.rs", "main.RS"]);
+    }
+
+    #[test]
+    fn test_find_in_files_string_context() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().to_str().unwrap();
+
+        fs::write(dir.path().join("test.txt"), "a\nb\nneedle\nc\nd\n").unwrap();
+
+        let matches = find_in_files(&FindInFilesParams {
+            context_lines: 1,
+            ..params("needle", root)
+        })
+        .unwrap();
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].context_before.as_deref(), Some("b"));
+        assert_eq!(matches[0].context_after.as_deref(), Some("c"));
+        assert!(matches[0].context_before_lines.is_none());
+    }
+
+    /// Structured context carries the real line numbers of the surrounding
+    /// lines and is clipped at the start of the file.
+    #[test]
+    fn test_find_in_files_structured_context_line_numbers() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().to_str().unwrap();
+
+        fs::write(dir.path().join("test.txt"), "one\nneedle\nthree\nfour\n").unwrap();
+
+        let matches = find_in_files(&FindInFilesParams {
+            context_lines: 2,
+            structured_context: true,
+            ..params("needle", root)
+        })
+        .unwrap();
+
+        assert_eq!(matches.len(), 1);
+        let m = &matches[0];
+        assert_eq!(m.line_number, 2);
+        assert_eq!(
+            m.context_before_lines.as_deref(),
+            Some(
+                &[ContextLine {
+                    line_number: 1,
+                    content: "one".to_string()
+                }][..]
+            )
+        );
+        let after: Vec<(u64, &str)> = m
+            .context_after_lines
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|l| (l.line_number, l.content.as_str()))
+            .collect();
+        assert_eq!(after, vec![(3, "three"), (4, "four")]);
+        assert!(m.context_before.is_none());
+
+        let json: serde_json::Value = m.clone().into();
+        assert_eq!(json["context_before"][0]["line_number"], 1);
+        assert_eq!(json["context_after"][1]["content"], "four");
+    }
+
+    #[test]
+    fn test_find_in_files_files_only_returns_each_file_once() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().to_str().unwrap();
+
+        fs::write(dir.path().join("hit.txt"), "needle\nneedle needle\n").unwrap();
+        fs::write(dir.path().join("miss.txt"), "hay\n").unwrap();
+        fs::write(dir.path().join(".hidden.txt"), "needle\n").unwrap();
+
+        let matches = find_in_files(&FindInFilesParams {
+            files_only: true,
+            ..params("needle", root)
+        })
+        .unwrap();
+
+        assert_eq!(matches.len(), 1, "got: {:?}", matches);
+        assert!(matches[0].file_path.ends_with("hit.txt"));
+    }
+
+    #[test]
+    fn test_find_in_files_include_hidden() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().to_str().unwrap();
+
+        fs::write(dir.path().join(".hidden.txt"), "needle\n").unwrap();
+
+        assert!(find_in_files(&params("needle", root)).unwrap().is_empty());
+        let matches = find_in_files(&FindInFilesParams {
+            include_hidden: true,
+            ..params("needle", root)
+        })
+        .unwrap();
+        assert_eq!(matches.len(), 1);
+    }
+
+    /// Regression: multiline matching used to run per line, so a pattern
+    /// spanning a newline could never match.
+    #[test]
+    fn test_find_in_files_multiline_spans_lines() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().to_str().unwrap();
+
+        fs::write(
+            dir.path().join("test.txt"),
+            "intro\nfn start() {\n    body\n}\n",
+        )
+        .unwrap();
+
+        let matches = find_in_files(&FindInFilesParams {
+            use_regex: true,
+            multiline: true,
+            ..params(r"start\(\) \{\n\s+body", root)
+        })
+        .unwrap();
+
+        assert_eq!(matches.len(), 1, "got: {:?}", matches);
+        let m = &matches[0];
+        assert_eq!(m.line_number, 2);
+        assert_eq!(m.end_line_number, 3);
+        assert_eq!(m.column_start, 3);
+        assert_eq!(m.column_end, 8);
+        assert_eq!(m.matched_text, "start() {\n    body");
+
+        let json: serde_json::Value = m.clone().into();
+        assert_eq!(json["end_line_number"], 3);
+    }
+
+    #[test]
+    fn test_find_in_files_multiline_anchors_per_line() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().to_str().unwrap();
+
+        fs::write(dir.path().join("test.txt"), "a\nfoo\nb foo\n").unwrap();
+
+        let matches = find_in_files(&FindInFilesParams {
+            use_regex: true,
+            multiline: true,
+            ..params("^foo$", root)
+        })
+        .unwrap();
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].line_number, 2);
+        assert_eq!(matches[0].end_line_number, 2);
+        assert_eq!((matches[0].column_start, matches[0].column_end), (0, 3));
+    }
+
+    #[test]
+    fn test_find_in_files_char_columns_differ_after_emoji() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().to_str().unwrap();
+
+        // The emoji is 4 bytes but a single character.
+        fs::write(dir.path().join("test.txt"), "🎉 needle\n").unwrap();
+
+        let matches = find_in_files(&FindInFilesParams {
+            char_columns: true,
+            ..params("needle", root)
+        })
+        .unwrap();
+
+        assert_eq!(matches.len(), 1);
+        let m = &matches[0];
+        assert_eq!((m.column_start, m.column_end), (5, 11));
+        assert_eq!((m.char_column_start, m.char_column_end), (Some(2), Some(8)));
+
+        let plain = find_in_files(&params("needle", root)).unwrap();
+        assert_eq!(plain[0].char_column_start, None);
+    }
+
+    #[test]
+    fn test_line_text_returns_full_line() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().to_str().unwrap();
+        fs::write(
+            dir.path().join("lib.rs"),
+            "use std::fs;\nlet total = compute_sum(values);\n",
+        )
+        .unwrap();
+
+        let matches = find_in_files(&params("compute", root)).unwrap();
+        assert_eq!(matches[0].line_text, None, "line_text is opt-in");
+
+        let matches = find_in_files(&FindInFilesParams {
+            line_text: true,
+            ..params("compute", root)
+        })
+        .unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].matched_text, "compute");
+        assert_eq!(
+            matches[0].line_text.as_deref(),
+            Some("let total = compute_sum(values);")
+        );
+        let json: serde_json::Value = matches[0].clone().into();
+        assert_eq!(json["line_text"], "let total = compute_sum(values);");
+    }
+
+    #[test]
+    fn test_group_by_file_returns_one_object_per_file() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().to_str().unwrap();
+        fs::write(
+            dir.path().join("a.txt"),
+            "hit
+miss
+hit hit
+",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("b.txt"),
+            "one hit
+",
+        )
+        .unwrap();
+
+        let mut groups = group_by_file(find_in_files(&params("hit", root)).unwrap());
+        groups.sort_by(|x, y| x.file_path.cmp(&y.file_path));
+        assert_eq!(groups.len(), 2);
+        assert!(groups[0].file_path.ends_with("a.txt"));
+        let positions: Vec<(u64, usize)> = groups[0]
+            .matches
+            .iter()
+            .map(|m| (m.line_number, m.column_start))
+            .collect();
+        assert_eq!(positions, vec![(1, 0), (3, 0), (3, 4)]);
+        assert!(groups[1].file_path.ends_with("b.txt"));
+        assert_eq!(groups[1].matches.len(), 1);
+
+        let json: serde_json::Value = groups.remove(1).into();
+        assert!(json["file_path"].as_str().unwrap().ends_with("b.txt"));
+        assert_eq!(json["matches"][0]["line_number"], 1);
+        assert!(json["matches"][0].get("file_path").is_none());
+    }
+
+    #[test]
+    fn test_skip_dirs_prunes_named_directories() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().to_str().unwrap();
+        fs::create_dir_all(dir.path().join("src")).unwrap();
+        fs::create_dir_all(dir.path().join("target/release")).unwrap();
+        fs::write(dir.path().join("src/lib.rs"), "needle\n").unwrap();
+        fs::write(dir.path().join("target/release/out.rs"), "needle\n").unwrap();
+        let skip = vec!["target".to_string()];
+
+        let matches = find_in_files(&FindInFilesParams {
+            skip_dirs: Some(&skip),
+            ..params("needle", root)
+        })
+        .unwrap();
+        assert_eq!(matches.len(), 1);
+        assert!(matches[0].file_path.ends_with("lib.rs"));
+    }
 }
@@ -0,0 +1,281 @@
+#![deny(warnings)]
+
+// Rewrite a JSON file pretty-printed or minified
+
+use crate::error::{FileIoError, Result};
+use serde::de::{Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeMap, SerializeSeq, Serializer};
+use std::fmt;
+use std::path::Path;
+
+/// Largest indent [`format_json`] accepts, in spaces.
+pub const MAX_INDENT: usize = 16;
+
+/// Output layout for [`format_json`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsonStyle {
+    /// One member per line, nested `indent` spaces per level, with a final
+    /// newline.
+    Pretty { indent: usize },
+    /// No insignificant whitespace at all.
+    Minified,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct FormatJsonResult {
+    pub path: String,
+    /// False when the file was already in the requested format.
+    pub changed: bool,
+    pub bytes: u64,
+}
+
+/// Reparse the JSON file at `path` and rewrite it in `style`.
+///
+/// Object keys keep their order in the file. Invalid JSON is an error and
+/// leaves the file untouched; otherwise the file is rewritten atomically,
+/// and only if the formatted text differs.
+pub fn format_json(path: &str, style: JsonStyle) -> Result<FormatJsonResult> {
+    match style {
+        JsonStyle::Pretty { indent } if indent > MAX_INDENT => {
+            return Err(crate::error::FileIoMcpError::InvalidParams(format!(
+                "indent must be at most {}, got {}",
+                MAX_INDENT, indent
+            )));
+        }
+        _ => {}
+    }
+    let expanded_path = shellexpand::full(path)
+        .map_err(|e| {
+            crate::error::FileIoMcpError::from(FileIoError::InvalidPath(format!(
+                "Failed to expand path \'{}\': {}",
+                path, e
+            )))
+        })
+        .map(|expanded| expanded.into_owned())?;
+    let path_obj = Path::new(&expanded_path);
+    if !path_obj.exists() {
+        return Err(FileIoError::NotFound(expanded_path).into());
+    }
+    if !path_obj.is_file() {
+        return Err(FileIoError::InvalidPath(format!("{} is not a file", expanded_path)).into());
+    }
+
+    let original = std::fs::read_to_string(path_obj)
+        .map_err(|e| FileIoError::from_io_error("read file", &expanded_path, e))?;
+    let value: OrderedJson = serde_json::from_str(&original).map_err(|e| {
+        FileIoError::ReadError(format!(
+            "{} is not valid JSON, leaving it unchanged: {}",
+            expanded_path, e
+        ))
+    })?;
+    let formatted = render(&value, style)?;
+
+    let changed = formatted != original;
+    if changed {
+        super::write_file::write_file(&expanded_path, &formatted, false)?;
+    }
+
+    Ok(FormatJsonResult {
+        path: expanded_path,
+        changed,
+        bytes: formatted.len() as u64,
+    })
+}
+
+/// A parsed JSON document whose objects keep their members in file order.
+///
+/// Why not `serde_json::Value`: its objects sort their keys unless
+/// serde_json's `preserve_order` feature is on, and that feature would
+/// change the key order of every JSON payload the server builds.
+enum OrderedJson {
+    Null,
+    Bool(bool),
+    Number(serde_json::Number),
+    String(String),
+    Array(Vec<OrderedJson>),
+    /// A repeated key keeps its first position and its last value, as a
+    /// `serde_json::Map` would.
+    Object(Vec<(String, OrderedJson)>),
+}
+
+impl<'de> Deserialize<'de> for OrderedJson {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        deserializer.deserialize_any(OrderedJsonVisitor)
+    }
+}
+
+struct OrderedJsonVisitor;
+
+impl<'de> Visitor<'de> for OrderedJsonVisitor {
+    type Value = OrderedJson;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("any JSON value")
+    }
+
+    fn visit_unit<E: serde::de::Error>(self) -> std::result::Result<OrderedJson, E> {
+        Ok(OrderedJson::Null)
+    }
+
+    fn visit_bool<E: serde::de::Error>(self, v: bool) -> std::result::Result<OrderedJson, E> {
+        Ok(OrderedJson::Bool(v))
+    }
+
+    fn visit_i64<E: serde::de::Error>(self, v: i64) -> std::result::Result<OrderedJson, E> {
+        Ok(OrderedJson::Number(v.into()))
+    }
+
+    fn visit_u64<E: serde::de::Error>(self, v: u64) -> std::result::Result<OrderedJson, E> {
+        Ok(OrderedJson::Number(v.into()))
+    }
+
+    fn visit_f64<E: serde::de::Error>(self, v: f64) -> std::result::Result<OrderedJson, E> {
+        serde_json::Number::from_f64(v)
+            .map(OrderedJson::Number)
+            .ok_or_else(|| E::custom("number is not finite"))
+    }
+
+    fn visit_str<E: serde::de::Error>(self, v: &str) -> std::result::Result<OrderedJson, E> {
+        Ok(OrderedJson::String(v.to_string()))
+    }
+
+    fn visit_string<E: serde::de::Error>(self, v: String) -> std::result::Result<OrderedJson, E> {
+        Ok(OrderedJson::String(v))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(
+        self,
+        mut seq: A,
+    ) -> std::result::Result<OrderedJson, A::Error> {
+        let mut items = Vec::new();
+        while let Some(item) = seq.next_element()? {
+            items.push(item);
+        }
+        Ok(OrderedJson::Array(items))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(
+        self,
+        mut map: A,
+    ) -> std::result::Result<OrderedJson, A::Error> {
+        let mut members: Vec<(String, OrderedJson)> = Vec::new();
+        while let Some((key, value)) = map.next_entry::<String, OrderedJson>()? {
+            match members.iter_mut().find(|(existing, _)| *existing == key) {
+                Some(member) => member.1 = value,
+                None => members.push((key, value)),
+            }
+        }
+        Ok(OrderedJson::Object(members))
+    }
+}
+
+impl Serialize for OrderedJson {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        match self {
+            OrderedJson::Null => serializer.serialize_unit(),
+            OrderedJson::Bool(v) => serializer.serialize_bool(*v),
+            OrderedJson::Number(v) => v.serialize(serializer),
+            OrderedJson::String(v) => serializer.serialize_str(v),
+            OrderedJson::Array(items) => {
+                let mut seq = serializer.serialize_seq(Some(items.len()))?;
+                for item in items {
+                    seq.serialize_element(item)?;
+                }
+                seq.end()
+            }
+            OrderedJson::Object(members) => {
+                let mut map = serializer.serialize_map(Some(members.len()))?;
+                for (key, value) in members {
+                    map.serialize_entry(key, value)?;
+                }
+                map.end()
+            }
+        }
+    }
+}
+
+fn render(value: &OrderedJson, style: JsonStyle) -> Result<String> {
+    match style {
+        JsonStyle::Minified => Ok(serde_json::to_string(value)?),
+        JsonStyle::Pretty { indent } => {
+            let indent = " ".repeat(indent);
+            let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
+            let mut out = Vec::new();
+            let mut serializer = serde_json::Serializer::with_formatter(&mut out, formatter);
+            value.serialize(&mut serializer)?;
+            out.push(b'\n');
+            // serde_json only ever emits UTF-8.
+            String::from_utf8(out).map_err(|e| {
+                FileIoError::WriteError(format!("formatted JSON is not UTF-8: {}", e)).into()
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_pretty_print_minified_file() {
+        let dir = TempDir::new().expect("create temp dir");
+        let path = dir.path().join("a.json");
+        fs::write(&path, r#"{"zeta":1,"alpha":[true,null],"mid":{}}"#).expect("write fixture");
+        let p = path.to_str().expect("utf-8 path");
+
+        let result = format_json(p, JsonStyle::Pretty { indent: 2 }).expect("format succeeds");
+        assert!(result.changed);
+        assert_eq!(
+            fs::read_to_string(&path).expect("read back"),
+            "{\n  \"zeta\": 1,\n  \"alpha\": [\n    true,\n    null\n  ],\n  \"mid\": {}\n}\n"
+        );
+
+        // Already formatted: nothing to rewrite.
+        let again = format_json(p, JsonStyle::Pretty { indent: 2 }).expect("format succeeds");
+        assert!(!again.changed);
+    }
+
+    #[test]
+    fn test_minify_pretty_file() {
+        let dir = TempDir::new().expect("create temp dir");
+        let path = dir.path().join("a.json");
+        fs::write(&path, "{\n    \"b\": [1, 2],\n    \"a\": \"x y\"\n}\n").expect("write fixture");
+
+        format_json(path.to_str().expect("utf-8 path"), JsonStyle::Minified)
+            .expect("format succeeds");
+        assert_eq!(
+            fs::read_to_string(&path).expect("read back"),
+            r#"{"b":[1,2],"a":"x y"}"#
+        );
+    }
+
+    #[test]
+    fn test_nested_key_order_and_duplicates() {
+        let dir = TempDir::new().expect("create temp dir");
+        let path = dir.path().join("a.json");
+        fs::write(
+            &path,
+            r#"{"b": {"z": 1, "y": 2.5}, "a": -3, "b2": "", "a": 4}"#,
+        )
+        .expect("write fixture");
+
+        format_json(path.to_str().expect("utf-8 path"), JsonStyle::Minified)
+            .expect("format succeeds");
+        assert_eq!(
+            fs::read_to_string(&path).expect("read back"),
+            r#"{"b":{"z":1,"y":2.5},"a":4,"b2":""}"#
+        );
+    }
+
+    #[test]
+    fn test_invalid_json_left_untouched() {
+        let dir = TempDir::new().expect("create temp dir");
+        let path = dir.path().join("a.json");
+        fs::write(&path, "{\"a\": 1,}").expect("write fixture");
+
+        assert!(format_json(path.to_str().expect("utf-8 path"), JsonStyle::Minified).is_err());
+        assert_eq!(fs::read_to_string(&path).expect("read back"), "{\"a\": 1,}");
+    }
+}
@@ -30,6 +30,53 @@ pub fn get_file_mode(paths: &[&str]) -> Result<std::collections::HashMap<String,
     Ok(results)
 }
 
+/// One path's result from [`get_file_mode_entries`], in the same shape as
+/// the count tools' per-path results.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct ModeEntry {
+    pub path: String,
+    /// Octal mode such as `0644`; `None` when the query failed.
+    pub mode: Option<String>,
+    pub exists: bool,
+    /// `ok`, or `error: ...` describing why `mode` is missing.
+    pub status: String,
+}
+
+/// Like [`get_file_mode`], but one entry per input path, in input order
+/// (duplicates included), with failures reported inline instead of failing
+/// the whole batch.
+pub fn get_file_mode_entries(paths: &[&str]) -> Vec<ModeEntry> {
+    paths.iter().map(|path| mode_entry(path)).collect()
+}
+
+/// The [`ModeEntry`] for a single path.
+pub fn mode_entry(path: &str) -> ModeEntry {
+    match get_file_mode_single(path) {
+        Ok(mode) => ModeEntry {
+            path: path.to_string(),
+            mode: Some(mode),
+            exists: true,
+            status: "ok".to_string(),
+        },
+        Err(e) => {
+            let is_not_found = matches!(
+                e,
+                crate::error::FileIoMcpError::FileIo(FileIoError::NotFound(_))
+            );
+            ModeEntry {
+                path: path.to_string(),
+                mode: None,
+                exists: !is_not_found,
+                status: if is_not_found {
+                    "error: not found".to_string()
+                } else {
+                    format!("error: {}", e)
+                },
+            }
+        }
+    }
+}
+
 /// Get file mode (permissions) as octal string for a single path
 pub fn get_file_mode_single(path: &str) -> Result<String> {
     let expanded_path = shellexpand::full(path)
@@ -80,4 +127,22 @@ mod tests {
         assert!(mode.len() == 4);
         assert!(mode.chars().all(|c| c.is_ascii_digit()));
     }
+
+    #[test]
+    fn test_get_file_mode_entries_reports_missing_inline() {
+        let file = NamedTempFile::new().expect("create temp file");
+        let present = file.path().to_str().expect("utf-8 path");
+        let missing = format!("{}.missing", present);
+
+        let entries = get_file_mode_entries(&[present, &missing]);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].path, present);
+        assert_eq!(entries[0].status, "ok");
+        assert!(entries[0].exists);
+        assert_eq!(entries[0].mode.as_deref().map(str::len), Some(4));
+        assert_eq!(entries[1].path, missing);
+        assert_eq!(entries[1].status, "error: not found");
+        assert!(!entries[1].exists);
+        assert_eq!(entries[1].mode, None);
+    }
 }
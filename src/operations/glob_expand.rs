@@ -0,0 +1,201 @@
+#![deny(warnings)]
+
+// Glob expansion shared by cp, mv, and rm, and exposed as a preview tool
+
+use crate::error::{FileIoError, Result};
+use globset::{Glob, GlobBuilder, GlobMatcher};
+use ignore::WalkBuilder;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Check if a string contains glob patterns
+pub(crate) fn is_glob_pattern(s: &str) -> bool {
+    s.contains('*') || s.contains('?') || s.contains('[') || s.contains('{')
+}
+
+/// Compile a single glob, reporting a bad pattern as an invalid path.
+pub fn compile_glob(pattern: &str) -> Result<GlobMatcher> {
+    Glob::new(pattern)
+        .map(|glob| glob.compile_matcher())
+        .map_err(|e| {
+            FileIoError::InvalidPath(format!("Invalid glob pattern {}: {}", pattern, e)).into()
+        })
+}
+
+/// Expand glob pattern to matching paths
+pub(crate) fn expand_glob(pattern: &str) -> Result<Vec<PathBuf>> {
+    let expanded_pattern = shellexpand::full(pattern)
+        .map_err(|e| {
+            crate::error::FileIoMcpError::from(crate::error::FileIoError::InvalidPath(format!(
+                "Failed to expand path \'{}\': {}",
+                pattern, e
+            )))
+        })
+        .map(|expanded| expanded.into_owned())?;
+    let path = Path::new(&expanded_pattern);
+    let (base_dir, glob_str) = if let Some(parent) = path.parent() {
+        if parent.as_os_str().is_empty() {
+            (
+                Path::new("."),
+                path.file_name().and_then(|n| n.to_str()).unwrap_or(pattern),
+            )
+        } else {
+            (
+                parent,
+                path.file_name().and_then(|n| n.to_str()).unwrap_or(pattern),
+            )
+        }
+    } else {
+        (Path::new("."), pattern)
+    };
+
+    let glob = Glob::new(glob_str).map_err(|e| {
+        FileIoError::InvalidPath(format!("Invalid glob pattern {}: {}", pattern, e))
+    })?;
+    let matcher: GlobMatcher = glob.compile_matcher();
+
+    let mut matches = Vec::new();
+    let entries = fs::read_dir(base_dir).map_err(|e| {
+        FileIoError::from_io_error("read directory", &base_dir.display().to_string(), e)
+    })?;
+
+    for entry in entries {
+        let entry = entry.map_err(|e| {
+            FileIoError::ReadError(format!("Failed to read directory entry: {}", e))
+        })?;
+        let entry_path = entry.path();
+        if let Some(file_name) = entry_path.file_name().and_then(|n| n.to_str())
+            && matcher.is_match(file_name)
+        {
+            matches.push(entry_path);
+        }
+    }
+
+    Ok(matches)
+}
+
+/// Resolve `pattern` to the sorted, absolute paths it matches.
+///
+/// Without `recursive` only the final path component may contain wildcards,
+/// exactly as [`super::rm::rm`], [`super::cp::cp`], and [`super::mv::mv`]
+/// expand their sources. With `recursive`, wildcards may appear in any
+/// component and `**` crosses directory boundaries (`src/**/*.rs`), while
+/// `*` still stops at `/`.
+///
+/// Unlike those operations, a pattern that matches nothing (including one
+/// whose base directory does not exist) yields an empty list rather than an
+/// error. Why: this is the preview step before a destructive call, and
+/// "nothing would be touched" is an answer, not a failure.
+pub fn expand_glob_paths(pattern: &str, recursive: bool) -> Result<Vec<String>> {
+    let expanded_pattern = shellexpand::full(pattern)
+        .map_err(|e| {
+            crate::error::FileIoMcpError::from(FileIoError::InvalidPath(format!(
+                "Failed to expand path \'{}\': {}",
+                pattern, e
+            )))
+        })
+        .map(|expanded| expanded.into_owned())?;
+    let absolute = std::path::absolute(&expanded_pattern)
+        .map_err(|e| FileIoError::from_io_error("resolve", &expanded_pattern, e))?;
+
+    let mut matches = if recursive {
+        expand_recursive(&absolute)?
+    } else {
+        let base = absolute.parent().unwrap_or(Path::new("/"));
+        if !base.is_dir() {
+            return Ok(Vec::new());
+        }
+        let absolute_str = absolute.to_str().ok_or_else(|| {
+            FileIoError::InvalidPath(format!("Path is not valid UTF-8: {}", absolute.display()))
+        })?;
+        expand_glob(absolute_str)?
+    };
+    matches.sort();
+
+    Ok(matches
+        .into_iter()
+        .map(|p| p.to_string_lossy().into_owned())
+        .collect())
+}
+
+/// Walk from the longest wildcard-free prefix of `pattern` and keep every
+/// entry whose full path matches it.
+fn expand_recursive(pattern: &Path) -> Result<Vec<PathBuf>> {
+    let pattern_str = pattern.to_str().ok_or_else(|| {
+        FileIoError::InvalidPath(format!("Path is not valid UTF-8: {}", pattern.display()))
+    })?;
+    let matcher = GlobBuilder::new(pattern_str)
+        .literal_separator(true)
+        .build()
+        .map_err(|e| {
+            FileIoError::InvalidPath(format!("Invalid glob pattern {}: {}", pattern_str, e))
+        })?
+        .compile_matcher();
+
+    let mut base = PathBuf::new();
+    for component in pattern.components() {
+        if let Component::Normal(name) = component
+            && is_glob_pattern(&name.to_string_lossy())
+        {
+            break;
+        }
+        base.push(component);
+    }
+    if base.as_os_str().is_empty() || !base.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut walker = WalkBuilder::new(&base);
+    walker.standard_filters(false).follow_links(false);
+    let mut matches = Vec::new();
+    for result in walker.build() {
+        let entry = result
+            .map_err(|e| FileIoError::ReadError(format!("Error walking directory: {}", e)))?;
+        if matcher.is_match(entry.path()) {
+            matches.push(entry.into_path());
+        }
+    }
+    Ok(matches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_expand_txt_in_dir() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("b.txt"), "").unwrap();
+        fs::write(dir.path().join("a.txt"), "").unwrap();
+        fs::write(dir.path().join("c.log"), "").unwrap();
+        fs::create_dir_all(dir.path().join("sub")).unwrap();
+        fs::write(dir.path().join("sub").join("d.txt"), "").unwrap();
+
+        let pattern = format!("{}/*.txt", dir.path().display());
+        let paths = expand_glob_paths(&pattern, false).unwrap();
+        assert_eq!(
+            paths,
+            vec![
+                dir.path().join("a.txt").to_string_lossy().into_owned(),
+                dir.path().join("b.txt").to_string_lossy().into_owned(),
+            ]
+        );
+
+        let pattern = format!("{}/**/*.txt", dir.path().display());
+        let paths = expand_glob_paths(&pattern, true).unwrap();
+        assert_eq!(paths.len(), 3);
+        assert!(paths.iter().any(|p| p.ends_with("sub/d.txt")));
+    }
+
+    #[test]
+    fn test_no_matches_is_empty_not_error() {
+        let dir = TempDir::new().unwrap();
+        let pattern = format!("{}/*.none", dir.path().display());
+        assert!(expand_glob_paths(&pattern, false).unwrap().is_empty());
+
+        let pattern = format!("{}/missing/*.txt", dir.path().display());
+        assert!(expand_glob_paths(&pattern, false).unwrap().is_empty());
+        assert!(expand_glob_paths(&pattern, true).unwrap().is_empty());
+    }
+}
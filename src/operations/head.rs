@@ -0,0 +1,49 @@
+#![deny(warnings)]
+
+// Return the first lines of a file (head equivalent)
+
+use crate::error::Result;
+
+/// Number of lines [`head`] returns when the caller gives none.
+pub const DEFAULT_HEAD_LINES: u64 = 10;
+
+/// Return the first `line_count` lines of `path`: all of them when the file
+/// is shorter, none for an empty file.
+///
+/// A thin wrapper over [`super::read_lines::read_lines`], so it clamps the
+/// same way and stops reading once the window is full.
+pub fn head(path: &str, line_count: u64) -> Result<Vec<String>> {
+    super::read_lines::read_lines(path, None, None, Some(line_count), None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_head_clamps_to_file_length() {
+        let dir = TempDir::new().expect("create temp dir");
+        let path = dir.path().join("a.txt");
+        fs::write(&path, "1\n2\n3\n").expect("write fixture");
+        let p = path.to_str().expect("utf-8 path");
+
+        assert_eq!(head(p, 2).expect("head succeeds"), vec!["1", "2"]);
+        assert_eq!(
+            head(p, DEFAULT_HEAD_LINES).expect("head succeeds"),
+            vec!["1", "2", "3"]
+        );
+        assert!(head(p, 0).expect("head succeeds").is_empty());
+    }
+
+    #[test]
+    fn test_head_of_empty_file() {
+        let dir = TempDir::new().expect("create temp dir");
+        let path = dir.path().join("empty.txt");
+        fs::write(&path, "").expect("write fixture");
+
+        let lines = head(path.to_str().expect("utf-8 path"), 10).expect("head succeeds");
+        assert!(lines.is_empty());
+    }
+}
@@ -0,0 +1,155 @@
+#![deny(warnings)]
+
+// Detect a file's line-ending style
+
+use crate::error::{FileIoError, Result};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+#[derive(Debug, PartialEq, Eq, serde::Serialize)]
+pub struct LineEndingStyle {
+    pub path: String,
+    /// `lf`, `crlf`, `cr`, `mixed`, or `none` (no line breaks at all).
+    pub style: String,
+    pub lf: u64,
+    pub crlf: u64,
+    pub cr: u64,
+}
+
+/// Running tally of line terminators, fed in arbitrary chunks.
+#[derive(Debug, Default)]
+struct Counter {
+    lf: u64,
+    crlf: u64,
+    cr: u64,
+    /// A `\r` ended the previous chunk; whether it is CR or CRLF depends on
+    /// the next byte.
+    pending_cr: bool,
+}
+
+impl Counter {
+    fn feed(&mut self, chunk: &[u8]) {
+        for &b in chunk {
+            if self.pending_cr {
+                self.pending_cr = false;
+                if b == b'\n' {
+                    self.crlf += 1;
+                    continue;
+                }
+                self.cr += 1;
+            }
+            match b {
+                b'\r' => self.pending_cr = true,
+                b'\n' => self.lf += 1,
+                _ => {}
+            }
+        }
+    }
+
+    fn finish(mut self) -> (u64, u64, u64) {
+        if self.pending_cr {
+            self.cr += 1;
+        }
+        (self.lf, self.crlf, self.cr)
+    }
+}
+
+/// Report the dominant line-ending style of `path` with per-kind counts.
+///
+/// The file is scanned in fixed-size chunks, so memory use does not grow with
+/// file size. A file with more than one kind of terminator is `mixed`.
+pub fn line_ending_style(path: &str) -> Result<LineEndingStyle> {
+    let expanded_path = shellexpand::full(path)
+        .map_err(|e| {
+            crate::error::FileIoMcpError::from(FileIoError::InvalidPath(format!(
+                "Failed to expand path \'{}\': {}",
+                path, e
+            )))
+        })
+        .map(|expanded| expanded.into_owned())?;
+    let path_obj = Path::new(&expanded_path);
+    if !path_obj.exists() {
+        return Err(super::path_utils::not_found("read file", path).into());
+    }
+    if !path_obj.is_file() {
+        return Err(FileIoError::InvalidPath(format!("{} is not a file", expanded_path)).into());
+    }
+
+    let mut file = File::open(path_obj)
+        .map_err(|e| FileIoError::from_io_error("open file", &expanded_path, e))?;
+    let mut counter = Counter::default();
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let n = file
+            .read(&mut buf)
+            .map_err(|e| FileIoError::from_io_error("read file", &expanded_path, e))?;
+        if n == 0 {
+            break;
+        }
+        counter.feed(&buf[..n]);
+    }
+    let (lf, crlf, cr) = counter.finish();
+
+    let style = match (lf > 0, crlf > 0, cr > 0) {
+        (false, false, false) => "none",
+        (true, false, false) => "lf",
+        (false, true, false) => "crlf",
+        (false, false, true) => "cr",
+        _ => "mixed",
+    };
+
+    Ok(LineEndingStyle {
+        path: expanded_path,
+        style: style.to_string(),
+        lf,
+        crlf,
+        cr,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn style_of(content: &[u8]) -> LineEndingStyle {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("f.txt");
+        fs::write(&path, content).unwrap();
+        line_ending_style(path.to_str().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_pure_lf() {
+        let result = style_of(b"a\nb\nc\n");
+        assert_eq!(result.style, "lf");
+        assert_eq!((result.lf, result.crlf, result.cr), (3, 0, 0));
+    }
+
+    #[test]
+    fn test_pure_crlf() {
+        let result = style_of(b"a\r\nb\r\n");
+        assert_eq!(result.style, "crlf");
+        assert_eq!((result.lf, result.crlf, result.cr), (0, 2, 0));
+    }
+
+    #[test]
+    fn test_mixed_and_none() {
+        let result = style_of(b"a\r\nb\nc\rd");
+        assert_eq!(result.style, "mixed");
+        assert_eq!((result.lf, result.crlf, result.cr), (1, 1, 1));
+
+        assert_eq!(style_of(b"no breaks").style, "none");
+        assert_eq!(style_of(b"old mac\r").style, "cr");
+    }
+
+    #[test]
+    fn test_crlf_split_across_chunks() {
+        let mut counter = Counter::default();
+        counter.feed(b"a\r");
+        counter.feed(b"\nb\r");
+        assert_eq!(counter.finish(), (0, 1, 1));
+    }
+}
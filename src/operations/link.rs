@@ -27,47 +27,23 @@ pub fn hard_link(target: &str, link_path: &str) -> Result<()> {
     let target_path = Path::new(&expanded_target);
 
     if !target_path.exists() {
-        return Err(FileIoError::NotFound(expanded_target.to_string()).into());
+        return Err(super::path_utils::not_found("link to", target).into());
     }
 
     // Create parent directories if needed
     let link_path_obj = Path::new(&expanded_link);
     if let Some(parent) = link_path_obj.parent() {
         fs::create_dir_all(parent).map_err(|e| {
-            FileIoError::WriteError(format!(
-                "Failed to create parent directories for {}: {}",
-                expanded_link, e
-            ))
+            FileIoError::from_io_error("create parent directories for", &expanded_link, e)
         })?;
     }
 
     fs::hard_link(&expanded_target, &expanded_link).map_err(|e| {
-        use std::io::ErrorKind;
-        match e.kind() {
-            ErrorKind::PermissionDenied => {
-                crate::error::FileIoMcpError::from(FileIoError::PermissionDenied(format!(
-                    "Permission denied when creating hard link {} to {}: {}",
-                    expanded_link, expanded_target, e
-                )))
-            }
-            ErrorKind::NotFound => {
-                crate::error::FileIoMcpError::from(FileIoError::NotFound(format!(
-                    "Target not found when creating hard link: {}",
-                    expanded_target
-                )))
-            }
-            ErrorKind::AlreadyExists => {
-                crate::error::FileIoMcpError::from(FileIoError::WriteError(format!(
-                    "Hard link already exists: {}. Cannot create duplicate link to {}",
-                    expanded_link, expanded_target
-                )))
-            }
-            _ => crate::error::FileIoMcpError::from(FileIoError::from_io_error(
-                "create hard link",
-                &format!("{} to {}", expanded_link, expanded_target),
-                e,
-            )),
-        }
+        crate::error::FileIoMcpError::from(FileIoError::from_io_error(
+            "create hard link",
+            &format!("{} to {}", expanded_link, expanded_target),
+            e,
+        ))
     })?;
 
     Ok(())
@@ -88,10 +64,7 @@ pub fn symlink(target: &str, link_path: &str) -> Result<()> {
     let link_path_obj = Path::new(&expanded_link);
     if let Some(parent) = link_path_obj.parent() {
         fs::create_dir_all(parent).map_err(|e| {
-            FileIoError::WriteError(format!(
-                "Failed to create parent directories for {}: {}",
-                expanded_link, e
-            ))
+            FileIoError::from_io_error("create parent directories for", &expanded_link, e)
         })?;
     }
 
@@ -99,26 +72,11 @@ pub fn symlink(target: &str, link_path: &str) -> Result<()> {
     {
         use std::os::unix::fs::symlink;
         symlink(target, &expanded_link).map_err(|e| {
-            use std::io::ErrorKind;
-            match e.kind() {
-                ErrorKind::PermissionDenied => {
-                    crate::error::FileIoMcpError::from(FileIoError::PermissionDenied(format!(
-                        "Permission denied when creating symbolic link {} to {}: {}",
-                        expanded_link, target, e
-                    )))
-                }
-                ErrorKind::AlreadyExists => {
-                    crate::error::FileIoMcpError::from(FileIoError::WriteError(format!(
-                        "Symbolic link already exists: {}. Cannot create duplicate link to {}",
-                        expanded_link, target
-                    )))
-                }
-                _ => crate::error::FileIoMcpError::from(FileIoError::from_io_error(
-                    "create symbolic link",
-                    &format!("{} to {}", expanded_link, target),
-                    e,
-                )),
-            }
+            crate::error::FileIoMcpError::from(FileIoError::from_io_error(
+                "create symbolic link",
+                &format!("{} to {}", expanded_link, target),
+                e,
+            ))
         })?;
     }
 
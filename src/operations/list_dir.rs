@@ -14,10 +14,47 @@ pub struct DirEntry {
     pub entry_type: String,
     pub size: Option<u64>,
     pub modified: Option<String>,
+    /// Permission bits as an octal string, set when `with_permissions` is
+    /// requested.
+    pub mode: Option<String>,
+    /// Owning user id (Unix, `with_permissions` only).
+    pub uid: Option<u32>,
+    /// Owning group id (Unix, `with_permissions` only).
+    pub gid: Option<u32>,
+    /// Raw link target as stored in a symlink (`resolve_symlinks` only).
+    pub target: Option<String>,
+    /// Whether a symlink's target is missing (`resolve_symlinks` only).
+    pub broken: Option<bool>,
+}
+
+/// Options for [`list_directory_with`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ListDirOptions {
+    pub recursive: bool,
+    pub include_hidden: bool,
+    /// Populate `mode`/`uid`/`gid` from the metadata already read for each
+    /// entry. Off by default to keep listings small.
+    pub with_permissions: bool,
+    /// Report symlinks as `symlink` entries, with no size, together with their
+    /// `target` and whether it is `broken`. Without this a link to a file or
+    /// directory is listed as what it points to.
+    pub resolve_symlinks: bool,
 }
 
 /// List directory contents
 pub fn list_directory(path: &str, recursive: bool, include_hidden: bool) -> Result<Vec<DirEntry>> {
+    list_directory_with(
+        path,
+        &ListDirOptions {
+            recursive,
+            include_hidden,
+            ..ListDirOptions::default()
+        },
+    )
+}
+
+/// List directory contents with the full set of [`ListDirOptions`].
+pub fn list_directory_with(path: &str, options: &ListDirOptions) -> Result<Vec<DirEntry>> {
     let expanded_path = shellexpand::full(path)
         .map_err(|e| {
             crate::error::FileIoMcpError::from(crate::error::FileIoError::InvalidPath(format!(
@@ -41,19 +78,22 @@ pub fn list_directory(path: &str, recursive: bool, include_hidden: bool) -> Resu
 
     let mut entries = Vec::new();
 
-    if recursive {
-        collect_entries_recursive(path_obj, &mut entries, include_hidden)?;
+    if options.recursive {
+        collect_entries_recursive(path_obj, &mut entries, options)?;
     } else {
-        collect_entries(path_obj, &mut entries, include_hidden)?;
+        collect_entries(path_obj, &mut entries, options)?;
     }
 
     Ok(entries)
 }
 
-fn collect_entries(dir: &Path, entries: &mut Vec<DirEntry>, include_hidden: bool) -> Result<()> {
-    let dir_entries = fs::read_dir(dir).map_err(|e| {
-        FileIoError::ReadError(format!("Failed to read directory {}: {}", dir.display(), e))
-    })?;
+fn collect_entries(
+    dir: &Path,
+    entries: &mut Vec<DirEntry>,
+    options: &ListDirOptions,
+) -> Result<()> {
+    let dir_entries = fs::read_dir(dir)
+        .map_err(|e| FileIoError::from_io_error("read directory", &dir.display().to_string(), e))?;
 
     for entry in dir_entries {
         let entry = entry.map_err(|e| {
@@ -68,19 +108,21 @@ fn collect_entries(dir: &Path, entries: &mut Vec<DirEntry>, include_hidden: bool
             .to_string();
 
         // Skip hidden files if not including them
-        if !include_hidden && name.starts_with('.') {
+        if !options.include_hidden && name.starts_with('.') {
             continue;
         }
 
         let metadata = entry.metadata().map_err(|e| {
-            FileIoError::ReadError(format!(
-                "Failed to read metadata for {}: {}",
-                path.display(),
-                e
-            ))
+            FileIoError::from_io_error("read metadata for", &path.display().to_string(), e)
         })?;
 
-        let entry_type = if path.is_dir() {
+        let is_link = options.resolve_symlinks
+            && entry
+                .file_type()
+                .is_ok_and(|file_type| file_type.is_symlink());
+        let entry_type = if is_link {
+            "symlink"
+        } else if path.is_dir() {
             "directory"
         } else if path.is_file() {
             "file"
@@ -91,7 +133,7 @@ fn collect_entries(dir: &Path, entries: &mut Vec<DirEntry>, include_hidden: bool
         }
         .to_string();
 
-        let size = if path.is_file() {
+        let size = if !is_link && path.is_file() {
             Some(metadata.len())
         } else {
             None
@@ -103,28 +145,67 @@ fn collect_entries(dir: &Path, entries: &mut Vec<DirEntry>, include_hidden: bool
                 .map(|d| d.as_secs().to_string())
         });
 
+        let (mode, uid, gid) = if options.with_permissions {
+            permissions_of(&metadata)
+        } else {
+            (None, None, None)
+        };
+
+        let (target, broken) = if is_link {
+            let target = fs::read_link(&path).map_err(|e| {
+                FileIoError::from_io_error("read link", &path.display().to_string(), e)
+            })?;
+            // `exists` follows the link, so it is false exactly when the
+            // chain ends nowhere.
+            (
+                Some(target.to_string_lossy().into_owned()),
+                Some(!path.exists()),
+            )
+        } else {
+            (None, None)
+        };
+
         entries.push(DirEntry {
             name,
             path: path.to_string_lossy().to_string(),
             entry_type,
             size,
             modified,
+            mode,
+            uid,
+            gid,
+            target,
+            broken,
         });
     }
 
     Ok(())
 }
 
+#[cfg(unix)]
+fn permissions_of(metadata: &fs::Metadata) -> (Option<String>, Option<u32>, Option<u32>) {
+    use std::os::unix::fs::MetadataExt;
+    (
+        Some(format!("{:04o}", metadata.mode() & 0o7777)),
+        Some(metadata.uid()),
+        Some(metadata.gid()),
+    )
+}
+
+#[cfg(not(unix))]
+fn permissions_of(_metadata: &fs::Metadata) -> (Option<String>, Option<u32>, Option<u32>) {
+    (None, None, None)
+}
+
 fn collect_entries_recursive(
     dir: &Path,
     entries: &mut Vec<DirEntry>,
-    include_hidden: bool,
+    options: &ListDirOptions,
 ) -> Result<()> {
-    collect_entries(dir, entries, include_hidden)?;
+    collect_entries(dir, entries, options)?;
 
-    let dir_entries = fs::read_dir(dir).map_err(|e| {
-        FileIoError::ReadError(format!("Failed to read directory {}: {}", dir.display(), e))
-    })?;
+    let dir_entries = fs::read_dir(dir)
+        .map_err(|e| FileIoError::from_io_error("read directory", &dir.display().to_string(), e))?;
 
     for entry in dir_entries {
         let entry = entry.map_err(|e| {
@@ -132,7 +213,8 @@ fn collect_entries_recursive(
         })?;
 
         let path = entry.path();
-        if path.is_dir() {
+        // A link already listed as `symlink` is not descended into.
+        if path.is_dir() && !(options.resolve_symlinks && path.is_symlink()) {
             let name = path
                 .file_name()
                 .and_then(|n| n.to_str())
@@ -140,11 +222,11 @@ fn collect_entries_recursive(
                 .to_string();
 
             // Skip hidden directories if not including them
-            if !include_hidden && name.starts_with('.') {
+            if !options.include_hidden && name.starts_with('.') {
                 continue;
             }
 
-            collect_entries_recursive(&path, entries, include_hidden)?;
+            collect_entries_recursive(&path, entries, options)?;
         }
     }
 
@@ -163,6 +245,21 @@ impl From<DirEntry> for Value {
         if let Some(modified) = entry.modified {
             obj.insert("modified".to_string(), Value::String(modified));
         }
+        if let Some(mode) = entry.mode {
+            obj.insert("mode".to_string(), Value::String(mode));
+        }
+        if let Some(uid) = entry.uid {
+            obj.insert("uid".to_string(), Value::Number(uid.into()));
+        }
+        if let Some(gid) = entry.gid {
+            obj.insert("gid".to_string(), Value::Number(gid.into()));
+        }
+        if let Some(target) = entry.target {
+            obj.insert("target".to_string(), Value::String(target));
+        }
+        if let Some(broken) = entry.broken {
+            obj.insert("broken".to_string(), Value::Bool(broken));
+        }
         Value::Object(obj)
     }
 }
@@ -199,4 +296,74 @@ mod tests {
         assert!(entries.iter().any(|e| e.path.contains("subdir")));
         assert!(entries.iter().any(|e| e.path.contains("file.txt")));
     }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_list_directory_with_permissions_reports_mode() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("file.txt");
+        fs::write(&file, "content").unwrap();
+        fs::set_permissions(&file, fs::Permissions::from_mode(0o640)).unwrap();
+
+        let entries = list_directory_with(
+            dir.path().to_str().unwrap(),
+            &ListDirOptions {
+                with_permissions: true,
+                ..ListDirOptions::default()
+            },
+        )
+        .unwrap();
+        let entry = entries.iter().find(|e| e.name == "file.txt").unwrap();
+        assert_eq!(entry.mode.as_deref(), Some("0640"));
+        assert_eq!(entry.uid, Some(nix::unistd::geteuid().as_raw()));
+        assert!(entry.gid.is_some());
+
+        // Off by default.
+        let plain = list_directory(dir.path().to_str().unwrap(), false, false).unwrap();
+        assert!(plain[0].mode.is_none());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_list_directory_resolve_symlinks() {
+        use std::os::unix::fs::symlink;
+
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("real.txt"), "content").unwrap();
+        symlink("real.txt", dir.path().join("good")).unwrap();
+        symlink("missing.txt", dir.path().join("dangling")).unwrap();
+        let p = dir.path().to_str().unwrap();
+
+        let entries = list_directory_with(
+            p,
+            &ListDirOptions {
+                resolve_symlinks: true,
+                ..ListDirOptions::default()
+            },
+        )
+        .unwrap();
+        let find = |name: &str| entries.iter().find(|e| e.name == name).unwrap();
+
+        let good = find("good");
+        assert_eq!(good.entry_type, "symlink");
+        assert_eq!(good.target.as_deref(), Some("real.txt"));
+        assert_eq!(good.broken, Some(false));
+        assert_eq!(good.size, None);
+
+        let dangling = find("dangling");
+        assert_eq!(dangling.target.as_deref(), Some("missing.txt"));
+        assert_eq!(dangling.broken, Some(true));
+
+        let real = find("real.txt");
+        assert_eq!(real.entry_type, "file");
+        assert_eq!(real.target, None);
+
+        // Without the option a link to a file is listed as a file.
+        let plain = list_directory(p, false, false).unwrap();
+        let good = plain.iter().find(|e| e.name == "good").unwrap();
+        assert_eq!(good.entry_type, "file");
+        assert!(good.target.is_none());
+    }
 }
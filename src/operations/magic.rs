@@ -0,0 +1,175 @@
+#![deny(warnings)]
+
+// Identify a file by its leading "magic number" bytes
+
+use crate::error::{FileIoError, Result};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// Default number of leading bytes [`magic`] returns.
+pub const DEFAULT_MAGIC_BYTES: usize = 16;
+/// Largest `count` [`magic`] accepts.
+pub const MAX_MAGIC_BYTES: usize = 4096;
+
+/// Bytes read for detection regardless of `count`; the deepest signature
+/// checked is tar's `ustar` at offset 257.
+const DETECT_LEN: usize = 265;
+
+#[derive(Debug, PartialEq, Eq, serde::Serialize)]
+pub struct MagicResult {
+    pub path: String,
+    /// Leading bytes as lowercase hex; shorter than requested when the file is.
+    pub hex: String,
+    pub bytes_read: u64,
+    /// Best-effort format label, absent when no known signature matches.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+}
+
+/// Known signatures: (offset, bytes, label). Checked in order, so more
+/// specific entries come before shorter prefixes they share.
+const SIGNATURES: &[(usize, &[u8], &str)] = &[
+    // First: a tar member's name could start with any short signature below.
+    (257, b"ustar", "tar archive"),
+    (0, b"\x89PNG\r\n\x1a\n", "PNG image"),
+    (0, b"\xff\xd8\xff", "JPEG image"),
+    (0, b"GIF87a", "GIF image"),
+    (0, b"GIF89a", "GIF image"),
+    (0, b"BM", "BMP image"),
+    (0, b"\x00\x00\x01\x00", "ICO image"),
+    (0, b"%PDF-", "PDF document"),
+    (0, b"%!PS", "PostScript document"),
+    (0, b"PK\x03\x04", "ZIP archive (also docx/xlsx/jar/apk)"),
+    (0, b"PK\x05\x06", "ZIP archive (empty)"),
+    (0, b"\x1f\x8b", "gzip compressed data"),
+    (0, b"BZh", "bzip2 compressed data"),
+    (0, b"\xfd7zXZ\x00", "xz compressed data"),
+    (0, b"\x28\xb5\x2f\xfd", "Zstandard compressed data"),
+    (0, b"7z\xbc\xaf\x27\x1c", "7-Zip archive"),
+    (0, b"Rar!\x1a\x07", "RAR archive"),
+    (0, b"\x7fELF", "ELF executable"),
+    (0, b"\xfe\xed\xfa\xce", "Mach-O executable"),
+    (0, b"\xfe\xed\xfa\xcf", "Mach-O executable"),
+    (0, b"\xce\xfa\xed\xfe", "Mach-O executable"),
+    (0, b"\xcf\xfa\xed\xfe", "Mach-O executable"),
+    (
+        0,
+        b"\xca\xfe\xba\xbe",
+        "Mach-O universal binary or Java class",
+    ),
+    (0, b"MZ", "DOS/Windows executable"),
+    (0, b"\x00asm", "WebAssembly binary"),
+    (0, b"SQLite format 3\x00", "SQLite database"),
+    (0, b"OggS", "Ogg media"),
+    (0, b"fLaC", "FLAC audio"),
+    (0, b"ID3", "MP3 audio (ID3 tag)"),
+    (0, b"\x1aE\xdf\xa3", "Matroska/WebM media"),
+    (0, b"\xef\xbb\xbf", "UTF-8 text (with BOM)"),
+    (0, b"\xff\xfe", "UTF-16LE text (with BOM)"),
+    (0, b"\xfe\xff", "UTF-16BE text (with BOM)"),
+    (0, b"<?xml", "XML document"),
+    (0, b"#!", "script (shebang)"),
+];
+
+/// Return the first `count` bytes of `path` as hex plus a best-effort label
+/// for the format its leading signature indicates.
+///
+/// Detection always looks at the first few hundred bytes, independent of
+/// `count`, so asking for fewer bytes never changes the label.
+///
+/// Why: a fixed signature table rather than a MIME database — it answers
+/// "what is this" for the formats agents actually meet, with no data files
+/// to ship or keep current.
+pub fn magic(path: &str, count: usize) -> Result<MagicResult> {
+    if count == 0 || count > MAX_MAGIC_BYTES {
+        return Err(crate::error::FileIoMcpError::InvalidParams(format!(
+            "count must be between 1 and {}, got {}",
+            MAX_MAGIC_BYTES, count
+        )));
+    }
+    let expanded_path = shellexpand::full(path)
+        .map_err(|e| {
+            crate::error::FileIoMcpError::from(FileIoError::InvalidPath(format!(
+                "Failed to expand path \'{}\': {}",
+                path, e
+            )))
+        })
+        .map(|expanded| expanded.into_owned())?;
+    let path_obj = Path::new(&expanded_path);
+    if !path_obj.exists() {
+        return Err(super::path_utils::not_found("read file", path).into());
+    }
+    if !path_obj.is_file() {
+        return Err(FileIoError::InvalidPath(format!("{} is not a file", expanded_path)).into());
+    }
+
+    let file = File::open(path_obj)
+        .map_err(|e| FileIoError::from_io_error("open file", &expanded_path, e))?;
+    let mut head = Vec::with_capacity(count.max(DETECT_LEN));
+    file.take(count.max(DETECT_LEN) as u64)
+        .read_to_end(&mut head)
+        .map_err(|e| FileIoError::from_io_error("read file", &expanded_path, e))?;
+
+    let shown = &head[..count.min(head.len())];
+    Ok(MagicResult {
+        path: expanded_path,
+        hex: super::checksum::to_hex(shown),
+        bytes_read: shown.len() as u64,
+        format: detect(&head).map(str::to_string),
+    })
+}
+
+/// Label for the first signature in [`SIGNATURES`] that `head` matches.
+fn detect(head: &[u8]) -> Option<&'static str> {
+    SIGNATURES
+        .iter()
+        .find(|(offset, sig, _)| {
+            head.get(*offset..offset + sig.len())
+                .is_some_and(|bytes| bytes == *sig)
+        })
+        .map(|(_, _, label)| *label)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_png_signature() {
+        let dir = TempDir::new().expect("create temp dir");
+        let path = dir.path().join("image.bin");
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        png.extend_from_slice(b"\x00\x00\x00\x0dIHDR\x00\x00\x00\x01");
+        fs::write(&path, &png).expect("write fixture");
+
+        let result = magic(path.to_str().expect("utf-8 path"), 4).expect("magic succeeds");
+        assert_eq!(result.hex, "89504e47");
+        assert_eq!(result.bytes_read, 4);
+        assert_eq!(result.format.as_deref(), Some("PNG image"));
+    }
+
+    #[test]
+    fn test_short_file_is_clamped() {
+        let dir = TempDir::new().expect("create temp dir");
+        let path = dir.path().join("tiny");
+        fs::write(&path, "hi").expect("write fixture");
+
+        let result =
+            magic(path.to_str().expect("utf-8 path"), DEFAULT_MAGIC_BYTES).expect("magic succeeds");
+        assert_eq!(result.hex, "6869");
+        assert_eq!(result.bytes_read, 2);
+        assert_eq!(result.format, None);
+    }
+
+    #[test]
+    fn test_tar_signature_beyond_count() {
+        let mut head = vec![0u8; 512];
+        head[257..262].copy_from_slice(b"ustar");
+        assert_eq!(detect(&head), Some("tar archive"));
+        assert_eq!(detect(b"\x7fELF\x02\x01"), Some("ELF executable"));
+        assert_eq!(detect(b""), None);
+    }
+}
@@ -4,6 +4,8 @@
 
 use crate::error::{FileIoError, Result};
 use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
 
 /// Create directories (with -p equivalent, i.e., create parent directories)
 /// Can accept a single path or multiple paths
@@ -45,8 +47,8 @@ pub fn mkdir_single(path: &str, recursive: bool) -> Result<()> {
             use std::io::ErrorKind;
             match e.kind() {
                 ErrorKind::AlreadyExists => {
-                    FileIoError::WriteError(format!(
-                        "Directory already exists: {}. Use recursive=true to create parent directories or if directory may already exist",
+                    FileIoError::AlreadyExists(format!(
+                        "{}. Use recursive=true to create parent directories or if directory may already exist",
                         expanded_path
                     )).into()
                 }
@@ -58,6 +60,90 @@ pub fn mkdir_single(path: &str, recursive: bool) -> Result<()> {
     Ok(())
 }
 
+#[derive(Debug, serde::Serialize)]
+pub struct EnsureDirResult {
+    pub path: String,
+    /// `created`, `chmodded`, `unchanged`, or `mode_differs` (the directory
+    /// exists with another mode and fixing it was not requested).
+    pub status: String,
+    /// The directory's mode afterwards, when a mode was requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+}
+
+/// Make sure `path` is a directory, optionally with permission bits `mode`.
+///
+/// A missing directory is created with its parents and then given `mode`
+/// explicitly, so the result doesn't depend on the process umask. An
+/// existing directory with a different mode is `chmod`ed only when
+/// `fix_existing_mode` is set. Running it again is a no-op that reports
+/// `unchanged`.
+pub fn ensure_dir(
+    path: &str,
+    mode: Option<&str>,
+    fix_existing_mode: bool,
+) -> Result<EnsureDirResult> {
+    let mode_value = mode.map(super::file_mode::parse_mode).transpose()?;
+    let expanded_path = shellexpand::full(path)
+        .map_err(|e| {
+            crate::error::FileIoMcpError::from(crate::error::FileIoError::InvalidPath(format!(
+                "Failed to expand path \'{}\': {}",
+                path, e
+            )))
+        })
+        .map(|expanded| expanded.into_owned())?;
+    let path_obj = Path::new(&expanded_path);
+
+    let set_mode = |bits: u32| -> Result<()> {
+        fs::set_permissions(path_obj, fs::Permissions::from_mode(bits)).map_err(|e| {
+            FileIoError::from_io_error("set permissions for", &expanded_path, e).into()
+        })
+    };
+
+    let status = if path_obj.exists() {
+        if !path_obj.is_dir() {
+            return Err(FileIoError::NotADirectory(expanded_path).into());
+        }
+        let current = fs::metadata(path_obj)
+            .map_err(|e| FileIoError::from_io_error("read metadata", &expanded_path, e))?
+            .permissions()
+            .mode()
+            & 0o7777;
+        match mode_value {
+            Some(bits) if bits & 0o7777 != current && fix_existing_mode => {
+                set_mode(bits)?;
+                "chmodded"
+            }
+            Some(bits) if bits & 0o7777 != current => "mode_differs",
+            _ => "unchanged",
+        }
+    } else {
+        fs::create_dir_all(path_obj)
+            .map_err(|e| FileIoError::from_io_error("create directory", &expanded_path, e))?;
+        if let Some(bits) = mode_value {
+            set_mode(bits)?;
+        }
+        "created"
+    };
+
+    let mode = match mode_value {
+        Some(_) => {
+            let bits = fs::metadata(path_obj)
+                .map_err(|e| FileIoError::from_io_error("read metadata", &expanded_path, e))?
+                .permissions()
+                .mode();
+            Some(format!("{:04o}", bits & 0o7777))
+        }
+        None => None,
+    };
+
+    Ok(EnsureDirResult {
+        path: expanded_path,
+        status: status.to_string(),
+        mode,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -92,4 +178,42 @@ mod tests {
         mkdir(&[&path], true).unwrap();
         assert!(std::path::Path::new(&path).exists());
     }
+
+    #[test]
+    fn test_ensure_dir_corrects_wrong_mode() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config");
+        fs::create_dir(&path).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        let p = path.to_str().unwrap();
+
+        let report = ensure_dir(p, Some("0700"), false).unwrap();
+        assert_eq!(report.status, "mode_differs");
+        assert_eq!(report.mode.as_deref(), Some("0755"));
+
+        let report = ensure_dir(p, Some("0700"), true).unwrap();
+        assert_eq!(report.status, "chmodded");
+        assert_eq!(report.mode.as_deref(), Some("0700"));
+        assert_eq!(
+            fs::metadata(&path).unwrap().permissions().mode() & 0o7777,
+            0o700
+        );
+
+        let report = ensure_dir(p, Some("0700"), true).unwrap();
+        assert_eq!(report.status, "unchanged");
+    }
+
+    #[test]
+    fn test_ensure_dir_creates_with_mode() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("a").join("b");
+
+        let report = ensure_dir(path.to_str().unwrap(), Some("0700"), true).unwrap();
+        assert_eq!(report.status, "created");
+        assert!(path.is_dir());
+        assert_eq!(
+            fs::metadata(&path).unwrap().permissions().mode() & 0o7777,
+            0o700
+        );
+    }
 }
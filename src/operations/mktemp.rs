@@ -21,10 +21,7 @@ pub fn mktemp_file(template: Option<&str>) -> Result<String> {
         let path = Path::new(&expanded_tmpl);
         let parent = path.parent().unwrap_or(Path::new("."));
         std::fs::create_dir_all(parent).map_err(|e| {
-            FileIoError::WriteError(format!(
-                "Failed to create parent directory for template {}: {}",
-                expanded_tmpl, e
-            ))
+            FileIoError::from_io_error("create parent directory for template", &expanded_tmpl, e)
         })?;
         let file = NamedTempFile::new_in(parent).map_err(|e| {
             FileIoError::WriteError(format!("Failed to create temporary file: {}", e))
@@ -61,10 +58,7 @@ pub fn mktemp_dir(template: Option<&str>) -> Result<String> {
         let path = Path::new(&expanded_tmpl);
         let parent = path.parent().unwrap_or(Path::new("."));
         std::fs::create_dir_all(parent).map_err(|e| {
-            FileIoError::WriteError(format!(
-                "Failed to create parent directory for template {}: {}",
-                expanded_tmpl, e
-            ))
+            FileIoError::from_io_error("create parent directory for template", &expanded_tmpl, e)
         })?;
         let dir = TempDir::new_in(parent).map_err(|e| {
             FileIoError::WriteError(format!("Failed to create temporary directory: {}", e))
@@ -2,25 +2,49 @@
 
 // File I/O operation implementations
 
+pub mod allocate;
+pub mod bom;
+pub mod checksum;
 pub mod chown;
+pub mod content_equals;
+pub mod convert_tabs;
 pub mod count_lines;
 pub mod count_words;
 pub mod cp;
+pub mod disk_usage;
 pub mod edit_file;
 pub mod file_find;
 pub mod file_mode;
 pub mod find_in_files;
+pub mod format_json;
 pub mod get_mode;
+pub mod glob_expand;
+pub mod head;
+pub mod line_endings;
 pub mod link;
 pub mod list_dir;
+pub mod magic;
 pub mod mkdir;
 pub mod mktemp;
 pub mod mv;
 pub mod path_utils;
+pub mod position;
+pub mod prepend;
 pub mod pwd;
+pub mod read_bytes;
+pub mod read_file;
 pub mod read_lines;
+pub mod rename_pattern;
+pub mod replace_markers;
 pub mod rm;
 pub mod rmdir;
 pub mod stat;
+pub mod strip_whitespace;
+pub mod tail_follow;
+pub mod temp_info;
 pub mod touch;
+pub mod transaction;
+pub mod truncate;
+pub mod validate;
+pub mod watch_dir;
 pub mod write_file;
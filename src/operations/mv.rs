@@ -3,70 +3,10 @@
 // Move or rename files or directories
 
 use crate::error::{FileIoError, Result};
-use globset::{Glob, GlobMatcher};
 use std::fs;
 use std::path::{Path, PathBuf};
 
-/// Check if a string contains glob patterns
-fn is_glob_pattern(s: &str) -> bool {
-    s.contains('*') || s.contains('?') || s.contains('[') || s.contains('{')
-}
-
-/// Expand glob pattern to matching paths
-fn expand_glob(pattern: &str) -> Result<Vec<PathBuf>> {
-    let expanded_pattern = shellexpand::full(pattern)
-        .map_err(|e| {
-            crate::error::FileIoMcpError::from(crate::error::FileIoError::InvalidPath(format!(
-                "Failed to expand path \'{}\': {}",
-                pattern, e
-            )))
-        })
-        .map(|expanded| expanded.into_owned())?;
-    let path = Path::new(&expanded_pattern);
-    let (base_dir, glob_str) = if let Some(parent) = path.parent() {
-        if parent.as_os_str().is_empty() {
-            (
-                Path::new("."),
-                path.file_name().and_then(|n| n.to_str()).unwrap_or(pattern),
-            )
-        } else {
-            (
-                parent,
-                path.file_name().and_then(|n| n.to_str()).unwrap_or(pattern),
-            )
-        }
-    } else {
-        (Path::new("."), pattern)
-    };
-
-    let glob = Glob::new(glob_str).map_err(|e| {
-        FileIoError::InvalidPath(format!("Invalid glob pattern {}: {}", pattern, e))
-    })?;
-    let matcher: GlobMatcher = glob.compile_matcher();
-
-    let mut matches = Vec::new();
-    let entries = fs::read_dir(base_dir).map_err(|e| {
-        FileIoError::ReadError(format!(
-            "Failed to read directory {}: {}",
-            base_dir.display(),
-            e
-        ))
-    })?;
-
-    for entry in entries {
-        let entry = entry.map_err(|e| {
-            FileIoError::ReadError(format!("Failed to read directory entry: {}", e))
-        })?;
-        let entry_path = entry.path();
-        if let Some(file_name) = entry_path.file_name().and_then(|n| n.to_str())
-            && matcher.is_match(file_name)
-        {
-            matches.push(entry_path);
-        }
-    }
-
-    Ok(matches)
-}
+use super::glob_expand::{expand_glob, is_glob_pattern};
 
 /// Move or rename files or directories (supports glob patterns and arrays of paths)
 #[derive(Debug, serde::Serialize)]
@@ -79,6 +19,93 @@ pub struct OpResult {
 /// Move or rename files or directories (supports glob patterns and arrays of paths)
 /// Returns per-source results and does not fail the whole call for per-file errors.
 pub fn mv(sources: &[&str], destination: &str) -> Result<Vec<OpResult>> {
+    mv_impl(sources, destination, false).map(|report| report.results)
+}
+
+/// A destination that more than one source would land on, or that already
+/// exists and would be overwritten.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct TransferConflict {
+    pub destination: String,
+    /// Whether `destination` exists before the operation runs.
+    pub exists: bool,
+    /// Every source that maps to `destination`, in input order.
+    pub sources: Vec<String>,
+}
+
+/// Per-source results of a `cp`/`mv` plus the conflicts found while planning
+/// it.
+#[derive(Debug, serde::Serialize)]
+pub struct TransferReport<R> {
+    pub results: Vec<R>,
+    pub conflicts: Vec<TransferConflict>,
+}
+
+/// Group planned `(source, destination)` pairs by destination and keep the
+/// ones that collide: the destination already exists, or two sources (e.g.
+/// `a/x.txt` and `b/x.txt` fanned into one directory) share it.
+pub(crate) fn find_conflicts(plan: &[(String, PathBuf)]) -> Vec<TransferConflict> {
+    let mut grouped: Vec<(&PathBuf, Vec<String>)> = Vec::new();
+    for (source, dest) in plan {
+        match grouped.iter_mut().find(|(d, _)| *d == dest) {
+            Some((_, sources)) => sources.push(source.clone()),
+            None => grouped.push((dest, vec![source.clone()])),
+        }
+    }
+    grouped
+        .into_iter()
+        .filter_map(|(dest, sources)| {
+            let exists = dest.symlink_metadata().is_ok();
+            (exists || sources.len() > 1).then(|| TransferConflict {
+                destination: dest.to_string_lossy().into_owned(),
+                exists,
+                sources,
+            })
+        })
+        .collect()
+}
+
+/// Where each source of a `cp`/`mv` into `destination` would land, without
+/// touching anything. Callers vet these before the op runs: with a directory
+/// destination the targets are children of it, which a check on
+/// `destination` alone doesn't cover. Inputs the op itself would reject
+/// (unmatched globs, unexpandable paths) yield no entries.
+pub fn planned_destinations(sources: &[&str], destination: &str) -> Vec<PathBuf> {
+    let Ok(expanded_dest) = shellexpand::full(destination) else {
+        return Vec::new();
+    };
+    let dest_path = Path::new(expanded_dest.as_ref());
+    if !dest_path.is_dir() {
+        return vec![dest_path.to_path_buf()];
+    }
+    super::cp::expand_sources(sources)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|source| Path::new(source).file_name())
+        .map(|name| dest_path.join(name))
+        .collect()
+}
+
+/// Like [`mv`], but with `dry_run` nothing is moved: each source that exists
+/// is reported as `would_move`.
+pub fn mv_with(sources: &[&str], destination: &str, dry_run: bool) -> Result<Vec<OpResult>> {
+    mv_impl(sources, destination, dry_run).map(|report| report.results)
+}
+
+/// Like [`mv_with`], but also reports destination conflicts.
+///
+/// Conflicts are computed from the plan before anything moves, so with
+/// `dry_run` an agent can see which sources would overwrite an existing path
+/// (or each other) and decide before committing.
+pub fn mv_reporting_conflicts(
+    sources: &[&str],
+    destination: &str,
+    dry_run: bool,
+) -> Result<TransferReport<OpResult>> {
+    mv_impl(sources, destination, dry_run)
+}
+
+fn mv_impl(sources: &[&str], destination: &str, dry_run: bool) -> Result<TransferReport<OpResult>> {
     let expanded_dest = shellexpand::full(destination)
         .map_err(|e| {
             crate::error::FileIoMcpError::from(crate::error::FileIoError::InvalidPath(format!(
@@ -127,10 +154,10 @@ pub fn mv(sources: &[&str], destination: &str) -> Result<Vec<OpResult>> {
         .into());
     }
 
-    let mut results = Vec::new();
-    for source_path in &all_sources {
+    let mut plan = Vec::new();
+    for source_path in all_sources {
         let dest = if dest_is_dir {
-            let source_path_obj = Path::new(source_path);
+            let source_path_obj = Path::new(&source_path);
             let file_name = source_path_obj.file_name().ok_or_else(|| {
                 FileIoError::InvalidPath(format!(
                     "Source path has no file name (is it the root?): {}",
@@ -141,17 +168,31 @@ pub fn mv(sources: &[&str], destination: &str) -> Result<Vec<OpResult>> {
         } else {
             dest_path.to_path_buf()
         };
+        plan.push((source_path, dest));
+    }
+    let conflicts = find_conflicts(&plan);
 
+    let mut results = Vec::new();
+    for (source_path, dest) in &plan {
         let dest_str = dest.to_str().ok_or_else(|| {
             FileIoError::InvalidPath(format!(
                 "Destination path is not valid UTF-8: {}",
                 dest.display()
             ))
         })?;
-        match mv_single(source_path, dest_str) {
-            Ok(()) => results.push(OpResult {
+        let outcome: Result<&str> = if dry_run {
+            if Path::new(source_path).exists() {
+                Ok("would_move")
+            } else {
+                Err(FileIoError::NotFound(source_path.clone()).into())
+            }
+        } else {
+            mv_single(source_path, dest_str).map(|()| "ok")
+        };
+        match outcome {
+            Ok(status) => results.push(OpResult {
                 path: source_path.clone(),
-                status: "ok".to_string(),
+                status: status.to_string(),
                 exists: true,
             }),
             Err(e) => {
@@ -168,7 +209,7 @@ pub fn mv(sources: &[&str], destination: &str) -> Result<Vec<OpResult>> {
         }
     }
 
-    Ok(results)
+    Ok(TransferReport { results, conflicts })
 }
 
 /// Move a single file or directory
@@ -183,31 +224,16 @@ fn mv_single(source: &str, destination: &str) -> Result<()> {
     let dest_path = Path::new(destination);
     if let Some(parent) = dest_path.parent() {
         fs::create_dir_all(parent).map_err(|e| {
-            FileIoError::WriteError(format!(
-                "Failed to create parent directories for {}: {}",
-                destination, e
-            ))
+            FileIoError::from_io_error("create parent directories for", destination, e)
         })?;
     }
 
     fs::rename(source, destination).map_err(|e| {
-        use std::io::ErrorKind;
-        match e.kind() {
-            ErrorKind::PermissionDenied => {
-                crate::error::FileIoMcpError::from(FileIoError::PermissionDenied(format!(
-                    "Permission denied when moving {} to {}: {}",
-                    source, destination, e
-                )))
-            }
-            ErrorKind::NotFound => crate::error::FileIoMcpError::from(FileIoError::NotFound(
-                format!("Source not found when moving: {}", source),
-            )),
-            _ => crate::error::FileIoMcpError::from(FileIoError::from_io_error(
-                "move",
-                &format!("{} to {}", source, destination),
-                e,
-            )),
-        }
+        crate::error::FileIoMcpError::from(FileIoError::from_io_error(
+            "move",
+            &format!("{} to {}", source, destination),
+            e,
+        ))
     })?;
 
     Ok(())
@@ -219,6 +245,25 @@ mod tests {
     use std::fs;
     use tempfile::TempDir;
 
+    #[test]
+    fn test_planned_destinations_into_directory() {
+        let dir = TempDir::new().unwrap();
+        let dest = dir.path().join("dest");
+        fs::create_dir(&dest).unwrap();
+        let dest_str = dest.to_str().unwrap();
+
+        assert_eq!(
+            planned_destinations(&["/tmp/x/.ssh", "a/b.txt"], dest_str),
+            vec![dest.join(".ssh"), dest.join("b.txt")]
+        );
+        // A non-directory destination is the target itself.
+        let file = dir.path().join("renamed.txt");
+        assert_eq!(
+            planned_destinations(&["a.txt"], file.to_str().unwrap()),
+            vec![file]
+        );
+    }
+
     #[test]
     fn test_mv_file() {
         let dir = TempDir::new().unwrap();
@@ -256,4 +301,37 @@ mod tests {
         assert!(dst_dir.join("file1.txt").exists());
         assert!(dst_dir.join("file2.txt").exists());
     }
+
+    #[test]
+    fn test_mv_dry_run_reports_conflicts_and_moves_nothing() {
+        let dir = TempDir::new().unwrap();
+        let base = dir.path();
+        fs::create_dir_all(base.join("a")).unwrap();
+        fs::create_dir_all(base.join("b")).unwrap();
+        fs::create_dir_all(base.join("dest")).unwrap();
+        let a = base.join("a").join("same.txt");
+        let b = base.join("b").join("same.txt");
+        fs::write(&a, "a").unwrap();
+        fs::write(&b, "b").unwrap();
+
+        let report = mv_reporting_conflicts(
+            &[a.to_str().unwrap(), b.to_str().unwrap()],
+            base.join("dest").to_str().unwrap(),
+            true,
+        )
+        .unwrap();
+
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(
+            report.conflicts[0].sources,
+            vec![
+                a.to_str().unwrap().to_string(),
+                b.to_str().unwrap().to_string()
+            ]
+        );
+        assert!(!report.conflicts[0].exists);
+        assert!(report.results.iter().all(|r| r.status == "would_move"));
+        assert!(a.exists());
+        assert!(b.exists());
+    }
 }
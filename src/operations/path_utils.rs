@@ -1,10 +1,46 @@
 #![deny(warnings)]
 
-// Path utility functions (basename, dirname, realpath, readlink)
+// Path utility functions (basename, dirname, realpath, readlink, is_within)
 
 use crate::error::{FileIoError, Result};
+use std::ffi::OsString;
 use std::fs;
-use std::path::Path;
+use std::path::{Component, Path, PathBuf};
+
+/// Expand `~` and environment variables in `path`, then anchor a relative
+/// result at the current working directory.
+///
+/// Why: relative paths resolve against the server's CWD, which is often not
+/// where the caller assumes. Operations that expand through here name the
+/// absolute path they actually tried in their results and errors, so a
+/// "not found" shows where the server looked. The path is only joined, not
+/// canonicalized: symlinks and missing components are left for the
+/// operation to deal with.
+pub fn resolve_path(path: &str) -> Result<String> {
+    let expanded = shellexpand::full(path).map_err(|e| {
+        crate::error::FileIoMcpError::from(FileIoError::InvalidPath(format!(
+            "Failed to expand path '{}': {}",
+            path, e
+        )))
+    })?;
+    let expanded_path = Path::new(expanded.as_ref());
+    if expanded_path.is_absolute() {
+        return Ok(expanded.into_owned());
+    }
+    let cwd = std::env::current_dir()
+        .map_err(|e| FileIoError::from_io_error("read working directory", path, e))?;
+    Ok(cwd.join(expanded_path).to_string_lossy().into_owned())
+}
+
+/// The error `operation` reports when `path` does not exist.
+///
+/// Why: every missing-path error, and the one reported for a denied path,
+/// is built here from the same resolved path and `from_io_error` wording,
+/// so a denial reads exactly like absence.
+pub fn not_found(operation: &str, path: &str) -> FileIoError {
+    let resolved = resolve_path(path).unwrap_or_else(|_| path.to_string());
+    FileIoError::from_io_error(operation, &resolved, std::io::ErrorKind::NotFound.into())
+}
 
 /// Get the basename (filename) from a path
 pub fn basename(path: &str) -> Result<String> {
@@ -67,7 +103,7 @@ pub fn realpath(path: &str) -> Result<String> {
     let path_obj = Path::new(&expanded_path);
 
     if !path_obj.exists() {
-        return Err(FileIoError::NotFound(expanded_path.to_string()).into());
+        return Err(not_found("canonicalize path", path).into());
     }
 
     let canonical = fs::canonicalize(&expanded_path).map_err(|e| {
@@ -87,8 +123,13 @@ pub fn realpath(path: &str) -> Result<String> {
     })
 }
 
-/// Read the target of a symbolic link
-pub fn readlink(path: &str) -> Result<String> {
+/// Like [`realpath`], but with `max_symlink_depth` set the path is resolved
+/// by [`resolve_symlinks`] instead of the OS, so a long or looping chain
+/// fails with [`FileIoError::SymlinkLoop`] at a predictable point.
+pub fn realpath_with_depth(path: &str, max_symlink_depth: Option<u64>) -> Result<String> {
+    let Some(max_depth) = max_symlink_depth else {
+        return realpath(path);
+    };
     let expanded_path = shellexpand::full(path)
         .map_err(|e| {
             crate::error::FileIoMcpError::from(crate::error::FileIoError::InvalidPath(format!(
@@ -97,6 +138,179 @@ pub fn readlink(path: &str) -> Result<String> {
             )))
         })
         .map(|expanded| expanded.into_owned())?;
+    let resolved = resolve_symlinks(Path::new(&expanded_path), max_depth)?;
+    resolved.to_str().map(|s| s.to_string()).ok_or_else(|| {
+        FileIoError::InvalidPath(format!(
+            "Path contains invalid UTF-8: {}",
+            resolved.display()
+        ))
+        .into()
+    })
+}
+
+/// One pending step of [`resolve_symlinks`].
+enum Step {
+    Root,
+    Parent,
+    Name(OsString),
+}
+
+fn push_steps(stack: &mut Vec<Step>, path: &Path) {
+    // The stack pops from the end, so push in reverse.
+    for component in path.components().rev() {
+        match component {
+            Component::RootDir | Component::Prefix(_) => stack.push(Step::Root),
+            Component::ParentDir => stack.push(Step::Parent),
+            Component::Normal(name) => stack.push(Step::Name(name.to_os_string())),
+            Component::CurDir => {}
+        }
+    }
+}
+
+/// Resolve `path` to an absolute path with no symlinks, one component at a
+/// time, following at most `max_depth` links in total.
+///
+/// Every component must exist. Why not `fs::canonicalize`: the OS limit on
+/// link chains (`ELOOP`) varies by platform and reports only "too many
+/// levels", not which path hit it; resolving by hand gives a fixed limit
+/// and an error naming the chain's start.
+pub fn resolve_symlinks(path: &Path, max_depth: u64) -> Result<PathBuf> {
+    resolve(path, max_depth, false)
+}
+
+/// Walk `path` component by component, following symlinks. With `lenient`,
+/// the first missing component and everything after it are applied
+/// lexically instead of failing, so `..` after a missing name cancels it.
+fn resolve(path: &Path, max_depth: u64, lenient: bool) -> Result<PathBuf> {
+    let display = path.display().to_string();
+    let absolute = std::path::absolute(path)
+        .map_err(|e| FileIoError::from_io_error("resolve", &display, e))?;
+
+    let mut stack = Vec::new();
+    push_steps(&mut stack, &absolute);
+    let mut resolved = PathBuf::new();
+    let mut followed = 0u64;
+    // Trailing components of `resolved` that do not exist on disk.
+    let mut missing = 0usize;
+    while let Some(step) = stack.pop() {
+        match step {
+            Step::Root => {
+                resolved = PathBuf::from("/");
+                missing = 0;
+            }
+            Step::Parent => {
+                resolved.pop();
+                missing = missing.saturating_sub(1);
+            }
+            Step::Name(name) => {
+                let candidate = resolved.join(&name);
+                if missing > 0 {
+                    resolved = candidate;
+                    missing += 1;
+                    continue;
+                }
+                let meta = match fs::symlink_metadata(&candidate) {
+                    Ok(meta) => meta,
+                    Err(e) if lenient && e.kind() == std::io::ErrorKind::NotFound => {
+                        resolved = candidate;
+                        missing = 1;
+                        continue;
+                    }
+                    Err(e) => {
+                        return Err(FileIoError::from_io_error(
+                            "lstat path",
+                            &candidate.display().to_string(),
+                            e,
+                        )
+                        .into());
+                    }
+                };
+                if !meta.file_type().is_symlink() {
+                    resolved = candidate;
+                    continue;
+                }
+                followed += 1;
+                if followed > max_depth {
+                    return Err(FileIoError::SymlinkLoop(format!(
+                        "{} (followed more than {} links)",
+                        display, max_depth
+                    ))
+                    .into());
+                }
+                let target = fs::read_link(&candidate).map_err(|e| {
+                    FileIoError::from_io_error(
+                        "read symbolic link",
+                        &candidate.display().to_string(),
+                        e,
+                    )
+                })?;
+                // A relative target continues from the link's directory,
+                // which is `resolved` as it stands.
+                push_steps(&mut stack, &target);
+            }
+        }
+    }
+    Ok(resolved)
+}
+
+/// Links [`is_within`] follows before giving up, matching Linux's
+/// `MAXSYMLINKS`.
+const IS_WITHIN_MAX_SYMLINKS: u64 = 40;
+
+/// Outcome of [`is_within`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct Containment {
+    pub within: bool,
+    /// `base` with every symlink resolved.
+    pub base: String,
+    /// `path` with every symlink on its existing prefix resolved.
+    pub path: String,
+    /// `path` relative to `base` (`.` for the base itself); only when
+    /// `within`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub relative: Option<String>,
+}
+
+/// Decide whether `path` stays inside `base` once both are resolved.
+///
+/// A relative `path` is taken relative to `base`. Symlinks are followed on
+/// the part of each path that exists, including dangling links, whose
+/// targets are then applied lexically like any other missing components, so
+/// neither `..` nor a link pointing elsewhere can escape unnoticed.
+pub fn is_within(base: &str, path: &str) -> Result<Containment> {
+    let expand = |p: &str| {
+        shellexpand::full(p)
+            .map_err(|e| {
+                crate::error::FileIoMcpError::from(FileIoError::InvalidPath(format!(
+                    "Failed to expand path '{}': {}",
+                    p, e
+                )))
+            })
+            .map(|expanded| PathBuf::from(expanded.into_owned()))
+    };
+    let base_path = expand(base)?;
+    let joined = base_path.join(expand(path)?);
+
+    let resolved_base = resolve(&base_path, IS_WITHIN_MAX_SYMLINKS, true)?;
+    let resolved_path = resolve(&joined, IS_WITHIN_MAX_SYMLINKS, true)?;
+    let relative = resolved_path.strip_prefix(&resolved_base).ok().map(|rel| {
+        if rel.as_os_str().is_empty() {
+            ".".to_string()
+        } else {
+            rel.to_string_lossy().into_owned()
+        }
+    });
+    Ok(Containment {
+        within: relative.is_some(),
+        base: resolved_base.to_string_lossy().into_owned(),
+        path: resolved_path.to_string_lossy().into_owned(),
+        relative,
+    })
+}
+
+/// Read the target of a symbolic link
+pub fn readlink(path: &str) -> Result<String> {
+    let expanded_path = resolve_path(path)?;
     // Use symlink_metadata so we don't follow the symlink. This lets us
     // observe and read broken symlinks (they may point at non-existent targets).
     let metadata = fs::symlink_metadata(&expanded_path).map_err(|e| {
@@ -135,6 +349,16 @@ mod tests {
     use std::fs;
     use tempfile::TempDir;
 
+    #[test]
+    fn test_resolve_path_anchors_relative_paths() {
+        let cwd = std::env::current_dir().unwrap();
+        assert_eq!(
+            resolve_path("some/file.txt").unwrap(),
+            cwd.join("some/file.txt").to_string_lossy()
+        );
+        assert_eq!(resolve_path("/etc/../tmp").unwrap(), "/etc/../tmp");
+    }
+
     #[test]
     fn test_basename() {
         assert_eq!(basename("/path/to/file.txt").unwrap(), "file.txt");
@@ -183,4 +407,83 @@ mod tests {
         let res = readlink(file.to_str().unwrap());
         assert!(res.is_err());
     }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_resolve_symlinks_follows_chain_within_limit() {
+        use std::os::unix::fs::symlink;
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        fs::write(root.join("target.txt"), "x").unwrap();
+        symlink("target.txt", root.join("l1")).unwrap();
+        symlink(root.join("l1"), root.join("l2")).unwrap();
+
+        let resolved = resolve_symlinks(&root.join("l2"), 2).unwrap();
+        assert_eq!(resolved, root.join("target.txt"));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_resolve_symlinks_chain_over_limit_errors() {
+        use std::os::unix::fs::symlink;
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("target.txt"), "x").unwrap();
+        symlink("target.txt", dir.path().join("l0")).unwrap();
+        for i in 1..5 {
+            symlink(format!("l{}", i - 1), dir.path().join(format!("l{}", i))).unwrap();
+        }
+        let top = dir.path().join("l4");
+
+        let err = realpath_with_depth(top.to_str().unwrap(), Some(3))
+            .expect_err("a 5-link chain must exceed a limit of 3");
+        assert!(matches!(
+            err,
+            crate::error::FileIoMcpError::FileIo(FileIoError::SymlinkLoop(_))
+        ));
+
+        // A self-loop trips the limit rather than spinning.
+        symlink("loop", dir.path().join("loop")).unwrap();
+        assert!(matches!(
+            resolve_symlinks(&dir.path().join("loop"), 8),
+            Err(crate::error::FileIoMcpError::FileIo(
+                FileIoError::SymlinkLoop(_)
+            ))
+        ));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_is_within() {
+        use std::os::unix::fs::symlink;
+        let dir = TempDir::new().expect("create temp dir");
+        let base = dir.path().join("base");
+        fs::create_dir_all(base.join("sub")).expect("create base/sub");
+        fs::create_dir(dir.path().join("outside")).expect("create outside");
+        let b = base.to_str().expect("utf-8 path");
+
+        let inside = is_within(b, "sub/new/file.txt").expect("in-base path resolves");
+        assert!(inside.within);
+        assert_eq!(inside.relative.as_deref(), Some("sub/new/file.txt"));
+        assert_eq!(
+            is_within(b, ".")
+                .expect("base resolves")
+                .relative
+                .as_deref(),
+            Some(".")
+        );
+
+        let escape = is_within(b, "sub/../../outside/x").expect("escape resolves");
+        assert!(!escape.within);
+        assert_eq!(escape.relative, None);
+
+        // Lexically inside, but the link leads out of the base.
+        symlink(dir.path().join("outside"), base.join("link")).expect("create link");
+        let via_link = is_within(b, "link/secret").expect("link resolves");
+        assert!(!via_link.within);
+        assert!(via_link.path.ends_with("/outside/secret"));
+
+        // A dangling link is followed too.
+        symlink("../outside/missing", base.join("dangling")).expect("create dangling");
+        assert!(!is_within(b, "dangling").expect("dangling resolves").within);
+    }
 }
@@ -0,0 +1,172 @@
+#![deny(warnings)]
+
+// Translate between byte offsets and line/column positions in a text file
+
+use crate::error::{FileIoError, Result};
+use std::path::Path;
+
+/// One location in a file, in every coordinate system callers use. Lines and
+/// columns are 1-based; `offset` is a 0-based byte offset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub struct Position {
+    pub offset: u64,
+    pub line: u64,
+    /// Column in characters (Unicode scalar values).
+    pub column: u64,
+    /// Column in UTF-8 bytes.
+    pub byte_column: u64,
+}
+
+/// Position of byte `offset` in the UTF-8 file at `path`.
+///
+/// `offset` may equal the file length (the position just past the end) but
+/// must not fall inside a multi-byte character.
+pub fn offset_to_position(path: &str, offset: u64) -> Result<Position> {
+    let content = read_text(path)?;
+    let offset = usize::try_from(offset).unwrap_or(usize::MAX);
+    locate(&content, offset)
+}
+
+/// Byte offset of 1-based `line` and character `column` in the UTF-8 file at
+/// `path`, returned as a full [`Position`].
+///
+/// `column` may be one past the last character of the line, addressing the
+/// line ending (or the end of the file on the last line).
+pub fn position_to_offset(path: &str, line: u64, column: u64) -> Result<Position> {
+    let content = read_text(path)?;
+    let offset = offset_of(&content, line, column)?;
+    locate(&content, offset)
+}
+
+fn read_text(path: &str) -> Result<String> {
+    let expanded_path = shellexpand::full(path)
+        .map_err(|e| {
+            crate::error::FileIoMcpError::from(FileIoError::InvalidPath(format!(
+                "Failed to expand path \'{}\': {}",
+                path, e
+            )))
+        })
+        .map(|expanded| expanded.into_owned())?;
+    let path_obj = Path::new(&expanded_path);
+    if !path_obj.exists() {
+        return Err(super::path_utils::not_found("read file", path).into());
+    }
+    if !path_obj.is_file() {
+        return Err(FileIoError::InvalidPath(format!("{} is not a file", expanded_path)).into());
+    }
+    let bytes = std::fs::read(path_obj)
+        .map_err(|e| FileIoError::from_io_error("read file", &expanded_path, e))?;
+    String::from_utf8(bytes).map_err(|_| {
+        FileIoError::ReadError(format!(
+            "{} is not valid UTF-8; character columns are undefined",
+            expanded_path
+        ))
+        .into()
+    })
+}
+
+/// [`Position`] of byte `offset` in `content`.
+fn locate(content: &str, offset: usize) -> Result<Position> {
+    if offset > content.len() {
+        return Err(crate::error::FileIoMcpError::InvalidParams(format!(
+            "offset {} is past the end of the file ({} bytes)",
+            offset,
+            content.len()
+        )));
+    }
+    if !content.is_char_boundary(offset) {
+        return Err(crate::error::FileIoMcpError::InvalidParams(format!(
+            "offset {} falls inside a multi-byte character",
+            offset
+        )));
+    }
+    let before = &content[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    Ok(Position {
+        offset: offset as u64,
+        line: before.matches('\n').count() as u64 + 1,
+        column: before[line_start..].chars().count() as u64 + 1,
+        byte_column: (offset - line_start) as u64 + 1,
+    })
+}
+
+/// Byte offset of 1-based `line` and character `column` in `content`.
+fn offset_of(content: &str, line: u64, column: u64) -> Result<usize> {
+    if line == 0 || column == 0 {
+        return Err(
+            FileIoError::InvalidLineNumbers("line and column are 1-based".to_string()).into(),
+        );
+    }
+    let line_count = content.matches('\n').count() as u64 + 1;
+    let line_start = match line {
+        1 => 0,
+        n => content
+            .match_indices('\n')
+            .nth((n - 2) as usize)
+            .map(|(i, _)| i + 1)
+            .ok_or_else(|| {
+                FileIoError::InvalidLineNumbers(format!(
+                    "line {} is past the end of the file ({} lines)",
+                    line, line_count
+                ))
+            })?,
+    };
+    let rest = &content[line_start..];
+    let text = &rest[..rest.find('\n').unwrap_or(rest.len())];
+    let width = text.chars().count() as u64;
+    if column > width + 1 {
+        return Err(FileIoError::InvalidLineNumbers(format!(
+            "column {} is past the end of line {} ({} characters)",
+            column, line, width
+        ))
+        .into());
+    }
+    let within = text
+        .char_indices()
+        .nth((column - 1) as usize)
+        .map_or(text.len(), |(i, _)| i);
+    Ok(line_start + within)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    const TEXT: &str = "first\nhé 🦀 x\nlast";
+
+    #[test]
+    fn test_multibyte_columns() {
+        // "x" on line 2: 'h','é','␠','🦀','␠' precede it.
+        let offset = TEXT.find('x').expect("fixture has x");
+        let position = locate(TEXT, offset).expect("valid offset");
+        assert_eq!(position.line, 2);
+        assert_eq!(position.column, 6);
+        assert_eq!(position.byte_column, 10);
+        assert_eq!(offset_of(TEXT, 2, 6).expect("valid position"), offset);
+
+        assert!(locate(TEXT, TEXT.find('é').expect("fixture has é") + 1).is_err());
+        assert!(
+            offset_of(TEXT, 2, 8).is_ok(),
+            "one past the end addresses the newline"
+        );
+        assert!(offset_of(TEXT, 2, 9).is_err());
+        assert!(offset_of(TEXT, 4, 1).is_err());
+    }
+
+    #[test]
+    fn test_round_trip_every_offset() {
+        let dir = TempDir::new().expect("create temp dir");
+        let path = dir.path().join("a.txt");
+        fs::write(&path, TEXT).expect("write fixture");
+        let p = path.to_str().expect("utf-8 path");
+
+        for (offset, _) in TEXT.char_indices().chain([(TEXT.len(), ' ')]) {
+            let position = offset_to_position(p, offset as u64).expect("valid offset");
+            let back =
+                position_to_offset(p, position.line, position.column).expect("valid position");
+            assert_eq!(back, position);
+        }
+    }
+}
@@ -0,0 +1,105 @@
+#![deny(warnings)]
+
+// Insert content at the start of a file
+
+use crate::error::{FileIoError, Result};
+use std::path::Path;
+
+#[derive(Debug, serde::Serialize)]
+pub struct PrependResult {
+    pub path: String,
+    /// True when the file did not exist and was created.
+    pub created: bool,
+}
+
+/// Insert `content` at the start of `path`, creating the file if missing.
+///
+/// When the file already has content and `content` does not end with a line
+/// break, one is added (CRLF if the file uses CRLF) so the prepended text
+/// and the old first line don't merge. The result is written atomically.
+pub fn prepend(path: &str, content: &str) -> Result<PrependResult> {
+    let expanded_path = shellexpand::full(path)
+        .map_err(|e| {
+            crate::error::FileIoMcpError::from(FileIoError::InvalidPath(format!(
+                "Failed to expand path \'{}\': {}",
+                path, e
+            )))
+        })
+        .map(|expanded| expanded.into_owned())?;
+
+    let path_obj = Path::new(&expanded_path);
+    let created = !path_obj.exists();
+    if !created && !path_obj.is_file() {
+        return Err(FileIoError::InvalidPath(format!("{} is not a file", expanded_path)).into());
+    }
+    let existing = if created {
+        String::new()
+    } else {
+        std::fs::read_to_string(path_obj)
+            .map_err(|e| FileIoError::from_io_error("read file", &expanded_path, e))?
+    };
+
+    let updated = join(content, &existing);
+    super::write_file::write_file(&expanded_path, &updated, false)?;
+
+    Ok(PrependResult {
+        path: expanded_path,
+        created,
+    })
+}
+
+/// `content` followed by `existing`, with a line break between them when
+/// `content` doesn't already end in one.
+fn join(content: &str, existing: &str) -> String {
+    let mut out = String::with_capacity(content.len() + existing.len() + 2);
+    out.push_str(content);
+    if !content.is_empty() && !existing.is_empty() && !content.ends_with('\n') {
+        out.push_str(if existing.contains("\r\n") {
+            "\r\n"
+        } else {
+            "\n"
+        });
+    }
+    out.push_str(existing);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_prepend_header_to_existing_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("main.rs");
+        fs::write(&path, "fn main() {}\n").unwrap();
+
+        let result = prepend(path.to_str().unwrap(), "// SPDX-License-Identifier: MIT").unwrap();
+
+        assert!(!result.created);
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "// SPDX-License-Identifier: MIT\nfn main() {}\n"
+        );
+    }
+
+    #[test]
+    fn test_prepend_creates_missing_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("new.sh");
+
+        let result = prepend(path.to_str().unwrap(), "#!/bin/sh").unwrap();
+
+        assert!(result.created);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "#!/bin/sh");
+    }
+
+    #[test]
+    fn test_join_boundaries() {
+        assert_eq!(join("a\n", "b\n"), "a\nb\n");
+        assert_eq!(join("a", "b\r\nc\r\n"), "a\r\nb\r\nc\r\n");
+        assert_eq!(join("", "b"), "b");
+    }
+}
@@ -0,0 +1,172 @@
+#![deny(warnings)]
+
+// Read a byte range of a file, returned base64-encoded (plus the base64 codec)
+
+use crate::error::{FileIoError, Result};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// Largest `length` [`read_bytes`] accepts.
+pub const MAX_READ_BYTES: u64 = 16 * 1024 * 1024;
+
+#[derive(Debug, PartialEq, Eq, serde::Serialize)]
+pub struct ReadBytesResult {
+    pub path: String,
+    pub offset: u64,
+    /// Fewer than requested when the range runs past the end of the file.
+    pub bytes_read: u64,
+    /// The bytes read, standard base64 with padding.
+    pub data: String,
+}
+
+/// Read up to `length` bytes of `path` starting at byte `offset`.
+///
+/// The file is seeked to `offset` and only the range is read, so any part
+/// of a large file is cheap to fetch. A range that runs past the end is a
+/// short read; an `offset` past the end is an error.
+pub fn read_bytes(path: &str, offset: u64, length: u64) -> Result<ReadBytesResult> {
+    if length > MAX_READ_BYTES {
+        return Err(crate::error::FileIoMcpError::InvalidParams(format!(
+            "length must be at most {}, got {}",
+            MAX_READ_BYTES, length
+        )));
+    }
+    let expanded_path = super::path_utils::resolve_path(path)?;
+    let path_obj = Path::new(&expanded_path);
+    if path_obj.is_dir() {
+        return Err(FileIoError::IsADirectory(expanded_path).into());
+    }
+    let mut file = File::open(path_obj)
+        .map_err(|e| FileIoError::from_io_error("open file", &expanded_path, e))?;
+    let len = file
+        .metadata()
+        .map_err(|e| FileIoError::from_io_error("read metadata for", &expanded_path, e))?
+        .len();
+    if offset > len {
+        return Err(FileIoError::ReadError(format!(
+            "offset {} is past the end of {} ({} bytes)",
+            offset, expanded_path, len
+        ))
+        .into());
+    }
+
+    file.seek(SeekFrom::Start(offset))
+        .map_err(|e| FileIoError::from_io_error("seek in", &expanded_path, e))?;
+    let mut bytes = Vec::with_capacity(length.min(len - offset) as usize);
+    file.take(length)
+        .read_to_end(&mut bytes)
+        .map_err(|e| FileIoError::from_io_error("read file", &expanded_path, e))?;
+
+    Ok(ReadBytesResult {
+        path: expanded_path,
+        offset,
+        bytes_read: bytes.len() as u64,
+        data: to_base64(&bytes),
+    })
+}
+
+/// Standard (RFC 4648) base64 with `=` padding.
+pub(crate) fn to_base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |acc, (i, b)| acc | (u32::from(*b) << (16 - 8 * i)));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[((group >> (18 - 6 * i)) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// Decode standard base64 as produced by [`to_base64`]. Padding is
+/// optional and ASCII whitespace (e.g. line wrapping) is ignored; `None` for
+/// any other character or a truncated final group.
+pub(crate) fn from_base64(text: &str) -> Option<Vec<u8>> {
+    let digits: Vec<u8> = text.bytes().filter(|b| !b.is_ascii_whitespace()).collect();
+    let unpadded = digits
+        .strip_suffix(b"==")
+        .or_else(|| digits.strip_suffix(b"="))
+        .unwrap_or(&digits);
+    let mut out = Vec::with_capacity(unpadded.len() / 4 * 3 + 2);
+    for chunk in unpadded.chunks(4) {
+        if chunk.len() == 1 {
+            return None;
+        }
+        let mut group = 0u32;
+        for (i, digit) in chunk.iter().enumerate() {
+            let value = match digit {
+                b'A'..=b'Z' => digit - b'A',
+                b'a'..=b'z' => digit - b'a' + 26,
+                b'0'..=b'9' => digit - b'0' + 52,
+                b'+' => 62,
+                b'/' => 63,
+                _ => return None,
+            };
+            group |= u32::from(value) << (18 - 6 * i);
+        }
+        out.extend_from_slice(&group.to_be_bytes()[1..chunk.len()]);
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_base64_vectors() {
+        for (plain, encoded) in [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ] {
+            assert_eq!(to_base64(plain.as_bytes()), encoded);
+        }
+    }
+
+    #[test]
+    fn test_base64_decode() {
+        let cases: [&[u8]; 6] = [b"", b"f", b"fo", b"foo", b"foob", &[0xFF, 0x00, 0x80]];
+        for bytes in cases {
+            assert_eq!(from_base64(&to_base64(bytes)).as_deref(), Some(bytes));
+        }
+        assert_eq!(from_base64("Zm9v\nYmE").as_deref(), Some(&b"fooba"[..]));
+        assert_eq!(from_base64("Zm9v!"), None);
+        assert_eq!(from_base64("Zm9vY"), None);
+    }
+
+    #[test]
+    fn test_read_range_and_short_read_at_eof() {
+        let dir = TempDir::new().expect("create temp dir");
+        let path = dir.path().join("data.bin");
+        fs::write(&path, b"0123456789").expect("write fixture");
+        let p = path.to_str().expect("utf-8 path");
+
+        let result = read_bytes(p, 2, 3).expect("read succeeds");
+        assert_eq!(result.bytes_read, 3);
+        assert_eq!(result.data, to_base64(b"234"));
+
+        // Spans the end of the file: a short read, not an error.
+        let result = read_bytes(p, 8, 10).expect("read succeeds");
+        assert_eq!(result.bytes_read, 2);
+        assert_eq!(result.data, to_base64(b"89"));
+
+        assert_eq!(read_bytes(p, 10, 4).expect("read succeeds").bytes_read, 0);
+        let err = read_bytes(p, 11, 1).expect_err("offset past EOF");
+        assert!(err.to_string().contains("past the end"), "got: {err}");
+    }
+}
@@ -0,0 +1,135 @@
+#![deny(warnings)]
+
+// Read a whole file as one string, byte for byte, or base64-encoded
+
+use crate::error::{FileIoError, Result};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// Largest file [`read_file`] returns when the caller gives no `max_bytes`.
+pub const DEFAULT_MAX_BYTES: u64 = 100 * 1024 * 1024;
+
+/// Return the exact contents of `path`, line endings and final newline
+/// included, refusing files larger than `max_bytes`.
+pub fn read_file(path: &str, max_bytes: u64) -> Result<String> {
+    let (expanded_path, bytes) = read_file_bytes(path, max_bytes)?;
+    String::from_utf8(bytes).map_err(|e| {
+        FileIoError::ReadError(format!(
+            "{} is not valid UTF-8 (at byte {}); read it with binary=true",
+            expanded_path,
+            e.utf8_error().valid_up_to()
+        ))
+        .into()
+    })
+}
+
+/// A file's raw bytes as returned by [`read_file_binary`].
+#[derive(Debug, PartialEq, Eq, serde::Serialize)]
+pub struct BinaryContent {
+    pub path: String,
+    /// How `data` is encoded; always `base64`.
+    pub encoding: &'static str,
+    pub data: String,
+    /// Size of the decoded content.
+    pub bytes: u64,
+}
+
+/// [`read_file`] without any text decoding: the bytes come back
+/// base64-encoded, so binary files can be retrieved too.
+pub fn read_file_binary(path: &str, max_bytes: u64) -> Result<BinaryContent> {
+    let (expanded_path, bytes) = read_file_bytes(path, max_bytes)?;
+    Ok(BinaryContent {
+        path: expanded_path,
+        encoding: "base64",
+        data: super::read_bytes::to_base64(&bytes),
+        bytes: bytes.len() as u64,
+    })
+}
+
+/// The resolved path and every byte of the file, refusing more than
+/// `max_bytes`.
+///
+/// Why: the limit is enforced on what is actually read, not just on the
+/// size reported up front, so a file that grows between the two checks
+/// still can't be pulled into memory past it.
+fn read_file_bytes(path: &str, max_bytes: u64) -> Result<(String, Vec<u8>)> {
+    let expanded_path = super::path_utils::resolve_path(path)?;
+    let path_obj = Path::new(&expanded_path);
+    if path_obj.is_dir() {
+        return Err(FileIoError::IsADirectory(expanded_path).into());
+    }
+    let file = File::open(path_obj)
+        .map_err(|e| FileIoError::from_io_error("open file", &expanded_path, e))?;
+    let too_large = |size: u64| {
+        FileIoError::ReadError(format!(
+            "File too large ({} bytes, max_bytes {}): {}",
+            size, max_bytes, expanded_path
+        ))
+    };
+    let size = file
+        .metadata()
+        .map_err(|e| FileIoError::from_io_error("read metadata for", &expanded_path, e))?
+        .len();
+    if size > max_bytes {
+        return Err(too_large(size).into());
+    }
+
+    let mut bytes = Vec::with_capacity(size as usize);
+    file.take(max_bytes.saturating_add(1))
+        .read_to_end(&mut bytes)
+        .map_err(|e| FileIoError::from_io_error("read file", &expanded_path, e))?;
+    if bytes.len() as u64 > max_bytes {
+        return Err(too_large(bytes.len() as u64).into());
+    }
+    Ok((expanded_path, bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_reads_exact_bytes() {
+        let dir = TempDir::new().expect("create temp dir");
+        let path = dir.path().join("a.txt");
+        let p = path.to_str().expect("utf-8 path");
+
+        for content in ["a\r\nb\n", "no final newline", "", "\n\n"] {
+            fs::write(&path, content).expect("write fixture");
+            assert_eq!(
+                read_file(p, DEFAULT_MAX_BYTES).expect("read succeeds"),
+                content
+            );
+        }
+    }
+
+    #[test]
+    fn test_binary_round_trips_through_base64() {
+        let dir = TempDir::new().expect("create temp dir");
+        let path = dir.path().join("blob.bin");
+        fs::write(&path, [0xFF, 0x00, 0x80]).expect("write fixture");
+        let p = path.to_str().expect("utf-8 path");
+
+        assert!(read_file(p, DEFAULT_MAX_BYTES).is_err());
+        let content = read_file_binary(p, DEFAULT_MAX_BYTES).expect("read succeeds");
+        assert_eq!(content.encoding, "base64");
+        assert_eq!(content.bytes, 3);
+        // 0xFF 0x00 0x80 = 111111 110000 000010 000000
+        assert_eq!(content.data, "/wCA");
+    }
+
+    #[test]
+    fn test_max_bytes_is_enforced() {
+        let dir = TempDir::new().expect("create temp dir");
+        let path = dir.path().join("a.txt");
+        fs::write(&path, "12345").expect("write fixture");
+        let p = path.to_str().expect("utf-8 path");
+
+        assert_eq!(read_file(p, 5).expect("read succeeds"), "12345");
+        let err = read_file(p, 4).expect_err("over the limit");
+        assert!(err.to_string().contains("too large"), "got: {err}");
+    }
+}
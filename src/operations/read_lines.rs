@@ -4,9 +4,17 @@
 
 use crate::error::{FileIoError, Result};
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
 
-/// Read lines from a file with optional windowing
+/// Unbounded reads (no end line or count) are refused past this size.
+const MAX_FILE_SIZE: u64 = 100 * 1024 * 1024;
+
+/// Read lines from a file with optional windowing.
+///
+/// Only the requested window is kept in memory, and reading stops once its
+/// last line has been read, so a small range near the top of a huge file is
+/// cheap. Reads with no end (neither `end_line` nor `line_count`) still have
+/// to walk the whole file and are capped at 100 MiB.
 pub fn read_lines(
     path: &str,
     start_line: Option<u64>,
@@ -14,14 +22,7 @@ pub fn read_lines(
     line_count: Option<u64>,
     start_offset: Option<u64>,
 ) -> Result<Vec<String>> {
-    let expanded_path = shellexpand::full(path)
-        .map_err(|e| {
-            crate::error::FileIoMcpError::from(crate::error::FileIoError::InvalidPath(format!(
-                "Failed to expand path \'{}\': {}",
-                path, e
-            )))
-        })
-        .map(|expanded| expanded.into_owned())?;
+    let expanded_path = super::path_utils::resolve_path(path)?;
     let file = File::open(&expanded_path).map_err(|e| {
         crate::error::FileIoMcpError::from(FileIoError::from_io_error(
             "open file",
@@ -29,10 +30,13 @@ pub fn read_lines(
             e,
         ))
     })?;
+    let (start, end) = window_bounds(start_line, end_line, line_count, start_offset)?;
 
-    // Reject files larger than 100 MiB to prevent memory exhaustion.
-    const MAX_FILE_SIZE: u64 = 100 * 1024 * 1024;
-    if let Ok(meta) = file.metadata()
+    // Reject unbounded reads of files larger than 100 MiB to prevent memory
+    // exhaustion. A bounded window only ever holds its own lines, so it may
+    // be taken from a file of any size.
+    if end.is_none()
+        && let Ok(meta) = file.metadata()
         && meta.len() > MAX_FILE_SIZE
     {
         return Err(FileIoError::ReadError(format!(
@@ -44,17 +48,105 @@ pub fn read_lines(
         .into());
     }
 
-    let reader = BufReader::new(file);
-    let lines: Vec<String> = reader
+    // Stream the file: skip the lines before the window without keeping
+    // them, collect the window, and stop as soon as its end is reached.
+    // `consumed` is the number of lines read; when reading stops early it is
+    // at least `end`, so it can stand in for the file length below.
+    let mut reader = BufReader::new(file);
+    let stop = end.map(|end| end.max(start));
+    let mut consumed = 0usize;
+    let mut at_eof = false;
+    while consumed < start {
+        let n = reader.skip_until(b'\n').map_err(|e| {
+            FileIoError::ReadError(format!("Failed to read line {}: {}", consumed + 1, e))
+        })?;
+        if n == 0 {
+            at_eof = true;
+            break;
+        }
+        consumed += 1;
+    }
+
+    let mut window = Vec::new();
+    if !at_eof {
+        let mut lines = reader.lines();
+        while stop.is_none_or(|stop| consumed < stop) {
+            let Some(line) = lines.next() else {
+                break;
+            };
+            window.push(line.map_err(|e| {
+                FileIoError::ReadError(format!("Failed to read line {}: {}", consumed + 1, e))
+            })?);
+            consumed += 1;
+        }
+    }
+
+    finish_window(window, start, end, consumed)
+}
+
+/// [`read_lines`] for a file in `encoding`.
+///
+/// UTF-8 takes the streaming path of [`read_lines`] unchanged. Any other
+/// encoding has to be decoded as a whole before it can be split into lines
+/// (a line break in UTF-16 isn't a single `\n` byte), so the file is read
+/// into memory and capped at 100 MiB whatever the window. Bytes that are
+/// invalid in `encoding` are an error, not replaced.
+pub fn read_lines_encoded(
+    path: &str,
+    start_line: Option<u64>,
+    end_line: Option<u64>,
+    line_count: Option<u64>,
+    start_offset: Option<u64>,
+    encoding: super::write_file::TargetEncoding,
+) -> Result<Vec<String>> {
+    if encoding == super::write_file::TargetEncoding::Utf8 {
+        return read_lines(path, start_line, end_line, line_count, start_offset);
+    }
+    let expanded_path = super::path_utils::resolve_path(path)?;
+    let file = File::open(&expanded_path)
+        .map_err(|e| FileIoError::from_io_error("open file", &expanded_path, e))?;
+    let (start, end) = window_bounds(start_line, end_line, line_count, start_offset)?;
+
+    let mut bytes = Vec::new();
+    file.take(MAX_FILE_SIZE + 1)
+        .read_to_end(&mut bytes)
+        .map_err(|e| FileIoError::from_io_error("read file", &expanded_path, e))?;
+    if bytes.len() as u64 > MAX_FILE_SIZE {
+        return Err(FileIoError::ReadError(format!(
+            "File too large (over {} bytes) to decode as {}: {}",
+            MAX_FILE_SIZE,
+            encoding.name(),
+            expanded_path
+        ))
+        .into());
+    }
+    let (text, _) = encoding.decode(&bytes).ok_or_else(|| {
+        FileIoError::ReadError(format!(
+            "{} is not valid {}; pass the file's actual encoding",
+            expanded_path,
+            encoding.name()
+        ))
+    })?;
+
+    let consumed = text.lines().count();
+    let stop = end.map(|end| end.max(start)).unwrap_or(consumed);
+    let window = text
         .lines()
-        .enumerate()
-        .map(|(i, line)| {
-            line.map_err(|e| {
-                FileIoError::ReadError(format!("Failed to read line {}: {}", i + 1, e))
-            })
-        })
-        .collect::<std::result::Result<Vec<_>, _>>()?;
+        .skip(start)
+        .take(stop.saturating_sub(start))
+        .map(str::to_string)
+        .collect();
+    finish_window(window, start, end, consumed)
+}
 
+/// 0-based index of the first line of the window and, if bounded, the index
+/// just past its last line.
+fn window_bounds(
+    start_line: Option<u64>,
+    end_line: Option<u64>,
+    line_count: Option<u64>,
+    start_offset: Option<u64>,
+) -> Result<(usize, Option<usize>)> {
     // Determine the range of lines to return
     let start = if let Some(start) = start_line {
         if start == 0 {
@@ -81,24 +173,33 @@ pub fn read_lines(
             )
             .into());
         }
-        end as usize
-    } else if let Some(count) = line_count {
-        start + count as usize
+        Some(end as usize)
     } else {
-        lines.len()
+        line_count.map(|count| start.saturating_add(count as usize))
     };
 
+    Ok((start, end))
+}
+
+/// Check the window against the `consumed` lines that were read and trim it
+/// to its end.
+fn finish_window(
+    mut window: Vec<String>,
+    start: usize,
+    end: Option<usize>,
+    consumed: usize,
+) -> Result<Vec<String>> {
     // Validate bounds
-    if start > lines.len() {
+    if start > consumed {
         return Err(FileIoError::InvalidLineNumbers(format!(
             "start_line {} exceeds file length {}",
             start + 1,
-            lines.len()
+            consumed
         ))
         .into());
     }
 
-    let end = end.min(lines.len());
+    let end = end.unwrap_or(consumed).min(consumed);
 
     if start > end {
         return Err(
@@ -106,7 +207,270 @@ pub fn read_lines(
         );
     }
 
-    Ok(lines[start..end].to_vec())
+    window.truncate(end - start);
+    Ok(window)
+}
+
+/// Bytes read per step when scanning backwards in [`read_tail`].
+const TAIL_CHUNK: u64 = 64 * 1024;
+
+/// Read the last `count` lines of a file (all of them if it has fewer).
+///
+/// Why: reading from the end in fixed-size chunks means the cost depends on
+/// the size of the tail, not the file, so the last lines of a large log come
+/// back without walking it and without the unbounded-read size cap.
+pub fn read_tail(path: &str, count: u64) -> Result<Vec<String>> {
+    tail_window(path, count).map(|(_, _, lines)| lines)
+}
+
+/// [`read_tail`], plus the 1-based number of the first returned line.
+///
+/// Numbering has to count every line break above the tail, so unlike
+/// [`read_tail`] this reads the whole file (streamed, not held in memory).
+pub fn read_tail_with_start(path: &str, count: u64) -> Result<(u64, Vec<String>)> {
+    let (expanded_path, start, lines) = tail_window(path, count)?;
+    let file = File::open(&expanded_path)
+        .map_err(|e| FileIoError::from_io_error("open file", &expanded_path, e))?;
+    // `start` is 0 or just past a line break, so every skipped segment
+    // is one whole line.
+    let mut reader = BufReader::new(file.take(start));
+    let mut first_line = 1u64;
+    loop {
+        let n = reader.skip_until(b'\n').map_err(|e| {
+            FileIoError::ReadError(format!("Failed to read line {}: {}", first_line, e))
+        })?;
+        if n == 0 {
+            break;
+        }
+        first_line += 1;
+    }
+    Ok((first_line, lines))
+}
+
+/// The last `count` lines of `path`, with the resolved path and the byte
+/// offset the first of them starts at.
+fn tail_window(path: &str, count: u64) -> Result<(String, u64, Vec<String>)> {
+    let expanded_path = super::path_utils::resolve_path(path)?;
+    let mut file = File::open(&expanded_path)
+        .map_err(|e| FileIoError::from_io_error("open file", &expanded_path, e))?;
+    let len = file
+        .metadata()
+        .map_err(|e| FileIoError::from_io_error("read metadata for", &expanded_path, e))?
+        .len();
+    if count == 0 {
+        return Ok((expanded_path, len, Vec::new()));
+    }
+
+    // Walk back chunk by chunk until `count` line breaks precede the final
+    // line (a trailing newline ends the last line rather than starting one).
+    let mut chunks: Vec<Vec<u8>> = Vec::new();
+    let mut pos = len;
+    let mut breaks = 0u64;
+    let mut start = None;
+    while pos > 0 && start.is_none() {
+        let step = TAIL_CHUNK.min(pos);
+        pos -= step;
+        let mut chunk = vec![0u8; step as usize];
+        file.seek(SeekFrom::Start(pos))
+            .and_then(|_| file.read_exact(&mut chunk))
+            .map_err(|e| FileIoError::from_io_error("read", &expanded_path, e))?;
+        for (i, byte) in chunk.iter().enumerate().rev() {
+            if *byte != b'\n' || pos + i as u64 == len - 1 {
+                continue;
+            }
+            breaks += 1;
+            if breaks == count {
+                start = Some(i + 1);
+                break;
+            }
+        }
+        chunks.push(chunk);
+    }
+
+    let mut tail = Vec::new();
+    for (n, chunk) in chunks.iter().rev().enumerate() {
+        match start {
+            Some(start) if n == 0 => tail.extend_from_slice(&chunk[start..]),
+            _ => tail.extend_from_slice(chunk),
+        }
+    }
+    let text = String::from_utf8(tail)
+        .map_err(|e| FileIoError::ReadError(format!("Failed to read {}: {}", expanded_path, e)))?;
+    let start_offset = start.map_or(0, |start| pos + start as u64);
+    Ok((
+        expanded_path,
+        start_offset,
+        text.lines().map(str::to_string).collect(),
+    ))
+}
+
+/// A line together with its 1-based position in the file.
+#[derive(Debug, PartialEq, Eq, serde::Serialize)]
+pub struct NumberedLine {
+    pub line_number: u64,
+    pub content: String,
+}
+
+/// Pair consecutive `lines` with their numbers, the first being `first_line`.
+pub fn number_lines(lines: Vec<String>, first_line: u64) -> Vec<NumberedLine> {
+    lines
+        .into_iter()
+        .zip(first_line..)
+        .map(|(content, line_number)| NumberedLine {
+            line_number,
+            content,
+        })
+        .collect()
+}
+
+/// One page of [`read_lines_page`].
+#[derive(Debug, PartialEq, Eq, serde::Serialize)]
+pub struct LinePage {
+    pub lines: Vec<String>,
+    /// 1-based number of the first line in `lines`.
+    pub start_line: u64,
+    /// Pass back as `cursor` to continue right after the last returned line;
+    /// absent once the end of the file has been reached.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+/// Read up to `line_count` lines starting at `cursor` (from a previous page)
+/// or, without one, at the 1-based `start_line` (default 1).
+///
+/// The cursor is opaque to callers; it records the byte offset just past the
+/// last returned line together with that line's number, so the next page
+/// seeks straight there.
+///
+/// Why: paging with `start_line` re-reads everything above the window on
+/// every call, making a full sequential read quadratic; resuming from a byte
+/// offset keeps it linear. A cursor is only meaningful for the file it came
+/// from, unchanged — after an edit above the offset it may land mid-line.
+pub fn read_lines_page(
+    path: &str,
+    start_line: Option<u64>,
+    cursor: Option<&str>,
+    line_count: Option<u64>,
+) -> Result<LinePage> {
+    if start_line == Some(0) {
+        return Err(FileIoError::InvalidLineNumbers("Line numbers start at 1".to_string()).into());
+    }
+    let resume = cursor.map(decode_cursor).transpose()?;
+    let expanded_path = super::path_utils::resolve_path(path)?;
+    let mut file = File::open(&expanded_path)
+        .map_err(|e| FileIoError::from_io_error("open file", &expanded_path, e))?;
+    let len = file
+        .metadata()
+        .map_err(|e| FileIoError::from_io_error("read metadata for", &expanded_path, e))?
+        .len();
+
+    let (mut offset, first_line) = match resume {
+        Some((offset, line)) => {
+            if offset > len {
+                return Err(crate::error::FileIoMcpError::InvalidParams(
+                    "cursor is past the end of the file (was it truncated?)".to_string(),
+                ));
+            }
+            file.seek(SeekFrom::Start(offset))
+                .map_err(|e| FileIoError::from_io_error("seek in", &expanded_path, e))?;
+            (offset, line)
+        }
+        None => (0, start_line.unwrap_or(1)),
+    };
+    if line_count.is_none() && len - offset.min(len) > MAX_FILE_SIZE {
+        return Err(FileIoError::ReadError(format!(
+            "Remaining file too large ({} bytes, max {}) to read without line_count: {}",
+            len - offset,
+            MAX_FILE_SIZE,
+            expanded_path
+        ))
+        .into());
+    }
+
+    let mut reader = BufReader::new(file);
+    let read_error = |line: u64, e: std::io::Error| {
+        FileIoError::ReadError(format!("Failed to read line {}: {}", line, e))
+    };
+    if resume.is_none() {
+        for line in 1..first_line {
+            let n = reader.skip_until(b'\n').map_err(|e| read_error(line, e))?;
+            if n == 0 {
+                return Err(FileIoError::InvalidLineNumbers(format!(
+                    "start_line {} exceeds file length {}",
+                    first_line,
+                    line - 1
+                ))
+                .into());
+            }
+            offset += n as u64;
+        }
+    }
+
+    let mut lines = Vec::new();
+    let mut buf = Vec::new();
+    while line_count.is_none_or(|count| (lines.len() as u64) < count) {
+        buf.clear();
+        let line_no = first_line + lines.len() as u64;
+        let n = reader
+            .read_until(b'\n', &mut buf)
+            .map_err(|e| read_error(line_no, e))?;
+        if n == 0 {
+            break;
+        }
+        offset += n as u64;
+        if buf.last() == Some(&b'\n') {
+            buf.pop();
+            if buf.last() == Some(&b'\r') {
+                buf.pop();
+            }
+        }
+        let line = String::from_utf8(std::mem::take(&mut buf)).map_err(|e| {
+            read_error(
+                line_no,
+                std::io::Error::new(std::io::ErrorKind::InvalidData, e),
+            )
+        })?;
+        lines.push(line);
+    }
+
+    let at_eof = reader
+        .fill_buf()
+        .map_err(|e| read_error(first_line + lines.len() as u64, e))?
+        .is_empty();
+    Ok(LinePage {
+        next_cursor: (!at_eof).then(|| encode_cursor(offset, first_line + lines.len() as u64)),
+        start_line: first_line,
+        lines,
+    })
+}
+
+fn encode_cursor(offset: u64, next_line: u64) -> String {
+    format!("v1.{:x}.{:x}", offset, next_line)
+}
+
+fn decode_cursor(cursor: &str) -> Result<(u64, u64)> {
+    let invalid = || {
+        crate::error::FileIoMcpError::InvalidParams(format!(
+            "invalid cursor '{}': pass back a next_cursor value unchanged",
+            cursor
+        ))
+    };
+    let mut parts = cursor.split('.');
+    if parts.next() != Some("v1") {
+        return Err(invalid());
+    }
+    let mut field = || {
+        parts
+            .next()
+            .and_then(|p| u64::from_str_radix(p, 16).ok())
+            .ok_or_else(invalid)
+    };
+    let offset = field()?;
+    let line = field()?;
+    if line == 0 || parts.next().is_some() {
+        return Err(invalid());
+    }
+    Ok((offset, line))
 }
 
 #[cfg(test)]
@@ -115,6 +479,16 @@ mod tests {
     use std::io::Write;
     use tempfile::NamedTempFile;
 
+    #[test]
+    fn test_not_found_names_resolved_path() {
+        let err = read_lines("no-such-dir/missing.txt", None, None, None, None).unwrap_err();
+        let tried = std::env::current_dir()
+            .unwrap()
+            .join("no-such-dir/missing.txt");
+        let msg = err.to_string();
+        assert!(msg.contains(&*tried.to_string_lossy()), "got: {msg}");
+    }
+
     #[test]
     fn test_read_all_lines() {
         let mut file = NamedTempFile::new().unwrap();
@@ -245,4 +619,141 @@ mod tests {
         let res = read_lines(path, None, None, Some(1), Some(3));
         assert!(res.is_err());
     }
+
+    #[test]
+    fn test_read_lines_window_stops_at_end() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, "a").unwrap();
+        writeln!(file, "b").unwrap();
+        writeln!(file, "c").unwrap();
+        // Invalid UTF-8 after the window: reading it would fail, so a
+        // successful call proves nothing past line 2 was read.
+        file.write_all(&[0xff, 0xfe, b'\n']).unwrap();
+        let path = file.path().to_str().unwrap();
+
+        let lines = read_lines(path, Some(2), None, Some(1), None).unwrap();
+        assert_eq!(lines, vec!["b".to_string()]);
+
+        assert!(read_lines(path, None, None, None, None).is_err());
+    }
+
+    #[test]
+    fn test_read_lines_window_from_file_over_size_cap() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, "first").unwrap();
+        writeln!(file, "second").unwrap();
+        // Sparse 200 MiB tail of NUL bytes with no newline: one enormous line
+        // that a whole-file read would have to buffer.
+        file.as_file().set_len(200 * 1024 * 1024).unwrap();
+        let path = file.path().to_str().unwrap();
+
+        let lines = read_lines(path, Some(1), Some(2), None, None).unwrap();
+        assert_eq!(lines, vec!["first".to_string(), "second".to_string()]);
+
+        // Unbounded reads are still refused.
+        assert!(read_lines(path, Some(2), None, None, None).is_err());
+    }
+
+    #[test]
+    fn test_read_lines_windows_1252() {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(b"caf\xe9\r\nna\xefve \x80\nend\n").unwrap();
+        let path = file.path().to_str().unwrap();
+        let encoding =
+            crate::operations::write_file::TargetEncoding::parse("windows-1252").unwrap();
+
+        let lines = read_lines_encoded(path, Some(1), Some(2), None, None, encoding).unwrap();
+        assert_eq!(lines, vec!["café", "naïve €"]);
+        let lines = read_lines_encoded(path, None, None, Some(5), Some(2), encoding).unwrap();
+        assert_eq!(lines, vec!["end"]);
+        assert!(read_lines_encoded(path, Some(9), None, None, None, encoding).is_err());
+
+        // Without an encoding the same bytes are not UTF-8.
+        assert!(read_lines(path, None, None, None, None).is_err());
+    }
+
+    #[test]
+    fn test_read_lines_encoded_rejects_invalid_bytes() {
+        let mut file = NamedTempFile::new().unwrap();
+        // An unpaired UTF-16 surrogate.
+        file.write_all(&[0x61, 0x00, 0x00, 0xD8]).unwrap();
+        let path = file.path().to_str().unwrap();
+        let encoding = crate::operations::write_file::TargetEncoding::parse("utf-16le").unwrap();
+
+        let err = read_lines_encoded(path, None, None, None, None, encoding).unwrap_err();
+        assert!(err.to_string().contains("not valid UTF-16LE"), "got: {err}");
+    }
+
+    #[test]
+    fn test_read_tail() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, "a\nb\r\nc\nd").unwrap();
+        let path = file.path().to_str().unwrap();
+
+        assert_eq!(read_tail(path, 2).unwrap(), vec!["c", "d"]);
+        assert_eq!(read_tail(path, 3).unwrap(), vec!["b", "c", "d"]);
+        // Fewer lines than asked for: all of them.
+        assert_eq!(read_tail(path, 10).unwrap(), vec!["a", "b", "c", "d"]);
+        assert!(read_tail(path, 0).unwrap().is_empty());
+
+        // No trailing newline: the last line still counts.
+        write!(file, "e").unwrap();
+        assert_eq!(read_tail(path, 2).unwrap(), vec!["d", "e"]);
+    }
+
+    #[test]
+    fn test_read_tail_empty_file_and_long_lines() {
+        let mut file = NamedTempFile::new().unwrap();
+        let path = file.path().to_str().unwrap().to_string();
+        assert!(read_tail(&path, 10).unwrap().is_empty());
+
+        // Lines longer than one chunk are stitched back together.
+        let long = "x".repeat(TAIL_CHUNK as usize + 10);
+        writeln!(file, "first\n{}\nlast", long).unwrap();
+        assert_eq!(read_tail(&path, 2).unwrap(), vec![long, "last".to_string()]);
+    }
+
+    #[test]
+    fn test_read_tail_with_start_numbers_lines() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, "a\nb\nc\nd").unwrap();
+        let path = file.path().to_str().unwrap();
+
+        assert_eq!(
+            read_tail_with_start(path, 2).unwrap(),
+            (3, vec!["c".to_string(), "d".to_string()])
+        );
+        assert_eq!(read_tail_with_start(path, 10).unwrap().0, 1);
+        assert_eq!(
+            number_lines(vec!["x".to_string()], 50),
+            vec![NumberedLine {
+                line_number: 50,
+                content: "x".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_read_lines_page_resumes_from_cursor() {
+        let mut file = NamedTempFile::new().unwrap();
+        write!(file, "one\ntwo\r\nthree\nfour\nfive").unwrap();
+        let path = file.path().to_str().unwrap();
+
+        let first = read_lines_page(path, None, None, Some(3)).unwrap();
+        assert_eq!(first.lines, vec!["one", "two", "three"]);
+        assert_eq!(first.start_line, 1);
+        let cursor = first.next_cursor.expect("more lines remain");
+
+        let second = read_lines_page(path, None, Some(&cursor), Some(3)).unwrap();
+        assert_eq!(second.lines, vec!["four", "five"]);
+        assert_eq!(second.start_line, 4);
+        assert_eq!(second.next_cursor, None, "end of file reached");
+
+        // Starting by line number lands on the same page boundary.
+        let by_line = read_lines_page(path, Some(4), None, Some(1)).unwrap();
+        assert_eq!(by_line.lines, vec!["four"]);
+
+        assert!(read_lines_page(path, None, Some("garbage"), Some(1)).is_err());
+        assert!(read_lines_page(path, None, Some("v1.ffff.1"), Some(1)).is_err());
+    }
 }
@@ -0,0 +1,241 @@
+#![deny(warnings)]
+
+// Batch-rename directory entries by regex substitution (like rename/mmv)
+
+use crate::error::{FileIoError, Result};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct RenameEntry {
+    pub from: String,
+    pub to: String,
+    /// `renamed`, `would_rename` (dry run), or `collision` (dry run only).
+    pub status: String,
+}
+
+/// Rename every entry directly inside `directory` whose name matches
+/// `pattern`, replacing the first match with `replacement` (`$1`, `${name}`
+/// capture references are expanded, as in [`regex::Regex::replace`]).
+///
+/// The whole batch is planned before anything is touched. A planned target
+/// that already exists, or that two sources would both rename to, is a
+/// collision. With `dry_run` the plan is returned with collisions marked;
+/// otherwise any collision aborts the batch before a single rename happens.
+/// If a rename fails partway through, the ones already done are renamed
+/// back before the error is returned.
+///
+/// Entries for which `denied` is true are neither planned nor listed, and a
+/// denied target counts as a collision whether or not it exists, so the
+/// result never says anything about it.
+///
+/// Why: all-or-nothing keeps a half-applied batch from leaving the directory
+/// in a state neither the old nor the new naming describes.
+pub fn rename_pattern(
+    directory: &str,
+    pattern: &regex::Regex,
+    replacement: &str,
+    dry_run: bool,
+    denied: &dyn Fn(&Path) -> bool,
+) -> Result<Vec<RenameEntry>> {
+    let expanded_dir = shellexpand::full(directory)
+        .map_err(|e| {
+            crate::error::FileIoMcpError::from(FileIoError::InvalidPath(format!(
+                "Failed to expand path \'{}\': {}",
+                directory, e
+            )))
+        })
+        .map(|expanded| expanded.into_owned())?;
+    let dir = Path::new(&expanded_dir);
+    if !dir.exists() {
+        return Err(super::path_utils::not_found("read directory", directory).into());
+    }
+    if !dir.is_dir() {
+        return Err(FileIoError::NotADirectory(expanded_dir).into());
+    }
+
+    let mut names = Vec::new();
+    for entry in fs::read_dir(dir)
+        .map_err(|e| FileIoError::from_io_error("read directory", &expanded_dir, e))?
+    {
+        let entry =
+            entry.map_err(|e| FileIoError::from_io_error("read directory", &expanded_dir, e))?;
+        if denied(&entry.path()) {
+            continue;
+        }
+        // Names that are not valid UTF-8 cannot be matched by a str regex.
+        if let Some(name) = entry.file_name().to_str() {
+            names.push(name.to_string());
+        }
+    }
+    names.sort();
+
+    let mut plan = Vec::new();
+    let mut claimed = HashSet::new();
+    for name in &names {
+        if !pattern.is_match(name) {
+            continue;
+        }
+        let new_name = pattern.replace(name, replacement).into_owned();
+        if new_name == *name {
+            continue;
+        }
+        if new_name.is_empty() || new_name.contains('/') || new_name == "." || new_name == ".." {
+            return Err(FileIoError::InvalidPath(format!(
+                "'{}' would be renamed to invalid name '{}'",
+                name, new_name
+            ))
+            .into());
+        }
+        let from = dir.join(name);
+        let to = dir.join(&new_name);
+        let collision = denied(&to) || to.symlink_metadata().is_ok() || !claimed.insert(new_name);
+        plan.push(RenameEntry {
+            from: from.to_string_lossy().into_owned(),
+            to: to.to_string_lossy().into_owned(),
+            status: if collision {
+                "collision"
+            } else {
+                "would_rename"
+            }
+            .to_string(),
+        });
+    }
+
+    if dry_run {
+        return Ok(plan);
+    }
+
+    let collisions: Vec<&str> = plan
+        .iter()
+        .filter(|e| e.status == "collision")
+        .map(|e| e.to.as_str())
+        .collect();
+    if !collisions.is_empty() {
+        return Err(FileIoError::AlreadyExists(format!(
+            "refusing to rename, targets collide: {}",
+            collisions.join(", ")
+        ))
+        .into());
+    }
+
+    for done in 0..plan.len() {
+        if let Err(e) = fs::rename(&plan[done].from, &plan[done].to) {
+            // Best effort: a rollback that fails too can't be reported any
+            // better than the error that caused it.
+            for entry in plan[..done].iter().rev() {
+                let _ = fs::rename(&entry.to, &entry.from);
+            }
+            return Err(FileIoError::from_io_error("rename", &plan[done].from, e).into());
+        }
+    }
+    for entry in &mut plan {
+        entry.status = "renamed".to_string();
+    }
+    Ok(plan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn regex(p: &str) -> regex::Regex {
+        regex::Regex::new(p).expect("test regex must compile")
+    }
+
+    #[test]
+    fn test_rename_txt_to_md() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("a.txt"), "a").unwrap();
+        fs::write(dir.path().join("b.txt"), "b").unwrap();
+        fs::write(dir.path().join("keep.rs"), "k").unwrap();
+        let d = dir.path().to_str().unwrap();
+
+        let preview = rename_pattern(d, &regex(r"^(.*)\.txt$"), "$1.md", true, &|_| false).unwrap();
+        assert_eq!(preview.len(), 2);
+        assert!(preview.iter().all(|e| e.status == "would_rename"));
+        assert!(dir.path().join("a.txt").exists());
+
+        let done = rename_pattern(d, &regex(r"^(.*)\.txt$"), "$1.md", false, &|_| false).unwrap();
+        assert!(done.iter().all(|e| e.status == "renamed"));
+        assert!(dir.path().join("a.md").exists());
+        assert!(dir.path().join("b.md").exists());
+        assert!(!dir.path().join("a.txt").exists());
+        assert!(dir.path().join("keep.rs").exists());
+    }
+
+    #[test]
+    fn test_collision_aborts_whole_batch() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("a.txt"), "a").unwrap();
+        fs::write(dir.path().join("b.txt"), "b").unwrap();
+        fs::write(dir.path().join("b.md"), "existing").unwrap();
+        let d = dir.path().to_str().unwrap();
+
+        let preview = rename_pattern(d, &regex(r"\.txt$"), ".md", true, &|_| false).unwrap();
+        let statuses: Vec<&str> = preview.iter().map(|e| e.status.as_str()).collect();
+        assert_eq!(statuses, vec!["would_rename", "collision"]);
+
+        assert!(rename_pattern(d, &regex(r"\.txt$"), ".md", false, &|_| false).is_err());
+        assert!(dir.path().join("a.txt").exists());
+        assert_eq!(
+            fs::read_to_string(dir.path().join("b.md")).unwrap(),
+            "existing"
+        );
+    }
+
+    #[test]
+    fn test_two_sources_same_target_collide() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("x1.log"), "").unwrap();
+        fs::write(dir.path().join("x2.log"), "").unwrap();
+        let d = dir.path().to_str().unwrap();
+
+        assert!(rename_pattern(d, &regex(r"\d"), "", false, &|_| false).is_err());
+        assert!(dir.path().join("x1.log").exists());
+    }
+
+    #[test]
+    fn test_denied_entries_are_not_planned() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir(dir.path().join("secret")).unwrap();
+        fs::write(dir.path().join("open"), "").unwrap();
+        let d = dir.path().to_str().unwrap();
+        let secret = dir.path().join("secret");
+        let denied = |p: &Path| p.starts_with(&secret);
+
+        let done = rename_pattern(d, &regex(r"^(.*)$"), "${1}_old", false, &denied).unwrap();
+        assert_eq!(done.len(), 1);
+        assert!(done[0].from.ends_with("open"));
+        assert!(secret.exists());
+
+        // A denied target is a collision even when nothing is there.
+        let taken = dir.path().join("taken");
+        let denied = |p: &Path| p == taken;
+        let preview = rename_pattern(d, &regex(r"^open_old$"), "taken", true, &denied).unwrap();
+        assert_eq!(preview[0].status, "collision");
+    }
+
+    #[test]
+    fn test_failed_rename_rolls_back_earlier_ones() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("a.txt"), "").unwrap();
+        fs::write(dir.path().join("b.txt"), "").unwrap();
+        let d = dir.path().to_str().unwrap();
+
+        // `b.txt` vanishes after planning, so its rename fails after
+        // `a.txt`'s has succeeded.
+        let b = dir.path().join("b.txt");
+        let denied = |p: &Path| {
+            if p.ends_with("b.md") {
+                let _ = fs::remove_file(&b);
+            }
+            false
+        };
+        assert!(rename_pattern(d, &regex(r"\.txt$"), ".md", false, &denied).is_err());
+        assert!(dir.path().join("a.txt").exists());
+        assert!(!dir.path().join("a.md").exists());
+    }
+}
@@ -0,0 +1,228 @@
+#![deny(warnings)]
+
+// Replace the block between two marker lines (managed/generated sections)
+
+use crate::error::{FileIoError, Result};
+use std::path::Path;
+
+use super::strip_whitespace::split_terminator;
+
+#[derive(Debug, serde::Serialize)]
+pub struct ReplaceMarkersResult {
+    pub path: String,
+    /// True when the markers were absent and the block was appended.
+    pub inserted: bool,
+    pub changed: bool,
+}
+
+/// Replace the lines strictly between `begin_marker` and `end_marker` in
+/// `path` with `content`.
+///
+/// A marker matches a whole line, ignoring surrounding whitespace, so
+/// indented markers still count. If neither marker is present and
+/// `create_if_missing` is set, the block (markers included) is appended to
+/// the end of the file; otherwise that is an error. Finding only one marker,
+/// or the end marker before the begin marker, is always an error rather than
+/// a guess about where the block should end. The file is rewritten
+/// atomically and only when something changed.
+pub fn replace_between_markers(
+    path: &str,
+    begin_marker: &str,
+    end_marker: &str,
+    content: &str,
+    create_if_missing: bool,
+) -> Result<ReplaceMarkersResult> {
+    if begin_marker.trim().is_empty() || end_marker.trim().is_empty() {
+        return Err(crate::error::FileIoMcpError::InvalidParams(
+            "begin_marker and end_marker must not be empty".to_string(),
+        ));
+    }
+    if begin_marker.trim() == end_marker.trim() {
+        return Err(crate::error::FileIoMcpError::InvalidParams(
+            "begin_marker and end_marker must differ".to_string(),
+        ));
+    }
+
+    let expanded_path = shellexpand::full(path)
+        .map_err(|e| {
+            crate::error::FileIoMcpError::from(FileIoError::InvalidPath(format!(
+                "Failed to expand path \'{}\': {}",
+                path, e
+            )))
+        })
+        .map(|expanded| expanded.into_owned())?;
+
+    if !Path::new(&expanded_path).is_file() {
+        return Err(FileIoError::NotFound(expanded_path).into());
+    }
+
+    let original = std::fs::read_to_string(&expanded_path)
+        .map_err(|e| FileIoError::from_io_error("read file", &expanded_path, e))?;
+
+    let (updated, inserted) = replace_block(
+        &original,
+        begin_marker,
+        end_marker,
+        content,
+        create_if_missing,
+    )
+    .map_err(|msg| FileIoError::PatchError(format!("{}: {}", expanded_path, msg)))?;
+
+    let changed = updated != original;
+    if changed {
+        super::write_file::write_file(&expanded_path, &updated, false)?;
+    }
+
+    Ok(ReplaceMarkersResult {
+        path: expanded_path,
+        inserted,
+        changed,
+    })
+}
+
+/// Pure transform behind [`replace_between_markers`]; returns the new content
+/// and whether the block was inserted, or a description of why the markers
+/// are unusable.
+fn replace_block(
+    original: &str,
+    begin_marker: &str,
+    end_marker: &str,
+    content: &str,
+    create_if_missing: bool,
+) -> std::result::Result<(String, bool), String> {
+    let eol = if original.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let pieces: Vec<&str> = original.split_inclusive('\n').collect();
+    let is_marker = |piece: &str, marker: &str| split_terminator(piece).0.trim() == marker.trim();
+    let begin = pieces.iter().position(|p| is_marker(p, begin_marker));
+    let end = pieces.iter().position(|p| is_marker(p, end_marker));
+
+    let mut block = content.to_string();
+    if !block.is_empty() && !block.ends_with('\n') {
+        block.push_str(eol);
+    }
+
+    match (begin, end) {
+        (Some(b), Some(e)) if b < e => {
+            let mut out = String::with_capacity(original.len() + block.len());
+            pieces[..=b].iter().for_each(|p| out.push_str(p));
+            out.push_str(&block);
+            pieces[e..].iter().for_each(|p| out.push_str(p));
+            Ok((out, false))
+        }
+        (Some(_), Some(_)) => Err(format!(
+            "end marker '{}' appears before begin marker '{}'",
+            end_marker, begin_marker
+        )),
+        (Some(_), None) => Err(format!(
+            "found begin marker '{}' but no end marker '{}'",
+            begin_marker, end_marker
+        )),
+        (None, Some(_)) => Err(format!(
+            "found end marker '{}' but no begin marker '{}'",
+            end_marker, begin_marker
+        )),
+        (None, None) if create_if_missing => {
+            let mut out = original.to_string();
+            if !out.is_empty() && !out.ends_with('\n') {
+                out.push_str(eol);
+            }
+            out.push_str(begin_marker);
+            out.push_str(eol);
+            out.push_str(&block);
+            out.push_str(end_marker);
+            out.push_str(eol);
+            Ok((out, true))
+        }
+        (None, None) => Err(format!(
+            "markers '{}' and '{}' not found (set create_if_missing to append them)",
+            begin_marker, end_marker
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    const BEGIN: &str = "# BEGIN GENERATED";
+    const END: &str = "# END GENERATED";
+
+    #[test]
+    fn test_replace_existing_block() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.txt");
+        fs::write(
+            &path,
+            "hand = 1\n# BEGIN GENERATED\nold = 1\nold = 2\n# END GENERATED\ntail = 2\n",
+        )
+        .unwrap();
+
+        let result =
+            replace_between_markers(path.to_str().unwrap(), BEGIN, END, "new = 3", false).unwrap();
+
+        assert!(result.changed);
+        assert!(!result.inserted);
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "hand = 1\n# BEGIN GENERATED\nnew = 3\n# END GENERATED\ntail = 2\n"
+        );
+    }
+
+    #[test]
+    fn test_insert_block_when_markers_missing() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.txt");
+        fs::write(&path, "hand = 1").unwrap();
+        let p = path.to_str().unwrap();
+
+        assert!(replace_between_markers(p, BEGIN, END, "gen = 1\n", false).is_err());
+
+        let result = replace_between_markers(p, BEGIN, END, "gen = 1\n", true).unwrap();
+        assert!(result.inserted);
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "hand = 1\n# BEGIN GENERATED\ngen = 1\n# END GENERATED\n"
+        );
+
+        // Running again finds the block it just wrote and changes nothing.
+        let result = replace_between_markers(p, BEGIN, END, "gen = 1\n", true).unwrap();
+        assert!(!result.inserted);
+        assert!(!result.changed);
+    }
+
+    #[test]
+    fn test_single_marker_is_an_error() {
+        assert!(replace_block("# BEGIN GENERATED\nx\n", BEGIN, END, "y", true).is_err());
+        assert!(replace_block("x\n# END GENERATED\n", BEGIN, END, "y", true).is_err());
+        assert!(
+            replace_block(
+                "# END GENERATED\n# BEGIN GENERATED\n",
+                BEGIN,
+                END,
+                "y",
+                true
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_indented_markers_and_crlf() {
+        let (out, inserted) = replace_block(
+            "a\r\n  # BEGIN GENERATED\r\nold\r\n  # END GENERATED\r\n",
+            BEGIN,
+            END,
+            "",
+            false,
+        )
+        .unwrap();
+        assert!(!inserted);
+        assert_eq!(out, "a\r\n  # BEGIN GENERATED\r\n  # END GENERATED\r\n");
+    }
+}
@@ -3,73 +3,53 @@
 // Remove files or directories
 
 use crate::error::{FileIoError, Result};
-use globset::{Glob, GlobMatcher};
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::Path;
 
-/// Check if a string contains glob patterns
-fn is_glob_pattern(s: &str) -> bool {
-    s.contains('*') || s.contains('?') || s.contains('[') || s.contains('{')
+use super::glob_expand::{expand_glob, is_glob_pattern};
+
+/// Result of [`rm_reporting_size`]: the usual per-path results plus the total
+/// number of bytes the removed entries occupied.
+#[derive(Debug, serde::Serialize)]
+pub struct RmReport {
+    pub results: Vec<super::mv::OpResult>,
+    pub freed_bytes: u64,
 }
 
-/// Expand glob pattern to matching paths
-fn expand_glob(pattern: &str) -> Result<Vec<PathBuf>> {
-    let expanded_pattern = shellexpand::full(pattern)
-        .map_err(|e| {
-            crate::error::FileIoMcpError::from(crate::error::FileIoError::InvalidPath(format!(
-                "Failed to expand path \'{}\': {}",
-                pattern, e
-            )))
-        })
-        .map(|expanded| expanded.into_owned())?;
-    let path = Path::new(&expanded_pattern);
-    let (base_dir, glob_str) = if let Some(parent) = path.parent() {
-        if parent.as_os_str().is_empty() {
-            (
-                Path::new("."),
-                path.file_name().and_then(|n| n.to_str()).unwrap_or(pattern),
-            )
-        } else {
-            (
-                parent,
-                path.file_name().and_then(|n| n.to_str()).unwrap_or(pattern),
-            )
-        }
-    } else {
-        (Path::new("."), pattern)
-    };
+/// Remove files or directories (supports glob patterns and arrays of paths)
+pub fn rm(paths: &[&str], recursive: bool, force: bool) -> Result<Vec<super::mv::OpResult>> {
+    rm_impl(paths, recursive, force, false, false).map(|report| report.results)
+}
 
-    let glob = Glob::new(glob_str).map_err(|e| {
-        FileIoError::InvalidPath(format!("Invalid glob pattern {}: {}", pattern, e))
-    })?;
-    let matcher: GlobMatcher = glob.compile_matcher();
-
-    let mut matches = Vec::new();
-    let entries = fs::read_dir(base_dir).map_err(|e| {
-        FileIoError::ReadError(format!(
-            "Failed to read directory {}: {}",
-            base_dir.display(),
-            e
-        ))
-    })?;
-
-    for entry in entries {
-        let entry = entry.map_err(|e| {
-            FileIoError::ReadError(format!("Failed to read directory entry: {}", e))
-        })?;
-        let entry_path = entry.path();
-        if let Some(file_name) = entry_path.file_name().and_then(|n| n.to_str())
-            && matcher.is_match(file_name)
-        {
-            matches.push(entry_path);
-        }
-    }
+/// Like [`rm`], but also reports how many bytes were reclaimed.
+///
+/// Sizes are summed (recursively for directories) before each entry is
+/// deleted, and only entries that were actually removed contribute. Why a
+/// separate entry point: the extra walk costs a stat per file, which plain
+/// cleanup calls shouldn't pay.
+pub fn rm_reporting_size(paths: &[&str], recursive: bool, force: bool) -> Result<RmReport> {
+    rm_impl(paths, recursive, force, true, false)
+}
 
-    Ok(matches)
+/// Plan a removal without deleting anything.
+///
+/// Globs and arrays are expanded exactly as [`rm`] would. Each path that
+/// would be deleted is reported as `would_remove`, a missing path that
+/// `force` would pass over as `skipped` (with `exists: false`), and a path
+/// the real call would fail on (missing without `force`, or a non-empty
+/// directory without `recursive`) with the same `error: ...` status. With
+/// `measure`, `freed_bytes` is what the removal would reclaim.
+pub fn rm_dry_run(paths: &[&str], recursive: bool, force: bool, measure: bool) -> Result<RmReport> {
+    rm_impl(paths, recursive, force, measure, true)
 }
 
-/// Remove files or directories (supports glob patterns and arrays of paths)
-pub fn rm(paths: &[&str], recursive: bool, force: bool) -> Result<Vec<super::mv::OpResult>> {
+fn rm_impl(
+    paths: &[&str],
+    recursive: bool,
+    force: bool,
+    measure: bool,
+    dry_run: bool,
+) -> Result<RmReport> {
     let mut all_paths = Vec::new();
 
     for path in paths {
@@ -97,13 +77,33 @@ pub fn rm(paths: &[&str], recursive: bool, force: bool) -> Result<Vec<super::mv:
 
     // Remove all collected paths and return per-path results
     let mut results = Vec::new();
+    let mut freed_bytes = 0u64;
     for path in &all_paths {
-        match rm_single(path, recursive, force) {
-            Ok(()) => results.push(super::mv::OpResult {
+        // Measure before deleting — afterwards there is nothing left to stat.
+        let size = if measure {
+            disk_size(Path::new(path))
+        } else {
+            0
+        };
+        let outcome = if dry_run {
+            plan_single(path, recursive, force)
+        } else {
+            rm_single(path, recursive, force).map(|()| "ok")
+        };
+        match outcome {
+            Ok("skipped") => results.push(super::mv::OpResult {
                 path: path.clone(),
-                status: "ok".to_string(),
-                exists: true,
+                status: "skipped".to_string(),
+                exists: false,
             }),
+            Ok(status) => {
+                freed_bytes += size;
+                results.push(super::mv::OpResult {
+                    path: path.clone(),
+                    status: status.to_string(),
+                    exists: true,
+                });
+            }
             Err(e) => {
                 let is_not_found = matches!(
                     e,
@@ -118,7 +118,64 @@ pub fn rm(paths: &[&str], recursive: bool, force: bool) -> Result<Vec<super::mv:
         }
     }
 
-    Ok(results)
+    Ok(RmReport {
+        results,
+        freed_bytes,
+    })
+}
+
+/// Total size in bytes of the regular files at or under `path`.
+///
+/// Symlinks are not followed (removing a link frees the link, not its
+/// target), and unreadable entries count as zero rather than failing the
+/// removal they are only reporting on.
+fn disk_size(path: &Path) -> u64 {
+    let Ok(meta) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if meta.is_file() {
+        return meta.len();
+    }
+    if !meta.is_dir() {
+        return 0;
+    }
+    fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| disk_size(&entry.path()))
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+/// What [`rm_single`] would do to `path`: `would_remove`, `skipped` for a
+/// missing path under `force`, or the error it would fail with.
+fn plan_single(path: &str, recursive: bool, force: bool) -> Result<&'static str> {
+    let path_obj = Path::new(path);
+    if !path_obj.exists() && !path_obj.is_symlink() {
+        if force {
+            return Ok("skipped");
+        }
+        return Err(FileIoError::NotFound(path.to_string()).into());
+    }
+    if path_obj.is_dir() && !path_obj.is_symlink() && !recursive {
+        let mut entries = fs::read_dir(path).map_err(|e| {
+            crate::error::FileIoMcpError::from(FileIoError::from_io_error(
+                "read directory",
+                path,
+                e,
+            ))
+        })?;
+        if entries.next().is_some() {
+            return Err(FileIoError::DirectoryNotEmpty(format!(
+                "{}. Use recursive=true to remove non-empty directories",
+                path
+            ))
+            .into());
+        }
+    }
+    Ok("would_remove")
 }
 
 /// Remove a single file or directory
@@ -139,10 +196,18 @@ fn rm_single(path: &str, recursive: bool, force: bool) -> Result<()> {
     } else if path_obj.is_dir() {
         if recursive {
             fs::remove_dir_all(path).map_err(|e| {
+                crate::error::FileIoMcpError::from(FileIoError::from_io_error(
+                    "remove directory",
+                    path,
+                    e,
+                ))
+            })?;
+        } else {
+            fs::remove_dir(path).map_err(|e| {
                 use std::io::ErrorKind;
                 match e.kind() {
-                    ErrorKind::PermissionDenied => FileIoError::PermissionDenied(format!(
-                        "Permission denied when removing directory: {}",
+                    ErrorKind::DirectoryNotEmpty => FileIoError::DirectoryNotEmpty(format!(
+                        "{}. Use recursive=true to remove non-empty directories",
                         path
                     ))
                     .into(),
@@ -153,25 +218,6 @@ fn rm_single(path: &str, recursive: bool, force: bool) -> Result<()> {
                     )),
                 }
             })?;
-        } else {
-            fs::remove_dir(path).map_err(|e| {
-                use std::io::ErrorKind;
-                match e.kind() {
-                    ErrorKind::PermissionDenied => {
-                        FileIoError::PermissionDenied(format!(
-                            "Permission denied when removing directory: {}",
-                            path
-                        )).into()
-                    }
-                    ErrorKind::InvalidInput => {
-                        FileIoError::WriteError(format!(
-                            "Directory is not empty: {}. Use recursive=true to remove non-empty directories",
-                            path
-                        )).into()
-                    }
-                    _ => crate::error::FileIoMcpError::from(FileIoError::from_io_error("remove directory", path, e))
-                }
-            })?;
         }
     }
 
@@ -225,4 +271,93 @@ mod tests {
         assert!(!base.join("file2.txt").exists());
         assert!(base.join("other.log").exists());
     }
+
+    #[test]
+    fn test_rm_reporting_size_sums_tree() {
+        let dir = TempDir::new().unwrap();
+        let tree = dir.path().join("tree");
+        fs::create_dir_all(tree.join("nested")).unwrap();
+        fs::write(tree.join("a.txt"), "12345").unwrap();
+        fs::write(tree.join("nested").join("b.txt"), "1234567890").unwrap();
+        let loose = dir.path().join("loose.bin");
+        fs::write(&loose, [0u8; 7]).unwrap();
+
+        let report = rm_reporting_size(
+            &[tree.to_str().unwrap(), loose.to_str().unwrap()],
+            true,
+            false,
+        )
+        .unwrap();
+        assert!(report.results.iter().all(|r| r.status == "ok"));
+        assert_eq!(report.freed_bytes, 5 + 10 + 7);
+        assert!(!tree.exists());
+        assert!(!loose.exists());
+    }
+
+    #[test]
+    fn test_rm_reporting_size_skips_failed_removals() {
+        let dir = TempDir::new().unwrap();
+        let subdir = dir.path().join("subdir");
+        fs::create_dir_all(&subdir).unwrap();
+        fs::write(subdir.join("file.txt"), "content").unwrap();
+
+        // Non-recursive removal of a non-empty dir fails, so nothing is freed.
+        let report = rm_reporting_size(&[subdir.to_str().unwrap()], false, false).unwrap();
+        assert!(report.results[0].status.starts_with("error"));
+        assert_eq!(report.freed_bytes, 0);
+        assert!(subdir.exists());
+    }
+
+    #[test]
+    fn test_rm_dry_run_over_glob_keeps_files() {
+        let dir = TempDir::new().expect("create temp dir");
+        let a = dir.path().join("a.tmp");
+        let b = dir.path().join("b.tmp");
+        fs::write(&a, "aa").expect("write a");
+        fs::write(&b, "bbb").expect("write b");
+        fs::write(dir.path().join("keep.txt"), "k").expect("write keep");
+        let pattern = dir.path().join("*.tmp");
+        let missing = dir.path().join("gone.tmp");
+
+        let report = rm_dry_run(
+            &[
+                pattern.to_str().expect("utf-8 path"),
+                missing.to_str().expect("utf-8 path"),
+            ],
+            false,
+            true,
+            true,
+        )
+        .expect("dry run succeeds");
+
+        let mut statuses: Vec<(&str, &str)> = report
+            .results
+            .iter()
+            .map(|r| {
+                let name = Path::new(&r.path)
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .expect("file name");
+                (name, r.status.as_str())
+            })
+            .collect();
+        // Glob matches come back in directory order.
+        statuses.sort();
+        assert_eq!(
+            statuses,
+            vec![
+                ("a.tmp", "would_remove"),
+                ("b.tmp", "would_remove"),
+                ("gone.tmp", "skipped")
+            ]
+        );
+        assert!(
+            report
+                .results
+                .iter()
+                .all(|r| r.exists == (r.status == "would_remove"))
+        );
+        assert_eq!(report.freed_bytes, 5);
+        assert!(a.exists() && b.exists(), "dry run must not delete");
+    }
 }
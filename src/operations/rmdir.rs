@@ -60,14 +60,15 @@ pub fn rmdir_single(path: &str, recursive: bool) -> Result<()> {
     // Check if directory is empty when recursive=false
     if !recursive {
         let mut entries = std::fs::read_dir(&expanded_path).map_err(|e| {
-            crate::error::FileIoMcpError::from(FileIoError::ReadError(format!(
-                "Failed to read directory {}: {}",
-                expanded_path, e
-            )))
+            crate::error::FileIoMcpError::from(FileIoError::from_io_error(
+                "read directory",
+                &expanded_path,
+                e,
+            ))
         })?;
         if entries.next().is_some() {
-            return Err(FileIoError::WriteError(format!(
-                "Directory is not empty: {}. Use recursive=true to remove non-empty directories",
+            return Err(FileIoError::DirectoryNotEmpty(format!(
+                "{}. Use recursive=true to remove non-empty directories",
                 expanded_path
             ))
             .into());
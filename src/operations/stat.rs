@@ -16,63 +16,172 @@ pub struct FileStat {
     pub modified: Option<String>,
     pub accessed: Option<String>,
     pub created: Option<String>,
+    /// ID of the device containing the entry (Unix only); differs across a
+    /// mount boundary.
+    pub dev: Option<u64>,
+    /// Device ID the entry itself represents, for device nodes (Unix only;
+    /// 0 for regular files and directories).
+    pub rdev: Option<u64>,
+    /// Content digest, for regular files when requested (see
+    /// [`StatOptions::hash`]).
+    pub hash: Option<String>,
     pub is_file: bool,
     pub is_dir: bool,
     pub is_symlink: bool,
     pub exists: bool,
+    /// `"ok"`, or `"error: <message>"` when this path's metadata couldn't be
+    /// read; the rest of the batch is unaffected.
+    pub status: String,
+}
+
+/// Options for [`stat_with_options`].
+#[derive(Debug, Clone, Copy)]
+pub struct StatOptions {
+    /// Report what a symlink points to (the default) rather than the link
+    /// itself. Without following, a link is `type: "symlink"` with its own
+    /// size and mode, and `max_symlink_depth` has no effect.
+    pub follow_symlinks: bool,
+    /// Resolve link chains step by step, failing past this many links.
+    pub max_symlink_depth: Option<u64>,
+    /// Also digest the content of regular files. Directories, symlinks that
+    /// don't resolve to a regular file, and special files get no hash.
+    pub hash: Option<super::checksum::HashAlgorithm>,
+}
+
+impl Default for StatOptions {
+    fn default() -> Self {
+        Self {
+            follow_symlinks: true,
+            max_symlink_depth: None,
+            hash: None,
+        }
+    }
 }
 
 /// Get file or directory statistics
 /// Can accept a single path or multiple paths, returns a vector of FileStat
 pub fn stat(paths: &[&str]) -> Result<Vec<FileStat>> {
-    let mut results = Vec::new();
-    let mut errors = Vec::new();
-    for path in paths {
-        match stat_single(path) {
-            Ok(stat_result) => results.push(stat_result),
-            Err(e) => {
-                errors.push(format!("{}: {}", path, e));
-            }
-        }
-    }
-    if !errors.is_empty() {
-        return Err(crate::error::FileIoMcpError::from(FileIoError::ReadError(
-            format!("Some stat queries failed: {}", errors.join("; ")),
-        )));
-    }
-    Ok(results)
+    stat_with(paths, None)
 }
 
-/// Get file or directory statistics for a single path
-pub fn stat_single(path: &str) -> Result<FileStat> {
-    let expanded_path = shellexpand::full(path)
-        .map_err(|e| {
-            crate::error::FileIoMcpError::from(crate::error::FileIoError::InvalidPath(format!(
-                "Failed to expand path \'{}\': {}",
-                path, e
-            )))
+/// Like [`stat`], but with `max_symlink_depth` each path's link chain is
+/// resolved step by step (see [`super::path_utils::resolve_symlinks`]) and a
+/// chain longer than the limit is reported as that path's error instead of
+/// whatever the OS does.
+pub fn stat_with(paths: &[&str], max_symlink_depth: Option<u64>) -> Result<Vec<FileStat>> {
+    stat_with_options(
+        paths,
+        &StatOptions {
+            max_symlink_depth,
+            ..StatOptions::default()
+        },
+    )
+}
+
+/// [`stat`] with [`StatOptions`].
+///
+/// Each path is reported on its own: one whose metadata can't be read (for
+/// example, permission denied on a parent directory) gets `type: "error"`
+/// and an `"error: ..."` status instead of failing the whole batch.
+pub fn stat_with_options(paths: &[&str], options: &StatOptions) -> Result<Vec<FileStat>> {
+    Ok(paths
+        .iter()
+        .map(|path| {
+            stat_single_with_options(path, options).unwrap_or_else(|e| FileStat {
+                path: path.to_string(),
+                entry_type: "error".to_string(),
+                status: format!("error: {}", e),
+                ..FileStat::absent(path)
+            })
         })
-        .map(|expanded| expanded.into_owned())?;
-    let path_obj = Path::new(&expanded_path);
+        .collect())
+}
 
-    // If the path does not exist, return a sentinel FileStat rather than an error.
-    if !path_obj.exists() {
-        return Ok(FileStat {
-            path: expanded_path.clone(),
+impl FileStat {
+    /// An entry for a path with nothing at it.
+    fn absent(path: &str) -> Self {
+        FileStat {
+            path: path.to_string(),
             entry_type: "not_found".to_string(),
             size: 0,
             mode: None,
             modified: None,
             accessed: None,
             created: None,
+            dev: None,
+            rdev: None,
+            hash: None,
             is_file: false,
             is_dir: false,
             is_symlink: false,
             exists: false,
-        });
+            status: "ok".to_string(),
+        }
     }
+}
 
-    let metadata = fs::metadata(&expanded_path).map_err(|e| {
+/// Get file or directory statistics for a single path
+pub fn stat_single(path: &str) -> Result<FileStat> {
+    stat_single_with(path, None)
+}
+
+/// [`stat_single`] with an optional symlink depth limit (see [`stat_with`]).
+pub fn stat_single_with(path: &str, max_symlink_depth: Option<u64>) -> Result<FileStat> {
+    stat_single_with_options(
+        path,
+        &StatOptions {
+            max_symlink_depth,
+            ..StatOptions::default()
+        },
+    )
+}
+
+/// [`stat_single`] with [`StatOptions`].
+pub fn stat_single_with_options(path: &str, options: &StatOptions) -> Result<FileStat> {
+    let max_symlink_depth = options.max_symlink_depth;
+    let expanded_path = super::path_utils::resolve_path(path)?;
+    let path_obj = Path::new(&expanded_path);
+
+    let read_metadata = |p: &Path| {
+        if options.follow_symlinks {
+            fs::metadata(p)
+        } else {
+            fs::symlink_metadata(p)
+        }
+    };
+    let resolved = match max_symlink_depth.filter(|_| options.follow_symlinks) {
+        Some(max_depth) => match super::path_utils::resolve_symlinks(path_obj, max_depth) {
+            Ok(resolved) => Some(resolved),
+            // Same as a missing path without a limit.
+            Err(crate::error::FileIoMcpError::FileIo(
+                FileIoError::NotFound(_) | FileIoError::NotADirectory(_),
+            )) => None,
+            Err(e) => return Err(e),
+        },
+        // Unlike `Path::exists`, only a genuinely missing path counts as
+        // absent; any other failure (e.g. permission denied) is an error.
+        None => match read_metadata(path_obj) {
+            Ok(_) => Some(path_obj.to_path_buf()),
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    std::io::ErrorKind::NotFound | std::io::ErrorKind::NotADirectory
+                ) =>
+            {
+                None
+            }
+            Err(e) => {
+                return Err(FileIoError::from_io_error("read metadata", &expanded_path, e).into());
+            }
+        },
+    };
+
+    // If the path does not exist, return a sentinel FileStat rather than an error.
+    let Some(resolved) = resolved else {
+        return Ok(FileStat::absent(&expanded_path));
+    };
+
+    let metadata = read_metadata(&resolved).map_err(|e| {
         crate::error::FileIoMcpError::from(FileIoError::from_io_error(
             "read metadata",
             &expanded_path,
@@ -80,9 +189,9 @@ pub fn stat_single(path: &str) -> Result<FileStat> {
         ))
     })?;
 
-    let entry_type = if path_obj.is_dir() {
+    let entry_type = if metadata.is_dir() {
         "directory"
-    } else if path_obj.is_file() {
+    } else if metadata.is_file() {
         "file"
     } else if path_obj.is_symlink() {
         "symlink"
@@ -102,6 +211,15 @@ pub fn stat_single(path: &str) -> Result<FileStat> {
     #[cfg(not(unix))]
     let mode: Option<String> = None;
 
+    #[cfg(unix)]
+    let (dev, rdev) = {
+        use std::os::unix::fs::MetadataExt;
+        (Some(metadata.dev()), Some(metadata.rdev()))
+    };
+
+    #[cfg(not(unix))]
+    let (dev, rdev): (Option<u64>, Option<u64>) = (None, None);
+
     let modified = metadata.modified().ok().and_then(|t| {
         t.duration_since(std::time::UNIX_EPOCH)
             .ok()
@@ -128,6 +246,14 @@ pub fn stat_single(path: &str) -> Result<FileStat> {
             .map(|d| d.as_secs().to_string())
     });
 
+    // Streamed, so hashing a large file costs time but not memory.
+    let hash = match options.hash {
+        Some(algorithm) if metadata.is_file() => {
+            Some(super::checksum::hash_file(&resolved, algorithm)?)
+        }
+        _ => None,
+    };
+
     Ok(FileStat {
         path: expanded_path.clone(),
         entry_type,
@@ -136,10 +262,14 @@ pub fn stat_single(path: &str) -> Result<FileStat> {
         modified,
         accessed,
         created,
-        is_file: path_obj.is_file(),
-        is_dir: path_obj.is_dir(),
+        dev,
+        rdev,
+        hash,
+        is_file: metadata.is_file(),
+        is_dir: metadata.is_dir(),
         is_symlink: path_obj.is_symlink(),
         exists: true,
+        status: "ok".to_string(),
     })
 }
 
@@ -161,10 +291,20 @@ impl From<FileStat> for Value {
         if let Some(created) = stat.created {
             obj.insert("created".to_string(), Value::String(created));
         }
+        if let Some(dev) = stat.dev {
+            obj.insert("dev".to_string(), Value::Number(dev.into()));
+        }
+        if let Some(rdev) = stat.rdev {
+            obj.insert("rdev".to_string(), Value::Number(rdev.into()));
+        }
+        if let Some(hash) = stat.hash {
+            obj.insert("hash".to_string(), Value::String(hash));
+        }
         obj.insert("is_file".to_string(), Value::Bool(stat.is_file));
         obj.insert("is_dir".to_string(), Value::Bool(stat.is_dir));
         obj.insert("is_symlink".to_string(), Value::Bool(stat.is_symlink));
         obj.insert("exists".to_string(), Value::Bool(stat.exists));
+        obj.insert("status".to_string(), Value::String(stat.status));
         Value::Object(obj)
     }
 }
@@ -198,10 +338,114 @@ mod tests {
         assert_eq!(stat_result.entry_type, "directory");
     }
 
+    #[test]
+    fn test_stat_hash_matches_checksum() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("data.bin");
+        fs::write(&file, "some content\n").unwrap();
+        let file_str = file.to_str().unwrap();
+        let dir_str = dir.path().to_str().unwrap();
+
+        let options = StatOptions {
+            hash: Some(crate::operations::checksum::HashAlgorithm::Sha256),
+            ..StatOptions::default()
+        };
+        let results = stat_with_options(&[file_str, dir_str], &options).unwrap();
+        assert_eq!(
+            results[0].hash.as_deref(),
+            Some(
+                crate::operations::checksum::sha256_file(&file)
+                    .unwrap()
+                    .as_str()
+            )
+        );
+        assert_eq!(results[1].hash, None, "directories are not hashed");
+
+        let plain = stat(&[file_str]).unwrap();
+        assert_eq!(plain[0].hash, None);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_stat_reports_errors_per_path() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new().unwrap();
+        let readable = dir.path().join("a.txt");
+        fs::write(&readable, "a").unwrap();
+        let missing = dir.path().join("missing.txt");
+        let locked = dir.path().join("locked");
+        fs::create_dir(&locked).unwrap();
+        fs::write(locked.join("secret.txt"), "s").unwrap();
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o000)).unwrap();
+        let hidden = locked.join("secret.txt");
+
+        let results = stat(&[
+            readable.to_str().unwrap(),
+            missing.to_str().unwrap(),
+            hidden.to_str().unwrap(),
+        ])
+        .unwrap();
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).unwrap();
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].status, "ok");
+        assert_eq!(results[0].entry_type, "file");
+        assert_eq!(results[1].status, "ok");
+        assert_eq!(results[1].entry_type, "not_found");
+        // root bypasses directory permissions, so the denial can't be provoked.
+        if !nix::unistd::geteuid().is_root() {
+            assert!(results[2].status.starts_with("error: "), "{:?}", results[2]);
+            assert_eq!(results[2].entry_type, "error");
+        }
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_stat_without_following_symlinks() {
+        let dir = TempDir::new().unwrap();
+        let target = dir.path().join("target.txt");
+        fs::write(&target, "content").unwrap();
+        let link = dir.path().join("link");
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+        let link_str = link.to_str().unwrap();
+
+        let followed = stat(&[link_str]).unwrap();
+        assert_eq!(followed[0].entry_type, "file");
+        assert_eq!(followed[0].size, 7);
+
+        let options = StatOptions {
+            follow_symlinks: false,
+            ..StatOptions::default()
+        };
+        let own = stat_with_options(&[link_str], &options).unwrap();
+        assert_eq!(own[0].entry_type, "symlink");
+        assert!(own[0].is_symlink);
+        assert!(!own[0].is_file);
+    }
+
     #[test]
     fn test_stat_not_found() {
         let result = stat(&["/nonexistent/path/that/does/not/exist"]).unwrap();
         let stat_result = &result[0];
         assert_eq!(stat_result.entry_type, "not_found");
     }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_stat_same_dir_same_dev() {
+        let dir = TempDir::new().unwrap();
+        let a = dir.path().join("a.txt");
+        let b = dir.path().join("b.txt");
+        std::fs::write(&a, "a").unwrap();
+        std::fs::write(&b, "b").unwrap();
+
+        let results = stat(&[a.to_str().unwrap(), b.to_str().unwrap()]).unwrap();
+        assert!(results[0].dev.is_some());
+        assert_eq!(results[0].dev, results[1].dev);
+        assert_eq!(results[0].rdev, Some(0));
+
+        let json: Value = results[0].clone().into();
+        assert!(json.get("dev").is_some());
+    }
 }
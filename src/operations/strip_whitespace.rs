@@ -0,0 +1,157 @@
+#![deny(warnings)]
+
+// Strip trailing whitespace from every line of a file
+
+use crate::error::{FileIoError, Result};
+use std::path::Path;
+
+#[derive(Debug, serde::Serialize)]
+pub struct StripWhitespaceResult {
+    pub path: String,
+    /// Number of lines whose content changed (trailing blanks removed, or
+    /// dropped as excess blank lines at end of file).
+    pub lines_changed: u64,
+    pub changed: bool,
+}
+
+/// Remove trailing spaces and tabs from every line of `path`.
+///
+/// Line terminators (`\n` or `\r\n`) are preserved per line. With
+/// `ensure_final_newline`, trailing blank lines are collapsed and the file is
+/// made to end in exactly one terminator, using the file's own line-ending
+/// style. The file is rewritten atomically and only when something changed.
+pub fn strip_trailing_whitespace(
+    path: &str,
+    ensure_final_newline: bool,
+) -> Result<StripWhitespaceResult> {
+    let expanded_path = shellexpand::full(path)
+        .map_err(|e| {
+            crate::error::FileIoMcpError::from(FileIoError::InvalidPath(format!(
+                "Failed to expand path \'{}\': {}",
+                path, e
+            )))
+        })
+        .map(|expanded| expanded.into_owned())?;
+
+    if !Path::new(&expanded_path).is_file() {
+        return Err(FileIoError::NotFound(expanded_path).into());
+    }
+
+    let content = std::fs::read_to_string(&expanded_path)
+        .map_err(|e| FileIoError::from_io_error("read file", &expanded_path, e))?;
+
+    let (stripped, lines_changed) = strip(&content, ensure_final_newline);
+    let changed = stripped != content;
+    if changed {
+        super::write_file::write_file(&expanded_path, &stripped, false)?;
+    }
+
+    Ok(StripWhitespaceResult {
+        path: expanded_path,
+        lines_changed,
+        changed,
+    })
+}
+
+/// Pure transform behind [`strip_trailing_whitespace`]; returns the new
+/// content and the number of lines changed.
+fn strip(content: &str, ensure_final_newline: bool) -> (String, u64) {
+    let eol = if content.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let mut lines: Vec<(&str, &str)> = Vec::new();
+    let mut lines_changed = 0u64;
+
+    for piece in content.split_inclusive('\n') {
+        let (body, terminator) = split_terminator(piece);
+        let trimmed = body.trim_end_matches([' ', '\t']);
+        if trimmed.len() != body.len() {
+            lines_changed += 1;
+        }
+        lines.push((trimmed, terminator));
+    }
+
+    if ensure_final_newline && !lines.is_empty() {
+        while lines.len() > 1 && lines[lines.len() - 1].0.is_empty() {
+            lines.pop();
+            lines_changed += 1;
+        }
+        if let Some(last) = lines.last_mut() {
+            if last.0.is_empty() {
+                // The whole file is blank: collapse to empty.
+                lines.clear();
+            } else if last.1.is_empty() {
+                last.1 = eol;
+            }
+        }
+    }
+
+    let mut out = String::with_capacity(content.len());
+    for (body, terminator) in lines {
+        out.push_str(body);
+        out.push_str(terminator);
+    }
+    (out, lines_changed)
+}
+
+/// Split a line produced by `split_inclusive('\n')` into body and terminator.
+pub(crate) fn split_terminator(piece: &str) -> (&str, &str) {
+    if let Some(body) = piece.strip_suffix("\r\n") {
+        (body, "\r\n")
+    } else if let Some(body) = piece.strip_suffix('\n') {
+        (body, "\n")
+    } else {
+        (piece, "")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_strip_trailing_whitespace() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("test.txt");
+        fs::write(&path, "keep  inner\nfoo  \nbar\t\n  indented \nbaz").unwrap();
+
+        let result = strip_trailing_whitespace(path.to_str().unwrap(), false).unwrap();
+
+        assert!(result.changed);
+        assert_eq!(result.lines_changed, 3);
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "keep  inner\nfoo\nbar\n  indented\nbaz"
+        );
+    }
+
+    #[test]
+    fn test_strip_preserves_crlf() {
+        let (out, changed) = strip("a \r\nb\r\n", false);
+        assert_eq!(out, "a\r\nb\r\n");
+        assert_eq!(changed, 1);
+    }
+
+    #[test]
+    fn test_ensure_final_newline() {
+        assert_eq!(strip("a\nb", true).0, "a\nb\n");
+        assert_eq!(strip("a\r\nb", true).0, "a\r\nb\r\n");
+        assert_eq!(strip("a\n\n  \n", true), ("a\n".to_string(), 3));
+        assert_eq!(strip("", true).0, "");
+    }
+
+    #[test]
+    fn test_clean_file_untouched() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("clean.txt");
+        fs::write(&path, "clean\n").unwrap();
+
+        let result = strip_trailing_whitespace(path.to_str().unwrap(), true).unwrap();
+        assert!(!result.changed);
+        assert_eq!(result.lines_changed, 0);
+    }
+}
@@ -0,0 +1,317 @@
+#![deny(warnings)]
+
+// Follow a growing file like `tail -f` for a bounded window
+
+use crate::error::{FileIoError, Result};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Bytes read per step when scanning backwards for the last lines.
+const CHUNK: u64 = 8192;
+
+/// Options for [`tail_follow`].
+#[derive(Debug, Clone, Copy)]
+pub struct TailOptions {
+    /// Lines from the end of the file to return before following.
+    pub lines: usize,
+    /// How long to follow before returning.
+    pub duration: Duration,
+    /// Return early once at least this many new lines have arrived.
+    pub max_lines: Option<usize>,
+    /// How often the file is checked for growth, truncation, or rotation.
+    pub poll_interval: Duration,
+}
+
+impl Default for TailOptions {
+    fn default() -> Self {
+        Self {
+            lines: 10,
+            duration: Duration::from_secs(5),
+            max_lines: None,
+            poll_interval: Duration::from_millis(100),
+        }
+    }
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct TailFollowResult {
+    pub path: String,
+    /// The last `lines` lines present when following started.
+    pub initial: Vec<String>,
+    /// Lines appended while following, in order. A final line still missing
+    /// its newline when the window closes is included as is.
+    pub appended: Vec<String>,
+    /// Times the path was replaced by a new file (log rotation) and reopened.
+    pub rotations: u32,
+    /// Times the file shrank and was reread from the start.
+    pub truncations: u32,
+}
+
+/// Return the last `options.lines` lines of `path`, then collect the lines
+/// appended to it until `options.duration` passes (or `options.max_lines`
+/// arrive).
+///
+/// Rotation is detected by the path naming a different file than the one
+/// open: the old file is drained first, then the new one is followed from
+/// its start. A file that shrinks is treated as truncated and reread from
+/// the start. Lines are decoded as UTF-8, lossily, without their line
+/// endings.
+///
+/// Why polling rather than a filesystem watcher: a watcher on the file stops
+/// seeing it once it is renamed away, and the size/identity check has to
+/// happen on every wakeup anyway. Why collect-then-return: tool calls have
+/// no channel for intermediate notifications (see the README), so appended
+/// lines are gathered for a bounded window, as `watch_dir` does.
+pub fn tail_follow(path: &str, options: &TailOptions) -> Result<TailFollowResult> {
+    let expanded_path = shellexpand::full(path)
+        .map_err(|e| {
+            crate::error::FileIoMcpError::from(FileIoError::InvalidPath(format!(
+                "Failed to expand path \'{}\': {}",
+                path, e
+            )))
+        })
+        .map(|expanded| expanded.into_owned())?;
+    let path_obj = Path::new(&expanded_path);
+    if !path_obj.exists() {
+        return Err(super::path_utils::not_found("follow file", path).into());
+    }
+    if !path_obj.is_file() {
+        return Err(FileIoError::InvalidPath(format!("{} is not a file", expanded_path)).into());
+    }
+    let io_err = |op: &str, e: std::io::Error| FileIoError::from_io_error(op, &expanded_path, e);
+
+    let mut file = File::open(path_obj).map_err(|e| io_err("open file", e))?;
+    let meta = file
+        .metadata()
+        .map_err(|e| io_err("read metadata for", e))?;
+    let initial =
+        last_lines(&mut file, meta.len(), options.lines).map_err(|e| io_err("read file", e))?;
+    let mut follower = Follower {
+        file,
+        pos: meta.len(),
+        identity: (meta.dev(), meta.ino()),
+        partial: Vec::new(),
+        appended: Vec::new(),
+    };
+
+    let deadline = Instant::now() + options.duration;
+    let (mut rotations, mut truncations) = (0, 0);
+    loop {
+        if options
+            .max_lines
+            .is_some_and(|max| follower.appended.len() >= max)
+        {
+            break;
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            break;
+        }
+        std::thread::sleep(options.poll_interval.min(deadline - now));
+
+        follower.read_new().map_err(|e| io_err("read file", e))?;
+        // A missing path is a rotation in progress; keep the old handle and
+        // look again on the next wakeup.
+        let Ok(current) = std::fs::metadata(path_obj) else {
+            continue;
+        };
+        if (current.dev(), current.ino()) != follower.identity {
+            let Ok(file) = File::open(path_obj) else {
+                continue;
+            };
+            let meta = file
+                .metadata()
+                .map_err(|e| io_err("read metadata for", e))?;
+            follower.restart(file, (meta.dev(), meta.ino()));
+            rotations += 1;
+        } else if current.len() < follower.pos {
+            follower.pos = 0;
+            follower.flush();
+            truncations += 1;
+        } else {
+            continue;
+        }
+        follower.read_new().map_err(|e| io_err("read file", e))?;
+    }
+    follower.read_new().map_err(|e| io_err("read file", e))?;
+    follower.flush();
+
+    Ok(TailFollowResult {
+        path: expanded_path,
+        initial,
+        appended: follower.appended,
+        rotations,
+        truncations,
+    })
+}
+
+/// The open file being followed and the lines read from it so far.
+struct Follower {
+    file: File,
+    /// Offset up to which `file` has been read.
+    pos: u64,
+    /// (device, inode) of `file`.
+    identity: (u64, u64),
+    /// Bytes after the last newline read.
+    partial: Vec<u8>,
+    appended: Vec<String>,
+}
+
+impl Follower {
+    /// Read whatever was written past `pos`, collecting completed lines.
+    fn read_new(&mut self) -> std::io::Result<()> {
+        self.file.seek(SeekFrom::Start(self.pos))?;
+        let read = (&mut self.file).read_to_end(&mut self.partial)?;
+        self.pos += read as u64;
+        while let Some(i) = self.partial.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.partial.drain(..=i).collect();
+            self.push(&line[..i]);
+        }
+        Ok(())
+    }
+
+    /// Switch to a replacement file, reading it from the start.
+    fn restart(&mut self, file: File, identity: (u64, u64)) {
+        self.flush();
+        self.file = file;
+        self.identity = identity;
+        self.pos = 0;
+    }
+
+    /// Emit an unterminated final line; nothing more will complete it.
+    fn flush(&mut self) {
+        if !self.partial.is_empty() {
+            let line = std::mem::take(&mut self.partial);
+            self.push(&line);
+        }
+    }
+
+    fn push(&mut self, line: &[u8]) {
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        self.appended
+            .push(String::from_utf8_lossy(line).into_owned());
+    }
+}
+
+/// The last `n` lines of the first `end` bytes of `file`, read backwards in
+/// chunks so a large file is not read whole.
+fn last_lines(file: &mut File, end: u64, n: usize) -> std::io::Result<Vec<String>> {
+    if n == 0 || end == 0 {
+        return Ok(Vec::new());
+    }
+    let mut start = end;
+    let mut buf: Vec<u8> = Vec::new();
+    while start > 0 {
+        let step = CHUNK.min(start);
+        start -= step;
+        let mut chunk = vec![0u8; step as usize];
+        file.seek(SeekFrom::Start(start))?;
+        file.read_exact(&mut chunk)?;
+        chunk.extend_from_slice(&buf);
+        buf = chunk;
+        let body = buf.strip_suffix(b"\n").unwrap_or(&buf);
+        // n newlines inside the body mean n full lines after the first one.
+        if body.iter().filter(|&&b| b == b'\n').count() >= n {
+            break;
+        }
+    }
+    let body = buf.strip_suffix(b"\n").unwrap_or(&buf);
+    let lines: Vec<&[u8]> = body.split(|&b| b == b'\n').collect();
+    Ok(lines[lines.len().saturating_sub(n)..]
+        .iter()
+        .map(|line| {
+            let line = line.strip_suffix(b"\r").unwrap_or(line);
+            String::from_utf8_lossy(line).into_owned()
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{self, OpenOptions};
+    use std::io::Write;
+    use std::thread;
+    use tempfile::TempDir;
+
+    fn options(lines: usize, max_lines: usize) -> TailOptions {
+        TailOptions {
+            lines,
+            duration: Duration::from_secs(5),
+            max_lines: Some(max_lines),
+            poll_interval: Duration::from_millis(20),
+        }
+    }
+
+    #[test]
+    fn test_appended_lines_in_order() {
+        let dir = TempDir::new().expect("create temp dir");
+        let path = dir.path().join("app.log");
+        fs::write(&path, "one\ntwo\nthree\n").expect("write fixture");
+
+        let writer_path = path.clone();
+        let writer = thread::spawn(move || {
+            for i in 0..5 {
+                thread::sleep(Duration::from_millis(30));
+                let mut file = OpenOptions::new()
+                    .append(true)
+                    .open(&writer_path)
+                    .expect("open for append");
+                writeln!(file, "new {}", i).expect("append line");
+            }
+        });
+        let result = tail_follow(path.to_str().expect("utf-8 path"), &options(2, 5))
+            .expect("tail_follow succeeds");
+        writer.join().expect("writer thread");
+
+        assert_eq!(result.initial, vec!["two", "three"]);
+        assert_eq!(
+            result.appended,
+            vec!["new 0", "new 1", "new 2", "new 3", "new 4"]
+        );
+        assert_eq!(result.rotations, 0);
+    }
+
+    #[test]
+    fn test_rotation_reopens_new_file() {
+        let dir = TempDir::new().expect("create temp dir");
+        let path = dir.path().join("app.log");
+        fs::write(&path, "old\n").expect("write fixture");
+
+        let writer_path = path.clone();
+        let writer = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            let mut file = OpenOptions::new()
+                .append(true)
+                .open(&writer_path)
+                .expect("open for append");
+            writeln!(file, "before rotate").expect("append line");
+            fs::rename(&writer_path, writer_path.with_extension("log.1")).expect("rotate");
+            fs::write(&writer_path, "after rotate\n").expect("write new file");
+        });
+        let result = tail_follow(path.to_str().expect("utf-8 path"), &options(5, 2))
+            .expect("tail_follow succeeds");
+        writer.join().expect("writer thread");
+
+        assert_eq!(result.initial, vec!["old"]);
+        assert_eq!(result.appended, vec!["before rotate", "after rotate"]);
+        assert_eq!(result.rotations, 1);
+    }
+
+    #[test]
+    fn test_last_lines_spans_chunks() {
+        let dir = TempDir::new().expect("create temp dir");
+        let path = dir.path().join("big.txt");
+        let text: String = (0..5000).map(|i| format!("line {}\n", i)).collect();
+        fs::write(&path, &text).expect("write fixture");
+
+        let mut file = File::open(&path).expect("open fixture");
+        let lines = last_lines(&mut file, text.len() as u64, 3000).expect("read tail");
+        assert_eq!(lines.len(), 3000);
+        assert_eq!(lines[0], "line 2000");
+        assert_eq!(lines[2999], "line 4999");
+    }
+}
@@ -0,0 +1,74 @@
+#![deny(warnings)]
+
+// Report where temporary files go and whether there is room for them
+
+use crate::error::{FileIoError, Result};
+use std::path::Path;
+
+#[derive(Debug, serde::Serialize)]
+pub struct TempInfo {
+    /// The directory `fileio_create_temporary` uses when given no template.
+    pub path: String,
+    /// Bytes an unprivileged process can still write on that filesystem.
+    pub available_bytes: u64,
+    pub total_bytes: u64,
+    /// Whether a file could actually be created there just now.
+    pub writable: bool,
+}
+
+/// Describe the system temp directory: its resolved path, free and total
+/// space on its filesystem, and whether it is writable.
+///
+/// Why a real probe for `writable`: permission bits say nothing about a
+/// read-only mount or a full disk, which are exactly the cases this exists
+/// to catch. The probe file is removed immediately.
+pub fn temp_info() -> Result<TempInfo> {
+    let dir = std::env::temp_dir();
+    let space = fs_space(&dir)?;
+    Ok(TempInfo {
+        path: dir.to_string_lossy().into_owned(),
+        available_bytes: space.available_bytes,
+        total_bytes: space.total_bytes,
+        writable: tempfile::NamedTempFile::new_in(&dir).is_ok(),
+    })
+}
+
+/// Capacity of the filesystem holding a path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct FsSpace {
+    pub available_bytes: u64,
+    pub total_bytes: u64,
+}
+
+/// Free and total space of the filesystem containing `path`, via `statvfs`.
+/// Available space excludes blocks reserved for root, like `df`.
+pub(crate) fn fs_space(path: &Path) -> Result<FsSpace> {
+    let stats = nix::sys::statvfs::statvfs(path).map_err(|e| {
+        FileIoError::from_io_error(
+            "query free space of",
+            &path.to_string_lossy(),
+            std::io::Error::from(e),
+        )
+    })?;
+    let fragment = stats.fragment_size() as u64;
+    Ok(FsSpace {
+        available_bytes: (stats.blocks_available() as u64).saturating_mul(fragment),
+        total_bytes: (stats.blocks() as u64).saturating_mul(fragment),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_temp_info_matches_env_temp_dir() {
+        let info = temp_info().expect("temp info");
+        assert_eq!(
+            info.path,
+            std::env::temp_dir().to_string_lossy().into_owned()
+        );
+        assert!(info.writable);
+        assert!(info.total_bytes >= info.available_bytes);
+    }
+}
@@ -5,7 +5,7 @@
 use crate::error::{FileIoError, Result};
 use filetime::{FileTime, set_file_times};
 use std::fs;
-use std::path::Path;
+use std::path::{Component, Path};
 use std::time::SystemTime;
 
 /// Touch files (create if they don't exist, update timestamp if they do)
@@ -25,6 +25,48 @@ pub fn touch(paths: &[&str]) -> Result<()> {
     Ok(())
 }
 
+/// Resolve `paths` against the directory `base` for a scaffolding-style
+/// touch, returning the joined paths in order.
+///
+/// Each path must be relative and stay inside `base` (no root, drive prefix,
+/// or `..` components). `base` may be missing — touching the joined paths
+/// creates it along with every intermediate directory — but if it exists it
+/// must be a directory.
+pub fn paths_under_base(base: &str, paths: &[String]) -> Result<Vec<String>> {
+    let expanded_base = shellexpand::full(base)
+        .map_err(|e| {
+            crate::error::FileIoMcpError::from(FileIoError::InvalidPath(format!(
+                "Failed to expand path \'{}\': {}",
+                base, e
+            )))
+        })
+        .map(|expanded| expanded.into_owned())?;
+    let base_path = Path::new(&expanded_base);
+    if base_path.exists() && !base_path.is_dir() {
+        return Err(FileIoError::NotADirectory(expanded_base).into());
+    }
+
+    paths
+        .iter()
+        .map(|rel| {
+            let escapes = Path::new(rel).components().any(|c| {
+                matches!(
+                    c,
+                    Component::RootDir | Component::Prefix(_) | Component::ParentDir
+                )
+            });
+            if rel.is_empty() || escapes {
+                return Err(FileIoError::InvalidPath(format!(
+                    "'{}' must be a relative path inside base '{}'",
+                    rel, base
+                ))
+                .into());
+            }
+            Ok(base_path.join(rel).to_string_lossy().into_owned())
+        })
+        .collect()
+}
+
 /// Touch a single file (create if it doesn't exist, update timestamp if it does)
 pub fn touch_single(path: &str) -> Result<()> {
     let expanded_path = shellexpand::full(path)
@@ -41,26 +83,18 @@ pub fn touch_single(path: &str) -> Result<()> {
         // Update timestamp using filetime crate
         let now = SystemTime::now();
         let file_time = FileTime::from_system_time(now);
-        set_file_times(&expanded_path, file_time, file_time).map_err(|e| {
-            FileIoError::WriteError(format!(
-                "Failed to update timestamp for {}: {}",
-                expanded_path, e
-            ))
-        })?;
+        set_file_times(&expanded_path, file_time, file_time)
+            .map_err(|e| FileIoError::from_io_error("update timestamp for", &expanded_path, e))?;
     } else {
         // Create empty file
         // Create parent directories if needed
         if let Some(parent) = path_obj.parent() {
             fs::create_dir_all(parent).map_err(|e| {
-                FileIoError::WriteError(format!(
-                    "Failed to create parent directories for {}: {}",
-                    expanded_path, e
-                ))
+                FileIoError::from_io_error("create parent directories for", &expanded_path, e)
             })?;
         }
-        fs::File::create(&expanded_path).map_err(|e| {
-            FileIoError::WriteError(format!("Failed to create file {}: {}", expanded_path, e))
-        })?;
+        fs::File::create(&expanded_path)
+            .map_err(|e| FileIoError::from_io_error("create file", &expanded_path, e))?;
     }
 
     Ok(())
@@ -116,4 +150,35 @@ mod tests {
         touch(&[&path_str]).unwrap();
         assert!(path.exists());
     }
+
+    #[test]
+    fn test_touch_paths_under_base() {
+        let dir = TempDir::new().unwrap();
+        let base = dir.path().join("project");
+        let rel = vec!["src/a.rs".to_string(), "tests/b.rs".to_string()];
+
+        let paths = paths_under_base(base.to_str().unwrap(), &rel).unwrap();
+        let refs: Vec<&str> = paths.iter().map(|p| p.as_str()).collect();
+        touch(&refs).unwrap();
+
+        assert!(base.join("src").join("a.rs").is_file());
+        assert!(base.join("tests").join("b.rs").is_file());
+    }
+
+    #[test]
+    fn test_paths_under_base_rejects_escapes() {
+        let dir = TempDir::new().unwrap();
+        let base = dir.path().to_str().unwrap();
+        for bad in ["/etc/passwd", "../outside.txt", "src/../../x", ""] {
+            assert!(
+                paths_under_base(base, &[bad.to_string()]).is_err(),
+                "{} must be rejected",
+                bad
+            );
+        }
+
+        let file = dir.path().join("file.txt");
+        fs::write(&file, "").unwrap();
+        assert!(paths_under_base(file.to_str().unwrap(), &["a".to_string()]).is_err());
+    }
 }
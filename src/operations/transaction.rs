@@ -0,0 +1,526 @@
+#![deny(warnings)]
+
+// Apply an ordered list of file operations all-or-nothing
+
+use crate::error::{FileIoError, Result};
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// One operation in a [`transaction`], tagged by `op`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum TxStep {
+    /// Create or overwrite (or, with `append`, extend) a file. Missing parent
+    /// directories are created.
+    Write {
+        path: String,
+        content: String,
+        #[serde(default)]
+        append: bool,
+    },
+    /// Create a directory and any missing parents; an existing directory is
+    /// a no-op.
+    Mkdir { path: String },
+    /// Rename `source` to the exact path `destination`, replacing an
+    /// existing file there. Both must be on the same filesystem.
+    Move { source: String, destination: String },
+    /// Create a symbolic link at `link_path` pointing to `target`.
+    Link { target: String, link_path: String },
+    /// Remove a file, symlink, or (with `recursive`) a directory tree.
+    Remove {
+        path: String,
+        #[serde(default)]
+        recursive: bool,
+    },
+}
+
+impl TxStep {
+    /// Every path the step reads or changes, for deny-list checks.
+    pub fn paths(&self) -> Vec<&str> {
+        match self {
+            TxStep::Write { path, .. } | TxStep::Mkdir { path } | TxStep::Remove { path, .. } => {
+                vec![path]
+            }
+            TxStep::Move {
+                source,
+                destination,
+            } => vec![source, destination],
+            TxStep::Link { target, link_path } => vec![target, link_path],
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, serde::Serialize)]
+pub struct TransactionResult {
+    /// True when every step applied and the changes were kept.
+    pub committed: bool,
+    /// Steps that completed before the transaction committed or failed.
+    pub applied: usize,
+    /// Zero-based index of the step that failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failed_step: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Undo or backup-removal actions that themselves failed. Non-empty means
+    /// the tree may match neither the before nor the after state.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub cleanup_errors: Vec<String>,
+}
+
+/// How to reverse one change made by a step.
+#[derive(Debug)]
+enum Undo {
+    /// Delete a file or symlink the transaction created.
+    RemoveCreated(PathBuf),
+    /// Delete directories the transaction created, deepest first.
+    RemoveDirs(Vec<PathBuf>),
+    /// Put the pre-transaction `original` back from its staged `backup`.
+    Restore { backup: PathBuf, original: PathBuf },
+    /// Rename a moved entry back to where it came from.
+    MoveBack { from: PathBuf, to: PathBuf },
+}
+
+/// Apply `steps` in order. If any step fails, the steps already applied are
+/// undone in reverse order and the result reports the failure; the tree is
+/// then as it was before the call.
+///
+/// Nothing is destroyed until every step has succeeded: removed entries are
+/// renamed aside and overwritten files are copied aside, and those backups
+/// are deleted only at commit.
+///
+/// Rollback is best-effort, not a filesystem transaction. Other processes
+/// see intermediate states while steps run, an undo can fail if something
+/// else changes the tree meanwhile (reported in `cleanup_errors`), and a
+/// restored file comes back from a copy, so its inode and timestamps differ.
+pub fn transaction(steps: &[TxStep]) -> Result<TransactionResult> {
+    if steps.is_empty() {
+        return Err(crate::error::FileIoMcpError::InvalidParams(
+            "steps must contain at least one operation".to_string(),
+        ));
+    }
+    // Expand every path before touching anything so a bad path fails the
+    // whole call up front.
+    let expanded = steps.iter().map(expand_step).collect::<Result<Vec<_>>>()?;
+
+    let mut log = Vec::new();
+    for (index, step) in expanded.iter().enumerate() {
+        if let Err(e) = apply(step, &mut log) {
+            let cleanup_errors = rollback(log);
+            return Ok(TransactionResult {
+                committed: false,
+                applied: index,
+                failed_step: Some(index),
+                error: Some(e.to_string()),
+                cleanup_errors,
+            });
+        }
+    }
+
+    Ok(TransactionResult {
+        committed: true,
+        applied: expanded.len(),
+        failed_step: None,
+        error: None,
+        cleanup_errors: commit(log),
+    })
+}
+
+fn expand(path: &str) -> Result<String> {
+    shellexpand::full(path)
+        .map_err(|e| {
+            crate::error::FileIoMcpError::from(FileIoError::InvalidPath(format!(
+                "Failed to expand path \'{}\': {}",
+                path, e
+            )))
+        })
+        .map(|expanded| expanded.into_owned())
+}
+
+fn expand_step(step: &TxStep) -> Result<TxStep> {
+    Ok(match step {
+        TxStep::Write {
+            path,
+            content,
+            append,
+        } => TxStep::Write {
+            path: expand(path)?,
+            content: content.clone(),
+            append: *append,
+        },
+        TxStep::Mkdir { path } => TxStep::Mkdir {
+            path: expand(path)?,
+        },
+        TxStep::Move {
+            source,
+            destination,
+        } => TxStep::Move {
+            source: expand(source)?,
+            destination: expand(destination)?,
+        },
+        // The link target is stored verbatim, like `fileio_create_symbolic_link`.
+        TxStep::Link { target, link_path } => TxStep::Link {
+            target: target.clone(),
+            link_path: expand(link_path)?,
+        },
+        TxStep::Remove { path, recursive } => TxStep::Remove {
+            path: expand(path)?,
+            recursive: *recursive,
+        },
+    })
+}
+
+/// Apply one expanded step, pushing an undo entry for each change as soon as
+/// it is made so a failure part-way through a step is still reversible.
+fn apply(step: &TxStep, log: &mut Vec<Undo>) -> Result<()> {
+    match step {
+        TxStep::Write {
+            path,
+            content,
+            append,
+        } => {
+            let p = Path::new(path);
+            if p.is_dir() {
+                return Err(FileIoError::IsADirectory(path.clone()).into());
+            }
+            if let Some(parent) = p.parent() {
+                create_dirs(parent, log)?;
+            }
+            if fs::symlink_metadata(p).is_ok() {
+                let backup = backup_path(p)?;
+                fs::copy(p, &backup).map_err(|e| FileIoError::from_io_error("back up", path, e))?;
+                log.push(Undo::Restore {
+                    backup,
+                    original: p.to_path_buf(),
+                });
+            } else {
+                log.push(Undo::RemoveCreated(p.to_path_buf()));
+            }
+            super::write_file::write_file(path, content, *append)?;
+        }
+        TxStep::Mkdir { path } => {
+            let p = Path::new(path);
+            if fs::symlink_metadata(p).is_ok() && !p.is_dir() {
+                return Err(FileIoError::AlreadyExists(path.clone()).into());
+            }
+            create_dirs(p, log)?;
+        }
+        TxStep::Move {
+            source,
+            destination,
+        } => {
+            let src = Path::new(source);
+            let dst = Path::new(destination);
+            if fs::symlink_metadata(src).is_err() {
+                return Err(FileIoError::NotFound(source.clone()).into());
+            }
+            if fs::symlink_metadata(dst).is_ok() {
+                if dst.is_dir() {
+                    return Err(FileIoError::AlreadyExists(format!(
+                        "{} is a directory; name the full destination path",
+                        destination
+                    ))
+                    .into());
+                }
+                stage_aside(dst, log)?;
+            }
+            if let Some(parent) = dst.parent() {
+                create_dirs(parent, log)?;
+            }
+            fs::rename(src, dst).map_err(|e| {
+                FileIoError::from_io_error("move", &format!("{} to {}", source, destination), e)
+            })?;
+            log.push(Undo::MoveBack {
+                from: dst.to_path_buf(),
+                to: src.to_path_buf(),
+            });
+        }
+        TxStep::Link { target, link_path } => {
+            let p = Path::new(link_path);
+            if fs::symlink_metadata(p).is_ok() {
+                return Err(FileIoError::AlreadyExists(link_path.clone()).into());
+            }
+            if let Some(parent) = p.parent() {
+                create_dirs(parent, log)?;
+            }
+            std::os::unix::fs::symlink(target, p).map_err(|e| {
+                FileIoError::from_io_error(
+                    "create symbolic link",
+                    &format!("{} to {}", link_path, target),
+                    e,
+                )
+            })?;
+            log.push(Undo::RemoveCreated(p.to_path_buf()));
+        }
+        TxStep::Remove { path, recursive } => {
+            let p = Path::new(path);
+            let meta = fs::symlink_metadata(p).map_err(|_| FileIoError::NotFound(path.clone()))?;
+            if meta.is_dir() && !recursive {
+                let mut entries = fs::read_dir(p)
+                    .map_err(|e| FileIoError::from_io_error("read directory", path, e))?;
+                if entries.next().is_some() {
+                    return Err(FileIoError::DirectoryNotEmpty(path.clone()).into());
+                }
+            }
+            stage_aside(p, log)?;
+        }
+    }
+    Ok(())
+}
+
+/// Create `dir` and any missing ancestors, logging exactly the ones created.
+fn create_dirs(dir: &Path, log: &mut Vec<Undo>) -> Result<()> {
+    let missing: Vec<PathBuf> = dir
+        .ancestors()
+        .take_while(|a| !a.as_os_str().is_empty() && fs::symlink_metadata(a).is_err())
+        .map(Path::to_path_buf)
+        .collect();
+    if missing.is_empty() {
+        return Ok(());
+    }
+    let result: Result<()> = fs::create_dir_all(dir).map_err(|e| {
+        FileIoError::from_io_error("create directory", &dir.to_string_lossy(), e).into()
+    });
+    // Logged even on failure: create_dir_all may have made some of them.
+    log.push(Undo::RemoveDirs(missing));
+    result
+}
+
+/// Rename `p` to a sibling backup name, to be restored on rollback or
+/// deleted on commit.
+fn stage_aside(p: &Path, log: &mut Vec<Undo>) -> Result<()> {
+    let backup = backup_path(p)?;
+    fs::rename(p, &backup)
+        .map_err(|e| FileIoError::from_io_error("stage", &p.to_string_lossy(), e))?;
+    log.push(Undo::Restore {
+        backup,
+        original: p.to_path_buf(),
+    });
+    Ok(())
+}
+
+/// An unused hidden sibling of `p`. Why a sibling: a rename within the same
+/// directory can't cross filesystems, so staging never copies a tree.
+fn backup_path(p: &Path) -> Result<PathBuf> {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let name = p
+        .file_name()
+        .ok_or_else(|| FileIoError::InvalidPath(format!("{} has no file name", p.display())))?;
+    loop {
+        let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+        let candidate = p.with_file_name(format!(
+            ".{}.fileio-tx-{}-{}",
+            name.to_string_lossy(),
+            std::process::id(),
+            n
+        ));
+        if fs::symlink_metadata(&candidate).is_err() {
+            return Ok(candidate);
+        }
+    }
+}
+
+/// Remove a file, symlink, or directory tree without following links.
+fn remove_entry(p: &Path) -> std::io::Result<()> {
+    match fs::symlink_metadata(p) {
+        Ok(meta) if meta.is_dir() => fs::remove_dir_all(p),
+        Ok(_) => fs::remove_file(p),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Undo `log` newest first, carrying on past failures and returning them.
+fn rollback(log: Vec<Undo>) -> Vec<String> {
+    let mut errors = Vec::new();
+    for undo in log.into_iter().rev() {
+        match undo {
+            Undo::RemoveCreated(p) => {
+                if let Err(e) = remove_entry(&p) {
+                    errors.push(format!("remove {}: {}", p.display(), e));
+                }
+            }
+            Undo::RemoveDirs(dirs) => {
+                for dir in dirs {
+                    // Only empty directories: anything left inside was not
+                    // ours to delete.
+                    if let Err(e) = fs::remove_dir(&dir)
+                        && e.kind() != std::io::ErrorKind::NotFound
+                    {
+                        errors.push(format!("remove directory {}: {}", dir.display(), e));
+                    }
+                }
+            }
+            Undo::Restore { backup, original } => {
+                let restored = fs::symlink_metadata(&original)
+                    .map_or(Ok(()), |meta| {
+                        if meta.is_dir() {
+                            fs::remove_dir(&original)
+                        } else {
+                            fs::remove_file(&original)
+                        }
+                    })
+                    .and_then(|()| fs::rename(&backup, &original));
+                if let Err(e) = restored {
+                    errors.push(format!(
+                        "restore {} from {}: {}",
+                        original.display(),
+                        backup.display(),
+                        e
+                    ));
+                }
+            }
+            Undo::MoveBack { from, to } => {
+                if let Err(e) = fs::rename(&from, &to) {
+                    errors.push(format!(
+                        "move {} back to {}: {}",
+                        from.display(),
+                        to.display(),
+                        e
+                    ));
+                }
+            }
+        }
+    }
+    errors
+}
+
+/// Delete the staged backups once every step has succeeded.
+fn commit(log: Vec<Undo>) -> Vec<String> {
+    log.into_iter()
+        .filter_map(|undo| match undo {
+            Undo::Restore { backup, .. } => remove_entry(&backup)
+                .err()
+                .map(|e| format!("remove backup {}: {}", backup.display(), e)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn names(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .expect("read dir")
+            .map(|e| {
+                e.expect("dir entry")
+                    .file_name()
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_failed_step_rolls_back_earlier_steps() {
+        let dir = TempDir::new().expect("create temp dir");
+        let root = dir.path();
+        fs::write(root.join("keep.txt"), "old").expect("write fixture");
+        fs::write(root.join("doomed.txt"), "still here").expect("write fixture");
+        fs::write(root.join("moved.txt"), "moving").expect("write fixture");
+        let before = names(root);
+        let at = |name: &str| root.join(name).to_string_lossy().into_owned();
+
+        let steps = vec![
+            TxStep::Write {
+                path: at("keep.txt"),
+                content: "new".to_string(),
+                append: false,
+            },
+            TxStep::Write {
+                path: at("fresh/new.txt"),
+                content: "x".to_string(),
+                append: false,
+            },
+            TxStep::Remove {
+                path: at("doomed.txt"),
+                recursive: false,
+            },
+            TxStep::Mkdir { path: at("a/b") },
+            TxStep::Move {
+                source: at("moved.txt"),
+                destination: at("a/b/moved.txt"),
+            },
+            TxStep::Link {
+                target: "keep.txt".to_string(),
+                link_path: at("link"),
+            },
+            TxStep::Move {
+                source: at("missing.txt"),
+                destination: at("anywhere.txt"),
+            },
+        ];
+        let result = transaction(&steps).expect("transaction runs");
+
+        assert!(!result.committed);
+        assert_eq!(result.failed_step, Some(6));
+        assert_eq!(result.applied, 6);
+        assert!(result.cleanup_errors.is_empty());
+        assert_eq!(names(root), before);
+        assert_eq!(
+            fs::read_to_string(root.join("keep.txt")).expect("read back"),
+            "old"
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("doomed.txt")).expect("read back"),
+            "still here"
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("moved.txt")).expect("read back"),
+            "moving"
+        );
+    }
+
+    #[test]
+    fn test_commit_applies_all_and_drops_backups() {
+        let dir = TempDir::new().expect("create temp dir");
+        let root = dir.path();
+        fs::write(root.join("log.txt"), "a\n").expect("write fixture");
+        fs::create_dir(root.join("old")).expect("create fixture dir");
+        fs::write(root.join("old/x"), "x").expect("write fixture");
+        let at = |name: &str| root.join(name).to_string_lossy().into_owned();
+
+        let steps = vec![
+            TxStep::Write {
+                path: at("log.txt"),
+                content: "b\n".to_string(),
+                append: true,
+            },
+            TxStep::Remove {
+                path: at("old"),
+                recursive: true,
+            },
+        ];
+        let result = transaction(&steps).expect("transaction runs");
+
+        assert!(result.committed);
+        assert_eq!(result.applied, 2);
+        assert_eq!(names(root), vec!["log.txt".to_string()]);
+        assert_eq!(
+            fs::read_to_string(root.join("log.txt")).expect("read back"),
+            "a\nb\n"
+        );
+    }
+
+    #[test]
+    fn test_step_json_shape() {
+        let steps: Vec<TxStep> = serde_json::from_str(
+            r#"[{"op":"mkdir","path":"/tmp/a"},{"op":"remove","path":"/tmp/b","recursive":true}]"#,
+        )
+        .expect("valid steps");
+        assert_eq!(
+            steps[1],
+            TxStep::Remove {
+                path: "/tmp/b".to_string(),
+                recursive: true
+            }
+        );
+        assert!(serde_json::from_str::<Vec<TxStep>>(r#"[{"op":"chmod","path":"/a"}]"#).is_err());
+    }
+}
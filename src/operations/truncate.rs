@@ -0,0 +1,93 @@
+#![deny(warnings)]
+
+// Shrink or extend a file to an exact size
+
+use crate::error::{FileIoError, Result};
+use std::fs::OpenOptions;
+use std::path::Path;
+
+#[derive(Debug, PartialEq, Eq, serde::Serialize)]
+pub struct TruncateResult {
+    pub path: String,
+    /// Size before the call, in bytes.
+    pub previous_size: u64,
+    pub size: u64,
+}
+
+/// Set the existing file at `path` to exactly `size` bytes with `set_len`.
+///
+/// Data past `size` is dropped; growing the file adds a range that reads as
+/// zeros (sparse where the filesystem supports it). Unlike
+/// [`super::allocate::allocate`], the file must already exist: a typo in the
+/// path should fail, not leave an empty file behind.
+pub fn truncate(path: &str, size: u64) -> Result<TruncateResult> {
+    let expanded_path = super::path_utils::resolve_path(path)?;
+    let path_obj = Path::new(&expanded_path);
+    if !path_obj.exists() {
+        return Err(FileIoError::NotFound(expanded_path).into());
+    }
+    if path_obj.is_dir() {
+        return Err(FileIoError::IsADirectory(expanded_path).into());
+    }
+
+    let file = OpenOptions::new()
+        .write(true)
+        .open(path_obj)
+        .map_err(|e| FileIoError::from_io_error("open file", &expanded_path, e))?;
+    let previous_size = file
+        .metadata()
+        .map_err(|e| FileIoError::from_io_error("read metadata for", &expanded_path, e))?
+        .len();
+    file.set_len(size)
+        .map_err(|e| FileIoError::from_io_error("resize file", &expanded_path, e))?;
+
+    Ok(TruncateResult {
+        path: expanded_path,
+        previous_size,
+        size,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_truncate_to_zero() {
+        let dir = TempDir::new().expect("create temp dir");
+        let path = dir.path().join("app.log");
+        fs::write(&path, "line 1\nline 2\n").expect("write fixture");
+
+        let result = truncate(path.to_str().expect("utf-8 path"), 0).expect("truncate succeeds");
+        assert_eq!(result.previous_size, 14);
+        assert_eq!(result.size, 0);
+        assert_eq!(fs::read(&path).expect("read back"), b"");
+    }
+
+    #[test]
+    fn test_extend_then_stat_size() {
+        let dir = TempDir::new().expect("create temp dir");
+        let path = dir.path().join("data.bin");
+        fs::write(&path, "abc").expect("write fixture");
+        let p = path.to_str().expect("utf-8 path");
+
+        truncate(p, 6).expect("extend succeeds");
+        assert_eq!(fs::metadata(&path).expect("stat").len(), 6);
+        assert_eq!(fs::read(&path).expect("read back"), b"abc\0\0\0");
+
+        truncate(p, 2).expect("shrink succeeds");
+        assert_eq!(fs::read(&path).expect("read back"), b"ab");
+    }
+
+    #[test]
+    fn test_missing_path_and_directory_are_errors() {
+        let dir = TempDir::new().expect("create temp dir");
+        let missing = dir.path().join("missing.log");
+
+        assert!(truncate(missing.to_str().expect("utf-8 path"), 0).is_err());
+        assert!(!missing.exists());
+        assert!(truncate(dir.path().to_str().expect("utf-8 path"), 0).is_err());
+    }
+}
@@ -0,0 +1,219 @@
+#![deny(warnings)]
+
+// Check that a JSON, YAML, or TOML file parses
+
+use crate::error::{FileIoError, Result};
+use serde::Deserialize;
+use std::path::Path;
+
+/// Structured-data formats [`validate`] understands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DataFormat {
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl DataFormat {
+    /// Parse the tool-level `format` string.
+    pub fn parse(format: &str) -> Result<Self> {
+        match format {
+            "json" => Ok(DataFormat::Json),
+            "yaml" => Ok(DataFormat::Yaml),
+            "toml" => Ok(DataFormat::Toml),
+            other => Err(crate::error::FileIoMcpError::InvalidParams(format!(
+                "format must be 'json', 'yaml', or 'toml', got '{}'",
+                other
+            ))),
+        }
+    }
+
+    /// Infer the format from a file extension (`.json`, `.yaml`/`.yml`,
+    /// `.toml`, case-insensitive).
+    pub fn from_extension(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "json" => Some(DataFormat::Json),
+            "yaml" | "yml" => Some(DataFormat::Yaml),
+            "toml" => Some(DataFormat::Toml),
+            _ => None,
+        }
+    }
+}
+
+/// Why a file failed to parse. `line` and `column` are 1-based and present
+/// whenever the parser reports a position.
+#[derive(Debug, PartialEq, Eq, serde::Serialize)]
+pub struct ParseFailure {
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<u64>,
+}
+
+#[derive(Debug, PartialEq, Eq, serde::Serialize)]
+pub struct ValidateResult {
+    pub valid: bool,
+    pub format: DataFormat,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<ParseFailure>,
+}
+
+/// Parse `path` as `format` (or, when `None`, the format its extension
+/// implies) and report whether it is syntactically valid.
+///
+/// A parse failure is a normal result (`valid: false` plus the error and its
+/// position), not an error; only an unreadable file or an undeterminable
+/// format is. YAML files may hold several `---`-separated documents; each
+/// must parse.
+pub fn validate(path: &str, format: Option<DataFormat>) -> Result<ValidateResult> {
+    let expanded_path = shellexpand::full(path)
+        .map_err(|e| {
+            crate::error::FileIoMcpError::from(FileIoError::InvalidPath(format!(
+                "Failed to expand path \'{}\': {}",
+                path, e
+            )))
+        })
+        .map(|expanded| expanded.into_owned())?;
+    let path_obj = Path::new(&expanded_path);
+    // Checked before the format so a missing file fails the same way
+    // whatever its extension.
+    if !path_obj.exists() {
+        return Err(super::path_utils::not_found("validate file", path).into());
+    }
+    let format = match format.or_else(|| DataFormat::from_extension(path_obj)) {
+        Some(format) => format,
+        None => {
+            return Err(crate::error::FileIoMcpError::InvalidParams(format!(
+                "cannot infer format from the extension of {}; pass format explicitly",
+                expanded_path
+            )));
+        }
+    };
+    if !path_obj.is_file() {
+        return Err(FileIoError::InvalidPath(format!("{} is not a file", expanded_path)).into());
+    }
+
+    let bytes = std::fs::read(path_obj)
+        .map_err(|e| FileIoError::from_io_error("read file", &expanded_path, e))?;
+    let error = match String::from_utf8(bytes) {
+        Ok(content) => check(&content, format),
+        Err(e) => Some(ParseFailure {
+            message: format!("file is not valid UTF-8: {}", e.utf8_error()),
+            line: None,
+            column: None,
+        }),
+    };
+
+    Ok(ValidateResult {
+        valid: error.is_none(),
+        format,
+        error,
+    })
+}
+
+/// Parse `content` as `format`, returning the failure if there is one.
+fn check(content: &str, format: DataFormat) -> Option<ParseFailure> {
+    match format {
+        DataFormat::Json => serde_json::from_str::<serde_json::Value>(content)
+            .err()
+            .map(|e| ParseFailure {
+                message: e.to_string(),
+                // serde_json reports line 0 when no position applies.
+                line: (e.line() > 0).then_some(e.line() as u64),
+                column: (e.line() > 0).then_some(e.column() as u64),
+            }),
+        DataFormat::Yaml => serde_yaml::Deserializer::from_str(content)
+            .find_map(|doc| serde_yaml::Value::deserialize(doc).err())
+            .map(|e| {
+                let location = e.location();
+                ParseFailure {
+                    message: e.to_string(),
+                    line: location.as_ref().map(|l| l.line() as u64),
+                    column: location.as_ref().map(|l| l.column() as u64),
+                }
+            }),
+        DataFormat::Toml => content.parse::<toml::Table>().err().map(|e| {
+            let position = e.span().map(|span| line_column(content, span.start));
+            ParseFailure {
+                message: e.message().to_string(),
+                line: position.map(|(line, _)| line),
+                column: position.map(|(_, column)| column),
+            }
+        }),
+    }
+}
+
+/// 1-based line and character column of byte `offset` in `content`.
+fn line_column(content: &str, offset: usize) -> (u64, u64) {
+    let before = content.get(..offset).unwrap_or(content);
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let line = before.matches('\n').count() as u64 + 1;
+    let column = before[line_start..].chars().count() as u64 + 1;
+    (line, column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn validate_str(name: &str, content: &str) -> ValidateResult {
+        let dir = TempDir::new().expect("create temp dir");
+        let path = dir.path().join(name);
+        fs::write(&path, content).expect("write fixture");
+        validate(path.to_str().expect("utf-8 path"), None).expect("validate runs")
+    }
+
+    #[test]
+    fn test_valid_json() {
+        let result = validate_str("config.json", "{\"a\": [1, 2, 3]}\n");
+        assert_eq!(
+            result,
+            ValidateResult {
+                valid: true,
+                format: DataFormat::Json,
+                error: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_malformed_json_reports_position() {
+        let result = validate_str("config.json", "{\n  \"a\": 1,\n  \"b\" 2\n}\n");
+        assert!(!result.valid);
+        let error = result.error.expect("malformed JSON carries an error");
+        assert_eq!(error.line, Some(3));
+        assert_eq!(error.column, Some(7));
+    }
+
+    #[test]
+    fn test_yaml_and_toml() {
+        assert!(validate_str("a.yml", "a: 1\n---\nb: [1, 2]\n").valid);
+        let bad = validate_str("a.yaml", "a: 1\n---\nb: [1, 2\n");
+        assert_eq!(bad.format, DataFormat::Yaml);
+        assert!(!bad.valid);
+
+        assert!(validate_str("Cargo.toml", "[package]\nname = \"x\"\n").valid);
+        let bad = validate_str("Cargo.toml", "[package]\nname = \n");
+        let error = bad.error.expect("malformed TOML carries an error");
+        assert_eq!(error.line, Some(2));
+    }
+
+    #[test]
+    fn test_format_required_without_known_extension() {
+        let dir = TempDir::new().expect("create temp dir");
+        let path = dir.path().join("data.txt");
+        fs::write(&path, "[]").expect("write fixture");
+        let p = path.to_str().expect("utf-8 path");
+        assert!(validate(p, None).is_err());
+        assert!(
+            validate(p, Some(DataFormat::Json))
+                .expect("validate runs")
+                .valid
+        );
+    }
+}
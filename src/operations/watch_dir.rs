@@ -0,0 +1,240 @@
+#![deny(warnings)]
+
+// Watch a directory tree and collect debounced change events
+
+use crate::error::{FileIoError, Result};
+use globset::GlobMatcher;
+use notify::event::ModifyKind;
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct WatchEvent {
+    pub path: String,
+    /// `create`, `modify`, `remove`, or `rename`.
+    pub kind: String,
+}
+
+/// Options for [`watch_dir`].
+#[derive(Debug, Clone)]
+pub struct WatchOptions {
+    /// Watch subdirectories too.
+    pub recursive: bool,
+    /// Only report paths (relative to the watched root) matching this glob.
+    pub include: Option<GlobMatcher>,
+    /// Drop paths (relative to the watched root) matching this glob.
+    pub exclude: Option<GlobMatcher>,
+    /// Events for the same path closer together than this are merged.
+    pub debounce: Duration,
+    /// How long to watch before returning.
+    pub duration: Duration,
+}
+
+impl Default for WatchOptions {
+    fn default() -> Self {
+        Self {
+            recursive: true,
+            include: None,
+            exclude: None,
+            debounce: Duration::from_millis(100),
+            duration: Duration::from_secs(5),
+        }
+    }
+}
+
+/// One pending, possibly merged, event.
+struct Pending {
+    path: PathBuf,
+    kind: &'static str,
+    last_seen: Instant,
+}
+
+/// Watch `path` for `options.duration` and return the changes seen, oldest
+/// first.
+///
+/// Bursts are debounced per path: an event that arrives within
+/// `options.debounce` of the previous one for the same path is folded into
+/// it, so an editor save that fires several writes is reported once. When
+/// merging, a removal wins, and a create absorbs the modifies that follow
+/// it.
+///
+/// Why collect-then-return: tool calls produce a single reply and have no
+/// channel for intermediate notifications (see the README), so events are
+/// gathered for a bounded window instead of streamed.
+pub fn watch_dir(path: &str, options: &WatchOptions) -> Result<Vec<WatchEvent>> {
+    let expanded_path = shellexpand::full(path)
+        .map_err(|e| {
+            crate::error::FileIoMcpError::from(FileIoError::InvalidPath(format!(
+                "Failed to expand path \'{}\': {}",
+                path, e
+            )))
+        })
+        .map(|expanded| expanded.into_owned())?;
+    let root = Path::new(&expanded_path);
+    if !root.exists() {
+        return Err(super::path_utils::not_found("watch directory", path).into());
+    }
+    if !root.is_dir() {
+        return Err(FileIoError::NotADirectory(expanded_path).into());
+    }
+    // Events carry canonical paths on some platforms; strip against the same.
+    let root = root
+        .canonicalize()
+        .map_err(|e| FileIoError::from_io_error("resolve", &expanded_path, e))?;
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx).map_err(|e| {
+        FileIoError::ReadError(format!(
+            "Failed to start watcher for {}: {}",
+            expanded_path, e
+        ))
+    })?;
+    let mode = if options.recursive {
+        RecursiveMode::Recursive
+    } else {
+        RecursiveMode::NonRecursive
+    };
+    watcher
+        .watch(&root, mode)
+        .map_err(|e| FileIoError::ReadError(format!("Failed to watch {}: {}", expanded_path, e)))?;
+
+    let deadline = Instant::now() + options.duration;
+    let mut pending: Vec<Pending> = Vec::new();
+    let mut done: Vec<Pending> = Vec::new();
+    loop {
+        let now = Instant::now();
+        if now >= deadline {
+            break;
+        }
+        let event = match rx.recv_timeout(deadline - now) {
+            Ok(Ok(event)) => event,
+            // A watcher-side error (e.g. queue overflow) loses events but
+            // shouldn't discard what was already collected.
+            Ok(Err(_)) => continue,
+            Err(mpsc::RecvTimeoutError::Timeout) => break,
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        };
+        let kind = match event.kind {
+            EventKind::Create(_) => "create",
+            EventKind::Modify(ModifyKind::Name(_)) => "rename",
+            EventKind::Modify(_) => "modify",
+            EventKind::Remove(_) => "remove",
+            _ => continue,
+        };
+        let seen = Instant::now();
+        for event_path in event.paths {
+            if !wanted(&root, &event_path, options) {
+                continue;
+            }
+            match pending.iter().position(|p| p.path == event_path) {
+                Some(i) if seen.duration_since(pending[i].last_seen) <= options.debounce => {
+                    let entry = &mut pending[i];
+                    entry.kind = merge(entry.kind, kind);
+                    entry.last_seen = seen;
+                }
+                Some(i) => {
+                    // Outside the window: the earlier burst is finished.
+                    done.push(pending.remove(i));
+                    pending.push(Pending {
+                        path: event_path,
+                        kind,
+                        last_seen: seen,
+                    });
+                }
+                None => pending.push(Pending {
+                    path: event_path,
+                    kind,
+                    last_seen: seen,
+                }),
+            }
+        }
+    }
+    done.extend(pending);
+
+    Ok(done
+        .into_iter()
+        .map(|p| WatchEvent {
+            path: p.path.to_string_lossy().into_owned(),
+            kind: p.kind.to_string(),
+        })
+        .collect())
+}
+
+/// Apply the include/exclude globs to a path relative to `root`.
+fn wanted(root: &Path, path: &Path, options: &WatchOptions) -> bool {
+    let rel = path.strip_prefix(root).unwrap_or(path);
+    if options.exclude.as_ref().is_some_and(|m| m.is_match(rel)) {
+        return false;
+    }
+    options.include.as_ref().is_none_or(|m| m.is_match(rel))
+}
+
+/// Fold a new event kind into the one already pending for a path.
+fn merge(existing: &'static str, new: &'static str) -> &'static str {
+    match (existing, new) {
+        (_, "remove") => "remove",
+        ("create", "modify") => "create",
+        (_, new) => new,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::thread;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_merge_rules() {
+        assert_eq!(merge("create", "modify"), "create");
+        assert_eq!(merge("modify", "modify"), "modify");
+        assert_eq!(merge("create", "remove"), "remove");
+        assert_eq!(merge("remove", "create"), "create");
+    }
+
+    #[test]
+    fn test_watch_coalesces_burst_of_writes() {
+        let dir = TempDir::new().expect("create temp dir");
+        let root = dir.path().to_path_buf();
+        let watched = root.to_str().expect("utf-8 temp path").to_string();
+
+        let handle = thread::spawn(move || {
+            watch_dir(
+                &watched,
+                &WatchOptions {
+                    debounce: Duration::from_millis(500),
+                    duration: Duration::from_millis(1500),
+                    exclude: Some(
+                        globset::Glob::new("*.tmp")
+                            .expect("valid glob")
+                            .compile_matcher(),
+                    ),
+                    ..WatchOptions::default()
+                },
+            )
+        });
+
+        // Give the watcher time to register before touching anything.
+        thread::sleep(Duration::from_millis(300));
+        let file = root.join("notes.txt");
+        fs::write(&file, "one").expect("write 1");
+        fs::write(&file, "two").expect("write 2");
+        fs::write(&file, "three").expect("write 3");
+        fs::write(root.join("scratch.tmp"), "ignored").expect("write tmp");
+
+        let events = handle
+            .join()
+            .expect("watch thread panicked")
+            .expect("watch_dir failed");
+        let for_file: Vec<&WatchEvent> = events
+            .iter()
+            .filter(|e| e.path.ends_with("notes.txt"))
+            .collect();
+        assert_eq!(for_file.len(), 1, "burst should coalesce: {:?}", events);
+        assert_eq!(for_file[0].kind, "create");
+        assert!(!events.iter().any(|e| e.path.ends_with(".tmp")));
+    }
+}
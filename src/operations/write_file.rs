@@ -3,35 +3,424 @@
 // Write content to a file
 
 use crate::error::{FileIoError, Result};
+use std::borrow::Cow;
 use std::fs;
 use std::path::Path;
 
+/// Line-ending convention applied to content before it is written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LineEnding {
+    /// Write the content exactly as given.
+    #[default]
+    Preserve,
+    /// Unix `\n`.
+    Lf,
+    /// Windows `\r\n`.
+    Crlf,
+}
+
+impl LineEnding {
+    /// Parse the tool-level `line_ending` string. `keep` is accepted as an
+    /// older spelling of `preserve`.
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "preserve" | "keep" => Ok(LineEnding::Preserve),
+            "lf" => Ok(LineEnding::Lf),
+            "crlf" => Ok(LineEnding::Crlf),
+            other => Err(crate::error::FileIoMcpError::InvalidParams(format!(
+                "line_ending must be 'lf', 'crlf', or 'preserve', got '{}'",
+                other
+            ))),
+        }
+    }
+
+    /// Rewrite every line break in `content` to this convention.
+    ///
+    /// `\r\n`, `\n`, and a lone `\r` (classic Mac OS) each count as one
+    /// break, so existing CRLFs are never doubled into `\r\r\n`.
+    pub fn apply<'a>(&self, content: &'a str) -> Cow<'a, str> {
+        let eol = match self {
+            LineEnding::Preserve => return Cow::Borrowed(content),
+            LineEnding::Lf => "\n",
+            LineEnding::Crlf => "\r\n",
+        };
+        let mut out = String::with_capacity(content.len());
+        let mut chars = content.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '\r' => {
+                    chars.next_if_eq(&'\n');
+                    out.push_str(eol);
+                }
+                '\n' => out.push_str(eol),
+                c => out.push(c),
+            }
+        }
+        Cow::Owned(out)
+    }
+}
+
+/// Character encoding the UTF-8 `content` is converted to on disk.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TargetEncoding {
+    #[default]
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    /// Any other WHATWG encoding label (`latin1`, `windows-1252`,
+    /// `shift_jis`, ...), encoded via `encoding_rs`.
+    Legacy(&'static encoding_rs::Encoding),
+}
+
+impl TargetEncoding {
+    /// Parse an encoding label such as `utf-16le` or `latin1`.
+    ///
+    /// Why: UTF-16 is handled here rather than by `encoding_rs`, whose
+    /// encoders follow the WHATWG rule of emitting UTF-8 for UTF-16 targets.
+    /// Note that `latin1` / `iso-8859-1` resolve to windows-1252 per WHATWG.
+    pub fn parse(label: &str) -> Result<Self> {
+        match label.trim().to_ascii_lowercase().as_str() {
+            "utf-16le" | "utf16le" => return Ok(TargetEncoding::Utf16Le),
+            "utf-16be" | "utf16be" => return Ok(TargetEncoding::Utf16Be),
+            _ => {}
+        }
+        match encoding_rs::Encoding::for_label(label.trim().as_bytes()) {
+            Some(enc) if enc == encoding_rs::UTF_8 => Ok(TargetEncoding::Utf8),
+            Some(enc) if enc == encoding_rs::UTF_16LE => Ok(TargetEncoding::Utf16Le),
+            Some(enc) if enc == encoding_rs::UTF_16BE => Ok(TargetEncoding::Utf16Be),
+            Some(enc) if enc == encoding_rs::REPLACEMENT => Err(unknown_encoding(label)),
+            Some(enc) => Ok(TargetEncoding::Legacy(enc)),
+            None => Err(unknown_encoding(label)),
+        }
+    }
+
+    /// Decode `bytes` in this encoding, stripping a leading byte-order mark
+    /// for UTF-16 (reported in the returned flag so a rewrite can restore
+    /// it). `None` if the bytes are not valid in this encoding.
+    ///
+    /// A UTF-8 BOM is kept as a U+FEFF character, so UTF-8 content round-trips
+    /// byte for byte without any flag.
+    pub fn decode(&self, bytes: &[u8]) -> Option<(String, bool)> {
+        let (encoding, bom) = match self {
+            TargetEncoding::Utf8 => {
+                return String::from_utf8(bytes.to_vec())
+                    .ok()
+                    .map(|text| (text, false));
+            }
+            TargetEncoding::Utf16Le => (encoding_rs::UTF_16LE, self.bom()),
+            TargetEncoding::Utf16Be => (encoding_rs::UTF_16BE, self.bom()),
+            TargetEncoding::Legacy(enc) => (*enc, None),
+        };
+        let (body, had_bom) = match bom.and_then(|bom| bytes.strip_prefix(bom)) {
+            Some(body) => (body, true),
+            None => (bytes, false),
+        };
+        encoding
+            .decode_without_bom_handling_and_without_replacement(body)
+            .map(|text| (text.into_owned(), had_bom))
+    }
+
+    /// Name of the encoding, as used in messages.
+    pub fn name(&self) -> &'static str {
+        match self {
+            TargetEncoding::Utf8 => "UTF-8",
+            TargetEncoding::Utf16Le => "UTF-16LE",
+            TargetEncoding::Utf16Be => "UTF-16BE",
+            TargetEncoding::Legacy(enc) => enc.name(),
+        }
+    }
+
+    /// Byte-order mark for this encoding, if it has one.
+    fn bom(&self) -> Option<&'static [u8]> {
+        match self {
+            TargetEncoding::Utf8 => Some(b"\xEF\xBB\xBF".as_slice()),
+            TargetEncoding::Utf16Le => Some(b"\xFF\xFE".as_slice()),
+            TargetEncoding::Utf16Be => Some(b"\xFE\xFF".as_slice()),
+            TargetEncoding::Legacy(_) => None,
+        }
+    }
+
+    /// Encode `content`, failing on the first character the target cannot
+    /// represent instead of substituting it.
+    pub fn encode<'a>(&self, content: &'a str) -> Result<Cow<'a, [u8]>> {
+        match self {
+            TargetEncoding::Utf8 => Ok(Cow::Borrowed(content.as_bytes())),
+            TargetEncoding::Utf16Le => Ok(Cow::Owned(
+                content.encode_utf16().flat_map(u16::to_le_bytes).collect(),
+            )),
+            TargetEncoding::Utf16Be => Ok(Cow::Owned(
+                content.encode_utf16().flat_map(u16::to_be_bytes).collect(),
+            )),
+            TargetEncoding::Legacy(enc) => {
+                let (bytes, _, had_errors) = enc.encode(content);
+                if had_errors {
+                    let mut buf = [0u8; 4];
+                    let bad = content
+                        .chars()
+                        .find(|c| enc.encode(c.encode_utf8(&mut buf)).2)
+                        .unwrap_or(char::REPLACEMENT_CHARACTER);
+                    return Err(crate::error::FileIoMcpError::InvalidParams(format!(
+                        "content contains {:?} (U+{:04X}), which cannot be represented in {}",
+                        bad,
+                        bad as u32,
+                        enc.name()
+                    )));
+                }
+                Ok(Cow::Owned(bytes.into_owned()))
+            }
+        }
+    }
+}
+
+/// What a replacing (non-append) write does when the target already exists.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IfExists {
+    /// Replace the existing file.
+    #[default]
+    Overwrite,
+    /// Fail with an "already exists" error, leaving the file untouched.
+    Error,
+    /// Leave the file untouched and report that nothing was written.
+    Skip,
+}
+
+impl IfExists {
+    /// Parse the tool-level `if_exists` string.
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "overwrite" => Ok(IfExists::Overwrite),
+            "error" => Ok(IfExists::Error),
+            "skip" => Ok(IfExists::Skip),
+            other => Err(crate::error::FileIoMcpError::InvalidParams(format!(
+                "if_exists must be 'overwrite', 'error', or 'skip', got '{}'",
+                other
+            ))),
+        }
+    }
+}
+
+fn unknown_encoding(label: &str) -> crate::error::FileIoMcpError {
+    crate::error::FileIoMcpError::InvalidParams(format!("Unknown encoding: {}", label))
+}
+
+/// Options for [`write_file_with`].
+#[derive(Debug, Clone, Default)]
+pub struct WriteOptions {
+    /// Append to the end of the file instead of replacing it.
+    pub append: bool,
+    /// Insert the content at the start of the file, ahead of what is already
+    /// there. The file is rewritten whole, atomically unless `in_place` is
+    /// set. Exclusive with `append`.
+    pub prepend: bool,
+    /// Line-ending normalization applied to the content first.
+    pub line_ending: LineEnding,
+    /// Encoding of the bytes written to disk.
+    pub encoding: TargetEncoding,
+    /// Start the file with the encoding's byte-order mark. When appending,
+    /// the BOM is only written if the file is new or empty.
+    pub bom: bool,
+    /// Size-based rotation for append mode; see [`Rotation`].
+    pub rotate: Option<Rotation>,
+    /// Truncate and rewrite the existing file instead of replacing it with a
+    /// new one, so its inode, hard links, and open handles elsewhere stay
+    /// attached to it.
+    ///
+    /// Why off by default: the rewrite is not atomic. A crash or full disk
+    /// part way through leaves the file truncated or half-written, and
+    /// readers can observe it empty, whereas the default temp-file-and-rename
+    /// swaps in the complete new content in one step.
+    pub in_place: bool,
+    /// Behaviour when replacing a file that already exists. Appends and
+    /// prepends ignore it, since extending an existing file is their purpose.
+    pub if_exists: IfExists,
+    /// Permission bits the written file ends up with (Unix only; ignored
+    /// elsewhere). `None` leaves them as the write produces them.
+    pub mode: Option<u32>,
+    /// Copy an existing file to `<path><suffix>` before changing it; see
+    /// [`WriteOutcome::backup`].
+    pub backup_suffix: Option<String>,
+    /// Flush the data to disk (`fsync`) before reporting success, and on
+    /// Unix also the parent directory, so an atomic replace survives a crash
+    /// once the call returns.
+    ///
+    /// Why off by default: an fsync can take tens of milliseconds, and most
+    /// writes only need to be visible to other processes, which the rename
+    /// already guarantees.
+    pub sync: bool,
+}
+
+/// What [`write_file_with`] did.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+pub struct WriteOutcome {
+    /// The path written, after `~` and variable expansion, made absolute.
+    pub path: String,
+    /// False when [`IfExists::Skip`] left an existing file untouched.
+    pub written: bool,
+    /// Bytes this call put on disk, including any BOM; for an append or
+    /// prepend, only the added bytes.
+    pub bytes_written: u64,
+    /// True when nothing existed at `path` before the call.
+    pub created: bool,
+    /// Where the previous content was copied, when a backup was requested
+    /// and there was an existing file to back up.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup: Option<String>,
+}
+
+/// Log-style rotation applied before an append that would grow the file past
+/// `max_size` bytes.
+///
+/// The file is renamed to `<name>.1`, older rotations shift up one number
+/// (`.1` to `.2`, ...), anything beyond `keep` is deleted, and the append
+/// starts a fresh file. With `keep == 0` the old content is simply dropped.
+/// A single write larger than `max_size` still lands whole in the fresh file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rotation {
+    pub max_size: u64,
+    pub keep: u32,
+}
+
 /// Write content to a file
 pub fn write_file(path: &str, content: &str, append: bool) -> Result<()> {
-    let expanded_path = shellexpand::full(path)
-        .map_err(|e| {
-            crate::error::FileIoMcpError::from(crate::error::FileIoError::InvalidPath(format!(
-                "Failed to expand path \'{}\': {}",
-                path, e
-            )))
-        })
-        .map(|expanded| expanded.into_owned())?;
+    write_file_with(
+        path,
+        content,
+        &WriteOptions {
+            append,
+            ..WriteOptions::default()
+        },
+    )
+    .map(|_| ())
+}
+
+/// [`write_file`] with the full set of [`WriteOptions`].
+pub fn write_file_with(path: &str, content: &str, options: &WriteOptions) -> Result<WriteOutcome> {
+    let normalized = options.line_ending.apply(content);
+    let (bom, encoded) = prepare(&normalized, options)?;
+    write_prepared(path, bom, &encoded, options)
+}
+
+/// Bytes [`write_file_with`] would put on disk for `content` when creating
+/// or replacing a file, without touching the filesystem.
+pub fn prepared_len(content: &str, options: &WriteOptions) -> Result<u64> {
+    let normalized = options.line_ending.apply(content);
+    let (bom, encoded) = prepare(&normalized, options)?;
+    Ok((bom.len() + encoded.len()) as u64)
+}
+
+/// The BOM (possibly empty) and encoded bytes for already-normalized text.
+fn prepare<'a>(
+    normalized: &'a str,
+    options: &WriteOptions,
+) -> Result<(&'static [u8], Cow<'a, [u8]>)> {
+    let encoded = options.encoding.encode(normalized)?;
+    let bom: &'static [u8] = if options.bom {
+        options.encoding.bom().ok_or_else(|| {
+            crate::error::FileIoMcpError::InvalidParams(
+                "bom is only supported for UTF-8 and UTF-16 encodings".to_string(),
+            )
+        })?
+    } else {
+        &[]
+    };
+    Ok((bom, encoded))
+}
+
+/// [`write_file_with`] for content that is already bytes, e.g. decoded from
+/// base64. They are written as given: `line_ending`, `encoding`, and `bom`
+/// do not apply.
+pub fn write_bytes_with(path: &str, bytes: &[u8], options: &WriteOptions) -> Result<WriteOutcome> {
+    write_prepared(path, &[], bytes, options)
+}
+
+/// The part of [`write_file_with`] after the content has become bytes.
+/// `bom` is written ahead of `encoded` unless appending to a non-empty file.
+fn write_prepared(
+    path: &str,
+    bom: &[u8],
+    encoded: &[u8],
+    options: &WriteOptions,
+) -> Result<WriteOutcome> {
+    if options.append && options.prepend {
+        return Err(crate::error::FileIoMcpError::InvalidParams(
+            "append and prepend cannot be combined".to_string(),
+        ));
+    }
+    if options.rotate.is_some() && !options.append {
+        return Err(crate::error::FileIoMcpError::InvalidParams(
+            "rotation (max_size) only applies in append mode".to_string(),
+        ));
+    }
+    if let Some(suffix) = &options.backup_suffix
+        && (suffix.is_empty() || suffix.contains('/'))
+    {
+        return Err(crate::error::FileIoMcpError::InvalidParams(format!(
+            "backup_suffix must be non-empty and must not contain '/', got '{}'",
+            suffix
+        )));
+    }
+    let expanded_path = super::path_utils::resolve_path(path)?;
     let path_obj = Path::new(&expanded_path);
+    let replace = options.append || options.prepend || options.if_exists == IfExists::Overwrite;
+    let existed = path_obj.symlink_metadata().is_ok();
+    if !replace && existed {
+        return refuse_existing(options.if_exists, &expanded_path);
+    }
 
-    // Create parent directories if they don't exist
+    // Create parent directories if they don't exist. This runs before the
+    // append/overwrite split so both modes behave the same on fresh paths.
     if let Some(parent) = path_obj.parent() {
         fs::create_dir_all(parent).map_err(|e| {
-            FileIoError::WriteError(format!(
-                "Failed to create parent directories for {}: {}",
-                expanded_path, e
+            crate::error::FileIoMcpError::from(FileIoError::from_io_error(
+                "create parent directories for",
+                &expanded_path,
+                e,
             ))
         })?;
     }
 
-    if append {
+    // Taken before any branch touches the file, so the copy always holds
+    // the content as it was before this call.
+    let backup = match &options.backup_suffix {
+        Some(suffix) if path_obj.is_file() => {
+            let backup_path = format!("{}{}", expanded_path, suffix);
+            fs::copy(path_obj, &backup_path)
+                .map_err(|e| FileIoError::from_io_error("back up", &expanded_path, e))?;
+            Some(backup_path)
+        }
+        _ => None,
+    };
+
+    let mut bytes_written = (bom.len() + encoded.len()) as u64;
+    // A prepend is a replace whose new content is followed by the old.
+    let existing;
+    let joined;
+    let (bom, encoded) = if options.prepend && path_obj.is_file() {
+        existing = fs::read(path_obj)
+            .map_err(|e| FileIoError::from_io_error("read file", &expanded_path, e))?;
+        // A BOM the file starts with stays in front of the new content.
+        let (lead, rest) = match options.encoding.bom() {
+            Some(mark) if existing.starts_with(mark) => (mark, &existing[mark.len()..]),
+            _ => (bom, existing.as_slice()),
+        };
+        joined = [encoded, rest].concat();
+        bytes_written = (lead.len() + joined.len() - existing.len()) as u64;
+        (lead, joined.as_slice())
+    } else {
+        (bom, encoded)
+    };
+    if options.append {
         use std::fs::OpenOptions;
         use std::io::Write;
 
+        if let Some(rotation) = options.rotate {
+            let current = fs::metadata(path_obj).map(|m| m.len()).unwrap_or(0);
+            let incoming = (bom.len() + encoded.len()) as u64;
+            if current > 0 && current.saturating_add(incoming) > rotation.max_size {
+                rotate_file(path_obj, rotation.keep)?;
+            }
+        }
+
         let mut file = OpenOptions::new()
             .create(true)
             .append(true)
@@ -44,53 +433,211 @@ pub fn write_file(path: &str, content: &str, append: bool) -> Result<()> {
                 ))
             })?;
 
-        file.write_all(content.as_bytes()).map_err(|e| {
+        let at_start = file.metadata().map(|m| m.len() == 0).unwrap_or(false);
+        let prefix: &[u8] = if at_start { bom } else { &[] };
+        bytes_written = (prefix.len() + encoded.len()) as u64;
+        file.write_all(&[prefix, encoded].concat()).map_err(|e| {
             crate::error::FileIoMcpError::from(FileIoError::from_io_error(
                 "write to file",
                 &expanded_path,
                 e,
             ))
         })?;
+        apply_mode(&file, options.mode, &expanded_path)?;
+        if options.sync {
+            sync_file(&file, &expanded_path)?;
+        }
+    } else if options.in_place {
+        use std::fs::OpenOptions;
+        use std::io::Write;
+
+        let mut open = OpenOptions::new();
+        open.write(true);
+        if replace {
+            open.create(true).truncate(true);
+        } else {
+            open.create_new(true);
+        }
+        let mut file = match open.open(&expanded_path) {
+            Ok(file) => file,
+            Err(e) if !replace && e.kind() == std::io::ErrorKind::AlreadyExists => {
+                return refuse_existing(options.if_exists, &expanded_path);
+            }
+            Err(e) => {
+                return Err(
+                    FileIoError::from_io_error("open file for writing", &expanded_path, e).into(),
+                );
+            }
+        };
+        file.write_all(&[bom, encoded].concat())
+            .map_err(|e| FileIoError::from_io_error("write to file", &expanded_path, e))?;
+        apply_mode(&file, options.mode, &expanded_path)?;
+        if options.sync {
+            sync_file(&file, &expanded_path)?;
+        }
     } else {
-        // Atomic write: write to a secure temp file, then persist (rename).
-        // Using tempfile::NamedTempFile avoids predictable temp filenames
-        // that could be exploited via symlink attacks.
-        let parent = path_obj.parent().unwrap_or(Path::new("."));
-        let mut tmp = tempfile::NamedTempFile::new_in(parent).map_err(|e| {
-            crate::error::FileIoMcpError::from(FileIoError::from_io_error(
-                "create temp file",
-                &expanded_path,
-                e,
-            ))
-        })?;
-        {
-            use std::io::Write;
-            tmp.write_all(content.as_bytes()).map_err(|e| {
-                crate::error::FileIoMcpError::from(FileIoError::from_io_error(
-                    "write to temp file",
-                    &expanded_path,
-                    e,
-                ))
-            })?;
+        let tmp = staged_temp_file(path_obj, &expanded_path, &[bom, encoded].concat())?;
+        // Set on the staged file so the target never appears with other
+        // permissions, not even between the rename and a chmod.
+        apply_mode(tmp.as_file(), options.mode, &expanded_path)?;
+        // The data must be on disk before the rename makes it visible, or a
+        // crash could leave the new name pointing at an empty file.
+        if options.sync {
+            sync_file(tmp.as_file(), &expanded_path)?;
         }
-        tmp.persist(&expanded_path).map_err(|e| {
-            use std::io::ErrorKind;
-            match e.error.kind() {
-                ErrorKind::PermissionDenied => {
-                    crate::error::FileIoMcpError::from(FileIoError::PermissionDenied(format!(
-                        "Permission denied when writing file: {}",
-                        expanded_path
-                    )))
-                }
-                _ => crate::error::FileIoMcpError::from(FileIoError::from_io_error(
+        // Without overwrite, persist_noclobber closes the window between the
+        // existence check above and the rename.
+        let persisted = if replace {
+            tmp.persist(&expanded_path).map(|_| ())
+        } else {
+            tmp.persist_noclobber(&expanded_path).map(|_| ())
+        };
+        match persisted {
+            Ok(()) => {}
+            Err(e) if !replace && e.error.kind() == std::io::ErrorKind::AlreadyExists => {
+                return refuse_existing(options.if_exists, &expanded_path);
+            }
+            Err(e) => {
+                return Err(FileIoError::from_io_error(
                     "rename temp file",
                     &expanded_path,
                     e.error,
-                )),
+                )
+                .into());
             }
-        })?;
+        }
+        if options.sync {
+            sync_parent_dir(path_obj, &expanded_path)?;
+        }
+    }
+
+    Ok(WriteOutcome {
+        path: expanded_path,
+        written: true,
+        bytes_written,
+        created: !existed,
+        backup,
+    })
+}
+
+/// Atomically replace the file at the already-expanded `path` with `bytes`,
+/// for callers whose new content is not text [`write_file_with`] can encode.
+pub(crate) fn replace_bytes(path: &str, bytes: &[u8]) -> Result<()> {
+    staged_temp_file(Path::new(path), path, bytes)?
+        .persist(path)
+        .map_err(|e| FileIoError::from_io_error("rename temp file", path, e.error))?;
+    Ok(())
+}
+
+/// A temp file holding `bytes` next to `path_obj`, ready to be renamed over
+/// it.
+///
+/// Atomic write: write to a secure temp file, then persist (rename). Using
+/// tempfile::NamedTempFile avoids predictable temp filenames that could be
+/// exploited via symlink attacks.
+fn staged_temp_file(
+    path_obj: &Path,
+    expanded_path: &str,
+    bytes: &[u8],
+) -> Result<tempfile::NamedTempFile> {
+    use std::io::Write;
+
+    let parent = path_obj.parent().unwrap_or(Path::new("."));
+    let mut tmp = tempfile::NamedTempFile::new_in(parent).map_err(|e| {
+        crate::error::FileIoMcpError::from(FileIoError::from_io_error(
+            "create temp file",
+            expanded_path,
+            e,
+        ))
+    })?;
+    tmp.write_all(bytes).map_err(|e| {
+        crate::error::FileIoMcpError::from(FileIoError::from_io_error(
+            "write to temp file",
+            expanded_path,
+            e,
+        ))
+    })?;
+    Ok(tmp)
+}
+
+/// Give `file` the permission bits `mode`, if any. A no-op off Unix.
+fn apply_mode(file: &fs::File, mode: Option<u32>, expanded_path: &str) -> Result<()> {
+    #[cfg(unix)]
+    if let Some(mode) = mode {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(fs::Permissions::from_mode(mode))
+            .map_err(|e| FileIoError::from_io_error("set permissions for", expanded_path, e))?;
+    }
+    #[cfg(not(unix))]
+    let _ = (file, mode, expanded_path);
+    Ok(())
+}
+
+fn sync_file(file: &fs::File, expanded_path: &str) -> Result<()> {
+    file.sync_all()
+        .map_err(|e| FileIoError::from_io_error("sync", expanded_path, e).into())
+}
+
+/// Persist the rename itself: on Unix a directory entry change is only
+/// durable once the directory is synced. Elsewhere this is a no-op.
+fn sync_parent_dir(path_obj: &Path, expanded_path: &str) -> Result<()> {
+    #[cfg(unix)]
+    {
+        let parent = match path_obj.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        fs::File::open(parent)
+            .and_then(|dir| dir.sync_all())
+            .map_err(|e| FileIoError::from_io_error("sync directory of", expanded_path, e))?;
     }
+    #[cfg(not(unix))]
+    let _ = (path_obj, expanded_path);
+    Ok(())
+}
+
+/// Outcome of a non-overwriting write whose target turned out to exist.
+fn refuse_existing(if_exists: IfExists, path: &str) -> Result<WriteOutcome> {
+    match if_exists {
+        IfExists::Skip => Ok(WriteOutcome {
+            path: path.to_string(),
+            ..WriteOutcome::default()
+        }),
+        _ => Err(FileIoError::AlreadyExists(format!("{} (if_exists is 'error')", path)).into()),
+    }
+}
+
+/// Shift `path` into the `<name>.1`, `<name>.2`, ... series, keeping at most
+/// `keep` old files. Leaves `path` absent.
+fn rotate_file(path: &Path, keep: u32) -> Result<()> {
+    let display = path.display().to_string();
+    let name = path
+        .file_name()
+        .ok_or_else(|| FileIoError::InvalidPath(format!("{} has no file name", display)))?
+        .to_string_lossy()
+        .into_owned();
+    let numbered = |n: u32| path.with_file_name(format!("{}.{}", name, n));
 
+    if keep == 0 {
+        return fs::remove_file(path)
+            .map_err(|e| FileIoError::from_io_error("remove rotated file", &display, e).into());
+    }
+    let oldest = numbered(keep);
+    if oldest.symlink_metadata().is_ok() {
+        fs::remove_file(&oldest).map_err(|e| {
+            FileIoError::from_io_error("remove rotated file", &oldest.display().to_string(), e)
+        })?;
+    }
+    for n in (1..keep).rev() {
+        let from = numbered(n);
+        if from.symlink_metadata().is_ok() {
+            fs::rename(&from, numbered(n + 1)).map_err(|e| {
+                FileIoError::from_io_error("rotate file", &from.display().to_string(), e)
+            })?;
+        }
+    }
+    fs::rename(path, numbered(1))
+        .map_err(|e| FileIoError::from_io_error("rotate file", &display, e))?;
     Ok(())
 }
 
@@ -134,4 +681,381 @@ mod tests {
         let content = fs::read_to_string(&path).unwrap();
         assert_eq!(content, "content");
     }
+
+    #[test]
+    fn test_write_file_append_creates_parent_dirs() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("a").join("b").join("new.log");
+        let path_str = path.to_str().unwrap().to_string();
+
+        write_file(&path_str, "first\n", true).unwrap();
+        write_file(&path_str, "second\n", true).unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "first\nsecond\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_file_sets_mode() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("run.sh");
+        let p = path.to_str().unwrap();
+        let mode_of = |p: &Path| fs::metadata(p).unwrap().permissions().mode() & 0o7777;
+
+        let options = WriteOptions {
+            mode: Some(0o700),
+            ..WriteOptions::default()
+        };
+        write_file_with(p, "#!/bin/sh\n", &options).unwrap();
+        assert_eq!(mode_of(&path), 0o700);
+
+        let options = WriteOptions {
+            append: true,
+            mode: Some(0o644),
+            ..WriteOptions::default()
+        };
+        write_file_with(p, "true\n", &options).unwrap();
+        assert_eq!(mode_of(&path), 0o644);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "#!/bin/sh\ntrue\n");
+    }
+
+    #[test]
+    fn test_write_file_crlf_line_endings() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("win.txt");
+        let options = WriteOptions {
+            line_ending: LineEnding::Crlf,
+            ..WriteOptions::default()
+        };
+
+        write_file_with(path.to_str().unwrap(), "a\nb\r\nc", &options).unwrap();
+
+        assert_eq!(fs::read(&path).unwrap(), b"a\r\nb\r\nc");
+    }
+
+    #[test]
+    fn test_write_file_utf16le_with_bom() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("legacy.txt");
+        let options = WriteOptions {
+            encoding: TargetEncoding::parse("UTF-16LE").unwrap(),
+            bom: true,
+            ..WriteOptions::default()
+        };
+
+        write_file_with(path.to_str().unwrap(), "hé", &options).unwrap();
+
+        assert_eq!(
+            fs::read(&path).unwrap(),
+            [0xFF, 0xFE, b'h', 0x00, 0xE9, 0x00]
+        );
+    }
+
+    #[test]
+    fn test_write_file_unrepresentable_character() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("latin.txt");
+        let options = WriteOptions {
+            encoding: TargetEncoding::parse("latin1").unwrap(),
+            ..WriteOptions::default()
+        };
+
+        write_file_with(path.to_str().unwrap(), "café", &options).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"caf\xE9");
+
+        assert!(write_file_with(path.to_str().unwrap(), "snow \u{2603}", &options).is_err());
+        assert!(TargetEncoding::parse("klingon").is_err());
+    }
+
+    #[test]
+    fn test_line_ending_apply() {
+        assert_eq!(LineEnding::Lf.apply("a\r\nb\nc\r\n"), "a\nb\nc\n");
+        assert_eq!(LineEnding::Preserve.apply("a\r\nb\n"), "a\r\nb\n");
+        assert!(LineEnding::parse("cr").is_err());
+        assert_eq!(LineEnding::parse("keep").ok(), Some(LineEnding::Preserve));
+    }
+
+    #[test]
+    fn test_line_ending_modes_on_mixed_input() {
+        let mixed = "a\r\nb\nc\rd\r\n\re";
+        assert_eq!(LineEnding::Lf.apply(mixed), "a\nb\nc\nd\n\ne");
+        assert_eq!(LineEnding::Crlf.apply(mixed), "a\r\nb\r\nc\r\nd\r\n\r\ne");
+        assert_eq!(LineEnding::Preserve.apply(mixed), mixed);
+
+        let dir = TempDir::new().expect("create temp dir");
+        let path = dir.path().join("mixed.txt");
+        let p = path.to_str().expect("utf-8 path");
+        for (label, expected) in [
+            ("lf", "a\nb\nc\nd\n\ne"),
+            ("crlf", "a\r\nb\r\nc\r\nd\r\n\r\ne"),
+            ("preserve", mixed),
+        ] {
+            let options = WriteOptions {
+                line_ending: LineEnding::parse(label).expect("valid line_ending"),
+                ..WriteOptions::default()
+            };
+            write_file_with(p, mixed, &options).expect("write succeeds");
+            assert_eq!(fs::read_to_string(&path).expect("read back"), expected);
+        }
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_write_file_permission_denied_maps_to_permission_denied() {
+        use crate::error::FileIoMcpError;
+        use std::os::unix::fs::PermissionsExt;
+
+        // root bypasses directory permissions, so the denial can't be provoked.
+        if nix::unistd::geteuid().is_root() {
+            return;
+        }
+
+        let dir = TempDir::new().unwrap();
+        let locked = dir.path().join("locked");
+        fs::create_dir(&locked).unwrap();
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o555)).unwrap();
+        let path = locked.join("test.txt").to_str().unwrap().to_string();
+
+        let overwrite = write_file(&path, "content", false);
+        let append = write_file(&path, "content", true);
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).unwrap();
+
+        for res in [overwrite, append] {
+            assert!(
+                matches!(
+                    res,
+                    Err(FileIoMcpError::FileIo(FileIoError::PermissionDenied(_)))
+                ),
+                "expected PermissionDenied, got {:?}",
+                res
+            );
+        }
+    }
+
+    #[test]
+    fn test_append_rotates_past_max_size() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("agent.log");
+        let p = path.to_str().unwrap();
+        let options = WriteOptions {
+            append: true,
+            rotate: Some(Rotation {
+                max_size: 12,
+                keep: 2,
+            }),
+            ..WriteOptions::default()
+        };
+        let read = |name: &str| fs::read_to_string(dir.path().join(name)).unwrap();
+
+        for line in ["one\n", "two\n"] {
+            write_file_with(p, line, &options).unwrap();
+        }
+        assert_eq!(read("agent.log"), "one\ntwo\n");
+        assert!(!dir.path().join("agent.log.1").exists());
+
+        // 8 + 6 bytes would exceed 12: rotate, then start fresh.
+        write_file_with(p, "three\n", &options).unwrap();
+        assert_eq!(read("agent.log"), "three\n");
+        assert_eq!(read("agent.log.1"), "one\ntwo\n");
+
+        write_file_with(p, "four\n", &options).unwrap();
+        write_file_with(p, "five\n", &options).unwrap();
+        assert_eq!(read("agent.log"), "five\n");
+        assert_eq!(read("agent.log.1"), "three\nfour\n");
+        assert_eq!(read("agent.log.2"), "one\ntwo\n");
+
+        // Only `keep` rotations survive.
+        write_file_with(p, "six seven\n", &options).unwrap();
+        assert_eq!(read("agent.log"), "six seven\n");
+        assert_eq!(read("agent.log.1"), "five\n");
+        assert_eq!(read("agent.log.2"), "three\nfour\n");
+        assert!(!dir.path().join("agent.log.3").exists());
+
+        let overwrite = WriteOptions {
+            append: false,
+            ..options
+        };
+        assert!(write_file_with(p, "x", &overwrite).is_err());
+    }
+
+    #[test]
+    fn test_if_exists_against_existing_file() {
+        let dir = TempDir::new().expect("create temp dir");
+        let path = dir.path().join("keep.txt");
+        let p = path.to_str().expect("utf-8 path");
+        fs::write(&path, "original").expect("write fixture");
+        let with = |if_exists| WriteOptions {
+            if_exists,
+            ..WriteOptions::default()
+        };
+
+        let skipped = write_file_with(p, "skip", &with(IfExists::Skip)).expect("skip succeeds");
+        assert!(!skipped.written);
+        assert_eq!(fs::read_to_string(&path).expect("read back"), "original");
+
+        let err = write_file_with(p, "error", &with(IfExists::Error))
+            .expect_err("existing file is refused");
+        assert!(matches!(
+            err,
+            crate::error::FileIoMcpError::FileIo(FileIoError::AlreadyExists(_))
+        ));
+        assert_eq!(fs::read_to_string(&path).expect("read back"), "original");
+
+        let written =
+            write_file_with(p, "replaced", &with(IfExists::Overwrite)).expect("overwrite succeeds");
+        assert!(written.written);
+        assert_eq!(fs::read_to_string(&path).expect("read back"), "replaced");
+
+        // A fresh path is written whatever the policy; appends ignore it.
+        let fresh = dir.path().join("fresh.txt");
+        assert!(
+            write_file_with(
+                fresh.to_str().expect("utf-8 path"),
+                "new",
+                &with(IfExists::Error)
+            )
+            .expect("fresh path is written")
+            .written
+        );
+        let append = WriteOptions {
+            append: true,
+            if_exists: IfExists::Error,
+            ..WriteOptions::default()
+        };
+        write_file_with(p, "+", &append).expect("append ignores if_exists");
+        assert_eq!(fs::read_to_string(&path).expect("read back"), "replaced+");
+    }
+
+    #[test]
+    fn test_backup_holds_previous_content() {
+        let dir = TempDir::new().expect("create temp dir");
+        let path = dir.path().join("config.toml");
+        let p = path.to_str().expect("utf-8 path");
+        let options = WriteOptions {
+            backup_suffix: Some(".bak".to_string()),
+            ..WriteOptions::default()
+        };
+
+        // Nothing to back up yet.
+        let outcome = write_file_with(p, "v1", &options).expect("write succeeds");
+        assert_eq!(outcome.backup, None);
+
+        let outcome = write_file_with(p, "v2", &options).expect("write succeeds");
+        let backup = outcome.backup.expect("backup made");
+        assert_eq!(backup, format!("{}.bak", p));
+        assert_eq!(fs::read_to_string(&backup).expect("read backup"), "v1");
+        assert_eq!(fs::read_to_string(&path).expect("read file"), "v2");
+    }
+
+    #[test]
+    fn test_outcome_reports_bytes_and_creation() {
+        let dir = TempDir::new().expect("create temp dir");
+        let path = dir.path().join("log.txt");
+        let p = path.to_str().expect("utf-8 path");
+        let append = WriteOptions {
+            append: true,
+            bom: true,
+            ..WriteOptions::default()
+        };
+
+        let first = write_file_with(p, "ab", &append).expect("write succeeds");
+        assert_eq!(first.path, p);
+        assert!(first.created);
+        // The BOM only goes at the start of the file.
+        assert_eq!(first.bytes_written, 5);
+
+        let second = write_file_with(p, "cd", &append).expect("append succeeds");
+        assert!(!second.created);
+        assert_eq!(second.bytes_written, 2);
+        assert_eq!(prepared_len("cd", &append).expect("length computed"), 5);
+    }
+
+    #[test]
+    fn test_prepend_puts_content_first() {
+        let dir = TempDir::new().expect("create temp dir");
+        let path = dir.path().join("lib.rs");
+        let p = path.to_str().expect("utf-8 path");
+        let prepend = WriteOptions {
+            prepend: true,
+            ..WriteOptions::default()
+        };
+
+        // Missing file: created with just the content.
+        let outcome = write_file_with(p, "body\n", &prepend).expect("prepend succeeds");
+        assert!(outcome.created);
+        let outcome = write_file_with(p, "// header\n", &prepend).expect("prepend succeeds");
+        assert!(!outcome.created);
+        assert_eq!(outcome.bytes_written, 10);
+        assert_eq!(
+            fs::read_to_string(&path).expect("read back"),
+            "// header\nbody\n"
+        );
+
+        // The existing BOM stays at the very start.
+        fs::write(&path, b"\xEF\xBB\xBFold").expect("write fixture");
+        write_file_with(p, "new ", &prepend).expect("prepend succeeds");
+        assert_eq!(fs::read(&path).expect("read back"), b"\xEF\xBB\xBFnew old");
+
+        let both = WriteOptions {
+            append: true,
+            ..prepend
+        };
+        assert!(write_file_with(p, "x", &both).is_err());
+    }
+
+    #[test]
+    fn test_sync_write_succeeds() {
+        let dir = TempDir::new().expect("create temp dir");
+        let path = dir.path().join("durable.txt");
+        let p = path.to_str().expect("utf-8 path");
+        let sync = |append| WriteOptions {
+            append,
+            sync: true,
+            ..WriteOptions::default()
+        };
+
+        write_file_with(p, "a", &sync(false)).expect("synced write succeeds");
+        write_file_with(p, "b", &sync(true)).expect("synced append succeeds");
+        assert_eq!(fs::read_to_string(&path).expect("read back"), "ab");
+    }
+
+    #[test]
+    fn test_write_bytes_is_byte_exact() {
+        let dir = TempDir::new().expect("create temp dir");
+        let path = dir.path().join("blob.bin");
+        // Not UTF-8, and a bare CR that line_ending must not touch.
+        let bytes = [0xFF, 0x00, 0x80, b'\r'];
+        let options = WriteOptions {
+            line_ending: LineEnding::Crlf,
+            ..WriteOptions::default()
+        };
+
+        write_bytes_with(path.to_str().expect("utf-8 path"), &bytes, &options)
+            .expect("write succeeds");
+        assert_eq!(fs::read(&path).expect("read back"), bytes);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_in_place_keeps_inode_and_hard_links() {
+        use std::os::unix::fs::MetadataExt;
+        let dir = TempDir::new().expect("create temp dir");
+        let path = dir.path().join("data.txt");
+        let link = dir.path().join("link.txt");
+        fs::write(&path, "old content that is longer").expect("write fixture");
+        fs::hard_link(&path, &link).expect("create hard link");
+        let inode = fs::metadata(&path).expect("stat fixture").ino();
+        let options = WriteOptions {
+            in_place: true,
+            ..WriteOptions::default()
+        };
+
+        write_file_with(path.to_str().expect("utf-8 path"), "new", &options)
+            .expect("in-place write succeeds");
+
+        assert_eq!(fs::metadata(&path).expect("stat file").ino(), inode);
+        assert_eq!(fs::read_to_string(&path).expect("read file"), "new");
+        assert_eq!(fs::read_to_string(&link).expect("read link"), "new");
+    }
 }
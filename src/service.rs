@@ -8,31 +8,292 @@
 
 use mcp_core::{CallError, McpService, ToolDef, ToolReply, async_trait};
 use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
 
-use crate::error::FileIoMcpError;
+use crate::audit::AuditLog;
+use crate::error::{FileIoError, FileIoMcpError};
+use crate::operations::write_file::IfExists;
 use crate::path_guard::PathGuard;
 use crate::tools::ToolRegistry;
 
+/// Operator-configured restriction on which tools the server exposes
+/// (`--allow-tools` / `--deny-tools`).
+///
+/// Unlike the [`PathGuard`], this restriction is deliberately visible: a
+/// filtered tool is absent from `tools/list` and a call to it is rejected with
+/// an explicit error, since the tool set is deployment policy rather than a
+/// secret.
+#[derive(Debug, Clone, Default)]
+pub struct ToolFilter {
+    /// When set, only these tools are exposed.
+    allow: Option<HashSet<String>>,
+    /// Tools hidden regardless of `allow`.
+    deny: HashSet<String>,
+}
+
+impl ToolFilter {
+    /// Build a filter from allow/deny name lists. An empty `allow` list means
+    /// "every tool"; `deny` is applied after `allow`.
+    pub fn new(allow: &[String], deny: &[String]) -> Self {
+        Self {
+            allow: (!allow.is_empty()).then(|| allow.iter().cloned().collect()),
+            deny: deny.iter().cloned().collect(),
+        }
+    }
+
+    /// Whether `name` may be listed and called.
+    pub fn permits(&self, name: &str) -> bool {
+        self.allow.as_ref().is_none_or(|allow| allow.contains(name)) && !self.deny.contains(name)
+    }
+
+    /// Every tool name the filter mentions, for validating against the
+    /// registry.
+    fn mentioned(&self) -> impl Iterator<Item = &String> {
+        self.allow.iter().flatten().chain(self.deny.iter())
+    }
+}
+
+/// Wall-clock limits on tool calls (`--tool-timeout-ms` / `--tool-timeout`).
+#[derive(Debug, Clone, Default)]
+pub struct ToolTimeouts {
+    /// Limit for every tool without an override; `None` means unlimited.
+    default: Option<Duration>,
+    /// Per-tool limits. A zero duration disables the limit for that tool.
+    per_tool: HashMap<String, Duration>,
+}
+
+impl ToolTimeouts {
+    /// Apply `default` to every tool.
+    pub fn new(default: Option<Duration>) -> Self {
+        Self {
+            default,
+            per_tool: HashMap::new(),
+        }
+    }
+
+    /// Override the limit for one tool; `Duration::ZERO` exempts it.
+    pub fn with_override(mut self, tool: impl Into<String>, limit: Duration) -> Self {
+        self.per_tool.insert(tool.into(), limit);
+        self
+    }
+
+    /// Parse a `name=milliseconds` override as given on the command line.
+    pub fn parse_override(spec: &str) -> std::result::Result<(String, Duration), String> {
+        let (name, ms) = spec
+            .split_once('=')
+            .ok_or_else(|| format!("expected <tool>=<milliseconds>, got '{}'", spec))?;
+        let ms: u64 = ms
+            .trim()
+            .parse()
+            .map_err(|_| format!("invalid milliseconds in '{}'", spec))?;
+        Ok((name.trim().to_string(), Duration::from_millis(ms)))
+    }
+
+    /// Effective limit for `tool`, if any.
+    pub fn for_tool(&self, tool: &str) -> Option<Duration> {
+        match self.per_tool.get(tool) {
+            Some(limit) if limit.is_zero() => None,
+            Some(limit) => Some(*limit),
+            None => self.default,
+        }
+    }
+}
+
+/// Name of the introspection tool [`FileIoService`] answers itself.
+const SERVER_INFO_TOOL: &str = "fileio_server_info";
+
+/// Transports enabled in [`crate::server_config`].
+///
+/// Why: mcp-core picks the transport at startup and does not tell the
+/// service which one is serving, so this reports what can be selected.
+const TRANSPORTS: &[&str] = &["stdio", "websocket", "unix"];
+
+/// Build details reported by `fileio_server_info`.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct BuildInfo {
+    pub target_os: &'static str,
+    pub target_arch: &'static str,
+    /// `debug` or `release`.
+    pub profile: &'static str,
+}
+
+/// The server's effective configuration, as reported by
+/// `fileio_server_info`.
+///
+/// The path guard's deny-list is deliberately absent: it is designed to be
+/// invisible to callers (see [`PathGuard`]).
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ServerInfo {
+    pub name: &'static str,
+    pub version: &'static str,
+    pub build: BuildInfo,
+    /// Directory relative tool paths resolve against.
+    pub working_dir: Option<String>,
+    /// True when the tool filter leaves no tool that changes the filesystem.
+    pub read_only: bool,
+    pub no_clobber: bool,
+    /// Whether mutating calls are written to an audit log.
+    pub audit_log: bool,
+    /// Limit applied to tools without a per-tool override.
+    pub tool_timeout_ms: Option<u64>,
+    /// `--allow-tools`, sorted; `None` when every tool is allowed.
+    pub allow_tools: Option<Vec<String>>,
+    /// `--deny-tools`, sorted.
+    pub deny_tools: Vec<String>,
+    pub transports: &'static [&'static str],
+    /// Enabled tools, in `tools/list` order.
+    pub tools: Vec<String>,
+}
+
 /// The fileio-mcp service.  Owns a `ToolRegistry` (which holds the
 /// `PathGuard`) and implements `McpService` for mcp-core.
 pub struct FileIoService {
-    registry: ToolRegistry,
+    /// Shared so a time-limited call can run on a blocking thread.
+    registry: Arc<ToolRegistry>,
+    tool_filter: ToolFilter,
+    audit_log: Option<AuditLog>,
+    timeouts: ToolTimeouts,
 }
 
 impl FileIoService {
     /// Create with the default path guard (hardcoded deny-list).
     pub fn new() -> Self {
-        Self {
-            registry: ToolRegistry::new(),
-        }
+        Self::with_registry(ToolRegistry::new())
     }
 
     /// Create with a custom path guard (CLI block-paths / block-file).
     pub fn with_guard(guard: PathGuard) -> Self {
+        Self::with_registry(ToolRegistry::with_guard(guard))
+    }
+
+    /// Create around a fully configured `registry`.
+    ///
+    /// Why: registry settings such as `--no-clobber`'s default `if_exists`
+    /// are applied before the service shares the registry, rather than by a
+    /// builder that would have to reach into the shared handle afterwards
+    /// and could only fail silently.
+    pub fn with_registry(registry: ToolRegistry) -> Self {
         Self {
-            registry: ToolRegistry::with_guard(guard),
+            registry: Arc::new(registry),
+            tool_filter: ToolFilter::default(),
+            audit_log: None,
+            timeouts: ToolTimeouts::default(),
+        }
+    }
+
+    /// Restrict the exposed tool set (CLI `--allow-tools` / `--deny-tools`).
+    ///
+    /// Names that match no registered tool are reported on stderr rather than
+    /// rejected, so a typo doesn't stop the server from starting — but the
+    /// operator still sees that the entry had no effect.
+    pub fn with_tool_filter(mut self, filter: ToolFilter) -> Self {
+        let known = self.tool_names();
+        for name in filter.mentioned() {
+            if !known.contains(name) {
+                eprintln!("warning: tool filter names unknown tool: {}", name);
+            }
+        }
+        self.tool_filter = filter;
+        self
+    }
+
+    /// Append a JSON line per mutating tool call to `log` (CLI `--audit-log`).
+    pub fn with_audit_log(mut self, log: AuditLog) -> Self {
+        self.audit_log = Some(log);
+        self
+    }
+
+    /// Limit how long tool calls may run (CLI `--tool-timeout-ms` /
+    /// `--tool-timeout`).
+    pub fn with_timeouts(mut self, timeouts: ToolTimeouts) -> Self {
+        self.timeouts = timeouts;
+        self
+    }
+
+    /// Run a tool call under `limit`.
+    ///
+    /// Why: the operations are synchronous filesystem code, so a plain
+    /// `tokio::time::timeout` around `execute_tool` would never get a chance
+    /// to fire. The call runs on a blocking thread instead and is abandoned
+    /// when the limit passes; the thread itself cannot be interrupted and
+    /// finishes (or stays blocked) in the background, but the session is
+    /// freed immediately.
+    async fn execute_with_timeout(
+        &self,
+        name: &str,
+        arguments: &Value,
+        limit: Duration,
+    ) -> crate::error::Result<Value> {
+        let registry = Arc::clone(&self.registry);
+        let tool = name.to_string();
+        let args = arguments.clone();
+        let handle = tokio::runtime::Handle::current();
+        let task = tokio::task::spawn_blocking(move || {
+            handle.block_on(registry.execute_tool(&tool, &args))
+        });
+        match tokio::time::timeout(limit, task).await {
+            Ok(Ok(outcome)) => outcome,
+            Ok(Err(join)) => Err(FileIoMcpError::Io(std::io::Error::other(format!(
+                "{} did not complete: {}",
+                name, join
+            )))),
+            Err(_) => Err(FileIoError::Timeout(format!(
+                "{} exceeded the {} ms tool timeout and was abandoned",
+                name,
+                limit.as_millis()
+            ))
+            .into()),
         }
     }
+
+    /// Describe the running configuration (`fileio_server_info`).
+    pub fn server_info(&self) -> ServerInfo {
+        let tools: Vec<String> = self.tools().into_iter().map(|t| t.name).collect();
+        let sorted = |names: &HashSet<String>| {
+            let mut names: Vec<String> = names.iter().cloned().collect();
+            names.sort();
+            names
+        };
+        ServerInfo {
+            name: "fileio-mcp",
+            version: env!("CARGO_PKG_VERSION"),
+            build: BuildInfo {
+                target_os: std::env::consts::OS,
+                target_arch: std::env::consts::ARCH,
+                profile: if cfg!(debug_assertions) {
+                    "debug"
+                } else {
+                    "release"
+                },
+            },
+            working_dir: std::env::current_dir()
+                .ok()
+                .map(|dir| dir.to_string_lossy().into_owned()),
+            read_only: !tools
+                .iter()
+                .any(|name| crate::audit::MUTATING_TOOLS.contains(&name.as_str())),
+            no_clobber: self.registry.default_if_exists() == IfExists::Error,
+            audit_log: self.audit_log.is_some(),
+            tool_timeout_ms: self.timeouts.default.map(|d| d.as_millis() as u64),
+            allow_tools: self.tool_filter.allow.as_ref().map(sorted),
+            deny_tools: sorted(&self.tool_filter.deny),
+            transports: TRANSPORTS,
+            tools,
+        }
+    }
+
+    /// Names of every tool in the registry, before filtering.
+    fn tool_names(&self) -> HashSet<String> {
+        self.registry
+            .list_tools()
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|tool| tool.get("name")?.as_str().map(str::to_string))
+            .collect()
+    }
 }
 
 impl Default for FileIoService {
@@ -53,6 +314,9 @@ impl McpService for FileIoService {
         arr.iter()
             .filter_map(|tool| {
                 let name = tool.get("name")?.as_str()?;
+                if !self.tool_filter.permits(name) {
+                    return None;
+                }
                 let description = tool.get("description")?.as_str()?;
                 let input_schema = tool.get("inputSchema")?.clone();
                 Some(ToolDef::new(name, description, input_schema))
@@ -61,7 +325,26 @@ impl McpService for FileIoService {
     }
 
     async fn call_tool(&self, name: &str, arguments: &Value) -> Result<ToolReply, CallError> {
-        match self.registry.execute_tool(name, arguments).await {
+        if !self.tool_filter.permits(name) {
+            return Err(CallError::tool(format!(
+                "Tool not available: {} (disabled by server configuration)",
+                name
+            )));
+        }
+        if name == SERVER_INFO_TOOL {
+            let info = serde_json::to_value(self.server_info())
+                .map_err(|e| CallError::tool(e.to_string()))?;
+            return Ok(ToolReply::text(info.to_string()).with_structured(info));
+        }
+        let outcome = match self.timeouts.for_tool(name) {
+            Some(limit) => self.execute_with_timeout(name, arguments, limit).await,
+            None => self.registry.execute_tool(name, arguments).await,
+        };
+        if let Some(log) = &self.audit_log {
+            let error = outcome.as_ref().err().map(|e| e.to_string());
+            log.record(name, arguments, error.as_deref());
+        }
+        match outcome {
             Ok(result) => {
                 // The registry returns a Value shaped like:
                 //   {"content": [{"type":"text","text":"..."}]}
@@ -102,3 +385,171 @@ fn map_error(e: FileIoMcpError) -> Result<ToolReply, CallError> {
         other => Err(CallError::tool(other.to_string())),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(service: &FileIoService) -> Vec<String> {
+        service.tools().into_iter().map(|t| t.name).collect()
+    }
+
+    /// Acceptance: a denied tool disappears from `tools/list` and a call to it
+    /// is rejected, while the remaining tools keep working.
+    #[tokio::test]
+    async fn deny_tools_hides_and_rejects_tool() {
+        let service = FileIoService::new()
+            .with_tool_filter(ToolFilter::new(&[], &["fileio_remove".to_string()]));
+
+        let listed = names(&service);
+        assert!(!listed.iter().any(|n| n == "fileio_remove"));
+        assert!(listed.iter().any(|n| n == "fileio_get_basename"));
+
+        let denied = service
+            .call_tool(
+                "fileio_remove",
+                &serde_json::json!({"path": ["/nonexistent"]}),
+            )
+            .await;
+        assert!(denied.is_err(), "denied tool must be rejected on call");
+
+        let allowed = service
+            .call_tool(
+                "fileio_get_basename",
+                &serde_json::json!({"path": "/a/b.txt"}),
+            )
+            .await;
+        assert!(allowed.is_ok(), "unfiltered tools must keep working");
+    }
+
+    /// Acceptance: an allow-list exposes exactly the named tools, and
+    /// `--deny-tools` still wins over it.
+    #[tokio::test]
+    async fn allow_tools_limits_surface_and_deny_wins() {
+        let allow = [
+            "fileio_read_lines".to_string(),
+            "fileio_get_basename".to_string(),
+        ];
+        let deny = ["fileio_read_lines".to_string()];
+        let service = FileIoService::new().with_tool_filter(ToolFilter::new(&allow, &deny));
+
+        assert_eq!(names(&service), vec!["fileio_get_basename".to_string()]);
+        let rejected = service
+            .call_tool(
+                "fileio_write_file",
+                &serde_json::json!({"path": "/tmp/x", "content": ""}),
+            )
+            .await;
+        assert!(
+            rejected.is_err(),
+            "tools outside the allow-list must be rejected"
+        );
+    }
+
+    /// Acceptance: a `fileio_remove` call through the service produces an
+    /// audit entry naming the tool and the removed path.
+    #[tokio::test]
+    async fn remove_produces_audit_entry() {
+        let dir = tempfile::TempDir::new().expect("tempdir");
+        let target = dir.path().join("victim.txt");
+        std::fs::write(&target, "x").expect("write victim");
+        let log_path = dir.path().join("audit.jsonl");
+        let service = FileIoService::new().with_audit_log(AuditLog::new(&log_path));
+
+        let target_str = target.to_string_lossy().into_owned();
+        service
+            .call_tool("fileio_remove", &serde_json::json!({"path": [target_str]}))
+            .await
+            .expect("remove should succeed");
+
+        let text = std::fs::read_to_string(&log_path).expect("audit log written");
+        let entry: Value =
+            serde_json::from_str(text.lines().next().expect("one entry")).expect("JSON line");
+        assert_eq!(entry["tool"], "fileio_remove");
+        assert_eq!(entry["paths"], serde_json::json!([target_str]));
+        assert_eq!(entry["success"], true);
+    }
+
+    /// Acceptance: `fileio_server_info` reports the crate version, and a
+    /// tool filter that leaves only read-only tools shows up as read-only.
+    #[tokio::test]
+    async fn server_info_reports_version_and_read_only() {
+        let full = FileIoService::new().server_info();
+        assert_eq!(full.version, env!("CARGO_PKG_VERSION"));
+        assert!(!full.read_only);
+        assert!(full.tools.iter().any(|n| n == SERVER_INFO_TOOL));
+
+        let allow = [
+            "fileio_read_lines".to_string(),
+            SERVER_INFO_TOOL.to_string(),
+        ];
+        let service = FileIoService::new().with_tool_filter(ToolFilter::new(&allow, &[]));
+        let reply = service
+            .call_tool(SERVER_INFO_TOOL, &serde_json::json!({}))
+            .await;
+        assert!(reply.is_ok(), "server info must answer");
+        let info = service.server_info();
+        assert!(info.read_only);
+        assert_eq!(info.tools, allow.to_vec());
+        assert_eq!(info.allow_tools, Some(allow.to_vec()));
+    }
+
+    #[test]
+    fn default_filter_permits_everything() {
+        let filter = ToolFilter::default();
+        assert!(filter.permits("fileio_remove"));
+        assert!(filter.permits("fileio_read_lines"));
+    }
+
+    #[test]
+    fn tool_timeouts_resolve_overrides() {
+        let timeouts = ToolTimeouts::new(Some(Duration::from_millis(100)))
+            .with_override("fileio_find_in_files", Duration::from_secs(5))
+            .with_override("fileio_copy", Duration::ZERO);
+        assert_eq!(
+            timeouts.for_tool("fileio_read_lines"),
+            Some(Duration::from_millis(100))
+        );
+        assert_eq!(
+            timeouts.for_tool("fileio_find_in_files"),
+            Some(Duration::from_secs(5))
+        );
+        assert_eq!(timeouts.for_tool("fileio_copy"), None);
+        assert_eq!(
+            ToolTimeouts::parse_override("fileio_stat=250"),
+            Ok(("fileio_stat".to_string(), Duration::from_millis(250)))
+        );
+        assert!(ToolTimeouts::parse_override("fileio_stat").is_err());
+    }
+
+    /// Acceptance: a call that blocks (reading a FIFO nobody writes to) is cut
+    /// off with a timeout error instead of hanging the session.
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn slow_tool_call_times_out() {
+        let dir = tempfile::TempDir::new().expect("tempdir");
+        let fifo = dir.path().join("never-written");
+        nix::unistd::mkfifo(&fifo, nix::sys::stat::Mode::S_IRWXU).expect("mkfifo");
+        let service =
+            FileIoService::new().with_timeouts(ToolTimeouts::new(Some(Duration::from_millis(100))));
+
+        let fifo_str = fifo.to_string_lossy().into_owned();
+        let result = service
+            .call_tool("fileio_read_lines", &serde_json::json!({"path": fifo_str}))
+            .await;
+        let err = result.expect_err("blocked read must time out");
+        assert!(
+            format!("{:?}", err).contains("timeout"),
+            "expected a timeout error, got {:?}",
+            err
+        );
+
+        // Release the abandoned reader so the blocking thread can finish.
+        drop(
+            std::fs::OpenOptions::new()
+                .write(true)
+                .open(&fifo)
+                .expect("open fifo for write"),
+        );
+    }
+}
@@ -2,38 +2,59 @@
 
 // Tool registry and MCP tool definitions
 
-use crate::error::{FileIoError, Result};
+use crate::error::Result;
+use crate::operations::write_file::IfExists;
 use crate::path_guard::PathGuard;
 use serde_json::Value;
 
 /// Tool registry that manages all available tools
 pub struct ToolRegistry {
     guard: PathGuard,
+    /// `if_exists` used by `fileio_write_file` calls that don't pass one.
+    default_if_exists: IfExists,
 }
 
 impl ToolRegistry {
     /// Create a new tool registry with default path guard
     pub fn new() -> Self {
-        Self {
-            guard: PathGuard::default(),
-        }
+        Self::with_guard(PathGuard::default())
     }
 
     /// Create a new tool registry with a specific path guard
     pub fn with_guard(guard: PathGuard) -> Self {
-        Self { guard }
+        Self {
+            guard,
+            default_if_exists: IfExists::default(),
+        }
+    }
+
+    /// Change what `fileio_write_file` does with an existing file when the
+    /// call doesn't say (CLI `--no-clobber` sets [`IfExists::Error`]).
+    pub fn set_default_if_exists(&mut self, if_exists: IfExists) {
+        self.default_if_exists = if_exists;
+    }
+
+    /// What `fileio_write_file` does with an existing file when the call
+    /// doesn't say.
+    pub fn default_if_exists(&self) -> IfExists {
+        self.default_if_exists
     }
 
     /// "File not found" error result in MCP JSON format
-    fn not_found_error(path: &str) -> Result<Value> {
-        Err(FileIoError::NotFound(format!("{} not found: {}", "File", path)).into())
+    ///
+    /// `operation` must be the one the tool's own missing-path error names;
+    /// both go through [`crate::operations::path_utils::not_found`], so
+    /// denial and absence read the same.
+    fn not_found_error(operation: &str, path: &str) -> Result<Value> {
+        Err(crate::operations::path_utils::not_found(operation, path).into())
     }
 
     /// Silent success result in MCP JSON format (for denied writes whose real
-    /// counterpart also returns a plain-text body — `write_file`, `touch`,
+    /// counterpart also returns a plain-text body — `touch`,
     /// `set_permissions`, `make_directory`, `change_ownership`, `link`).
-    /// Handlers whose real success path returns serialized JSON (`edit_file`,
-    /// `copy`, `move`, `remove`, `remove_directory`) must NOT use this — they
+    /// Handlers whose real success path returns serialized JSON (`write_file`,
+    /// `edit_file`, `copy`, `move`, `remove`, `remove_directory`) must NOT
+    /// use this — they
     /// build a synthetic result of the matching JSON shape instead, see
     /// `synthesize_op_results` and the per-handler synthesis blocks. Mixing
     /// the two creates a response-shape oracle (issue #3).
@@ -66,6 +87,27 @@ impl ToolRegistry {
             .collect()
     }
 
+    /// Shape a synthetic cp/mv result the way the real op would for the
+    /// requested `dry_run` status and `report_conflicts` wrapper. Denied
+    /// calls always report no conflicts: anything else would describe the
+    /// denied destination.
+    fn synthesize_transfer_body(
+        mut synthetic: Vec<Value>,
+        dry_run_status: Option<&str>,
+        report_conflicts: bool,
+    ) -> Value {
+        if let Some(status) = dry_run_status {
+            for entry in &mut synthetic {
+                entry["status"] = Value::String(status.to_string());
+            }
+        }
+        if report_conflicts {
+            serde_json::json!({ "results": synthetic, "conflicts": [] })
+        } else {
+            Value::Array(synthetic)
+        }
+    }
+
     /// Partition `paths` into (allowed, denied) while preserving order.
     /// Returns (allowed_paths, denied_set) where denied_set is a
     /// `HashSet` of the denied path strings for O(1) look-up.
@@ -85,18 +127,33 @@ impl ToolRegistry {
         (allowed, denied)
     }
 
+    /// Whether a cp/mv of `sources` into `destination` would write to a
+    /// denied path: the destination itself, or, for a directory destination,
+    /// any child a source would land on. Without the second check, moving
+    /// `/tmp/x/.ssh` into `~` would touch a denied `~/.ssh`, and a
+    /// `report_conflicts` dry run would reveal whether it exists.
+    fn transfer_target_denied(&self, sources: &[String], destination: &str) -> bool {
+        if self.guard.is_denied(destination) {
+            return true;
+        }
+        let source_refs: Vec<&str> = sources.iter().map(|s| s.as_str()).collect();
+        crate::operations::mv::planned_destinations(&source_refs, destination)
+            .iter()
+            .any(|dest| self.guard.is_denied(&dest.to_string_lossy()))
+    }
+
     /// Get all tools in MCP format
     pub fn list_tools(&self) -> Value {
         serde_json::json!([
             {
                 "name": "fileio_read_lines",
-                "description": "Read lines from a file with flexible windowing options. Use this to read specific line ranges from a file. Supports two modes: (1) start_line/end_line for range-based reading, or (2) start_line/line_count for count-based reading. Line numbers are 1-based. If no parameters are provided, reads the entire file. Returns an array of lines (strings).",
+                "description": "Read lines from a file with flexible windowing options. Use this to read specific line ranges from a file. Supports two modes: (1) start_line/end_line for range-based reading, or (2) start_line/line_count for count-based reading. Line numbers are 1-based. If no parameters are provided, reads the entire file. Returns an array of lines (strings), or of { line_number, content } objects with with_line_numbers=true. To page through a large file, pass with_cursor=true (with start_line/line_count) and then each returned next_cursor as cursor.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
                         "path": {
                             "type": "string",
-                            "description": "Path to the file to read. Must exist and be readable. Use absolute paths to avoid ambiguity - relative paths are resolved from the current working directory, which may not be the directory you expect. If you need to read a specific file, use an absolute path or verify the working directory first."
+                            "description": "Path to the file to read. Must exist and be readable. Use absolute paths to avoid ambiguity - relative paths are resolved from the current working directory, which may not be the directory you expect. If you need to read a specific file, use an absolute path or verify the working directory first. A not-found error names the absolute path that was tried."
                         },
                         "start_line": {
                             "type": "integer",
@@ -113,6 +170,30 @@ impl ToolRegistry {
                         "start_offset": {
                             "type": "integer",
                             "description": "Starting line offset (0-based index) as alternative to start_line. Less commonly used."
+                        },
+                        "tail": {
+                            "type": "integer",
+                            "description": "Return the last N lines of the file (all of them if it has fewer), without knowing its length; e.g. tail=50 for the end of a log. The file is read backwards from the end, so this is cheap on large files. Cannot be combined with start_line, end_line, line_count, start_offset, or cursor paging."
+                        },
+                        "encoding": {
+                            "type": "string",
+                            "description": "Encoding to decode the file from before splitting it into lines, e.g. 'latin1', 'windows-1252', 'utf-16le', 'shift_jis'. Bytes that are invalid in it are an error. Non-UTF-8 files are read whole (up to 100 MiB) and cannot be combined with tail or cursor paging. Default: 'utf-8'."
+                        },
+                        "with_line_numbers": {
+                            "type": "boolean",
+                            "description": "If true, return objects { line_number, content } instead of plain strings, where line_number is the line's 1-based position in the file (a window starting at line 50 reports 50). Works with every windowing option including tail and reverse; with tail the whole file is scanned to number the lines. Not available with cursor paging. Default: false."
+                        },
+                        "reverse": {
+                            "type": "boolean",
+                            "description": "If true, return the selected lines newest-first: the window (start_line/end_line, line_count, start_offset, or tail) is chosen in file order and then reversed, so tail=20 with reverse=true gives the last 20 lines with the final line first. Not available with cursor paging. Default: false."
+                        },
+                        "with_cursor": {
+                            "type": "boolean",
+                            "description": "If true, return { lines, start_line, next_cursor } instead of a bare array. next_cursor is an opaque token (absent at end of file) marking the position after the last returned line. Works with start_line and line_count. Default: false."
+                        },
+                        "cursor": {
+                            "type": "string",
+                            "description": "A next_cursor from a previous call: resume right after that call's last line without rescanning the file from the top, returning the same object shape as with_cursor. Use with line_count for the page size; cannot be combined with start_line, end_line, or start_offset. Only valid while the file is unchanged."
                         }
                     },
                     "required": ["path"]
@@ -120,7 +201,7 @@ impl ToolRegistry {
             },
             {
                 "name": "fileio_write_file",
-                "description": "Write content to a file. This tool will create the file if it doesn't exist, and create any necessary parent directories automatically. By default, overwrites existing files. Use append mode to add content to the end of an existing file. The write operation is atomic (uses temporary file then rename) to prevent corruption.",
+                "description": "Write content to a file. This tool will create the file if it doesn't exist, and create any necessary parent directories automatically. By default, overwrites existing files; pass if_exists to refuse or skip instead. Use append mode to add content to the end of a file; appending to a missing file creates it (and its parent directories) too. Overwrites are atomic (uses temporary file then rename) to prevent corruption, unless in_place is set. A successful return means other processes see the new content; pass sync=true to also guarantee it is on disk. Returns { path, written, bytes_written, created, backup? }: path is the absolute path after ~ and variable expansion, bytes_written counts what this call wrote (for an append, only the appended bytes), created is true when no file existed before, and written is false only when if_exists='skip' left an existing file alone.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
@@ -135,6 +216,65 @@ impl ToolRegistry {
                         "append": {
                             "type": "boolean",
                             "description": "If true, append content to the end of the file instead of overwriting. Default: false (overwrite)."
+                        },
+                        "prepend": {
+                            "type": "boolean",
+                            "description": "If true, insert content at the start of the file, before its existing content (e.g. a license header), creating the file if missing. Content is inserted exactly as given - include the trailing newline. The file is rewritten atomically; a BOM it starts with stays first. Cannot be combined with append, exclusive, or an if_exists other than 'overwrite'; like append, it is not affected by --no-clobber. Default: false."
+                        },
+                        "line_ending": {
+                            "type": "string",
+                            "enum": ["preserve", "lf", "crlf"],
+                            "description": "Rewrite the content's line breaks before writing: 'lf' (\\n), 'crlf' (\\r\\n), or 'preserve' to write the content exactly as given. \\r\\n, \\n and a lone \\r each count as one break, so mixed input comes out consistent and existing \\r\\n are never doubled. Default: 'preserve'."
+                        },
+                        "encoding": {
+                            "type": "string",
+                            "description": "Encoding to write the content in, e.g. 'utf-8', 'utf-16le', 'utf-16be', 'latin1', 'windows-1252', 'shift_jis'. Fails if the content has characters the encoding cannot represent. Default: 'utf-8'."
+                        },
+                        "bom": {
+                            "type": "boolean",
+                            "description": "If true, start the file with a byte-order mark (UTF-8 and UTF-16 only; when appending, only if the file is new or empty). Default: false."
+                        },
+                        "max_size": {
+                            "type": "integer",
+                            "description": "Append mode only: log rotation threshold in bytes. If appending would grow the file past this size, the file is first renamed to <name>.1 (older rotations shift to .2, .3, ...) and the content starts a fresh file. If not specified, the file grows without limit."
+                        },
+                        "keep_rotations": {
+                            "type": "integer",
+                            "description": "With max_size: how many rotated files (<name>.1 ... <name>.N) to keep; older ones are deleted. 0 discards the old content instead of keeping it. Default: 5."
+                        },
+                        "backup": {
+                            "type": "boolean",
+                            "description": "If true and the file already exists, copy its current content to path + backup_suffix before writing, so the change can be undone; the result then names the backup path. An existing backup is replaced. No backup is made for a new file. Default: false."
+                        },
+                        "backup_suffix": {
+                            "type": "string",
+                            "description": "Suffix appended to path to name the backup; giving it implies backup=true. Must not contain '/'. Default: '.bak'."
+                        },
+                        "mode": {
+                            "type": "string",
+                            "description": "Octal permissions for the written file, e.g. '755' or '0644' (same formats as fileio_set_permissions). Applied as part of the write - before the atomic rename - so the file never appears with other permissions; saves a separate, racy fileio_set_permissions call when creating scripts. Ignored on non-Unix systems. Default: permissions are left as the write produces them."
+                        },
+                        "content_encoding": {
+                            "type": "string",
+                            "enum": ["utf8", "base64"],
+                            "description": "How content is given: 'utf8' text, or 'base64' for binary data (images, archives, ...), which is decoded and written byte-for-byte. line_ending, encoding and bom cannot be combined with 'base64'. Default: 'utf8'."
+                        },
+                        "exclusive": {
+                            "type": "boolean",
+                            "description": "If true, only create the file: fail with an 'already exists' error if anything is at path, checked atomically with the write, so concurrent writers can't clobber each other. Same as if_exists='error'. Cannot be combined with append. Default: false."
+                        },
+                        "if_exists": {
+                            "type": "string",
+                            "enum": ["overwrite", "error", "skip"],
+                            "description": "What to do when the file already exists (ignored when appending or prepending, which extend an existing file by design; an explicit 'error' or 'skip' cannot be combined with prepend): 'overwrite' replaces it, 'error' fails without touching it, 'skip' leaves it unchanged and succeeds with written=false. Default: 'overwrite', unless the server runs with --no-clobber, which makes it 'error'."
+                        },
+                        "in_place": {
+                            "type": "boolean",
+                            "description": "If true, truncate and rewrite the existing file instead of atomically replacing it, so its inode is kept: hard links and processes holding it open (e.g. tail -f) see the new content. Not crash-safe - an interrupted write leaves the file truncated or partial, and readers may briefly see it empty. Default: false (atomic replace)."
+                        },
+                        "sync": {
+                            "type": "boolean",
+                            "description": "If true, fsync the new content before the atomic rename and, on Unix, fsync the parent directory after it: once the call returns, the file survives a crash or power loss, not just a process exit. Use before handing the file to another system. Slower. Default: false."
                         }
                     },
                     "required": ["path", "content"]
@@ -156,6 +296,14 @@ impl ToolRegistry {
                         "mode": {
                             "type": "string",
                             "description": "File mode in octal format. Examples: '755' (executable), '644' (readable), '600' (owner only), '0644' (same as 644). Format: owner-group-other permissions as 3 octal digits."
+                        },
+                        "recursive": {
+                            "type": "boolean",
+                            "description": "If true, also apply to everything under each directory (chmod -R). Symlinks inside the tree are skipped. Returns { dry_run, changes: [{ path, old_mode, new_mode }] } listing each entry whose mode changed. Default: false."
+                        },
+                        "dry_run": {
+                            "type": "boolean",
+                            "description": "If true, change nothing and return the plan { dry_run: true, changes: [{ path, old_mode, new_mode }] } of entries that would change. Preview recursive changes with this first. Default: false."
                         }
                     },
                     "required": ["path", "mode"]
@@ -177,6 +325,14 @@ impl ToolRegistry {
                         "mode": {
                             "type": "string",
                             "description": "File mode in octal format. Examples: '755' (executable), '644' (readable), '600' (owner only). Format: owner-group-other permissions as 3 octal digits."
+                        },
+                        "recursive": {
+                            "type": "boolean",
+                            "description": "If true, also apply to everything under each directory (chmod -R). Symlinks inside the tree are skipped. Returns { dry_run, changes: [{ path, old_mode, new_mode }] } listing each entry whose mode changed. Default: false."
+                        },
+                        "dry_run": {
+                            "type": "boolean",
+                            "description": "If true, change nothing and return the plan { dry_run: true, changes: [{ path, old_mode, new_mode }] } of entries that would change. Preview recursive changes with this first. Default: false."
                         }
                     },
                     "required": ["path", "mode"]
@@ -184,7 +340,7 @@ impl ToolRegistry {
             },
             {
                 "name": "fileio_get_permissions",
-                "description": "Get file or directory permissions (mode) as an octal string. Returns the current permissions in octal format (e.g., '0755', '0644'). Useful for checking current permissions before modifying them or for auditing purposes. Accepts an array of paths to get permissions for multiple files/directories.",
+                "description": "Get file or directory permissions (mode) as an octal string. Returns the current permissions in octal format (e.g., '0755', '0644'). Useful for checking current permissions before modifying them or for auditing purposes. Accepts an array of paths to get permissions for multiple files/directories; returns a map of path to mode, or with entries=true an array of per-path results.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
@@ -194,6 +350,10 @@ impl ToolRegistry {
                                 "type": "string"
                             },
                             "description": "Array of paths to files or directories to query. Returns permissions for all paths. Use absolute paths to avoid ambiguity - relative paths are resolved from the current working directory, which may not be the directory you expect."
+                        },
+                        "entries": {
+                            "type": "boolean",
+                            "description": "If true, return an array of { path, mode, exists, status } in input order instead of a map keyed by path; a missing or unreadable path gets mode null and status 'error: ...' instead of failing the call. Default: false."
                         }
                     },
                     "required": ["path"]
@@ -210,7 +370,11 @@ impl ToolRegistry {
                             "items": {
                                 "type": "string"
                             },
-                            "description": "Array of paths to files to touch. All files will be created or have their timestamps updated. Parent directories will be created if they don't exist. Use absolute paths to avoid ambiguity - relative paths are resolved from the current working directory, which may not be the directory you expect."
+                            "description": "Array of paths to files to touch. All files will be created or have their timestamps updated. Parent directories will be created if they don't exist. Use absolute paths to avoid ambiguity - relative paths are resolved from the current working directory, which may not be the directory you expect. When base is set, these must instead be relative paths inside base."
+                        },
+                        "base": {
+                            "type": "string",
+                            "description": "Optional base directory. When set, every entry in path is resolved relative to it (absolute paths and '..' are rejected) and the whole tree is created, including base itself if missing - e.g. base='/proj', path=['src/main.rs', 'tests/it.rs'] scaffolds a project skeleton in one call. Must be a directory if it already exists."
                         }
                     },
                     "required": ["path"]
@@ -218,7 +382,7 @@ impl ToolRegistry {
             },
             {
                 "name": "fileio_stat",
-                "description": "Get comprehensive file or directory statistics. Returns detailed metadata including: size in bytes, file type (file/directory/symlink), permissions (mode) as octal string, timestamps (modified, accessed, created as Unix epoch seconds), and boolean flags (is_file, is_dir, is_symlink). Returns JSON with all available information about the file system entry. Accepts an array of paths to get statistics for multiple files/directories. If a path does not exist, returns an entry with exists=false and type=not_found (not an error).",
+                "description": "Get comprehensive file or directory statistics. Returns detailed metadata including: size in bytes, file type (file/directory/symlink), permissions (mode) as octal string, timestamps (modified, accessed, created as Unix epoch seconds), on Unix the containing device id (dev) and device-node id (rdev) for detecting mount boundaries and special files, and boolean flags (is_file, is_dir, is_symlink). Returns JSON with all available information about the file system entry. Accepts an array of paths to get statistics for multiple files/directories. If a path does not exist, returns an entry with exists=false and type=not_found (not an error). Every entry carries status: 'ok', or 'error: <message>' with type=error when that path's metadata can't be read (e.g. permission denied); one bad path never fails the batch.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
@@ -228,6 +392,20 @@ impl ToolRegistry {
                                 "type": "string"
                             },
                             "description": "Array of paths to files or directories to query. Returns statistics for all paths. Must exist. Use absolute paths to avoid ambiguity - relative paths are resolved from the current working directory, which may not be the directory you expect."
+                        },
+                        "max_symlink_depth": {
+                            "type": "integer",
+                            "minimum": 0,
+                            "description": "If set, resolve symlink chains one link at a time and fail with 'Too many levels of symbolic links' after following more than this many, instead of relying on the OS limit. Default: unset (OS resolution)."
+                        },
+                        "follow_symlinks": {
+                            "type": "boolean",
+                            "description": "If false, report symlinks themselves (type 'symlink', the link's own size and mode) instead of what they point to. Applies to every path in the call. Default: true."
+                        },
+                        "hash": {
+                            "type": "string",
+                            "enum": ["none", "sha256", "md5"],
+                            "description": "Also include a hex content digest as hash for each regular file (streamed, so large files cost time, not memory), giving metadata and a change-detection fingerprint in one call. Directories and special files get no hash. md5 is for matching existing manifests only. Default: none."
                         }
                     },
                     "required": ["path"]
@@ -271,6 +449,14 @@ impl ToolRegistry {
                         "include_hidden": {
                             "type": "boolean",
                             "description": "If true, include hidden files and directories (those starting with '.'). Default: false (exclude hidden files)."
+                        },
+                        "with_permissions": {
+                            "type": "boolean",
+                            "description": "If true, include each entry's permission bits (mode, octal string) and on Unix its owner (uid, gid), like 'ls -l'. Default: false."
+                        },
+                        "resolve_symlinks": {
+                            "type": "boolean",
+                            "description": "If true, every symlink is listed with type 'symlink' (not followed for its type or size) plus target (the raw link text) and broken (true if the target does not exist). Without it, a link to a file or directory is listed as that type and only dangling links show as 'symlink'. Default: false."
                         }
                     },
                     "required": ["path"]
@@ -298,6 +484,32 @@ impl ToolRegistry {
                             "type": "string",
                             "description": "Filter results by entry type. Options: 'file' (regular files only), 'dir' or 'directory' (directories only), 'symlink' (symbolic links only). If not specified, returns all types.",
                             "enum": ["file", "dir", "directory", "symlink"]
+                        },
+                        "relative": {
+                            "type": "boolean",
+                            "description": "If true, return paths relative to root (handy for feeding into other tools). Default: false (paths as walked from root)."
+                        },
+                        "with_metadata": {
+                            "type": "boolean",
+                            "description": "If true, return objects { path, type, size } instead of bare paths. size is in bytes for files and null for symlinks; for directories it is null unless compute_dir_size is set. Default: false."
+                        },
+                        "compute_dir_size": {
+                            "type": "boolean",
+                            "description": "If true, report each matched directory's size as the recursive total of the files under it (symlinks not followed), e.g. to rank directories by footprint. Expensive on large trees. Implies with_metadata. Default: false."
+                        },
+                        "skip_dirs": {
+                            "type": "array",
+                            "items": { "type": "string" },
+                            "description": "Directory names to skip entirely, e.g. ['target', 'node_modules', '.git']. Any directory below the search root with one of these names is not descended into (and is not itself returned), which is simpler and faster than an exclude glob for build output. Default: none."
+                        },
+                        "limit": {
+                            "type": "integer",
+                            "description": "Maximum number of matches to return. When set, the result is an object { matches, total_matched, truncated } where total_matched counts every match and truncated is true if some were cut. Without sort, the tree is walked in name order so the first matches are deterministic, and matches past the limit are only counted. If not specified, returns all matches."
+                        },
+                        "sort": {
+                            "type": "string",
+                            "enum": ["path", "smallest", "largest", "newest", "oldest"],
+                            "description": "Order matches by path, size, or modification time before limit is applied, e.g. sort='largest' with limit=10 for the ten biggest files. Entries without a size (symlinks, directories without compute_dir_size) sort last. Requires visiting every match. Default: walk order."
                         }
                     },
                     "required": ["pattern"]
@@ -345,13 +557,51 @@ impl ToolRegistry {
                             "type": "string",
                             "description": "Exclude files matching this glob pattern. Examples: '*.log', 'node_modules/*', 'target/*'. Applied after file_glob filtering."
                         },
+                        "extensions": {
+                            "type": "array",
+                            "items": { "type": "string" },
+                            "description": "Only search files with one of these extensions, e.g. ['rs', 'toml'] (leading dot optional, case-insensitive). A simpler alternative to a brace glob in file_glob; combines with file_glob and exclude_glob, and subdirectories are still searched."
+                        },
+                        "skip_dirs": {
+                            "type": "array",
+                            "items": { "type": "string" },
+                            "description": "Directory names to skip entirely, e.g. ['target', 'node_modules', '.git']. Any directory below the search root with one of these names is not descended into (so none of its files are searched), which is simpler and faster than an exclude glob for build output. Default: none."
+                        },
                         "whole_word": {
                             "type": "boolean",
                             "description": "If true, match only complete words (word boundaries). Example: 'test' matches 'test' but not 'testing'. Default: false."
                         },
                         "multiline": {
                             "type": "boolean",
-                            "description": "If true, allow regex patterns to match across multiple lines. Only applies when use_regex is true. Default: false."
+                            "description": "If true, allow regex patterns to match across multiple lines: the pattern runs over the whole file, ^/$ match at line boundaries, and a match spanning lines reports end_line_number (column_end is then relative to that line). Only applies when use_regex is true. Default: false."
+                        },
+                        "context_lines": {
+                            "type": "integer",
+                            "description": "Number of lines of context to include before and after each match (like grep -C). Default: 0 (no context)."
+                        },
+                        "structured_context": {
+                            "type": "boolean",
+                            "description": "If true, context_before/context_after are arrays of {line_number, content} objects instead of newline-joined strings. Only applies when context_lines > 0. Default: false."
+                        },
+                        "files_only": {
+                            "type": "boolean",
+                            "description": "If true, return only the distinct list of file paths containing at least one match (like grep -l) instead of match objects. Each file stops being scanned at its first match. Default: false."
+                        },
+                        "char_columns": {
+                            "type": "boolean",
+                            "description": "If true, also report char_column_start/char_column_end as character indices (column_start/column_end are UTF-8 byte offsets, which differ on lines containing multi-byte characters). Default: false."
+                        },
+                        "max_total": {
+                            "type": "integer",
+                            "description": "Maximum number of matches to return across all files; the search stops once it is reached. Works alongside the per-file max_count. When set, the result is an object { matches, truncated } instead of a bare array, where truncated is true if more matches existed. If not specified, returns all matches."
+                        },
+                        "line_text": {
+                            "type": "boolean",
+                            "description": "If true, each match includes line_text: the complete line the match is on (all spanned lines, newline-joined, for multiline matches), so the surrounding code can be shown without re-reading the file. Off by default to keep results small. Default: false."
+                        },
+                        "group_by_file": {
+                            "type": "boolean",
+                            "description": "If true, return one { file_path, matches } object per file instead of a flat list, with each file's matches ordered by line then column (and without the repeated file_path). With max_total, the groups go in the 'matches' field of the wrapper. Cannot be combined with files_only. Default: false (flat list)."
                         }
                     },
                     "required": ["pattern", "path"]
@@ -359,7 +609,7 @@ impl ToolRegistry {
             },
             {
                 "name": "fileio_edit_file",
-                "description": "Edit a text file using deterministic, structured operations (LLM-friendly). Supports anchor-based edits (insert_before/insert_after/replace/delete with literal or regex search) and line-based edits (insert_at_line/replace_lines/delete_lines). Prefer this over patch-style diffs. By default, anchor-based edits require a match and will error if not found.",
+                "description": "Edit a text file using deterministic, structured operations (LLM-friendly). Supports anchor-based edits (insert_before/insert_after/replace/delete with literal or regex search) and line-based edits (insert_at_line/replace_lines/delete_lines; insert_at_line keeps the inserted text on its own line). Prefer this over patch-style diffs. By default, anchor-based edits require a match and will error if not found.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
@@ -408,6 +658,19 @@ impl ToolRegistry {
                         "return_content": {
                             "type": "boolean",
                             "description": "If true, returns the updated file content in the tool result. Default: false (unless dry_run=true)."
+                        },
+                        "return_spans": {
+                            "type": "boolean",
+                            "description": "If true, returns 'spans': one {edit, op, start, end} per applied edit, where edit is its index in 'edits' and start..end is the UTF-8 byte range its new text occupies in the final content (empty for deletions). Works with dry_run. Default: false."
+                        },
+                        "final_newline": {
+                            "type": "string",
+                            "enum": ["keep", "ensure", "strip"],
+                            "description": "Final line ending after the edits: 'keep' ends the file with a newline exactly when the original did, even if an edit replaced the last line; 'ensure' adds one if missing; 'strip' removes it. Default: 'keep'."
+                        },
+                        "encoding": {
+                            "type": "string",
+                            "description": "Encoding the file is read in and written back in, e.g. 'utf-16le', 'utf-16be', 'latin1', 'windows-1252', 'shift_jis'. Search and replacement text are plain strings either way. A UTF-16 byte-order mark is preserved. Fails without changing anything if the file isn't valid in this encoding or an edit introduces a character it can't represent. Default: 'utf-8'."
                         }
                     },
                     "required": ["path", "edits"]
@@ -433,6 +696,36 @@ impl ToolRegistry {
                         "recursive": {
                             "type": "boolean",
                             "description": "If true, copy directories recursively (required for copying directories). For files, this parameter is ignored. Default: false."
+                        },
+                        "link": {
+                            "type": "boolean",
+                            "description": "If true, create hard links at the destination instead of copying file contents (like cp -l): instant and uses no extra space, but source and destination must be on the same filesystem. Directories are recreated and their files linked. Default: false."
+                        },
+                        "reflink": {
+                            "type": "string",
+                            "enum": ["never", "auto", "always"],
+                            "description": "Copy-on-write cloning on filesystems that support it (Btrfs, XFS, APFS): near-instant and space-sharing for large files. 'auto' clones where possible and falls back to a normal copy; 'always' fails instead of falling back. When set to 'auto' or 'always', each result includes reflinked: true/false. Cannot be combined with link. Default: 'never'."
+                        },
+                        "dry_run": {
+                            "type": "boolean",
+                            "description": "If true, plan the copy without writing anything; each existing source is reported with status 'would_copy'. Default: false."
+                        },
+                        "report_conflicts": {
+                            "type": "boolean",
+                            "description": "If true, return {results, conflicts} where conflicts lists every destination that already exists or that more than one source maps to, with the sources that would write it. Combine with dry_run to check a glob fan-in before overwriting anything. Default: false."
+                        },
+                        "verify": {
+                            "type": "boolean",
+                            "description": "If true, re-read each copied file and its source after copying and compare SHA-256 digests, failing that source's result on a mismatch (catches silent corruption on flaky or network storage). For a single-file source the result includes the verified sha256. Costs a full read of both sides. Default: false."
+                        },
+                        "flatten": {
+                            "type": "boolean",
+                            "description": "If true, copy every file from the sources (directories need recursive=true) directly into the destination directory, dropping subdirectory structure, and return {results, collisions}. A file whose name is already taken, by an earlier file or by an existing destination entry, is a collision resolved per on_collision; nothing is ever overwritten. Cannot be combined with report_conflicts. Default: false."
+                        },
+                        "on_collision": {
+                            "type": "string",
+                            "enum": ["error", "skip", "suffix"],
+                            "description": "With flatten: 'error' refuses the whole copy before anything is written (dry_run lists the collisions instead), 'skip' keeps the first file and skips the rest, 'suffix' renames later files to name-1.ext, name-2.ext, .... Each collision is reported with its source, intended path, resolution, and (for renames) the destination used. Default: 'error'."
                         }
                     },
                     "required": ["source", "destination"]
@@ -454,6 +747,14 @@ impl ToolRegistry {
                         "destination": {
                             "type": "string",
                             "description": "Destination path. For glob patterns or arrays: must be a directory. For single files: can be a file path (rename) or directory path (move into directory). Parent directories will be created if needed. Use absolute paths to avoid ambiguity - relative paths are resolved from the current working directory, which may not be the directory you expect."
+                        },
+                        "dry_run": {
+                            "type": "boolean",
+                            "description": "If true, plan the move without touching anything; each existing source is reported with status 'would_move'. Default: false."
+                        },
+                        "report_conflicts": {
+                            "type": "boolean",
+                            "description": "If true, return {results, conflicts} where conflicts lists every destination that already exists or that more than one source maps to, with the sources that would write it. Combine with dry_run to check a glob fan-in before overwriting anything. Default: false."
                         }
                     },
                     "required": ["source", "destination"]
@@ -461,7 +762,7 @@ impl ToolRegistry {
             },
             {
                 "name": "fileio_remove",
-                "description": "Remove files or directories (rm equivalent). Permanently deletes the specified path. Supports glob patterns (e.g., '*.tmp', 'file?.log', 'dir/*.bak') to remove multiple files matching the pattern, or an array of paths. For directories, recursive=true is required to remove non-empty directories. Use force=true to suppress errors if the file doesn't exist (idempotent removal). Warning: This operation cannot be undone.",
+                "description": "Remove files or directories (rm equivalent). Permanently deletes the specified path. Supports glob patterns (e.g., '*.tmp', 'file?.log', 'dir/*.bak') to remove multiple files matching the pattern, or an array of paths. For directories, recursive=true is required to remove non-empty directories. Use force=true to suppress errors if the file doesn't exist (idempotent removal). Warning: This operation cannot be undone; use dry_run=true to preview what a call would delete.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
@@ -479,6 +780,14 @@ impl ToolRegistry {
                         "force": {
                             "type": "boolean",
                             "description": "If true, don't return an error if the file doesn't exist or no files match the pattern (idempotent). Default: false (error if missing/no matches)."
+                        },
+                        "report_size": {
+                            "type": "boolean",
+                            "description": "If true, sum the sizes of everything removed (recursively for directories) and return { results: [...], freed_bytes } instead of the bare per-path array. Costs an extra stat per file. Default: false."
+                        },
+                        "dry_run": {
+                            "type": "boolean",
+                            "description": "If true, delete nothing: expand the globs and report per path what would happen - status 'would_remove', 'skipped' (missing, with force=true; exists is false), or the 'error: ...' the real call would hit (e.g. a non-empty directory without recursive). With report_size, freed_bytes is what would be reclaimed. Use it to preview a glob before removing. Default: false."
                         }
                     },
                     "required": ["path"]
@@ -578,6 +887,11 @@ impl ToolRegistry {
                         "path": {
                             "type": "string",
                             "description": "Path to canonicalize. Can be relative or absolute, and can contain symlinks. Must exist. Use absolute paths to avoid ambiguity - relative paths are resolved from the current working directory, which may not be the directory you expect. If you need to canonicalize a specific file, use an absolute path or verify the working directory first."
+                        },
+                        "max_symlink_depth": {
+                            "type": "integer",
+                            "minimum": 0,
+                            "description": "If set, resolve symlinks one link at a time and fail with 'Too many levels of symbolic links' after following more than this many, instead of relying on the OS limit. Default: unset (OS resolution)."
                         }
                     },
                     "required": ["path"]
@@ -636,6 +950,14 @@ impl ToolRegistry {
                         "group": {
                             "type": "string",
                             "description": "Group ID (numeric GID as string, e.g., '1000'). Currently only numeric GIDs are supported. If not provided, group ownership is unchanged."
+                        },
+                        "recursive": {
+                            "type": "boolean",
+                            "description": "If true, also apply to everything under each directory (chown -R). Symlinks inside the tree are skipped. Returns { dry_run, changes: [{ path, old_uid, old_gid, new_uid, new_gid }] } listing each entry whose ownership changed. Default: false."
+                        },
+                        "dry_run": {
+                            "type": "boolean",
+                            "description": "If true, change nothing and return the plan { dry_run: true, changes: [{ path, old_uid, old_gid, new_uid, new_gid }] } of entries that would change. Preview recursive changes with this first. Default: false."
                         }
                     },
                     "required": ["path"]
@@ -651,7 +973,7 @@ impl ToolRegistry {
             },
             {
                 "name": "fileio_count_lines",
-                "description": "Count the number of lines in files. Returns a result object per path with { path, status, lines, exists }. Useful for getting line counts in code files, logs, or any text file. Empty files return 0 lines; files with content but no trailing newline count as 1 line. Accepts an array of paths to count lines in multiple files.",
+                "description": "Count the number of lines in files. Returns a result object per path with { path, status, lines, exists }. Useful for getting line counts in code files, logs, or any text file. Empty files return 0 lines; files with content but no trailing newline count as 1 line. Accepts an array of paths to count lines in multiple files. With pattern set, counts only matching lines (like grep -c; invert for grep -vc). With with_size, results also carry bytes and chars.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
@@ -661,6 +983,26 @@ impl ToolRegistry {
                                 "type": "string"
                             },
                             "description": "Array of paths to files to count lines in. Returns line count results for all files."
+                        },
+                        "pattern": {
+                            "type": "string",
+                            "description": "If set, count only lines matching this pattern (like grep -c). Literal unless use_regex is true."
+                        },
+                        "use_regex": {
+                            "type": "boolean",
+                            "description": "If true, treat pattern as a regular expression. Default: false (literal matching)."
+                        },
+                        "case_sensitive": {
+                            "type": "boolean",
+                            "description": "If true, pattern matching is case-sensitive. Default: true."
+                        },
+                        "invert": {
+                            "type": "boolean",
+                            "description": "If true, count lines that do NOT match pattern (like grep -vc). Default: false."
+                        },
+                        "with_size": {
+                            "type": "boolean",
+                            "description": "If true, each result also includes bytes and chars (UTF-8 characters) for the whole file, from the same read as the line count. Useful for judging how much of a file fits in a context window. Default: false."
                         }
                     },
                     "required": ["path"]
@@ -668,7 +1010,7 @@ impl ToolRegistry {
             },
             {
                 "name": "fileio_count_words",
-                "description": "Count the number of words in files. Returns a result object per path with { path, status, words, exists }. Useful for text analysis, document statistics, or content metrics. Words are separated by any whitespace (spaces, tabs, newlines). Accepts an array of paths to count words in multiple files.",
+                "description": "Count the number of words in files. Returns a result object per path with { path, status, words, exists }. Useful for text analysis, document statistics, or content metrics. Words are separated by any whitespace (spaces, tabs, newlines) unless unicode is set. Accepts an array of paths to count words in multiple files.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
@@ -678,357 +1020,2543 @@ impl ToolRegistry {
                                 "type": "string"
                             },
                             "description": "Array of paths to files to count words in. Returns word counts for all files. Must exist and be files (not directories). Use absolute paths to avoid ambiguity - relative paths are resolved from the current working directory, which may not be the directory you expect."
+                        },
+                        "unicode": {
+                            "type": "boolean",
+                            "description": "If true, use Unicode word segmentation (UAX #29) instead of a whitespace split: punctuation is not counted as part of words ('don't stop—now' is 3 words, not 2), and text in scripts without spaces is segmented (each CJK ideograph is a word, where a whitespace split counts a whole run as one). Better for non-Latin text. Default: false."
+                        },
+                        "words_per_line": {
+                            "type": "boolean",
+                            "description": "If true, each result also includes words_per_line, the word count of every line in order. Default: false."
+                        },
+                        "top_words": {
+                            "type": "integer",
+                            "description": "If set, each result also includes top_words: the N most frequent words as [{ word, count }], most frequent first, lowercased, ties broken alphabetically. Handy for surfacing a document's key terms."
+                        },
+                        "strip_punctuation": {
+                            "type": "boolean",
+                            "description": "With top_words, trim leading and trailing punctuation from words before counting, so 'end.' and 'end' count together. Unicode segmentation already excludes punctuation. Default: false."
                         }
                     },
                     "required": ["path"]
                 }
-            }
-        ])
-    }
-
-    /// Helper to parse path parameter (array of strings)
-    fn parse_paths(value: &Value) -> Result<Vec<String>> {
-        let arr = value.as_array().ok_or_else(|| {
-            crate::error::McpError::InvalidToolParameters(
-                "Path must be an array of strings".to_string(),
-            )
-        })?;
-        let mut paths = Vec::new();
-        for item in arr {
-            if let Some(s) = item.as_str() {
-                paths.push(s.to_string());
-            } else {
-                return Err(crate::error::McpError::InvalidToolParameters(
-                    "Path array must contain only strings".to_string(),
-                )
-                .into());
-            }
-        }
-        Ok(paths)
-    }
-
-    /// Parse an optional non-negative integer argument.
-    ///
-    /// Absent or explicitly-null values yield `None`. A present value is coerced
-    /// from any lossless encoding (integer, whole-valued float, decimal string)
-    /// via [`crate::coerce::value_to_u64`]; a genuinely invalid value (negative,
-    /// fractional, out of range, non-numeric) is an error rather than a silent
-    /// `None`, so a caller's constraint is never dropped without notice.
-    fn parse_optional_u64(args: &serde_json::Map<String, Value>, key: &str) -> Result<Option<u64>> {
-        match args.get(key) {
-            None | Some(Value::Null) => Ok(None),
-            Some(v) => crate::coerce::value_to_u64(v).map(Some).map_err(|reason| {
-                crate::error::McpError::InvalidToolParameters(format!("{key} {reason}")).into()
-            }),
-        }
-    }
-
-    /// Parse an optional boolean argument, coercing lossless encodings
-    /// (`"true"`/`"false"`/`"1"`/`"0"`, `1`/`0`) via
-    /// [`crate::coerce::value_to_bool`]. Absent or null yields `None`; a
-    /// present-but-uncoercible value is an error instead of silently falling
-    /// back to the default. Call `.unwrap_or(default)` on the result to keep a
-    /// tool's default-when-omitted behaviour.
-    fn parse_optional_bool(
-        args: &serde_json::Map<String, Value>,
-        key: &str,
-    ) -> Result<Option<bool>> {
-        match args.get(key) {
-            None | Some(Value::Null) => Ok(None),
-            Some(v) => crate::coerce::value_to_bool(v).map(Some).map_err(|reason| {
-                crate::error::McpError::InvalidToolParameters(format!("{key} {reason}")).into()
-            }),
-        }
-    }
-
-    /// Execute a tool by name
-    pub async fn execute_tool(&self, name: &str, arguments: &Value) -> Result<Value> {
-        let args = arguments.as_object().ok_or_else(|| {
-            crate::error::McpError::InvalidToolParameters("Arguments must be an object".to_string())
-        })?;
-
-        match name {
-            "fileio_read_lines" => {
-                let path = args.get("path").and_then(|v| v.as_str()).ok_or_else(|| {
-                    crate::error::McpError::InvalidToolParameters(
-                        "Missing required parameter: path".to_string(),
-                    )
-                })?;
-                if self.guard.is_denied(path) {
-                    return Self::not_found_error(path);
-                }
-                let start_line = Self::parse_optional_u64(args, "start_line")?;
-                let end_line = Self::parse_optional_u64(args, "end_line")?;
-                let line_count = Self::parse_optional_u64(args, "line_count")?;
-                let start_offset = Self::parse_optional_u64(args, "start_offset")?;
-
-                let lines = crate::operations::read_lines::read_lines(
-                    path,
-                    start_line,
-                    end_line,
-                    line_count,
-                    start_offset,
+            },
+            {
+                "name": "fileio_strip_trailing_whitespace",
+                "description": "Remove trailing spaces and tabs from every line of a text file (a common pre-commit cleanup). Preserves each line's ending (LF or CRLF) and rewrites the file atomically, only if something changed. Returns { path, lines_changed, changed }.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": {
+                            "type": "string",
+                            "description": "Path to the file to clean. Use absolute paths to avoid ambiguity - relative paths are resolved from the current working directory."
+                        },
+                        "ensure_final_newline": {
+                            "type": "boolean",
+                            "description": "If true, collapse trailing blank lines and make the file end with exactly one line ending (in the file's own style). Default: false."
+                        }
+                    },
+                    "required": ["path"]
+                }
+            },
+            {
+                "name": "fileio_convert_tabs",
+                "description": "Convert indentation between tabs and spaces, like the expand/unexpand utilities. mode='expand' turns tabs into spaces up to the next tab stop; mode='unexpand' turns runs of spaces that reach a tab stop into tabs. By default only leading whitespace is converted, so alignment inside lines is preserved. Rewrites the file atomically and returns { path, lines_changed, changed }.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": {
+                            "type": "string",
+                            "description": "Path to the file to convert. Use absolute paths to avoid ambiguity - relative paths are resolved from the current working directory."
+                        },
+                        "mode": {
+                            "type": "string",
+                            "enum": ["expand", "unexpand"],
+                            "description": "'expand' (tabs to spaces) or 'unexpand' (spaces to tabs)."
+                        },
+                        "tab_width": {
+                            "type": "integer",
+                            "description": "Columns per tab stop. Default: 8."
+                        },
+                        "leading_only": {
+                            "type": "boolean",
+                            "description": "If true, only convert each line's leading whitespace. If false, convert whitespace anywhere in the line. Default: true."
+                        }
+                    },
+                    "required": ["path", "mode"]
+                }
+            },
+            {
+                "name": "fileio_replace_between_markers",
+                "description": "Replace the content between two marker lines (e.g. '# BEGIN GENERATED' / '# END GENERATED') with new text, leaving the markers and everything outside them untouched. Markers match whole lines, ignoring surrounding whitespace. Errors if only one marker is found. With create_if_missing, appends the markers and block when neither is present. Rewrites the file atomically and returns { path, inserted, changed }.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": {
+                            "type": "string",
+                            "description": "Path to the file to update. Use absolute paths to avoid ambiguity - relative paths are resolved from the current working directory."
+                        },
+                        "begin_marker": {
+                            "type": "string",
+                            "description": "Text of the line that opens the managed block."
+                        },
+                        "end_marker": {
+                            "type": "string",
+                            "description": "Text of the line that closes the managed block."
+                        },
+                        "content": {
+                            "type": "string",
+                            "description": "New content for the block. A trailing newline is added if missing; empty content empties the block."
+                        },
+                        "create_if_missing": {
+                            "type": "boolean",
+                            "description": "If true and neither marker exists, append the markers and content to the end of the file. Default: false."
+                        }
+                    },
+                    "required": ["path", "begin_marker", "end_marker", "content"]
+                }
+            },
+            {
+                "name": "fileio_rename_pattern",
+                "description": "Rename entries directly inside a directory by applying a regex substitution to their names, like the rename/mmv utilities. The first match in each name is replaced; the replacement may reference capture groups as $1 or ${name}. The whole batch is planned first: if any target already exists or two names would map to the same target, nothing is renamed; if a rename fails partway, the ones already done are undone. Use dry_run to preview. Returns an array of { from, to, status } where status is 'renamed', 'would_rename', or 'collision'.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": {
+                            "type": "string",
+                            "description": "Directory whose entries to rename. Not recursive. Use absolute paths to avoid ambiguity - relative paths are resolved from the current working directory."
+                        },
+                        "pattern": {
+                            "type": "string",
+                            "description": "Regular expression matched against each entry name (e.g. '^(.*)\\.txt$')."
+                        },
+                        "replacement": {
+                            "type": "string",
+                            "description": "Replacement text; $1, ${name} refer to capture groups (e.g. '$1.md')."
+                        },
+                        "dry_run": {
+                            "type": "boolean",
+                            "description": "If true, return the planned renames (with collisions marked) without renaming anything. Default: false."
+                        }
+                    },
+                    "required": ["path", "pattern", "replacement"]
+                }
+            },
+            {
+                "name": "fileio_dir_checksum",
+                "description": "Compute a stable aggregate SHA-256 of a directory tree from each file's relative path and content, so you can cheaply tell whether a tree changed between runs. Independent of walk order, timestamps, and where the tree is located; empty directories do not count. Hidden and gitignored files are included. Returns { path, algorithm, checksum, file_count }.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": {
+                            "type": "string",
+                            "description": "Directory to checksum. Use absolute paths to avoid ambiguity - relative paths are resolved from the current working directory."
+                        },
+                        "exclude_glob": {
+                            "type": "string",
+                            "description": "Glob matched against paths relative to path; matching files are skipped and matching directories pruned (e.g. '*.log', 'target')."
+                        }
+                    },
+                    "required": ["path"]
+                }
+            },
+            {
+                "name": "fileio_line_ending_style",
+                "description": "Detect a file's line-ending style before editing or converting it. Scans the file (streaming, so large files are fine) and returns { path, style, lf, crlf, cr } where style is 'lf', 'crlf', 'cr', 'mixed' (more than one kind present), or 'none' (no line breaks), and lf/crlf/cr are the counts of each terminator.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": {
+                            "type": "string",
+                            "description": "Path to the file to inspect. Use absolute paths to avoid ambiguity - relative paths are resolved from the current working directory."
+                        }
+                    },
+                    "required": ["path"]
+                }
+            },
+            {
+                "name": "fileio_prepend",
+                "description": "Insert content at the start of a file, creating the file if it doesn't exist. Suited to adding license headers or shebang lines. If the file has content and the inserted text doesn't end with a newline, one is added (CRLF if the file uses CRLF) so the text doesn't merge into the existing first line. The file is rewritten atomically. Returns { path, created }.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": {
+                            "type": "string",
+                            "description": "Path to the file. Use absolute paths to avoid ambiguity - relative paths are resolved from the current working directory."
+                        },
+                        "content": {
+                            "type": "string",
+                            "description": "Text to insert before the existing content."
+                        }
+                    },
+                    "required": ["path", "content"]
+                }
+            },
+            {
+                "name": "fileio_expand_glob",
+                "description": "Preview what a glob pattern matches before running a destructive fileio_remove / fileio_move / fileio_copy with it. Returns a sorted JSON array of absolute paths. By default wildcards are only allowed in the last path component, exactly as those tools expand their sources; with recursive=true wildcards may appear anywhere and '**' matches across directories (e.g. 'src/**/*.rs'). A pattern that matches nothing returns an empty array rather than an error (unlike the operations themselves, which reject a glob with no matches).",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "pattern": {
+                            "type": "string",
+                            "description": "Glob pattern to expand (e.g. '/tmp/build/*.o'). Use absolute paths to avoid ambiguity - relative patterns are resolved from the current working directory."
+                        },
+                        "recursive": {
+                            "type": "boolean",
+                            "description": "If true, allow wildcards in any component and '**' to match any number of directories. Default: false."
+                        }
+                    },
+                    "required": ["pattern"]
+                }
+            },
+            {
+                "name": "fileio_watch_dir",
+                "description": "Watch a directory tree for changes and return the create/modify/remove/rename events seen during a time window, as a JSON array of { path, kind } oldest first. Bursts on the same path (e.g. an editor save that fires several writes) are debounced into one event: within the debounce window a create absorbs following modifies and a remove wins. Events are collected and returned when the window ends, not streamed. Useful for build tooling and live-reload checks: start the watch, then inspect what changed.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": {
+                            "type": "string",
+                            "description": "Directory to watch. Use absolute paths to avoid ambiguity - relative paths are resolved from the current working directory."
+                        },
+                        "recursive": {
+                            "type": "boolean",
+                            "description": "If true, watch subdirectories too. Default: true."
+                        },
+                        "file_glob": {
+                            "type": "string",
+                            "description": "Only report paths matching this glob, relative to the watched directory (e.g. '*.rs', 'src/**')."
+                        },
+                        "exclude_glob": {
+                            "type": "string",
+                            "description": "Drop paths matching this glob, relative to the watched directory (e.g. 'target/**', '*.swp')."
+                        },
+                        "debounce_ms": {
+                            "type": "integer",
+                            "minimum": 0,
+                            "description": "Events on the same path closer together than this are merged. Default: 100."
+                        },
+                        "duration_ms": {
+                            "type": "integer",
+                            "minimum": 1,
+                            "maximum": 60000,
+                            "description": "How long to watch before returning. Default: 5000; maximum: 60000."
+                        }
+                    },
+                    "required": ["path"]
+                }
+            },
+            {
+                "name": "fileio_content_equals",
+                "description": "Check whether a file's content equals a given string, e.g. to verify that a write produced exactly the intended content without reading the file back. Returns { path, equal, first_diff_line } where first_diff_line (1-based) is present only on a mismatch. The file is compared line by line as it is read and stops at the first difference, so large files are fine.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": {
+                            "type": "string",
+                            "description": "Path to the file to check. Use absolute paths to avoid ambiguity - relative paths are resolved from the current working directory."
+                        },
+                        "content": {
+                            "type": "string",
+                            "description": "The expected content."
+                        },
+                        "ignore_trailing_whitespace": {
+                            "type": "boolean",
+                            "description": "If true, ignore whitespace at the end of each line and blank lines at the end (so a missing or extra final newline is not a difference). Default: false."
+                        },
+                        "ignore_line_endings": {
+                            "type": "boolean",
+                            "description": "If true, treat CRLF and LF line endings as equal. Default: false."
+                        }
+                    },
+                    "required": ["path", "content"]
+                }
+            },
+            {
+                "name": "fileio_ensure_dir",
+                "description": "Make sure a directory exists, optionally with a specific mode, in one idempotent call (mkdir -p plus chmod). Creates it and any parents if missing, applying mode exactly (independent of umask). If it already exists with a different mode, chmods it when fix_existing_mode is true. Returns { path, status, mode } where status is 'created', 'chmodded', 'unchanged', or 'mode_differs' (exists with another mode and fix_existing_mode is false), and mode (only when requested) is the directory's resulting mode. Fails if the path exists and is not a directory.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": {
+                            "type": "string",
+                            "description": "Directory path. Use absolute paths to avoid ambiguity - relative paths are resolved from the current working directory."
+                        },
+                        "mode": {
+                            "type": "string",
+                            "description": "Permission bits as octal, e.g. '0700' or '755'. If omitted, a created directory gets the default mode and an existing one is left alone."
+                        },
+                        "fix_existing_mode": {
+                            "type": "boolean",
+                            "description": "If true, chmod an existing directory whose mode differs from mode. Default: true."
+                        }
+                    },
+                    "required": ["path"]
+                }
+            },
+            {
+                "name": "fileio_validate",
+                "description": "Check whether a JSON, YAML, or TOML file is syntactically valid without reading it back yourself. Returns { valid, format, error? } where error (only when invalid) is { message, line?, column? } with 1-based position of the parse failure. A parse failure is a normal result, not a tool error. YAML files may contain several '---'-separated documents; each must parse.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": {
+                            "type": "string",
+                            "description": "Path to the file to validate. Use absolute paths to avoid ambiguity - relative paths are resolved from the current working directory."
+                        },
+                        "format": {
+                            "type": "string",
+                            "enum": ["json", "yaml", "toml"],
+                            "description": "Format to parse as. If omitted, inferred from the extension (.json, .yaml/.yml, .toml); other extensions require it."
+                        }
+                    },
+                    "required": ["path"]
+                }
+            },
+            {
+                "name": "fileio_format_json",
+                "description": "Reformat a JSON file in place: parse it and write it back pretty-printed (with a configurable indent and a final newline) or minified. Object key order is preserved. Invalid JSON is reported as an error and the file is left untouched. The rewrite is atomic and skipped when the file is already in that format. Returns { path, changed, bytes }.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": {
+                            "type": "string",
+                            "description": "Path to the JSON file. Use absolute paths to avoid ambiguity - relative paths are resolved from the current working directory."
+                        },
+                        "minify": {
+                            "type": "boolean",
+                            "description": "If true, remove all insignificant whitespace instead of pretty-printing. Default: false."
+                        },
+                        "indent": {
+                            "type": "integer",
+                            "description": "Spaces per nesting level when pretty-printing (0-16). Ignored with minify. Default: 2."
+                        }
+                    },
+                    "required": ["path"]
+                }
+            },
+            {
+                "name": "fileio_magic",
+                "description": "Quick 'what is this file' check from its leading signature (magic number): returns { path, hex, bytes_read, format? } with the first count bytes as lowercase hex and, when a known signature matches, a human label such as 'PNG image', 'gzip compressed data', 'ELF executable', or 'SQLite database'. Lighter than full MIME detection; files shorter than count return what they have.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": {
+                            "type": "string",
+                            "description": "Path to the file to inspect. Use absolute paths to avoid ambiguity - relative paths are resolved from the current working directory."
+                        },
+                        "count": {
+                            "type": "integer",
+                            "description": "Number of leading bytes to return as hex (1-4096). Detection always examines enough of the file regardless. Default: 16."
+                        }
+                    },
+                    "required": ["path"]
+                }
+            },
+            {
+                "name": "fileio_transaction",
+                "description": "Apply an ordered list of file operations all-or-nothing. Each step is an object tagged by op: {op:'write', path, content, append?}, {op:'mkdir', path}, {op:'move', source, destination}, {op:'link', target, link_path} (symbolic), {op:'remove', path, recursive?}. Steps run in order; if one fails, the earlier ones are undone in reverse and the result is { committed:false, applied, failed_step, error, cleanup_errors? }. On success returns { committed:true, applied }. Removed and overwritten entries are kept aside until every step succeeds. Rollback is best-effort: other processes can see intermediate states, and undo steps that fail are listed in cleanup_errors. move needs source and destination on the same filesystem and names the full destination path.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "steps": {
+                            "type": "array",
+                            "description": "Operations to apply in order. Use absolute paths to avoid ambiguity - relative paths are resolved from the current working directory.",
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "op": { "type": "string", "enum": ["write", "mkdir", "move", "link", "remove"] },
+                                    "path": { "type": "string" },
+                                    "content": { "type": "string" },
+                                    "append": { "type": "boolean" },
+                                    "source": { "type": "string" },
+                                    "destination": { "type": "string" },
+                                    "target": { "type": "string" },
+                                    "link_path": { "type": "string" },
+                                    "recursive": { "type": "boolean" }
+                                },
+                                "required": ["op"]
+                            }
+                        }
+                    },
+                    "required": ["steps"]
+                }
+            },
+            {
+                "name": "fileio_temp_info",
+                "description": "Report the system temp directory (where fileio_create_temporary puts files when given no template) as { path, available_bytes, total_bytes, writable }. writable comes from actually creating and removing a probe file, so a read-only or full tmpfs shows up as false. Check this before staging large intermediate files.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {}
+                }
+            },
+            {
+                "name": "fileio_allocate",
+                "description": "Create a file, or extend an existing one, to exactly size bytes, e.g. to prepare fixed-size test fixtures or reserve space. Existing content is kept and the added range reads as zeros; a file already larger than size is an error (never truncated). On Linux the blocks are reserved with fallocate, which is much faster than writing zeros; where that isn't supported the file is grown sparse with set_len. Returns { path, size, created, preallocated } where preallocated=false means the new range is sparse and takes no disk space until written.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": {
+                            "type": "string",
+                            "description": "Path of the file to create or extend. Use absolute paths to avoid ambiguity - relative paths are resolved from the current working directory."
+                        },
+                        "size": {
+                            "type": "integer",
+                            "minimum": 0,
+                            "description": "Final size in bytes."
+                        },
+                        "preallocate": {
+                            "type": "boolean",
+                            "description": "If true, reserve real disk blocks where the platform supports it, failing early if the space isn't available. If false, always grow the file sparse. Default: true."
+                        }
+                    },
+                    "required": ["path", "size"]
+                }
+            },
+            {
+                "name": "fileio_offset_to_position",
+                "description": "Translate a byte offset in a UTF-8 text file (e.g. from a compiler diagnostic or a grep -b match) into a line/column position. Returns { offset, line, column, byte_column }: line and both columns are 1-based, column counts characters and byte_column counts bytes from the start of the line. offset may equal the file size (end of file) but must not fall inside a multi-byte character. See fileio_position_to_offset for the reverse.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": {
+                            "type": "string",
+                            "description": "Path to the text file. Use absolute paths to avoid ambiguity - relative paths are resolved from the current working directory."
+                        },
+                        "offset": {
+                            "type": "integer",
+                            "description": "0-based byte offset into the file."
+                        }
+                    },
+                    "required": ["path", "offset"]
+                }
+            },
+            {
+                "name": "fileio_position_to_offset",
+                "description": "Translate a 1-based line and character column in a UTF-8 text file into a byte offset. Returns the same { offset, line, column, byte_column } shape as fileio_offset_to_position. column may be one past the last character of the line to address the line ending.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": {
+                            "type": "string",
+                            "description": "Path to the text file. Use absolute paths to avoid ambiguity - relative paths are resolved from the current working directory."
+                        },
+                        "line": {
+                            "type": "integer",
+                            "description": "1-based line number."
+                        },
+                        "column": {
+                            "type": "integer",
+                            "description": "1-based column, counted in characters (not bytes)."
+                        }
+                    },
+                    "required": ["path", "line", "column"]
+                }
+            },
+            {
+                "name": "fileio_disk_usage",
+                "description": "Summarize how much space a directory tree uses, like du. Returns { path, total_bytes, total_files, directories: [{ path, bytes, files }], unreadable? }: bytes is the apparent size of the regular files beneath each directory, directories lists the root and its subdirectories down to max_depth (sorted by path), and unreadable names directories that could not be listed. Symlinks are not followed; hard-linked files are counted once. Use exclude_glob to leave out trees such as node_modules or .git entirely.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": {
+                            "type": "string",
+                            "description": "Directory (or file) to measure. Use absolute paths to avoid ambiguity - relative paths are resolved from the current working directory."
+                        },
+                        "max_depth": {
+                            "type": "integer",
+                            "description": "Only list directories this many levels below path (0 = just the total for path, 1 = path and its immediate subdirectories). Deeper content still counts towards the listed totals. If not specified, every directory is listed."
+                        },
+                        "exclude_glob": {
+                            "type": "string",
+                            "description": "Glob matched against each entry's name (e.g. 'node_modules', '*.log'). Matching files and directories, including everything inside those directories, are left out of all totals."
+                        }
+                    },
+                    "required": ["path"]
+                }
+            },
+            {
+                "name": "fileio_is_within",
+                "description": "Check whether a path stays inside a base directory once symlinks and '..' are resolved - the check to run before acting on a user-supplied path that must not escape an allowed root. Returns { within, base, path, relative? }: base and path are the resolved absolute forms, and relative is path relative to base ('.' for the base itself), present only when within is true. Symlinks are followed on the existing part of each path (dangling ones too); the part that does not exist yet is resolved lexically, so the paths need not exist.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "base": {
+                            "type": "string",
+                            "description": "Directory the path must stay inside. Use absolute paths to avoid ambiguity - relative paths are resolved from the current working directory."
+                        },
+                        "path": {
+                            "type": "string",
+                            "description": "Path to check. A relative path is taken relative to base."
+                        }
+                    },
+                    "required": ["base", "path"]
+                }
+            },
+            {
+                "name": "fileio_tail_follow",
+                "description": "Follow a growing file like tail -f for a bounded window: returns its last lines, then every line appended until duration_ms passes or max_lines new lines arrive, as { path, initial, appended, rotations, truncations }. Log rotation (the path replaced by a new file) is handled by draining the old file and then reading the new one from its start; a file that shrinks is reread from the start. Lines are returned without line endings. The reply is sent once the window closes, so prefer a short duration_ms with max_lines when waiting for a specific event, and call again to keep following.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": {
+                            "type": "string",
+                            "description": "Path to the file to follow. Use absolute paths to avoid ambiguity - relative paths are resolved from the current working directory."
+                        },
+                        "lines": {
+                            "type": "integer",
+                            "description": "Number of existing lines from the end of the file to return first. Default: 10."
+                        },
+                        "duration_ms": {
+                            "type": "integer",
+                            "description": "How long to follow the file, in milliseconds (1-60000). Default: 5000."
+                        },
+                        "max_lines": {
+                            "type": "integer",
+                            "description": "Return as soon as at least this many new lines have been appended. If not specified, follow for the whole duration."
+                        }
+                    },
+                    "required": ["path"]
+                }
+            },
+            {
+                "name": "fileio_bom",
+                "description": "Check a file for a leading byte-order mark (BOM) and optionally remove it. Stray BOMs break shebang lines in shell scripts and trip up some parsers. mode 'detect' reports { path, bom, stripped:false } where bom is 'utf-8', 'utf-16le', 'utf-16be', 'utf-32le', 'utf-32be', or null; mode 'strip' also removes the BOM, rewriting the file atomically, and reports stripped:true. A file without a BOM is never rewritten. Only the mark is removed; the rest of the bytes (and so the encoding) are unchanged.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": {
+                            "type": "string",
+                            "description": "Path to the file. Use absolute paths to avoid ambiguity - relative paths are resolved from the current working directory."
+                        },
+                        "mode": {
+                            "type": "string",
+                            "enum": ["detect", "strip"],
+                            "description": "'detect' only reports the BOM; 'strip' removes it when present. Default: 'detect'."
+                        }
+                    },
+                    "required": ["path"]
+                }
+            },
+            {
+                // Answered by `FileIoService`, which owns the configuration it
+                // reports; listed here so tool filtering and listing see it.
+                "name": "fileio_server_info",
+                "description": "Report how this server is configured: { name, version, build: { target_os, target_arch, profile }, working_dir (where relative paths resolve), read_only (true when no tool that changes the filesystem is enabled), no_clobber, audit_log, tool_timeout_ms, allow_tools, deny_tools, transports, tools (enabled tool names) }. Use it to debug a deployment that behaves differently than expected.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {}
+                }
+            },
+            {
+                "name": "fileio_read_file",
+                "description": "Read a whole text file and return its exact contents as one string, with original line endings and the final newline (or its absence) preserved - unlike fileio_read_lines, which splits into lines. Use it when the exact bytes matter, e.g. before rewriting a file with fileio_write_file. Fails if the file is larger than max_bytes or (unless binary=true) not valid UTF-8.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": {
+                            "type": "string",
+                            "description": "Path to the file to read. Use absolute paths to avoid ambiguity - relative paths are resolved from the current working directory, which may not be the directory you expect."
+                        },
+                        "max_bytes": {
+                            "type": "integer",
+                            "description": "Refuse files larger than this many bytes instead of returning them. Default: 104857600 (100 MiB)."
+                        },
+                        "binary": {
+                            "type": "boolean",
+                            "description": "If true, skip text decoding and return { path, encoding: 'base64', data, bytes } with the raw bytes base64-encoded, so binary and non-UTF-8 files can be read. Default: false."
+                        }
+                    },
+                    "required": ["path"]
+                }
+            },
+            {
+                "name": "fileio_head",
+                "description": "Return the first lines of a file as an array of strings, like head: line_count lines (default 10), all of them if the file is shorter, and [] for an empty file. Only the returned lines are read, so it is a cheap preview of any file. For other ranges use fileio_read_lines; for the end of a file use its tail parameter.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": {
+                            "type": "string",
+                            "description": "Path to the file to preview. Use absolute paths to avoid ambiguity - relative paths are resolved from the current working directory, which may not be the directory you expect."
+                        },
+                        "line_count": {
+                            "type": "integer",
+                            "description": "Number of lines to return. Default: 10."
+                        }
+                    },
+                    "required": ["path"]
+                }
+            },
+            {
+                "name": "fileio_read_bytes",
+                "description": "Read a byte range of any file, text or binary: length bytes starting at byte offset (0-based, unlike read_lines' start_offset, which counts lines). Seeks straight to the offset, so it is cheap on large files. Returns { path, offset, bytes_read, data } with data base64-encoded; bytes_read is less than length when the range runs past the end of the file. An offset past the end is an error.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": {
+                            "type": "string",
+                            "description": "Path to the file to read. Use absolute paths to avoid ambiguity - relative paths are resolved from the current working directory, which may not be the directory you expect."
+                        },
+                        "offset": {
+                            "type": "integer",
+                            "description": "Byte offset to start reading at (0-based)."
+                        },
+                        "length": {
+                            "type": "integer",
+                            "description": "Maximum number of bytes to read, at most 16777216 (16 MiB)."
+                        }
+                    },
+                    "required": ["path", "offset", "length"]
+                }
+            },
+            {
+                "name": "fileio_truncate",
+                "description": "Set an existing file to exactly size bytes, e.g. to empty a log file (size=0) or cut a file down. Data past size is dropped; a larger size extends the file with bytes that read as zeros (sparse where the filesystem supports it). Fails if the path does not exist or is a directory - use fileio_allocate to create a file of a given size. Returns { path, previous_size, size }.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": {
+                            "type": "string",
+                            "description": "Path of the file to resize. Use absolute paths to avoid ambiguity - relative paths are resolved from the current working directory."
+                        },
+                        "size": {
+                            "type": "integer",
+                            "minimum": 0,
+                            "description": "New size in bytes."
+                        }
+                    },
+                    "required": ["path", "size"]
+                }
+            }
+        ])
+    }
+
+    /// Helper to parse path parameter (array of strings)
+    fn parse_paths(value: &Value) -> Result<Vec<String>> {
+        let arr = value.as_array().ok_or_else(|| {
+            crate::error::McpError::InvalidToolParameters(
+                "Path must be an array of strings".to_string(),
+            )
+        })?;
+        let mut paths = Vec::new();
+        for item in arr {
+            if let Some(s) = item.as_str() {
+                paths.push(s.to_string());
+            } else {
+                return Err(crate::error::McpError::InvalidToolParameters(
+                    "Path array must contain only strings".to_string(),
+                )
+                .into());
+            }
+        }
+        Ok(paths)
+    }
+
+    /// Parse an optional non-negative integer argument.
+    ///
+    /// Absent or explicitly-null values yield `None`. A present value is coerced
+    /// from any lossless encoding (integer, whole-valued float, decimal string)
+    /// via [`crate::coerce::value_to_u64`]; a genuinely invalid value (negative,
+    /// fractional, out of range, non-numeric) is an error rather than a silent
+    /// `None`, so a caller's constraint is never dropped without notice.
+    fn parse_optional_u64(args: &serde_json::Map<String, Value>, key: &str) -> Result<Option<u64>> {
+        match args.get(key) {
+            None | Some(Value::Null) => Ok(None),
+            Some(v) => crate::coerce::value_to_u64(v).map(Some).map_err(|reason| {
+                crate::error::McpError::InvalidToolParameters(format!("{key} {reason}")).into()
+            }),
+        }
+    }
+
+    /// Parse an optional boolean argument, coercing lossless encodings
+    /// (`"true"`/`"false"`/`"1"`/`"0"`, `1`/`0`) via
+    /// [`crate::coerce::value_to_bool`]. Absent or null yields `None`; a
+    /// present-but-uncoercible value is an error instead of silently falling
+    /// back to the default. Call `.unwrap_or(default)` on the result to keep a
+    /// tool's default-when-omitted behaviour.
+    fn parse_optional_bool(
+        args: &serde_json::Map<String, Value>,
+        key: &str,
+    ) -> Result<Option<bool>> {
+        match args.get(key) {
+            None | Some(Value::Null) => Ok(None),
+            Some(v) => crate::coerce::value_to_bool(v).map(Some).map_err(|reason| {
+                crate::error::McpError::InvalidToolParameters(format!("{key} {reason}")).into()
+            }),
+        }
+    }
+
+    /// Parse an optional array-of-strings argument. Absent or null yields
+    /// `None`; anything other than an array of strings is an error.
+    fn parse_optional_strings(
+        args: &serde_json::Map<String, Value>,
+        key: &str,
+    ) -> Result<Option<Vec<String>>> {
+        match args.get(key) {
+            None | Some(Value::Null) => Ok(None),
+            Some(v) => v
+                .as_array()
+                .and_then(|items| {
+                    items
+                        .iter()
+                        .map(|item| item.as_str().map(str::to_string))
+                        .collect::<Option<Vec<_>>>()
+                })
+                .map(Some)
+                .ok_or_else(|| {
+                    crate::error::McpError::InvalidToolParameters(format!(
+                        "{key} must be an array of strings"
+                    ))
+                    .into()
+                }),
+        }
+    }
+
+    /// Execute a tool by name
+    pub async fn execute_tool(&self, name: &str, arguments: &Value) -> Result<Value> {
+        let args = arguments.as_object().ok_or_else(|| {
+            crate::error::McpError::InvalidToolParameters("Arguments must be an object".to_string())
+        })?;
+
+        match name {
+            "fileio_read_lines" => {
+                let path = args.get("path").and_then(|v| v.as_str()).ok_or_else(|| {
+                    crate::error::McpError::InvalidToolParameters(
+                        "Missing required parameter: path".to_string(),
+                    )
+                })?;
+                if self.guard.is_denied(path) {
+                    return Self::not_found_error("open file", path);
+                }
+                let start_line = Self::parse_optional_u64(args, "start_line")?;
+                let end_line = Self::parse_optional_u64(args, "end_line")?;
+                let line_count = Self::parse_optional_u64(args, "line_count")?;
+                let start_offset = Self::parse_optional_u64(args, "start_offset")?;
+                let cursor = args.get("cursor").and_then(|v| v.as_str());
+                let with_cursor = Self::parse_optional_bool(args, "with_cursor")?.unwrap_or(false);
+                let tail = Self::parse_optional_u64(args, "tail")?;
+                let reverse = Self::parse_optional_bool(args, "reverse")?.unwrap_or(false);
+                let with_line_numbers =
+                    Self::parse_optional_bool(args, "with_line_numbers")?.unwrap_or(false);
+                let encoding = args
+                    .get("encoding")
+                    .and_then(|v| v.as_str())
+                    .map(crate::operations::write_file::TargetEncoding::parse)
+                    .transpose()?
+                    .unwrap_or_default();
+                if encoding != crate::operations::write_file::TargetEncoding::Utf8
+                    && (tail.is_some() || cursor.is_some() || with_cursor)
+                {
+                    return Err(crate::error::McpError::InvalidToolParameters(
+                        "encoding other than utf-8 cannot be combined with tail or cursor paging"
+                            .to_string(),
+                    )
+                    .into());
+                }
+                // Same precedence as `read_lines`: start_line wins over start_offset.
+                let mut first_line = start_line
+                    .or(start_offset.map(|offset| offset.saturating_add(1)))
+                    .unwrap_or(1);
+
+                let lines = if let Some(tail) = tail {
+                    if start_line.is_some()
+                        || end_line.is_some()
+                        || line_count.is_some()
+                        || start_offset.is_some()
+                        || cursor.is_some()
+                        || with_cursor
+                    {
+                        return Err(crate::error::McpError::InvalidToolParameters(
+                            "tail selects the last lines itself and cannot be combined with start_line, end_line, line_count, start_offset, or cursor paging".to_string(),
+                        )
+                        .into());
+                    }
+                    if with_line_numbers {
+                        let (first, lines) =
+                            crate::operations::read_lines::read_tail_with_start(path, tail)?;
+                        first_line = first;
+                        lines
+                    } else {
+                        crate::operations::read_lines::read_tail(path, tail)?
+                    }
+                } else if cursor.is_some() || with_cursor {
+                    if end_line.is_some() || start_offset.is_some() {
+                        return Err(crate::error::McpError::InvalidToolParameters(
+                            "cursor paging takes start_line and line_count, not end_line or start_offset".to_string(),
+                        )
+                        .into());
+                    }
+                    if cursor.is_some() && start_line.is_some() {
+                        return Err(crate::error::McpError::InvalidToolParameters(
+                            "cursor and start_line cannot be combined".to_string(),
+                        )
+                        .into());
+                    }
+                    if reverse || with_line_numbers {
+                        return Err(crate::error::McpError::InvalidToolParameters(
+                            "reverse and with_line_numbers cannot be combined with cursor paging (its result already has start_line)".to_string(),
+                        )
+                        .into());
+                    }
+                    let page = crate::operations::read_lines::read_lines_page(
+                        path, start_line, cursor, line_count,
+                    )?;
+                    return Ok(serde_json::json!({
+                        "content": [{
+                            "type": "text",
+                            "text": serde_json::to_string(&page)
+                                .map_err(crate::error::FileIoMcpError::Json)?
+                        }]
+                    }));
+                } else {
+                    crate::operations::read_lines::read_lines_encoded(
+                        path,
+                        start_line,
+                        end_line,
+                        line_count,
+                        start_offset,
+                        encoding,
+                    )?
+                };
+                // The window is chosen (and numbered) in file order first, then
+                // flipped.
+                let text = if with_line_numbers {
+                    let mut numbered =
+                        crate::operations::read_lines::number_lines(lines, first_line);
+                    if reverse {
+                        numbered.reverse();
+                    }
+                    serde_json::to_string(&numbered)
+                } else {
+                    let mut lines = lines;
+                    if reverse {
+                        lines.reverse();
+                    }
+                    serde_json::to_string(&lines)
+                }
+                .map_err(crate::error::FileIoMcpError::Json)?;
+
+                Ok(serde_json::json!({
+                    "content": [{
+                        "type": "text",
+                        "text": text
+                    }]
+                }))
+            }
+            "fileio_write_file" => {
+                let path = args.get("path").and_then(|v| v.as_str()).ok_or_else(|| {
+                    crate::error::McpError::InvalidToolParameters(
+                        "Missing required parameter: path".to_string(),
+                    )
+                })?;
+                let line_ending = args
+                    .get("line_ending")
+                    .and_then(|v| v.as_str())
+                    .map(crate::operations::write_file::LineEnding::parse)
+                    .transpose()?
+                    .unwrap_or_default();
+                let encoding = args
+                    .get("encoding")
+                    .and_then(|v| v.as_str())
+                    .map(crate::operations::write_file::TargetEncoding::parse)
+                    .transpose()?
+                    .unwrap_or_default();
+                let bom = Self::parse_optional_bool(args, "bom")?.unwrap_or(false);
+                let max_size = Self::parse_optional_u64(args, "max_size")?;
+                let keep_rotations = Self::parse_optional_u64(args, "keep_rotations")?;
+                let rotate = match (max_size, keep_rotations) {
+                    (Some(max_size), keep) => Some(crate::operations::write_file::Rotation {
+                        max_size,
+                        keep: u32::try_from(keep.unwrap_or(5)).map_err(|_| {
+                            crate::error::McpError::InvalidToolParameters(
+                                "keep_rotations is too large".to_string(),
+                            )
+                        })?,
+                    }),
+                    (None, Some(_)) => {
+                        return Err(crate::error::McpError::InvalidToolParameters(
+                            "keep_rotations requires max_size".to_string(),
+                        )
+                        .into());
+                    }
+                    (None, None) => None,
+                };
+                let explicit_if_exists = args
+                    .get("if_exists")
+                    .and_then(|v| v.as_str())
+                    .map(IfExists::parse)
+                    .transpose()?;
+                let append = Self::parse_optional_bool(args, "append")?.unwrap_or(false);
+                let prepend = Self::parse_optional_bool(args, "prepend")?.unwrap_or(false);
+                if append && prepend {
+                    return Err(crate::error::McpError::InvalidToolParameters(
+                        "append and prepend cannot be combined".to_string(),
+                    )
+                    .into());
+                }
+                // Prepend rewrites the whole file, so an explicit request to
+                // leave an existing file alone must not be dropped silently.
+                if prepend && explicit_if_exists.is_some_and(|value| value != IfExists::Overwrite) {
+                    return Err(crate::error::McpError::InvalidToolParameters(
+                        "prepend extends an existing file; it cannot be combined with if_exists='error' or 'skip'".to_string(),
+                    )
+                    .into());
+                }
+                let exclusive = Self::parse_optional_bool(args, "exclusive")?.unwrap_or(false);
+                let backup = Self::parse_optional_bool(args, "backup")?.unwrap_or(false);
+                let backup_suffix = match args.get("backup_suffix").and_then(|v| v.as_str()) {
+                    Some(suffix) => Some(suffix.to_string()),
+                    None => backup.then(|| ".bak".to_string()),
+                };
+                let mode = args
+                    .get("mode")
+                    .and_then(|v| v.as_str())
+                    .map(crate::operations::file_mode::parse_mode)
+                    .transpose()?;
+                let base64_content = match args.get("content_encoding").and_then(|v| v.as_str()) {
+                    None | Some("utf8") | Some("utf-8") => false,
+                    Some("base64") => true,
+                    Some(other) => {
+                        return Err(crate::error::McpError::InvalidToolParameters(format!(
+                            "Invalid content_encoding '{}': expected 'utf8' or 'base64'",
+                            other
+                        ))
+                        .into());
+                    }
+                };
+                if base64_content
+                    && (line_ending != crate::operations::write_file::LineEnding::default()
+                        || encoding != crate::operations::write_file::TargetEncoding::default()
+                        || bom)
+                {
+                    return Err(crate::error::McpError::InvalidToolParameters(
+                        "content_encoding='base64' writes raw bytes; line_ending, encoding and bom do not apply".to_string(),
+                    )
+                    .into());
+                }
+                let if_exists = if exclusive {
+                    if append || prepend {
+                        return Err(crate::error::McpError::InvalidToolParameters(
+                            "exclusive creates a new file and cannot be combined with append or prepend"
+                                .to_string(),
+                        )
+                        .into());
+                    }
+                    if explicit_if_exists.is_some_and(|value| value != IfExists::Error) {
+                        return Err(crate::error::McpError::InvalidToolParameters(
+                            "exclusive is if_exists='error'; it cannot be combined with another if_exists".to_string(),
+                        )
+                        .into());
+                    }
+                    IfExists::Error
+                } else {
+                    explicit_if_exists.unwrap_or(self.default_if_exists)
+                };
+                let content = args
+                    .get("content")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| {
+                        crate::error::McpError::InvalidToolParameters(
+                            "Missing required parameter: content".to_string(),
+                        )
+                    })?;
+                let in_place = Self::parse_optional_bool(args, "in_place")?.unwrap_or(false);
+                let sync = Self::parse_optional_bool(args, "sync")?.unwrap_or(false);
+
+                let options = crate::operations::write_file::WriteOptions {
+                    append,
+                    prepend,
+                    line_ending,
+                    encoding,
+                    bom,
+                    rotate,
+                    if_exists,
+                    in_place,
+                    mode,
+                    backup_suffix,
+                    sync,
+                };
+                let bytes = if base64_content {
+                    Some(
+                        crate::operations::read_bytes::from_base64(content).ok_or_else(|| {
+                            crate::error::McpError::InvalidToolParameters(
+                                "content is not valid base64".to_string(),
+                            )
+                        })?,
+                    )
+                } else {
+                    None
+                };
+
+                // The backup is a second write target: `~/.kube/confi` with
+                // suffix `g` would otherwise overwrite a denied file.
+                let backup_denied = options
+                    .backup_suffix
+                    .as_ref()
+                    .is_some_and(|suffix| self.guard.is_denied(&format!("{}{}", path, suffix)));
+                let outcome = if self.guard.is_denied(path) || backup_denied {
+                    // Shaped like a real write under the same if_exists,
+                    // so no field combination is unique to denied paths.
+                    // Where the outcome depends on whether the file exists,
+                    // it is taken to exist: the common case for denied
+                    // targets such as dotfiles and keys.
+                    let resolved = crate::operations::path_utils::resolve_path(path)
+                        .unwrap_or_else(|_| path.to_string());
+                    let bytes_written = match &bytes {
+                        Some(bytes) => bytes.len() as u64,
+                        None => crate::operations::write_file::prepared_len(content, &options)?,
+                    };
+                    let effective = if append || prepend {
+                        IfExists::Overwrite
+                    } else {
+                        if_exists
+                    };
+                    match effective {
+                        // Only ever succeeds by creating the file.
+                        IfExists::Error => crate::operations::write_file::WriteOutcome {
+                            path: resolved,
+                            written: true,
+                            bytes_written,
+                            created: true,
+                            backup: None,
+                        },
+                        IfExists::Skip => crate::operations::write_file::WriteOutcome {
+                            path: resolved,
+                            ..Default::default()
+                        },
+                        IfExists::Overwrite => crate::operations::write_file::WriteOutcome {
+                            backup: options
+                                .backup_suffix
+                                .as_ref()
+                                .map(|suffix| format!("{}{}", resolved, suffix)),
+                            path: resolved,
+                            written: true,
+                            bytes_written,
+                            created: false,
+                        },
+                    }
+                } else {
+                    match &bytes {
+                        Some(bytes) => {
+                            crate::operations::write_file::write_bytes_with(path, bytes, &options)?
+                        }
+                        None => {
+                            crate::operations::write_file::write_file_with(path, content, &options)?
+                        }
+                    }
+                };
+
+                Ok(serde_json::json!({
+                    "content": [{
+                        "type": "text",
+                        "text": serde_json::to_string(&outcome)
+                            .map_err(crate::error::FileIoMcpError::Json)?
+                    }]
+                }))
+            }
+            "fileio_set_permissions" | "fileio_set_mode" => {
+                let path_value = args.get("path").ok_or_else(|| {
+                    crate::error::McpError::InvalidToolParameters(
+                        "Missing required parameter: path".to_string(),
+                    )
+                })?;
+                let paths = Self::parse_paths(path_value)?;
+                let options = crate::operations::file_mode::TreeOptions {
+                    recursive: Self::parse_optional_bool(args, "recursive")?.unwrap_or(false),
+                    dry_run: Self::parse_optional_bool(args, "dry_run")?.unwrap_or(false),
+                };
+                // Plan-returning mode: the reply lists what changed (or would).
+                let report = options.recursive || options.dry_run;
+                if report {
+                    let mode = args.get("mode").and_then(|v| v.as_str()).ok_or_else(|| {
+                        crate::error::McpError::InvalidToolParameters(
+                            "Missing required parameter: mode".to_string(),
+                        )
+                    })?;
+                    // Denied inputs stay in the list: the op prunes denied
+                    // entries from each walk and stands in a placeholder for
+                    // a denied root (issue #6).
+                    let path_refs: Vec<&str> = paths.iter().map(|s| s.as_str()).collect();
+                    let changes = crate::operations::file_mode::set_file_mode_with(
+                        &path_refs,
+                        mode,
+                        &options,
+                        &|p| self.guard.is_denied(&p.to_string_lossy()),
+                    )?;
+                    return Ok(serde_json::json!({
+                        "content": [{
+                            "type": "text",
+                            "text": serde_json::to_string(&serde_json::json!({
+                                "dry_run": options.dry_run,
+                                "changes": changes
+                            }))
+                            .map_err(crate::error::FileIoMcpError::Json)?
+                        }]
+                    }));
+                }
+                let paths: Vec<String> = paths
+                    .into_iter()
+                    .filter(|p| !self.guard.is_denied(p))
+                    .collect();
+                if paths.is_empty() {
+                    return Self::silent_success("File mode set successfully");
+                }
+                let mode = args.get("mode").and_then(|v| v.as_str()).ok_or_else(|| {
+                    crate::error::McpError::InvalidToolParameters(
+                        "Missing required parameter: mode".to_string(),
+                    )
+                })?;
+                let path_refs: Vec<&str> = paths.iter().map(|s| s.as_str()).collect();
+                crate::operations::file_mode::set_file_mode(&path_refs, mode)?;
+
+                Ok(serde_json::json!({
+                    "content": [{
+                        "type": "text",
+                        "text": "File mode set successfully"
+                    }]
+                }))
+            }
+            "fileio_get_permissions" => {
+                let path_value = args.get("path").ok_or_else(|| {
+                    crate::error::McpError::InvalidToolParameters(
+                        "Missing required parameter: path".to_string(),
+                    )
+                })?;
+                let paths = Self::parse_paths(path_value)?;
+                if Self::parse_optional_bool(args, "entries")?.unwrap_or(false) {
+                    // One entry per input path, in order; denied paths get the
+                    // same "0000" sentinel the map form uses.
+                    let entries: Vec<crate::operations::get_mode::ModeEntry> = paths
+                        .iter()
+                        .map(|p| {
+                            if self.guard.is_denied(p) {
+                                crate::operations::get_mode::ModeEntry {
+                                    path: p.clone(),
+                                    mode: Some("0000".to_string()),
+                                    exists: true,
+                                    status: "ok".to_string(),
+                                }
+                            } else {
+                                crate::operations::get_mode::mode_entry(p)
+                            }
+                        })
+                        .collect();
+                    return Ok(serde_json::json!({
+                        "content": [{
+                            "type": "text",
+                            "text": serde_json::to_string(&entries)
+                                .map_err(crate::error::FileIoMcpError::Json)?
+                        }]
+                    }));
+                }
+                // Partial-denial oracle fix (issue #6): do NOT filter denied
+                // paths before calling the op. Instead, call the op only on
+                // allowed paths, then insert sentinel entries for denied paths
+                // so the output HashMap has the same key count as the input.
+                let (allowed, denied_set) = self.partition_by_guard(&paths);
+                let allowed_refs: Vec<&str> = allowed.iter().map(|s| s.as_str()).collect();
+
+                let mut modes = crate::operations::get_mode::get_file_mode(&allowed_refs)?;
+                // Sentinel: denied paths look like any other path in the map.
+                for p in &denied_set {
+                    modes.insert(p.to_string(), "0000".to_string());
+                }
+                Ok(serde_json::json!({
+                    "content": [{
+                        "type": "text",
+                        "text": serde_json::to_string(&modes)
+                            .map_err(crate::error::FileIoMcpError::Json)?
+                    }]
+                }))
+            }
+            "fileio_touch" => {
+                let path_value = args.get("path").ok_or_else(|| {
+                    crate::error::McpError::InvalidToolParameters(
+                        "Missing required parameter: path".to_string(),
+                    )
+                })?;
+                let mut paths = Self::parse_paths(path_value)?;
+                if let Some(base) = args.get("base").and_then(|v| v.as_str()) {
+                    // Checked before resolving: a denied base must not reveal
+                    // whether it exists as a file.
+                    if self.guard.is_denied(base) {
+                        return Self::silent_success("File(s) touched successfully");
+                    }
+                    paths = crate::operations::touch::paths_under_base(base, &paths)?;
+                }
+                let paths: Vec<String> = paths
+                    .into_iter()
+                    .filter(|p| !self.guard.is_denied(p))
+                    .collect();
+                if paths.is_empty() {
+                    return Self::silent_success("File(s) touched successfully");
+                }
+                let path_refs: Vec<&str> = paths.iter().map(|s| s.as_str()).collect();
+
+                crate::operations::touch::touch(&path_refs)?;
+
+                Ok(serde_json::json!({
+                    "content": [{
+                        "type": "text",
+                        "text": "File(s) touched successfully"
+                    }]
+                }))
+            }
+            "fileio_stat" => {
+                let path_value = args.get("path").ok_or_else(|| {
+                    crate::error::McpError::InvalidToolParameters(
+                        "Missing required parameter: path".to_string(),
+                    )
+                })?;
+                let paths = Self::parse_paths(path_value)?;
+                // Partial-denial oracle fix (issue #6): run stat only on allowed
+                // paths; re-merge sentinel FileStat entries for denied paths in
+                // original input order so the output array length == input length.
+                let (allowed, denied_set) = self.partition_by_guard(&paths);
+                let allowed_refs: Vec<&str> = allowed.iter().map(|s| s.as_str()).collect();
+
+                let max_symlink_depth = Self::parse_optional_u64(args, "max_symlink_depth")?;
+                let hash = match args.get("hash").and_then(|v| v.as_str()) {
+                    Some(name) => crate::operations::checksum::HashAlgorithm::parse(name)?,
+                    None => None,
+                };
+                let options = crate::operations::stat::StatOptions {
+                    follow_symlinks: Self::parse_optional_bool(args, "follow_symlinks")?
+                        .unwrap_or(true),
+                    max_symlink_depth,
+                    hash,
+                };
+                // One entry per allowed input, in order. Matched by position:
+                // entries carry the resolved path, not the input as given.
+                let mut real_results =
+                    crate::operations::stat::stat_with_options(&allowed_refs, &options)?
+                        .into_iter();
+
+                // Build the result array in original input order.
+                let stat_json_array: Vec<Value> = paths
+                    .iter()
+                    .map(|p| {
+                        if denied_set.contains(p) {
+                            // Sentinel: looks like a regular file with exists:true
+                            // so callers cannot distinguish denial from a real entry.
+                            crate::operations::stat::FileStat {
+                                // Resolved like a real entry's path.
+                                path: crate::operations::path_utils::resolve_path(p)
+                                    .unwrap_or_else(|_| p.clone()),
+                                entry_type: "file".to_string(),
+                                size: 0,
+                                mode: Some("0000".to_string()),
+                                modified: None,
+                                accessed: None,
+                                created: None,
+                                // Match the platform's real shape: dev/rdev
+                                // only exist on Unix.
+                                dev: cfg!(unix).then_some(0),
+                                rdev: cfg!(unix).then_some(0),
+                                // The digest of the empty content size 0 implies.
+                                hash: hash.map(|algorithm| algorithm.digest_hex(b"")),
+                                is_file: true,
+                                is_dir: false,
+                                is_symlink: false,
+                                exists: true,
+                                status: "ok".to_string(),
+                            }
+                            .into()
+                        } else {
+                            real_results
+                                .next()
+                                .map(Value::from)
+                                .unwrap_or_else(|| serde_json::json!({"path": p, "exists": false}))
+                        }
+                    })
+                    .collect();
+
+                Ok(serde_json::json!({
+                    "content": [{
+                        "type": "text",
+                        "text": serde_json::to_string(&stat_json_array)
+                            .map_err(crate::error::FileIoMcpError::Json)?
+                    }]
+                }))
+            }
+            "fileio_make_directory" => {
+                let path_value = args.get("path").ok_or_else(|| {
+                    crate::error::McpError::InvalidToolParameters(
+                        "Missing required parameter: path".to_string(),
+                    )
+                })?;
+                let paths = Self::parse_paths(path_value)?;
+                let paths: Vec<String> = paths
+                    .into_iter()
+                    .filter(|p| !self.guard.is_denied(p))
+                    .collect();
+                if paths.is_empty() {
+                    return Self::silent_success("Directory(ies) created successfully");
+                }
+                let path_refs: Vec<&str> = paths.iter().map(|s| s.as_str()).collect();
+                let recursive = Self::parse_optional_bool(args, "recursive")?.unwrap_or(true);
+
+                crate::operations::mkdir::mkdir(&path_refs, recursive)?;
+
+                Ok(serde_json::json!({
+                    "content": [{
+                        "type": "text",
+                        "text": "Directory(ies) created successfully"
+                    }]
+                }))
+            }
+            "fileio_list_directory" => {
+                let path = args.get("path").and_then(|v| v.as_str()).ok_or_else(|| {
+                    crate::error::McpError::InvalidToolParameters(
+                        "Missing required parameter: path".to_string(),
+                    )
+                })?;
+                let recursive = Self::parse_optional_bool(args, "recursive")?.unwrap_or(false);
+                let include_hidden =
+                    Self::parse_optional_bool(args, "include_hidden")?.unwrap_or(false);
+                let with_permissions =
+                    Self::parse_optional_bool(args, "with_permissions")?.unwrap_or(false);
+                let resolve_symlinks =
+                    Self::parse_optional_bool(args, "resolve_symlinks")?.unwrap_or(false);
+                if self.guard.is_denied(path) {
+                    // What a missing directory lists as.
+                    return Ok(serde_json::json!({
+                        "content": [{
+                            "type": "text",
+                            "text": "[]"
+                        }]
+                    }));
+                }
+
+                let entries = crate::operations::list_dir::list_directory_with(
+                    path,
+                    &crate::operations::list_dir::ListDirOptions {
+                        recursive,
+                        include_hidden,
+                        with_permissions,
+                        resolve_symlinks,
+                    },
+                )?;
+                let entries_json: Vec<Value> = entries.into_iter().map(|e| e.into()).collect();
+
+                Ok(serde_json::json!({
+                    "content": [{
+                        "type": "text",
+                        "text": serde_json::to_string(&entries_json)
+                            .map_err(crate::error::FileIoMcpError::Json)?
+                    }]
+                }))
+            }
+            "fileio_find_files" => {
+                let pattern = args
+                    .get("pattern")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| {
+                        crate::error::McpError::InvalidToolParameters(
+                            "Missing required parameter: pattern".to_string(),
+                        )
+                    })?;
+                let root = args.get("root").and_then(|v| v.as_str());
+                if let Some(root_path) = root
+                    && self.guard.is_denied(root_path)
+                {
+                    return Self::not_found_error("search directory", root_path);
+                }
+                let max_depth = Self::parse_optional_u64(args, "max_depth")?.map(|v| v as usize);
+                let file_type = args.get("file_type").and_then(|v| v.as_str());
+
+                let relative = Self::parse_optional_bool(args, "relative")?.unwrap_or(false);
+                let compute_dir_size =
+                    Self::parse_optional_bool(args, "compute_dir_size")?.unwrap_or(false);
+                let with_metadata = compute_dir_size
+                    || Self::parse_optional_bool(args, "with_metadata")?.unwrap_or(false);
+
+                let limit = Self::parse_optional_u64(args, "limit")?.map(|v| v as usize);
+                let sort = args
+                    .get("sort")
+                    .and_then(|v| v.as_str())
+                    .map(crate::operations::file_find::FindSort::parse)
+                    .transpose()?;
+                let skip_dirs = Self::parse_optional_strings(args, "skip_dirs")?;
+
+                let results = crate::operations::file_find::file_find_limited(
+                    pattern,
+                    root,
+                    max_depth,
+                    file_type,
+                    relative,
+                    compute_dir_size,
+                    &crate::operations::file_find::FindOptions {
+                        limit,
+                        sort,
+                        skip_dirs: skip_dirs.as_deref(),
+                    },
+                )?;
+                let total_matched = results.total_matched;
+                let found = results.matches;
+                let truncated = total_matched > found.len() as u64;
+                let matches_json: Vec<Value> = if with_metadata {
+                    found
+                        .into_iter()
+                        .map(serde_json::to_value)
+                        .collect::<std::result::Result<_, _>>()
+                        .map_err(crate::error::FileIoMcpError::Json)?
+                } else {
+                    found.into_iter().map(|m| Value::String(m.path)).collect()
+                };
+                let payload = if limit.is_some() {
+                    serde_json::json!({
+                        "matches": matches_json,
+                        "total_matched": total_matched,
+                        "truncated": truncated,
+                    })
+                } else {
+                    Value::Array(matches_json)
+                };
+
+                Ok(serde_json::json!({
+                    "content": [{
+                        "type": "text",
+                        "text": serde_json::to_string(&payload)
+                            .map_err(crate::error::FileIoMcpError::Json)?
+                    }]
+                }))
+            }
+            "fileio_find_in_files" => {
+                let pattern = args
+                    .get("pattern")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| {
+                        crate::error::McpError::InvalidToolParameters(
+                            "Missing required parameter: pattern".to_string(),
+                        )
+                    })?;
+                let path = args.get("path").and_then(|v| v.as_str()).ok_or_else(|| {
+                    crate::error::McpError::InvalidToolParameters(
+                        "Missing required parameter: path".to_string(),
+                    )
+                })?;
+                if self.guard.is_denied(path) {
+                    return Self::not_found_error("search", path);
+                }
+                let case_sensitive =
+                    Self::parse_optional_bool(args, "case_sensitive")?.unwrap_or(true);
+                let use_regex = Self::parse_optional_bool(args, "use_regex")?.unwrap_or(false);
+                let max_count = Self::parse_optional_u64(args, "max_count")?;
+                let max_depth = Self::parse_optional_u64(args, "max_depth")?.map(|v| v as usize);
+                let include_hidden =
+                    Self::parse_optional_bool(args, "include_hidden")?.unwrap_or(false);
+                let file_glob = args.get("file_glob").and_then(|v| v.as_str());
+                let exclude_glob = args.get("exclude_glob").and_then(|v| v.as_str());
+                let extensions = Self::parse_optional_strings(args, "extensions")?;
+                let skip_dirs = Self::parse_optional_strings(args, "skip_dirs")?;
+                let whole_word = Self::parse_optional_bool(args, "whole_word")?.unwrap_or(false);
+                let multiline = Self::parse_optional_bool(args, "multiline")?.unwrap_or(false);
+                let context_lines =
+                    Self::parse_optional_u64(args, "context_lines")?.unwrap_or(0) as usize;
+                let structured_context =
+                    Self::parse_optional_bool(args, "structured_context")?.unwrap_or(false);
+                let files_only = Self::parse_optional_bool(args, "files_only")?.unwrap_or(false);
+                let char_columns =
+                    Self::parse_optional_bool(args, "char_columns")?.unwrap_or(false);
+                let max_total = Self::parse_optional_u64(args, "max_total")?;
+                let line_text = Self::parse_optional_bool(args, "line_text")?.unwrap_or(false);
+                let group_by_file =
+                    Self::parse_optional_bool(args, "group_by_file")?.unwrap_or(false);
+                if group_by_file && files_only {
+                    return Err(crate::error::McpError::InvalidToolParameters(
+                        "group_by_file cannot be combined with files_only".to_string(),
+                    )
+                    .into());
+                }
+
+                let mut matches = crate::operations::find_in_files::find_in_files(
+                    &crate::operations::find_in_files::FindInFilesParams {
+                        pattern,
+                        path,
+                        case_sensitive,
+                        use_regex,
+                        max_count,
+                        max_depth,
+                        include_hidden,
+                        file_glob,
+                        exclude_glob,
+                        extensions: extensions.as_deref(),
+                        skip_dirs: skip_dirs.as_deref(),
+                        whole_word,
+                        multiline,
+                        context_lines,
+                        structured_context,
+                        files_only,
+                        char_columns,
+                        // Ask for one extra match so a cut-off result can be
+                        // told apart from one that happens to hit the cap.
+                        max_total: max_total.map(|total| total.saturating_add(1)),
+                        line_text,
+                    },
                 )?;
+                let truncated = max_total.is_some_and(|total| matches.len() as u64 > total);
+                if let Some(total) = max_total {
+                    matches.truncate(total as usize);
+                }
+                let matches_json: Vec<Value> = if files_only {
+                    matches
+                        .into_iter()
+                        .map(|m| Value::String(m.file_path))
+                        .collect()
+                } else if group_by_file {
+                    crate::operations::find_in_files::group_by_file(matches)
+                        .into_iter()
+                        .map(Value::from)
+                        .collect()
+                } else {
+                    matches.into_iter().map(|m| m.into()).collect()
+                };
+                let payload = if max_total.is_some() {
+                    serde_json::json!({ "matches": matches_json, "truncated": truncated })
+                } else {
+                    Value::Array(matches_json)
+                };
+
+                Ok(serde_json::json!({
+                    "content": [{
+                        "type": "text",
+                        "text": serde_json::to_string(&payload)
+                            .map_err(crate::error::FileIoMcpError::Json)?
+                    }]
+                }))
+            }
+            "fileio_edit_file" => {
+                // A malformed edit request is a parameter error (-32602), not an
+                // internal JSON fault — surface the coercion/shape message as such.
+                let req: crate::operations::edit_file::EditFileRequest = serde_json::from_value(
+                    serde_json::Value::Object(args.clone()),
+                )
+                .map_err(|e| crate::error::McpError::InvalidToolParameters(e.to_string()))?;
+
+                // Denied edits return a synthetic EditFileResult matching the
+                // real shape — `changed: false, applied_edits: 0` looks
+                // indistinguishable from a real edit where no patterns
+                // matched (a legitimate outcome with `require_match: false`).
+                // Returning plain text would create an oracle (real success
+                // returns serialized JSON; the LLM can map the deny-list with
+                // one no-op probe per path). Issue #3.
+                //
+                // Don't peek at on-disk state to decide between this synthetic
+                // and a real `NotFound` error — the existence check would
+                // itself leak whether the path exists.
+                if self.guard.is_denied(&req.path) {
+                    let synthetic = crate::operations::edit_file::EditFileResult {
+                        path: req.path.clone(),
+                        changed: false,
+                        applied_edits: 0,
+                        dry_run: req.dry_run,
+                        content: None,
+                        spans: req.return_spans.then(Vec::new),
+                    };
+                    return Ok(serde_json::json!({
+                        "content": [{
+                            "type": "text",
+                            "text": serde_json::to_string(&synthetic)
+                                .map_err(crate::error::FileIoMcpError::Json)?
+                        }]
+                    }));
+                }
+
+                let result = crate::operations::edit_file::edit_file(req)?;
+
+                Ok(serde_json::json!({
+                    "content": [{
+                        "type": "text",
+                        "text": serde_json::to_string(&result)
+                            .map_err(crate::error::FileIoMcpError::Json)?
+                    }]
+                }))
+            }
+            "fileio_copy" => {
+                let source_value = args.get("source").ok_or_else(|| {
+                    crate::error::McpError::InvalidToolParameters(
+                        "Missing required parameter: source".to_string(),
+                    )
+                })?;
+                let sources = Self::parse_paths(source_value)?;
+                let destination = args
+                    .get("destination")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| {
+                        crate::error::McpError::InvalidToolParameters(
+                            "Missing required parameter: destination".to_string(),
+                        )
+                    })?;
+
+                let link = Self::parse_optional_bool(args, "link")?.unwrap_or(false);
+                let reflink = args
+                    .get("reflink")
+                    .and_then(|v| v.as_str())
+                    .map(crate::operations::cp::ReflinkMode::parse)
+                    .transpose()?
+                    .unwrap_or_default();
+                // Validate before the deny check so a denied call fails the
+                // same way a real one would.
+                if link && reflink != crate::operations::cp::ReflinkMode::Never {
+                    return Err(crate::error::McpError::InvalidToolParameters(
+                        "link and reflink cannot be combined".to_string(),
+                    )
+                    .into());
+                }
+                let dry_run = Self::parse_optional_bool(args, "dry_run")?.unwrap_or(false);
+                let report_conflicts =
+                    Self::parse_optional_bool(args, "report_conflicts")?.unwrap_or(false);
+                let flatten = Self::parse_optional_bool(args, "flatten")?.unwrap_or(false);
+                let verify = Self::parse_optional_bool(args, "verify")?.unwrap_or(false);
+                let on_collision = args
+                    .get("on_collision")
+                    .and_then(|v| v.as_str())
+                    .map(crate::operations::cp::FlattenCollisionPolicy::parse)
+                    .transpose()?
+                    .unwrap_or_default();
+                if flatten && report_conflicts {
+                    return Err(crate::error::McpError::InvalidToolParameters(
+                        "flatten and report_conflicts cannot be combined; flatten reports its own collisions".to_string(),
+                    )
+                    .into());
+                }
+
+                // Two oracles to defeat (issue #3):
+                //   1. Real cp returns serialized Vec<OpResult>; the previous
+                //      silent-success path returned plain text. One probe
+                //      revealed denial.
+                //   2. Filtering denied sources individually and running cp
+                //      with the rest leaked which sources were denied via the
+                //      result count (input N, output N-1 means one was
+                //      filtered).
+                // Fix: if ANY input (source OR destination) is denied, build
+                // a synthetic Vec<OpResult> covering all sources verbatim and
+                // skip the real op entirely. Cost: legitimate calls that
+                // intentionally mix allowed + denied paths now no-op instead
+                // of partially succeeding — split the call to copy allowed
+                // paths.
+                let dest_denied = self.transfer_target_denied(&sources, destination);
+                let any_source_denied = sources.iter().any(|s| self.guard.is_denied(s));
+                if dest_denied || any_source_denied {
+                    let mut synthetic = Self::synthesize_op_results(&sources);
+                    // Match the real shape, which reports `reflinked` when a
+                    // reflink mode was requested.
+                    if reflink != crate::operations::cp::ReflinkMode::Never {
+                        for entry in &mut synthetic {
+                            entry["reflinked"] = Value::Bool(false);
+                        }
+                    }
+                    // Likewise `sha256` for a verified (non-dry-run) file copy.
+                    // An allowed source gets its real digest, which the caller
+                    // could compute anyway; a denied one a per-process keyed
+                    // value nobody can predict or check.
+                    if verify && !dry_run && !flatten {
+                        for (entry, source) in synthetic.iter_mut().zip(&sources) {
+                            let resolved = crate::operations::path_utils::resolve_path(source)
+                                .unwrap_or_else(|_| source.clone());
+                            let source_path = std::path::Path::new(&resolved);
+                            if source_path.is_dir() {
+                                continue;
+                            }
+                            let real = (!self.guard.is_denied(source))
+                                .then(|| crate::operations::checksum::sha256_file(source_path).ok())
+                                .flatten();
+                            let digest = real.unwrap_or_else(|| {
+                                crate::operations::checksum::keyed_sha256_hex(source.as_bytes())
+                            });
+                            entry["sha256"] = Value::String(digest);
+                        }
+                    }
+                    let mut body = Self::synthesize_transfer_body(
+                        synthetic,
+                        dry_run.then_some("would_copy"),
+                        report_conflicts,
+                    );
+                    if flatten {
+                        body = serde_json::json!({ "results": body, "collisions": [] });
+                    }
+                    return Ok(serde_json::json!({
+                        "content": [{
+                            "type": "text",
+                            "text": serde_json::to_string(&body)
+                                .map_err(crate::error::FileIoMcpError::Json)?
+                        }]
+                    }));
+                }
 
+                let source_refs: Vec<&str> = sources.iter().map(|s| s.as_str()).collect();
+                let recursive = Self::parse_optional_bool(args, "recursive")?.unwrap_or(false);
+                let options = crate::operations::cp::CpOptions {
+                    recursive,
+                    link,
+                    reflink,
+                    dry_run,
+                    verify,
+                };
+
+                let text = if flatten {
+                    let report = crate::operations::cp::cp_flatten(
+                        &source_refs,
+                        destination,
+                        &options,
+                        on_collision,
+                    )?;
+                    serde_json::to_string(&report)
+                } else if report_conflicts {
+                    let report = crate::operations::cp::cp_reporting_conflicts(
+                        &source_refs,
+                        destination,
+                        &options,
+                    )?;
+                    serde_json::to_string(&report)
+                } else {
+                    let results =
+                        crate::operations::cp::cp_with(&source_refs, destination, &options)?;
+                    serde_json::to_string(&results)
+                }
+                .map_err(crate::error::FileIoMcpError::Json)?;
                 Ok(serde_json::json!({
                     "content": [{
                         "type": "text",
-                        "text": serde_json::to_string(&lines)
+                        "text": text
+                    }]
+                }))
+            }
+            "fileio_move" => {
+                let source_value = args.get("source").ok_or_else(|| {
+                    crate::error::McpError::InvalidToolParameters(
+                        "Missing required parameter: source".to_string(),
+                    )
+                })?;
+                let sources = Self::parse_paths(source_value)?;
+                let destination = args
+                    .get("destination")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| {
+                        crate::error::McpError::InvalidToolParameters(
+                            "Missing required parameter: destination".to_string(),
+                        )
+                    })?;
+
+                let dry_run = Self::parse_optional_bool(args, "dry_run")?.unwrap_or(false);
+                let report_conflicts =
+                    Self::parse_optional_bool(args, "report_conflicts")?.unwrap_or(false);
+
+                // Same oracle defeat as fileio_copy (issue #3): synthesize the
+                // full Vec<OpResult> shape on any denial, no real op runs.
+                let dest_denied = self.transfer_target_denied(&sources, destination);
+                let any_source_denied = sources.iter().any(|s| self.guard.is_denied(s));
+                if dest_denied || any_source_denied {
+                    let body = Self::synthesize_transfer_body(
+                        Self::synthesize_op_results(&sources),
+                        dry_run.then_some("would_move"),
+                        report_conflicts,
+                    );
+                    return Ok(serde_json::json!({
+                        "content": [{
+                            "type": "text",
+                            "text": serde_json::to_string(&body)
+                                .map_err(crate::error::FileIoMcpError::Json)?
+                        }]
+                    }));
+                }
+
+                let source_refs: Vec<&str> = sources.iter().map(|s| s.as_str()).collect();
+
+                let text = if report_conflicts {
+                    let report = crate::operations::mv::mv_reporting_conflicts(
+                        &source_refs,
+                        destination,
+                        dry_run,
+                    )?;
+                    serde_json::to_string(&report)
+                } else {
+                    let results =
+                        crate::operations::mv::mv_with(&source_refs, destination, dry_run)?;
+                    serde_json::to_string(&results)
+                }
+                .map_err(crate::error::FileIoMcpError::Json)?;
+                Ok(serde_json::json!({
+                    "content": [{
+                        "type": "text",
+                        "text": text
+                    }]
+                }))
+            }
+            "fileio_remove" => {
+                let path_value = args.get("path").ok_or_else(|| {
+                    crate::error::McpError::InvalidToolParameters(
+                        "Missing required parameter: path".to_string(),
+                    )
+                })?;
+                let paths = Self::parse_paths(path_value)?;
+
+                let recursive = Self::parse_optional_bool(args, "recursive")?.unwrap_or(false);
+                let force = Self::parse_optional_bool(args, "force")?.unwrap_or(false);
+                let report_size = Self::parse_optional_bool(args, "report_size")?.unwrap_or(false);
+                let dry_run = Self::parse_optional_bool(args, "dry_run")?.unwrap_or(false);
+
+                // Issue #3: synthesize Vec<OpResult> for denied calls so the
+                // response shape matches a real rm. Otherwise the LLM can
+                // probe paths via rm and detect denial (real returns JSON
+                // results; old silent-success returned plain text). The
+                // report_size wrapper is synthesized too, for the same reason.
+                if paths.iter().any(|p| self.guard.is_denied(p)) {
+                    let mut synthetic = Self::synthesize_op_results(&paths);
+                    if dry_run {
+                        for entry in &mut synthetic {
+                            entry["status"] = Value::String("would_remove".to_string());
+                        }
+                    }
+                    let body = if report_size {
+                        serde_json::json!({ "results": synthetic, "freed_bytes": 0 })
+                    } else {
+                        Value::Array(synthetic)
+                    };
+                    return Ok(serde_json::json!({
+                        "content": [{
+                            "type": "text",
+                            "text": serde_json::to_string(&body)
+                                .map_err(crate::error::FileIoMcpError::Json)?
+                        }]
+                    }));
+                }
+
+                let path_refs: Vec<&str> = paths.iter().map(|s| s.as_str()).collect();
+
+                let text = if dry_run {
+                    let report = crate::operations::rm::rm_dry_run(
+                        &path_refs,
+                        recursive,
+                        force,
+                        report_size,
+                    )?;
+                    if report_size {
+                        serde_json::to_string(&report)
+                    } else {
+                        serde_json::to_string(&report.results)
+                    }
+                } else if report_size {
+                    let report =
+                        crate::operations::rm::rm_reporting_size(&path_refs, recursive, force)?;
+                    serde_json::to_string(&report)
+                } else {
+                    let results = crate::operations::rm::rm(&path_refs, recursive, force)?;
+                    serde_json::to_string(&results)
+                }
+                .map_err(crate::error::FileIoMcpError::Json)?;
+                Ok(serde_json::json!({
+                    "content": [{
+                        "type": "text",
+                        "text": text
+                    }]
+                }))
+            }
+            "fileio_remove_directory" => {
+                let path_value = args.get("path").ok_or_else(|| {
+                    crate::error::McpError::InvalidToolParameters(
+                        "Missing required parameter: path".to_string(),
+                    )
+                })?;
+                let paths = Self::parse_paths(path_value)?;
+
+                // Issue #3: same shape-matching as fileio_remove.
+                if paths.iter().any(|p| self.guard.is_denied(p)) {
+                    let synthetic = Self::synthesize_op_results(&paths);
+                    return Ok(serde_json::json!({
+                        "content": [{
+                            "type": "text",
+                            "text": serde_json::to_string(&synthetic)
+                                .map_err(crate::error::FileIoMcpError::Json)?
+                        }]
+                    }));
+                }
+
+                let path_refs: Vec<&str> = paths.iter().map(|s| s.as_str()).collect();
+                let recursive = Self::parse_optional_bool(args, "recursive")?.unwrap_or(false);
+
+                let results = crate::operations::rmdir::rmdir(&path_refs, recursive)?;
+                Ok(serde_json::json!({
+                    "content": [{
+                        "type": "text",
+                        "text": serde_json::to_string(&results)
                             .map_err(crate::error::FileIoMcpError::Json)?
                     }]
                 }))
             }
-            "fileio_write_file" => {
-                let path = args.get("path").and_then(|v| v.as_str()).ok_or_else(|| {
+            "fileio_create_hard_link" => {
+                let target = args.get("target").and_then(|v| v.as_str()).ok_or_else(|| {
+                    crate::error::McpError::InvalidToolParameters(
+                        "Missing required parameter: target".to_string(),
+                    )
+                })?;
+                let link_path =
+                    args.get("link_path")
+                        .and_then(|v| v.as_str())
+                        .ok_or_else(|| {
+                            crate::error::McpError::InvalidToolParameters(
+                                "Missing required parameter: link_path".to_string(),
+                            )
+                        })?;
+                if self.guard.is_denied(target) {
+                    return Self::not_found_error("link to", target);
+                }
+                if self.guard.is_denied(link_path) {
+                    return Self::silent_success("Hard link created successfully");
+                }
+
+                crate::operations::link::hard_link(target, link_path)?;
+
+                Ok(serde_json::json!({
+                    "content": [{
+                        "type": "text",
+                        "text": "Hard link created successfully"
+                    }]
+                }))
+            }
+            "fileio_create_symbolic_link" => {
+                let target = args.get("target").and_then(|v| v.as_str()).ok_or_else(|| {
+                    crate::error::McpError::InvalidToolParameters(
+                        "Missing required parameter: target".to_string(),
+                    )
+                })?;
+                let link_path =
+                    args.get("link_path")
+                        .and_then(|v| v.as_str())
+                        .ok_or_else(|| {
+                            crate::error::McpError::InvalidToolParameters(
+                                "Missing required parameter: link_path".to_string(),
+                            )
+                        })?;
+                // A real symlink may dangle, so a denied target can't be
+                // reported as missing.
+                if self.guard.is_denied(target) || self.guard.is_denied(link_path) {
+                    return Self::silent_success("Symbolic link created successfully");
+                }
+
+                crate::operations::link::symlink(target, link_path)?;
+
+                Ok(serde_json::json!({
+                    "content": [{
+                        "type": "text",
+                        "text": "Symbolic link created successfully"
+                    }]
+                }))
+            }
+            "fileio_get_basename" => {
+                let path = args.get("path").and_then(|v| v.as_str()).ok_or_else(|| {
+                    crate::error::McpError::InvalidToolParameters(
+                        "Missing required parameter: path".to_string(),
+                    )
+                })?;
+                // Pure string handling: answering reveals nothing about a
+                // denied path, while refusing would mark it as denied.
+                let basename = crate::operations::path_utils::basename(path)?;
+
+                Ok(serde_json::json!({
+                    "content": [{
+                        "type": "text",
+                        "text": basename
+                    }]
+                }))
+            }
+            "fileio_get_dirname" => {
+                let path = args.get("path").and_then(|v| v.as_str()).ok_or_else(|| {
+                    crate::error::McpError::InvalidToolParameters(
+                        "Missing required parameter: path".to_string(),
+                    )
+                })?;
+                // Pure string handling, as for `fileio_get_basename`.
+                let dirname = crate::operations::path_utils::dirname(path)?;
+
+                Ok(serde_json::json!({
+                    "content": [{
+                        "type": "text",
+                        "text": dirname
+                    }]
+                }))
+            }
+            "fileio_get_canonical_path" => {
+                let path = args.get("path").and_then(|v| v.as_str()).ok_or_else(|| {
+                    crate::error::McpError::InvalidToolParameters(
+                        "Missing required parameter: path".to_string(),
+                    )
+                })?;
+                let max_symlink_depth = Self::parse_optional_u64(args, "max_symlink_depth")?;
+                if self.guard.is_denied(path) {
+                    // Resolving by hand fails on the missing component.
+                    let operation = if max_symlink_depth.is_some() {
+                        "lstat path"
+                    } else {
+                        "canonicalize path"
+                    };
+                    return Self::not_found_error(operation, path);
+                }
+
+                let realpath =
+                    crate::operations::path_utils::realpath_with_depth(path, max_symlink_depth)?;
+
+                Ok(serde_json::json!({
+                    "content": [{
+                        "type": "text",
+                        "text": realpath
+                    }]
+                }))
+            }
+            "fileio_read_symbolic_link" => {
+                let path = args.get("path").and_then(|v| v.as_str()).ok_or_else(|| {
+                    crate::error::McpError::InvalidToolParameters(
+                        "Missing required parameter: path".to_string(),
+                    )
+                })?;
+                if self.guard.is_denied(path) {
+                    return Self::not_found_error("lstat path", path);
+                }
+
+                let target = crate::operations::path_utils::readlink(path)?;
+
+                Ok(serde_json::json!({
+                    "content": [{
+                        "type": "text",
+                        "text": target
+                    }]
+                }))
+            }
+            "fileio_create_temporary" => {
+                let temp_type = args.get("type").and_then(|v| v.as_str()).ok_or_else(|| {
                     crate::error::McpError::InvalidToolParameters(
-                        "Missing required parameter: path".to_string(),
+                        "Missing required parameter: type".to_string(),
                     )
                 })?;
-                if self.guard.is_denied(path) {
-                    return Self::silent_success("File written successfully");
+                let template = args.get("template").and_then(|v| v.as_str());
+
+                // mktemp_{file,dir} uses the template's parent directory as the
+                // creation site. A template like `/etc/security/probe-XXXXXX`
+                // would happily create a file inside a protected directory if
+                // we don't check first. No directory check needed when the
+                // template is None or has no path component — those land in
+                // $TMPDIR (typically /tmp) which the deny-list does not cover.
+                if let Some(t) = template
+                    && t.contains('/')
+                    && self.guard.is_denied(t)
+                {
+                    return Self::silent_success(match temp_type {
+                        "file" => "Temporary file created",
+                        "dir" => "Temporary directory created",
+                        _ => "Temporary created",
+                    });
                 }
-                let content = args
-                    .get("content")
-                    .and_then(|v| v.as_str())
-                    .ok_or_else(|| {
-                        crate::error::McpError::InvalidToolParameters(
-                            "Missing required parameter: content".to_string(),
-                        )
-                    })?;
-                let append = Self::parse_optional_bool(args, "append")?.unwrap_or(false);
 
-                crate::operations::write_file::write_file(path, content, append)?;
+                let path = match temp_type {
+                    "file" => crate::operations::mktemp::mktemp_file(template)?,
+                    "dir" => crate::operations::mktemp::mktemp_dir(template)?,
+                    _ => {
+                        return Err(crate::error::McpError::InvalidToolParameters(format!(
+                            "Invalid type: {} (must be 'file' or 'dir')",
+                            temp_type
+                        ))
+                        .into());
+                    }
+                };
 
                 Ok(serde_json::json!({
                     "content": [{
                         "type": "text",
-                        "text": "File written successfully"
+                        "text": path
                     }]
                 }))
             }
-            "fileio_set_permissions" | "fileio_set_mode" => {
+            "fileio_change_ownership" => {
                 let path_value = args.get("path").ok_or_else(|| {
                     crate::error::McpError::InvalidToolParameters(
                         "Missing required parameter: path".to_string(),
                     )
                 })?;
                 let paths = Self::parse_paths(path_value)?;
+                let options = crate::operations::file_mode::TreeOptions {
+                    recursive: Self::parse_optional_bool(args, "recursive")?.unwrap_or(false),
+                    dry_run: Self::parse_optional_bool(args, "dry_run")?.unwrap_or(false),
+                };
+                // Plan-returning mode: the reply lists what changed (or would).
+                let report = options.recursive || options.dry_run;
+                let user = args.get("user").and_then(|v| v.as_str());
+                let group = args.get("group").and_then(|v| v.as_str());
+
+                if report {
+                    // Denied inputs stay in the list; see fileio_set_permissions.
+                    let path_refs: Vec<&str> = paths.iter().map(|s| s.as_str()).collect();
+                    let changes = crate::operations::chown::chown_with(
+                        &path_refs,
+                        user,
+                        group,
+                        &options,
+                        &|p| self.guard.is_denied(&p.to_string_lossy()),
+                    )?;
+                    return Ok(serde_json::json!({
+                        "content": [{
+                            "type": "text",
+                            "text": serde_json::to_string(&serde_json::json!({
+                                "dry_run": options.dry_run,
+                                "changes": changes
+                            }))
+                            .map_err(crate::error::FileIoMcpError::Json)?
+                        }]
+                    }));
+                }
+
                 let paths: Vec<String> = paths
                     .into_iter()
                     .filter(|p| !self.guard.is_denied(p))
                     .collect();
                 if paths.is_empty() {
-                    return Self::silent_success("File mode set successfully");
+                    return Self::silent_success("Ownership changed successfully");
                 }
-                let mode = args.get("mode").and_then(|v| v.as_str()).ok_or_else(|| {
+                let path_refs: Vec<&str> = paths.iter().map(|s| s.as_str()).collect();
+                crate::operations::chown::chown(&path_refs, user, group)?;
+
+                Ok(serde_json::json!({
+                    "content": [{
+                        "type": "text",
+                        "text": "Ownership changed successfully"
+                    }]
+                }))
+            }
+            "fileio_get_current_directory" => {
+                let cwd = crate::operations::pwd::pwd()?;
+
+                Ok(serde_json::json!({
+                    "content": [{
+                        "type": "text",
+                        "text": cwd
+                    }]
+                }))
+            }
+            "fileio_count_lines" => {
+                let path_value = args.get("path").ok_or_else(|| {
                     crate::error::McpError::InvalidToolParameters(
-                        "Missing required parameter: mode".to_string(),
+                        "Missing required parameter: path".to_string(),
                     )
                 })?;
+                let paths = Self::parse_paths(path_value)?;
+                // Partial-denial oracle fix (issue #6): run count_lines only on
+                // allowed paths then re-merge sentinels for denied paths so the
+                // output array length always equals the input length.
+                let (allowed, denied_set) = self.partition_by_guard(&paths);
+                let allowed_refs: Vec<&str> = allowed.iter().map(|s| s.as_str()).collect();
 
-                let path_refs: Vec<&str> = paths.iter().map(|s| s.as_str()).collect();
-                crate::operations::file_mode::set_file_mode(&path_refs, mode)?;
+                let pattern = args.get("pattern").and_then(|v| v.as_str());
+                let use_regex = Self::parse_optional_bool(args, "use_regex")?.unwrap_or(false);
+                let case_sensitive =
+                    Self::parse_optional_bool(args, "case_sensitive")?.unwrap_or(true);
+                let invert = Self::parse_optional_bool(args, "invert")?.unwrap_or(false);
+                // Compile before running anything so a bad pattern fails the
+                // whole call, denied paths included.
+                let regex = pattern
+                    .map(|p| {
+                        crate::operations::find_in_files::build_regex(
+                            p,
+                            use_regex,
+                            case_sensitive,
+                            false,
+                            false,
+                        )
+                    })
+                    .transpose()?;
+                let with_size = Self::parse_optional_bool(args, "with_size")?.unwrap_or(false);
+                let real_counts = if with_size {
+                    crate::operations::count_lines::count_lines_with_size(
+                        &allowed_refs,
+                        regex.as_ref().map(|regex| (regex, invert)),
+                    )?
+                } else {
+                    match &regex {
+                        Some(regex) => crate::operations::count_lines::count_lines_matching(
+                            &allowed_refs,
+                            regex,
+                            invert,
+                        )?,
+                        None => crate::operations::count_lines::count_lines(&allowed_refs)?,
+                    }
+                };
+
+                let mut real_map: std::collections::HashMap<
+                    String,
+                    crate::operations::count_lines::LineCountResult,
+                > = real_counts
+                    .into_iter()
+                    .map(|r| (r.path.clone(), r))
+                    .collect();
+
+                let counts: Vec<crate::operations::count_lines::LineCountResult> = paths
+                    .iter()
+                    .map(|p| {
+                        if denied_set.contains(p) {
+                            crate::operations::count_lines::LineCountResult {
+                                path: p.clone(),
+                                status: "ok".to_string(),
+                                lines: Some(0),
+                                exists: true,
+                                bytes: with_size.then_some(0),
+                                chars: with_size.then_some(0),
+                            }
+                        } else {
+                            real_map.remove(p).unwrap_or_else(|| {
+                                crate::operations::count_lines::LineCountResult {
+                                    path: p.clone(),
+                                    status: "error: not found".to_string(),
+                                    lines: None,
+                                    exists: false,
+                                    bytes: None,
+                                    chars: None,
+                                }
+                            })
+                        }
+                    })
+                    .collect();
+
+                let counts_json =
+                    serde_json::to_string(&counts).map_err(crate::error::FileIoMcpError::Json)?;
 
                 Ok(serde_json::json!({
                     "content": [{
                         "type": "text",
-                        "text": "File mode set successfully"
+                        "text": counts_json
                     }]
                 }))
             }
-            "fileio_get_permissions" => {
+            "fileio_count_words" => {
                 let path_value = args.get("path").ok_or_else(|| {
                     crate::error::McpError::InvalidToolParameters(
                         "Missing required parameter: path".to_string(),
                     )
                 })?;
                 let paths = Self::parse_paths(path_value)?;
-                // Partial-denial oracle fix (issue #6): do NOT filter denied
-                // paths before calling the op. Instead, call the op only on
-                // allowed paths, then insert sentinel entries for denied paths
-                // so the output HashMap has the same key count as the input.
+                // Partial-denial oracle fix (issue #6): same pattern as count_lines.
                 let (allowed, denied_set) = self.partition_by_guard(&paths);
                 let allowed_refs: Vec<&str> = allowed.iter().map(|s| s.as_str()).collect();
 
-                let mut modes = crate::operations::get_mode::get_file_mode(&allowed_refs)?;
-                // Sentinel: denied paths look like any other path in the map.
-                for p in &denied_set {
-                    modes.insert(p.to_string(), "0000".to_string());
+                let top_words = Self::parse_optional_u64(args, "top_words")?;
+                if top_words == Some(0) {
+                    return Err(crate::error::McpError::InvalidToolParameters(
+                        "top_words must be at least 1".to_string(),
+                    )
+                    .into());
                 }
+                let options = crate::operations::count_words::WordCountOptions {
+                    unicode: Self::parse_optional_bool(args, "unicode")?.unwrap_or(false),
+                    per_line: Self::parse_optional_bool(args, "words_per_line")?.unwrap_or(false),
+                    top_words: top_words.map(|n| n as usize),
+                    strip_punctuation: Self::parse_optional_bool(args, "strip_punctuation")?
+                        .unwrap_or(false),
+                };
+                let mut real_map: std::collections::HashMap<
+                    String,
+                    crate::operations::count_words::WordCountResult,
+                > = crate::operations::count_words::count_words_with(&allowed_refs, &options)?
+                    .into_iter()
+                    .map(|r| (r.path.clone(), r))
+                    .collect();
+
+                let counts: Vec<crate::operations::count_words::WordCountResult> = paths
+                    .iter()
+                    .map(|p| {
+                        if denied_set.contains(p) {
+                            crate::operations::count_words::WordCountResult {
+                                path: p.clone(),
+                                status: "ok".to_string(),
+                                words: Some(0),
+                                exists: true,
+                                words_per_line: options.per_line.then(Vec::new),
+                                top_words: options.top_words.map(|_| Vec::new()),
+                            }
+                        } else {
+                            real_map.remove(p).unwrap_or_else(|| {
+                                crate::operations::count_words::WordCountResult {
+                                    path: p.clone(),
+                                    status: "error: not found".to_string(),
+                                    words: None,
+                                    exists: false,
+                                    words_per_line: None,
+                                    top_words: None,
+                                }
+                            })
+                        }
+                    })
+                    .collect();
+
+                let counts_json =
+                    serde_json::to_string(&counts).map_err(crate::error::FileIoMcpError::Json)?;
+
                 Ok(serde_json::json!({
                     "content": [{
                         "type": "text",
-                        "text": serde_json::to_string(&modes)
+                        "text": counts_json
+                    }]
+                }))
+            }
+            "fileio_strip_trailing_whitespace" => {
+                let path = args.get("path").and_then(|v| v.as_str()).ok_or_else(|| {
+                    crate::error::McpError::InvalidToolParameters(
+                        "Missing required parameter: path".to_string(),
+                    )
+                })?;
+                let ensure_final_newline =
+                    Self::parse_optional_bool(args, "ensure_final_newline")?.unwrap_or(false);
+
+                // Denied: a synthetic "nothing to strip" result of the real
+                // shape, indistinguishable from an already-clean file.
+                let result = if self.guard.is_denied(path) {
+                    crate::operations::strip_whitespace::StripWhitespaceResult {
+                        path: path.to_string(),
+                        lines_changed: 0,
+                        changed: false,
+                    }
+                } else {
+                    crate::operations::strip_whitespace::strip_trailing_whitespace(
+                        path,
+                        ensure_final_newline,
+                    )?
+                };
+
+                Ok(serde_json::json!({
+                    "content": [{
+                        "type": "text",
+                        "text": serde_json::to_string(&result)
                             .map_err(crate::error::FileIoMcpError::Json)?
                     }]
                 }))
             }
-            "fileio_touch" => {
-                let path_value = args.get("path").ok_or_else(|| {
+            "fileio_convert_tabs" => {
+                let path = args.get("path").and_then(|v| v.as_str()).ok_or_else(|| {
                     crate::error::McpError::InvalidToolParameters(
                         "Missing required parameter: path".to_string(),
                     )
                 })?;
-                let paths = Self::parse_paths(path_value)?;
-                let paths: Vec<String> = paths
-                    .into_iter()
-                    .filter(|p| !self.guard.is_denied(p))
-                    .collect();
-                if paths.is_empty() {
-                    return Self::silent_success("File(s) touched successfully");
+                let mode = args.get("mode").and_then(|v| v.as_str()).ok_or_else(|| {
+                    crate::error::McpError::InvalidToolParameters(
+                        "Missing required parameter: mode".to_string(),
+                    )
+                })?;
+                let mode = crate::operations::convert_tabs::TabMode::parse(mode)?;
+                let tab_width = Self::parse_optional_u64(args, "tab_width")?.unwrap_or(8) as usize;
+                // Validate before the deny check so a denied path fails the
+                // same way a real one would.
+                if tab_width == 0 {
+                    return Err(crate::error::McpError::InvalidToolParameters(
+                        "tab_width must be at least 1".to_string(),
+                    )
+                    .into());
                 }
-                let path_refs: Vec<&str> = paths.iter().map(|s| s.as_str()).collect();
+                let leading_only = Self::parse_optional_bool(args, "leading_only")?.unwrap_or(true);
 
-                crate::operations::touch::touch(&path_refs)?;
+                let result = if self.guard.is_denied(path) {
+                    crate::operations::convert_tabs::ConvertTabsResult {
+                        path: path.to_string(),
+                        lines_changed: 0,
+                        changed: false,
+                    }
+                } else {
+                    crate::operations::convert_tabs::convert_tabs(
+                        path,
+                        mode,
+                        tab_width,
+                        leading_only,
+                    )?
+                };
+
+                Ok(serde_json::json!({
+                    "content": [{
+                        "type": "text",
+                        "text": serde_json::to_string(&result)
+                            .map_err(crate::error::FileIoMcpError::Json)?
+                    }]
+                }))
+            }
+            "fileio_replace_between_markers" => {
+                let path = args.get("path").and_then(|v| v.as_str()).ok_or_else(|| {
+                    crate::error::McpError::InvalidToolParameters(
+                        "Missing required parameter: path".to_string(),
+                    )
+                })?;
+                let begin_marker = args
+                    .get("begin_marker")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| {
+                        crate::error::McpError::InvalidToolParameters(
+                            "Missing required parameter: begin_marker".to_string(),
+                        )
+                    })?;
+                let end_marker =
+                    args.get("end_marker")
+                        .and_then(|v| v.as_str())
+                        .ok_or_else(|| {
+                            crate::error::McpError::InvalidToolParameters(
+                                "Missing required parameter: end_marker".to_string(),
+                            )
+                        })?;
+                let content = args
+                    .get("content")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| {
+                        crate::error::McpError::InvalidToolParameters(
+                            "Missing required parameter: content".to_string(),
+                        )
+                    })?;
+                let create_if_missing =
+                    Self::parse_optional_bool(args, "create_if_missing")?.unwrap_or(false);
+
+                let result = if self.guard.is_denied(path) {
+                    crate::operations::replace_markers::ReplaceMarkersResult {
+                        path: path.to_string(),
+                        inserted: false,
+                        changed: true,
+                    }
+                } else {
+                    crate::operations::replace_markers::replace_between_markers(
+                        path,
+                        begin_marker,
+                        end_marker,
+                        content,
+                        create_if_missing,
+                    )?
+                };
 
                 Ok(serde_json::json!({
                     "content": [{
                         "type": "text",
-                        "text": "File(s) touched successfully"
+                        "text": serde_json::to_string(&result)
+                            .map_err(crate::error::FileIoMcpError::Json)?
                     }]
                 }))
             }
-            "fileio_stat" => {
-                let path_value = args.get("path").ok_or_else(|| {
+            "fileio_rename_pattern" => {
+                let path = args.get("path").and_then(|v| v.as_str()).ok_or_else(|| {
                     crate::error::McpError::InvalidToolParameters(
                         "Missing required parameter: path".to_string(),
                     )
                 })?;
-                let paths = Self::parse_paths(path_value)?;
-                // Partial-denial oracle fix (issue #6): run stat only on allowed
-                // paths; re-merge sentinel FileStat entries for denied paths in
-                // original input order so the output array length == input length.
-                let (allowed, denied_set) = self.partition_by_guard(&paths);
-                let allowed_refs: Vec<&str> = allowed.iter().map(|s| s.as_str()).collect();
-
-                let mut real_results: std::collections::HashMap<String, Value> =
-                    crate::operations::stat::stat(&allowed_refs)?
-                        .into_iter()
-                        .map(|s| {
-                            let p = s.path.clone();
-                            (p, s.into())
-                        })
-                        .collect();
+                let pattern = args
+                    .get("pattern")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| {
+                        crate::error::McpError::InvalidToolParameters(
+                            "Missing required parameter: pattern".to_string(),
+                        )
+                    })?;
+                let replacement = args
+                    .get("replacement")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| {
+                        crate::error::McpError::InvalidToolParameters(
+                            "Missing required parameter: replacement".to_string(),
+                        )
+                    })?;
+                let dry_run = Self::parse_optional_bool(args, "dry_run")?.unwrap_or(false);
+                let regex = crate::operations::find_in_files::build_regex(
+                    pattern, true, true, false, false,
+                )?;
+                if self.guard.is_denied(path) {
+                    return Self::not_found_error("read directory", path);
+                }
 
-                // Build the result array in original input order.
-                let stat_json_array: Vec<Value> = paths
-                    .iter()
-                    .map(|p| {
-                        if denied_set.contains(p) {
-                            // Sentinel: looks like a regular file with exists:true
-                            // so callers cannot distinguish denial from a real entry.
-                            crate::operations::stat::FileStat {
-                                path: p.clone(),
-                                entry_type: "file".to_string(),
-                                size: 0,
-                                mode: Some("0000".to_string()),
-                                modified: None,
-                                accessed: None,
-                                created: None,
-                                is_file: true,
-                                is_dir: false,
-                                is_symlink: false,
-                                exists: true,
-                            }
-                            .into()
-                        } else {
-                            real_results
-                                .remove(p)
-                                .unwrap_or_else(|| serde_json::json!({"path": p, "exists": false}))
-                        }
-                    })
-                    .collect();
+                let results = crate::operations::rename_pattern::rename_pattern(
+                    path,
+                    &regex,
+                    replacement,
+                    dry_run,
+                    &|p| self.guard.is_denied(&p.to_string_lossy()),
+                )?;
 
                 Ok(serde_json::json!({
                     "content": [{
                         "type": "text",
-                        "text": serde_json::to_string(&stat_json_array)
+                        "text": serde_json::to_string(&results)
                             .map_err(crate::error::FileIoMcpError::Json)?
                     }]
                 }))
             }
-            "fileio_make_directory" => {
-                let path_value = args.get("path").ok_or_else(|| {
+            "fileio_dir_checksum" => {
+                let path = args.get("path").and_then(|v| v.as_str()).ok_or_else(|| {
                     crate::error::McpError::InvalidToolParameters(
                         "Missing required parameter: path".to_string(),
                     )
                 })?;
-                let paths = Self::parse_paths(path_value)?;
-                let paths: Vec<String> = paths
-                    .into_iter()
-                    .filter(|p| !self.guard.is_denied(p))
-                    .collect();
-                if paths.is_empty() {
-                    return Self::silent_success("Directory(ies) created successfully");
+                if self.guard.is_denied(path) {
+                    return Self::not_found_error("checksum directory", path);
                 }
-                let path_refs: Vec<&str> = paths.iter().map(|s| s.as_str()).collect();
-                let recursive = Self::parse_optional_bool(args, "recursive")?.unwrap_or(true);
+                let exclude_glob = args.get("exclude_glob").and_then(|v| v.as_str());
 
-                crate::operations::mkdir::mkdir(&path_refs, recursive)?;
+                // The walk needs an owned filter, hence the clone.
+                let guard = self.guard.clone();
+                let result =
+                    crate::operations::checksum::dir_checksum(path, exclude_glob, move |p| {
+                        guard.is_denied(&p.to_string_lossy())
+                    })?;
 
                 Ok(serde_json::json!({
                     "content": [{
                         "type": "text",
-                        "text": "Directory(ies) created successfully"
+                        "text": serde_json::to_string(&result)
+                            .map_err(crate::error::FileIoMcpError::Json)?
                     }]
                 }))
             }
-            "fileio_list_directory" => {
+            "fileio_line_ending_style" => {
                 let path = args.get("path").and_then(|v| v.as_str()).ok_or_else(|| {
                     crate::error::McpError::InvalidToolParameters(
                         "Missing required parameter: path".to_string(),
                     )
                 })?;
                 if self.guard.is_denied(path) {
-                    return Self::not_found_error(path);
+                    return Self::not_found_error("read file", path);
                 }
-                let recursive = Self::parse_optional_bool(args, "recursive")?.unwrap_or(false);
-                let include_hidden =
-                    Self::parse_optional_bool(args, "include_hidden")?.unwrap_or(false);
 
-                let entries =
-                    crate::operations::list_dir::list_directory(path, recursive, include_hidden)?;
-                let entries_json: Vec<Value> = entries.into_iter().map(|e| e.into()).collect();
+                let result = crate::operations::line_endings::line_ending_style(path)?;
 
                 Ok(serde_json::json!({
                     "content": [{
                         "type": "text",
-                        "text": serde_json::to_string(&entries_json)
+                        "text": serde_json::to_string(&result)
                             .map_err(crate::error::FileIoMcpError::Json)?
                     }]
                 }))
             }
-            "fileio_find_files" => {
-                let pattern = args
-                    .get("pattern")
+            "fileio_prepend" => {
+                let path = args.get("path").and_then(|v| v.as_str()).ok_or_else(|| {
+                    crate::error::McpError::InvalidToolParameters(
+                        "Missing required parameter: path".to_string(),
+                    )
+                })?;
+                let content = args
+                    .get("content")
                     .and_then(|v| v.as_str())
                     .ok_or_else(|| {
                         crate::error::McpError::InvalidToolParameters(
-                            "Missing required parameter: pattern".to_string(),
+                            "Missing required parameter: content".to_string(),
                         )
                     })?;
-                let root = args.get("root").and_then(|v| v.as_str());
-                if let Some(root_path) = root
-                    && self.guard.is_denied(root_path)
-                {
-                    return Self::not_found_error(root_path);
-                }
-                let max_depth = Self::parse_optional_u64(args, "max_depth")?.map(|v| v as usize);
-                let file_type = args.get("file_type").and_then(|v| v.as_str());
 
-                let matches =
-                    crate::operations::file_find::file_find(pattern, root, max_depth, file_type)?;
-                let matches_json: Vec<Value> = matches.into_iter().map(|m| m.into()).collect();
+                let result = if self.guard.is_denied(path) {
+                    crate::operations::prepend::PrependResult {
+                        path: path.to_string(),
+                        created: false,
+                    }
+                } else {
+                    crate::operations::prepend::prepend(path, content)?
+                };
 
                 Ok(serde_json::json!({
                     "content": [{
                         "type": "text",
-                        "text": serde_json::to_string(&matches_json)
+                        "text": serde_json::to_string(&result)
                             .map_err(crate::error::FileIoMcpError::Json)?
                     }]
                 }))
             }
-            "fileio_find_in_files" => {
+            "fileio_expand_glob" => {
                 let pattern = args
                     .get("pattern")
                     .and_then(|v| v.as_str())
@@ -1037,88 +3565,105 @@ impl ToolRegistry {
                             "Missing required parameter: pattern".to_string(),
                         )
                     })?;
+                let recursive = Self::parse_optional_bool(args, "recursive")?.unwrap_or(false);
+
+                let paths: Vec<String> =
+                    crate::operations::glob_expand::expand_glob_paths(pattern, recursive)?
+                        .into_iter()
+                        .filter(|p| !self.guard.is_denied(p))
+                        .collect();
+
+                Ok(serde_json::json!({
+                    "content": [{
+                        "type": "text",
+                        "text": serde_json::to_string(&paths)
+                            .map_err(crate::error::FileIoMcpError::Json)?
+                    }]
+                }))
+            }
+            "fileio_watch_dir" => {
                 let path = args.get("path").and_then(|v| v.as_str()).ok_or_else(|| {
                     crate::error::McpError::InvalidToolParameters(
                         "Missing required parameter: path".to_string(),
                     )
                 })?;
+                let recursive = Self::parse_optional_bool(args, "recursive")?.unwrap_or(true);
+                let include = args
+                    .get("file_glob")
+                    .and_then(|v| v.as_str())
+                    .map(crate::operations::glob_expand::compile_glob)
+                    .transpose()?;
+                let exclude = args
+                    .get("exclude_glob")
+                    .and_then(|v| v.as_str())
+                    .map(crate::operations::glob_expand::compile_glob)
+                    .transpose()?;
+                let debounce_ms = Self::parse_optional_u64(args, "debounce_ms")?.unwrap_or(100);
+                let duration_ms = Self::parse_optional_u64(args, "duration_ms")?.unwrap_or(5000);
+                // Why a cap: the call blocks for the whole window, and an
+                // unbounded one would pin a worker indefinitely.
+                if duration_ms == 0 || duration_ms > 60_000 {
+                    return Err(crate::error::McpError::InvalidToolParameters(
+                        "duration_ms must be between 1 and 60000".to_string(),
+                    )
+                    .into());
+                }
                 if self.guard.is_denied(path) {
-                    return Self::not_found_error(path);
+                    return Self::not_found_error("watch directory", path);
                 }
-                let case_sensitive =
-                    Self::parse_optional_bool(args, "case_sensitive")?.unwrap_or(true);
-                let use_regex = Self::parse_optional_bool(args, "use_regex")?.unwrap_or(false);
-                let max_count = Self::parse_optional_u64(args, "max_count")?;
-                let max_depth = Self::parse_optional_u64(args, "max_depth")?.map(|v| v as usize);
-                let include_hidden =
-                    Self::parse_optional_bool(args, "include_hidden")?.unwrap_or(false);
-                let file_glob = args.get("file_glob").and_then(|v| v.as_str());
-                let exclude_glob = args.get("exclude_glob").and_then(|v| v.as_str());
-                let whole_word = Self::parse_optional_bool(args, "whole_word")?.unwrap_or(false);
-                let multiline = Self::parse_optional_bool(args, "multiline")?.unwrap_or(false);
 
-                let matches = crate::operations::find_in_files::find_in_files(
-                    &crate::operations::find_in_files::FindInFilesParams {
-                        pattern,
+                let events: Vec<crate::operations::watch_dir::WatchEvent> =
+                    crate::operations::watch_dir::watch_dir(
                         path,
-                        case_sensitive,
-                        use_regex,
-                        max_count,
-                        max_depth,
-                        include_hidden,
-                        file_glob,
-                        exclude_glob,
-                        whole_word,
-                        multiline,
-                    },
-                )?;
-                let matches_json: Vec<Value> = matches.into_iter().map(|m| m.into()).collect();
+                        &crate::operations::watch_dir::WatchOptions {
+                            recursive,
+                            include,
+                            exclude,
+                            debounce: std::time::Duration::from_millis(debounce_ms),
+                            duration: std::time::Duration::from_millis(duration_ms),
+                        },
+                    )?
+                    .into_iter()
+                    .filter(|e| !self.guard.is_denied(&e.path))
+                    .collect();
 
                 Ok(serde_json::json!({
                     "content": [{
                         "type": "text",
-                        "text": serde_json::to_string(&matches_json)
+                        "text": serde_json::to_string(&events)
                             .map_err(crate::error::FileIoMcpError::Json)?
                     }]
                 }))
             }
-            "fileio_edit_file" => {
-                // A malformed edit request is a parameter error (-32602), not an
-                // internal JSON fault — surface the coercion/shape message as such.
-                let req: crate::operations::edit_file::EditFileRequest = serde_json::from_value(
-                    serde_json::Value::Object(args.clone()),
-                )
-                .map_err(|e| crate::error::McpError::InvalidToolParameters(e.to_string()))?;
-
-                // Denied edits return a synthetic EditFileResult matching the
-                // real shape — `changed: false, applied_edits: 0` looks
-                // indistinguishable from a real edit where no patterns
-                // matched (a legitimate outcome with `require_match: false`).
-                // Returning plain text would create an oracle (real success
-                // returns serialized JSON; the LLM can map the deny-list with
-                // one no-op probe per path). Issue #3.
-                //
-                // Don't peek at on-disk state to decide between this synthetic
-                // and a real `NotFound` error — the existence check would
-                // itself leak whether the path exists.
-                if self.guard.is_denied(&req.path) {
-                    let synthetic = crate::operations::edit_file::EditFileResult {
-                        path: req.path.clone(),
-                        changed: false,
-                        applied_edits: 0,
-                        dry_run: req.dry_run,
-                        content: None,
-                    };
-                    return Ok(serde_json::json!({
-                        "content": [{
-                            "type": "text",
-                            "text": serde_json::to_string(&synthetic)
-                                .map_err(crate::error::FileIoMcpError::Json)?
-                        }]
-                    }));
+            "fileio_content_equals" => {
+                let path = args.get("path").and_then(|v| v.as_str()).ok_or_else(|| {
+                    crate::error::McpError::InvalidToolParameters(
+                        "Missing required parameter: path".to_string(),
+                    )
+                })?;
+                let content = args
+                    .get("content")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| {
+                        crate::error::McpError::InvalidToolParameters(
+                            "Missing required parameter: content".to_string(),
+                        )
+                    })?;
+                let options = crate::operations::content_equals::CompareOptions {
+                    ignore_trailing_whitespace: Self::parse_optional_bool(
+                        args,
+                        "ignore_trailing_whitespace",
+                    )?
+                    .unwrap_or(false),
+                    ignore_line_endings: Self::parse_optional_bool(args, "ignore_line_endings")?
+                        .unwrap_or(false),
+                };
+                if self.guard.is_denied(path) {
+                    return Self::not_found_error("compare file", path);
                 }
 
-                let result = crate::operations::edit_file::edit_file(req)?;
+                let result =
+                    crate::operations::content_equals::content_equals(path, content, &options)?;
 
                 Ok(serde_json::json!({
                     "content": [{
@@ -1128,491 +3673,514 @@ impl ToolRegistry {
                     }]
                 }))
             }
-            "fileio_copy" => {
-                let source_value = args.get("source").ok_or_else(|| {
+            "fileio_ensure_dir" => {
+                let path = args.get("path").and_then(|v| v.as_str()).ok_or_else(|| {
                     crate::error::McpError::InvalidToolParameters(
-                        "Missing required parameter: source".to_string(),
+                        "Missing required parameter: path".to_string(),
                     )
                 })?;
-                let sources = Self::parse_paths(source_value)?;
-                let destination = args
-                    .get("destination")
-                    .and_then(|v| v.as_str())
-                    .ok_or_else(|| {
-                        crate::error::McpError::InvalidToolParameters(
-                            "Missing required parameter: destination".to_string(),
-                        )
-                    })?;
+                let mode = args.get("mode").and_then(|v| v.as_str());
+                let mode_value = mode
+                    .map(crate::operations::file_mode::parse_mode)
+                    .transpose()?;
+                let fix_existing_mode =
+                    Self::parse_optional_bool(args, "fix_existing_mode")?.unwrap_or(true);
+
+                let result = if self.guard.is_denied(path) {
+                    crate::operations::mkdir::EnsureDirResult {
+                        path: path.to_string(),
+                        status: "unchanged".to_string(),
+                        mode: mode_value.map(|bits| format!("{:04o}", bits & 0o7777)),
+                    }
+                } else {
+                    crate::operations::mkdir::ensure_dir(path, mode, fix_existing_mode)?
+                };
 
-                // Two oracles to defeat (issue #3):
-                //   1. Real cp returns serialized Vec<OpResult>; the previous
-                //      silent-success path returned plain text. One probe
-                //      revealed denial.
-                //   2. Filtering denied sources individually and running cp
-                //      with the rest leaked which sources were denied via the
-                //      result count (input N, output N-1 means one was
-                //      filtered).
-                // Fix: if ANY input (source OR destination) is denied, build
-                // a synthetic Vec<OpResult> covering all sources verbatim and
-                // skip the real op entirely. Cost: legitimate calls that
-                // intentionally mix allowed + denied paths now no-op instead
-                // of partially succeeding — split the call to copy allowed
-                // paths.
-                let dest_denied = self.guard.is_denied(destination);
-                let any_source_denied = sources.iter().any(|s| self.guard.is_denied(s));
-                if dest_denied || any_source_denied {
-                    let synthetic = Self::synthesize_op_results(&sources);
-                    return Ok(serde_json::json!({
-                        "content": [{
-                            "type": "text",
-                            "text": serde_json::to_string(&synthetic)
-                                .map_err(crate::error::FileIoMcpError::Json)?
-                        }]
-                    }));
+                Ok(serde_json::json!({
+                    "content": [{
+                        "type": "text",
+                        "text": serde_json::to_string(&result)
+                            .map_err(crate::error::FileIoMcpError::Json)?
+                    }]
+                }))
+            }
+            "fileio_validate" => {
+                let path = args.get("path").and_then(|v| v.as_str()).ok_or_else(|| {
+                    crate::error::McpError::InvalidToolParameters(
+                        "Missing required parameter: path".to_string(),
+                    )
+                })?;
+                let format = args
+                    .get("format")
+                    .and_then(|v| v.as_str())
+                    .map(crate::operations::validate::DataFormat::parse)
+                    .transpose()?;
+                if self.guard.is_denied(path) {
+                    return Self::not_found_error("validate file", path);
                 }
 
-                let source_refs: Vec<&str> = sources.iter().map(|s| s.as_str()).collect();
-                let recursive = Self::parse_optional_bool(args, "recursive")?.unwrap_or(false);
+                let result = crate::operations::validate::validate(path, format)?;
 
-                let results = crate::operations::cp::cp(&source_refs, destination, recursive)?;
                 Ok(serde_json::json!({
                     "content": [{
                         "type": "text",
-                        "text": serde_json::to_string(&results)
+                        "text": serde_json::to_string(&result)
                             .map_err(crate::error::FileIoMcpError::Json)?
                     }]
                 }))
             }
-            "fileio_move" => {
-                let source_value = args.get("source").ok_or_else(|| {
+            "fileio_format_json" => {
+                let path = args.get("path").and_then(|v| v.as_str()).ok_or_else(|| {
                     crate::error::McpError::InvalidToolParameters(
-                        "Missing required parameter: source".to_string(),
+                        "Missing required parameter: path".to_string(),
                     )
                 })?;
-                let sources = Self::parse_paths(source_value)?;
-                let destination = args
-                    .get("destination")
-                    .and_then(|v| v.as_str())
-                    .ok_or_else(|| {
-                        crate::error::McpError::InvalidToolParameters(
-                            "Missing required parameter: destination".to_string(),
-                        )
-                    })?;
-
-                // Same oracle defeat as fileio_copy (issue #3): synthesize the
-                // full Vec<OpResult> shape on any denial, no real op runs.
-                let dest_denied = self.guard.is_denied(destination);
-                let any_source_denied = sources.iter().any(|s| self.guard.is_denied(s));
-                if dest_denied || any_source_denied {
-                    let synthetic = Self::synthesize_op_results(&sources);
-                    return Ok(serde_json::json!({
-                        "content": [{
-                            "type": "text",
-                            "text": serde_json::to_string(&synthetic)
-                                .map_err(crate::error::FileIoMcpError::Json)?
-                        }]
-                    }));
+                let minify = Self::parse_optional_bool(args, "minify")?.unwrap_or(false);
+                let indent = Self::parse_optional_u64(args, "indent")?.unwrap_or(2);
+                if indent > crate::operations::format_json::MAX_INDENT as u64 {
+                    return Err(crate::error::McpError::InvalidToolParameters(format!(
+                        "indent must be at most {}",
+                        crate::operations::format_json::MAX_INDENT
+                    ))
+                    .into());
                 }
+                let style = if minify {
+                    crate::operations::format_json::JsonStyle::Minified
+                } else {
+                    crate::operations::format_json::JsonStyle::Pretty {
+                        indent: indent as usize,
+                    }
+                };
+                let result = if self.guard.is_denied(path) {
+                    crate::operations::format_json::FormatJsonResult {
+                        path: path.to_string(),
+                        changed: false,
+                        bytes: 0,
+                    }
+                } else {
+                    crate::operations::format_json::format_json(path, style)?
+                };
 
-                let source_refs: Vec<&str> = sources.iter().map(|s| s.as_str()).collect();
-
-                let results = crate::operations::mv::mv(&source_refs, destination)?;
                 Ok(serde_json::json!({
                     "content": [{
                         "type": "text",
-                        "text": serde_json::to_string(&results)
+                        "text": serde_json::to_string(&result)
                             .map_err(crate::error::FileIoMcpError::Json)?
                     }]
                 }))
             }
-            "fileio_remove" => {
-                let path_value = args.get("path").ok_or_else(|| {
+            "fileio_magic" => {
+                let path = args.get("path").and_then(|v| v.as_str()).ok_or_else(|| {
                     crate::error::McpError::InvalidToolParameters(
                         "Missing required parameter: path".to_string(),
                     )
                 })?;
-                let paths = Self::parse_paths(path_value)?;
-
-                // Issue #3: synthesize Vec<OpResult> for denied calls so the
-                // response shape matches a real rm. Otherwise the LLM can
-                // probe paths via rm and detect denial (real returns JSON
-                // results; old silent-success returned plain text).
-                if paths.iter().any(|p| self.guard.is_denied(p)) {
-                    let synthetic = Self::synthesize_op_results(&paths);
-                    return Ok(serde_json::json!({
-                        "content": [{
-                            "type": "text",
-                            "text": serde_json::to_string(&synthetic)
-                                .map_err(crate::error::FileIoMcpError::Json)?
-                        }]
-                    }));
+                let count = Self::parse_optional_u64(args, "count")?
+                    .map(|v| v as usize)
+                    .unwrap_or(crate::operations::magic::DEFAULT_MAGIC_BYTES);
+                if count == 0 || count > crate::operations::magic::MAX_MAGIC_BYTES {
+                    return Err(crate::error::McpError::InvalidToolParameters(format!(
+                        "count must be between 1 and {}",
+                        crate::operations::magic::MAX_MAGIC_BYTES
+                    ))
+                    .into());
+                }
+                if self.guard.is_denied(path) {
+                    return Self::not_found_error("read file", path);
                 }
 
-                let path_refs: Vec<&str> = paths.iter().map(|s| s.as_str()).collect();
-                let recursive = Self::parse_optional_bool(args, "recursive")?.unwrap_or(false);
-                let force = Self::parse_optional_bool(args, "force")?.unwrap_or(false);
+                let result = crate::operations::magic::magic(path, count)?;
 
-                let results = crate::operations::rm::rm(&path_refs, recursive, force)?;
                 Ok(serde_json::json!({
                     "content": [{
                         "type": "text",
-                        "text": serde_json::to_string(&results)
+                        "text": serde_json::to_string(&result)
                             .map_err(crate::error::FileIoMcpError::Json)?
                     }]
                 }))
             }
-            "fileio_remove_directory" => {
-                let path_value = args.get("path").ok_or_else(|| {
+            "fileio_transaction" => {
+                let steps = args.get("steps").ok_or_else(|| {
                     crate::error::McpError::InvalidToolParameters(
-                        "Missing required parameter: path".to_string(),
+                        "Missing required parameter: steps".to_string(),
                     )
                 })?;
-                let paths = Self::parse_paths(path_value)?;
-
-                // Issue #3: same shape-matching as fileio_remove.
-                if paths.iter().any(|p| self.guard.is_denied(p)) {
-                    let synthetic = Self::synthesize_op_results(&paths);
-                    return Ok(serde_json::json!({
-                        "content": [{
-                            "type": "text",
-                            "text": serde_json::to_string(&synthetic)
-                                .map_err(crate::error::FileIoMcpError::Json)?
-                        }]
-                    }));
+                let steps: Vec<crate::operations::transaction::TxStep> =
+                    serde_json::from_value(steps.clone()).map_err(|e| {
+                        crate::error::McpError::InvalidToolParameters(format!(
+                            "Invalid steps: {}",
+                            e
+                        ))
+                    })?;
+                if steps.is_empty() {
+                    return Err(crate::error::McpError::InvalidToolParameters(
+                        "steps must contain at least one operation".to_string(),
+                    )
+                    .into());
                 }
+                // A denied path anywhere in the batch yields a synthetic
+                // commit of the same shape, so the reply doesn't reveal which
+                // step touched the deny list.
+                let denied = steps
+                    .iter()
+                    .flat_map(|step| step.paths())
+                    .any(|path| self.guard.is_denied(path));
+                let result = if denied {
+                    crate::operations::transaction::TransactionResult {
+                        committed: true,
+                        applied: steps.len(),
+                        failed_step: None,
+                        error: None,
+                        cleanup_errors: Vec::new(),
+                    }
+                } else {
+                    crate::operations::transaction::transaction(&steps)?
+                };
 
-                let path_refs: Vec<&str> = paths.iter().map(|s| s.as_str()).collect();
-                let recursive = Self::parse_optional_bool(args, "recursive")?.unwrap_or(false);
+                Ok(serde_json::json!({
+                    "content": [{
+                        "type": "text",
+                        "text": serde_json::to_string(&result)
+                            .map_err(crate::error::FileIoMcpError::Json)?
+                    }]
+                }))
+            }
+            "fileio_temp_info" => {
+                let info = crate::operations::temp_info::temp_info()?;
 
-                let results = crate::operations::rmdir::rmdir(&path_refs, recursive)?;
                 Ok(serde_json::json!({
                     "content": [{
                         "type": "text",
-                        "text": serde_json::to_string(&results)
+                        "text": serde_json::to_string(&info)
                             .map_err(crate::error::FileIoMcpError::Json)?
                     }]
                 }))
             }
-            "fileio_create_hard_link" => {
-                let target = args.get("target").and_then(|v| v.as_str()).ok_or_else(|| {
+            "fileio_allocate" => {
+                let path = args.get("path").and_then(|v| v.as_str()).ok_or_else(|| {
                     crate::error::McpError::InvalidToolParameters(
-                        "Missing required parameter: target".to_string(),
+                        "Missing required parameter: path".to_string(),
                     )
                 })?;
-                let link_path =
-                    args.get("link_path")
-                        .and_then(|v| v.as_str())
-                        .ok_or_else(|| {
-                            crate::error::McpError::InvalidToolParameters(
-                                "Missing required parameter: link_path".to_string(),
-                            )
-                        })?;
-                if self.guard.is_denied(target) {
-                    return Self::not_found_error(target);
-                }
-                if self.guard.is_denied(link_path) {
-                    return Self::silent_success("Hard link created successfully");
-                }
-
-                crate::operations::link::hard_link(target, link_path)?;
+                let size = Self::parse_optional_u64(args, "size")?.ok_or_else(|| {
+                    crate::error::McpError::InvalidToolParameters(
+                        "Missing required parameter: size".to_string(),
+                    )
+                })?;
+                let preallocate = Self::parse_optional_bool(args, "preallocate")?.unwrap_or(true);
+                let result = if self.guard.is_denied(path) {
+                    crate::operations::allocate::AllocateResult {
+                        path: path.to_string(),
+                        size,
+                        created: true,
+                        preallocated: preallocate && size > 0 && cfg!(target_os = "linux"),
+                    }
+                } else {
+                    crate::operations::allocate::allocate(path, size, preallocate)?
+                };
 
                 Ok(serde_json::json!({
                     "content": [{
                         "type": "text",
-                        "text": "Hard link created successfully"
+                        "text": serde_json::to_string(&result)
+                            .map_err(crate::error::FileIoMcpError::Json)?
                     }]
                 }))
             }
-            "fileio_create_symbolic_link" => {
-                let target = args.get("target").and_then(|v| v.as_str()).ok_or_else(|| {
+            "fileio_offset_to_position" => {
+                let path = args.get("path").and_then(|v| v.as_str()).ok_or_else(|| {
                     crate::error::McpError::InvalidToolParameters(
-                        "Missing required parameter: target".to_string(),
+                        "Missing required parameter: path".to_string(),
                     )
                 })?;
-                let link_path =
-                    args.get("link_path")
-                        .and_then(|v| v.as_str())
-                        .ok_or_else(|| {
-                            crate::error::McpError::InvalidToolParameters(
-                                "Missing required parameter: link_path".to_string(),
-                            )
-                        })?;
-                if self.guard.is_denied(target) {
-                    return Self::not_found_error(target);
-                }
-                if self.guard.is_denied(link_path) {
-                    return Self::silent_success("Symbolic link created successfully");
+                let offset = Self::parse_optional_u64(args, "offset")?.ok_or_else(|| {
+                    crate::error::McpError::InvalidToolParameters(
+                        "Missing required parameter: offset".to_string(),
+                    )
+                })?;
+                if self.guard.is_denied(path) {
+                    return Self::not_found_error("read file", path);
                 }
 
-                crate::operations::link::symlink(target, link_path)?;
+                let result = crate::operations::position::offset_to_position(path, offset)?;
 
                 Ok(serde_json::json!({
                     "content": [{
                         "type": "text",
-                        "text": "Symbolic link created successfully"
+                        "text": serde_json::to_string(&result)
+                            .map_err(crate::error::FileIoMcpError::Json)?
                     }]
                 }))
             }
-            "fileio_get_basename" => {
+            "fileio_position_to_offset" => {
                 let path = args.get("path").and_then(|v| v.as_str()).ok_or_else(|| {
                     crate::error::McpError::InvalidToolParameters(
                         "Missing required parameter: path".to_string(),
                     )
                 })?;
+                let line = Self::parse_optional_u64(args, "line")?.ok_or_else(|| {
+                    crate::error::McpError::InvalidToolParameters(
+                        "Missing required parameter: line".to_string(),
+                    )
+                })?;
+                let column = Self::parse_optional_u64(args, "column")?.ok_or_else(|| {
+                    crate::error::McpError::InvalidToolParameters(
+                        "Missing required parameter: column".to_string(),
+                    )
+                })?;
                 if self.guard.is_denied(path) {
-                    return Self::not_found_error(path);
+                    return Self::not_found_error("read file", path);
                 }
 
-                let basename = crate::operations::path_utils::basename(path)?;
+                let result = crate::operations::position::position_to_offset(path, line, column)?;
 
                 Ok(serde_json::json!({
                     "content": [{
                         "type": "text",
-                        "text": basename
+                        "text": serde_json::to_string(&result)
+                            .map_err(crate::error::FileIoMcpError::Json)?
                     }]
                 }))
             }
-            "fileio_get_dirname" => {
+            "fileio_disk_usage" => {
                 let path = args.get("path").and_then(|v| v.as_str()).ok_or_else(|| {
                     crate::error::McpError::InvalidToolParameters(
                         "Missing required parameter: path".to_string(),
                     )
                 })?;
+                let max_depth = Self::parse_optional_u64(args, "max_depth")?.map(|v| v as usize);
+                let exclude_glob = args.get("exclude_glob").and_then(|v| v.as_str());
                 if self.guard.is_denied(path) {
-                    return Self::not_found_error(path);
+                    return Self::not_found_error("read metadata for", path);
                 }
 
-                let dirname = crate::operations::path_utils::dirname(path)?;
+                let result = crate::operations::disk_usage::disk_usage(
+                    path,
+                    &crate::operations::disk_usage::DiskUsageOptions {
+                        max_depth,
+                        exclude_glob,
+                    },
+                    &|p| self.guard.is_denied(&p.to_string_lossy()),
+                )?;
 
                 Ok(serde_json::json!({
                     "content": [{
                         "type": "text",
-                        "text": dirname
+                        "text": serde_json::to_string(&result)
+                            .map_err(crate::error::FileIoMcpError::Json)?
                     }]
                 }))
             }
-            "fileio_get_canonical_path" => {
+            "fileio_is_within" => {
+                let base = args.get("base").and_then(|v| v.as_str()).ok_or_else(|| {
+                    crate::error::McpError::InvalidToolParameters(
+                        "Missing required parameter: base".to_string(),
+                    )
+                })?;
                 let path = args.get("path").and_then(|v| v.as_str()).ok_or_else(|| {
                     crate::error::McpError::InvalidToolParameters(
                         "Missing required parameter: path".to_string(),
                     )
                 })?;
+                if self.guard.is_denied(base) {
+                    return Self::not_found_error("open file", base);
+                }
                 if self.guard.is_denied(path) {
-                    return Self::not_found_error(path);
+                    return Self::not_found_error("open file", path);
                 }
 
-                let realpath = crate::operations::path_utils::realpath(path)?;
+                let result = crate::operations::path_utils::is_within(base, path)?;
 
                 Ok(serde_json::json!({
                     "content": [{
                         "type": "text",
-                        "text": realpath
+                        "text": serde_json::to_string(&result)
+                            .map_err(crate::error::FileIoMcpError::Json)?
                     }]
                 }))
             }
-            "fileio_read_symbolic_link" => {
+            "fileio_tail_follow" => {
                 let path = args.get("path").and_then(|v| v.as_str()).ok_or_else(|| {
                     crate::error::McpError::InvalidToolParameters(
                         "Missing required parameter: path".to_string(),
                     )
                 })?;
+                let lines = Self::parse_optional_u64(args, "lines")?.unwrap_or(10) as usize;
+                let max_lines = Self::parse_optional_u64(args, "max_lines")?.map(|v| v as usize);
+                let duration_ms = Self::parse_optional_u64(args, "duration_ms")?.unwrap_or(5000);
+                // Same cap as fileio_watch_dir: the call blocks for the window.
+                if duration_ms == 0 || duration_ms > 60_000 {
+                    return Err(crate::error::McpError::InvalidToolParameters(
+                        "duration_ms must be between 1 and 60000".to_string(),
+                    )
+                    .into());
+                }
                 if self.guard.is_denied(path) {
-                    return Self::not_found_error(path);
+                    return Self::not_found_error("follow file", path);
                 }
 
-                let target = crate::operations::path_utils::readlink(path)?;
+                let result = crate::operations::tail_follow::tail_follow(
+                    path,
+                    &crate::operations::tail_follow::TailOptions {
+                        lines,
+                        duration: std::time::Duration::from_millis(duration_ms),
+                        max_lines,
+                        ..crate::operations::tail_follow::TailOptions::default()
+                    },
+                )?;
 
                 Ok(serde_json::json!({
                     "content": [{
                         "type": "text",
-                        "text": target
+                        "text": serde_json::to_string(&result)
+                            .map_err(crate::error::FileIoMcpError::Json)?
                     }]
                 }))
             }
-            "fileio_create_temporary" => {
-                let temp_type = args.get("type").and_then(|v| v.as_str()).ok_or_else(|| {
+            "fileio_bom" => {
+                let path = args.get("path").and_then(|v| v.as_str()).ok_or_else(|| {
                     crate::error::McpError::InvalidToolParameters(
-                        "Missing required parameter: type".to_string(),
+                        "Missing required parameter: path".to_string(),
                     )
                 })?;
-                let template = args.get("template").and_then(|v| v.as_str());
-
-                // mktemp_{file,dir} uses the template's parent directory as the
-                // creation site. A template like `/etc/security/probe-XXXXXX`
-                // would happily create a file inside a protected directory if
-                // we don't check first. No directory check needed when the
-                // template is None or has no path component — those land in
-                // $TMPDIR (typically /tmp) which the deny-list does not cover.
-                if let Some(t) = template
-                    && t.contains('/')
-                    && self.guard.is_denied(t)
+                let strip = match args
+                    .get("mode")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("detect")
                 {
-                    return Self::silent_success(match temp_type {
-                        "file" => "Temporary file created",
-                        "dir" => "Temporary directory created",
-                        _ => "Temporary created",
-                    });
-                }
-
-                let path = match temp_type {
-                    "file" => crate::operations::mktemp::mktemp_file(template)?,
-                    "dir" => crate::operations::mktemp::mktemp_dir(template)?,
-                    _ => {
+                    "detect" => false,
+                    "strip" => true,
+                    other => {
                         return Err(crate::error::McpError::InvalidToolParameters(format!(
-                            "Invalid type: {} (must be 'file' or 'dir')",
-                            temp_type
+                            "mode must be 'detect' or 'strip', got '{}'",
+                            other
                         ))
                         .into());
                     }
                 };
+                // Both modes read the file first, so a denied path looks
+                // missing either way.
+                if self.guard.is_denied(path) {
+                    return Self::not_found_error("read file", path);
+                }
+
+                let result = crate::operations::bom::bom(path, strip)?;
 
                 Ok(serde_json::json!({
                     "content": [{
                         "type": "text",
-                        "text": path
+                        "text": serde_json::to_string(&result)
+                            .map_err(crate::error::FileIoMcpError::Json)?
                     }]
                 }))
             }
-            "fileio_change_ownership" => {
-                let path_value = args.get("path").ok_or_else(|| {
+            "fileio_read_file" => {
+                let path = args.get("path").and_then(|v| v.as_str()).ok_or_else(|| {
                     crate::error::McpError::InvalidToolParameters(
                         "Missing required parameter: path".to_string(),
                     )
                 })?;
-                let paths = Self::parse_paths(path_value)?;
-                let paths: Vec<String> = paths
-                    .into_iter()
-                    .filter(|p| !self.guard.is_denied(p))
-                    .collect();
-                if paths.is_empty() {
-                    return Self::silent_success("Ownership changed successfully");
+                let max_bytes = Self::parse_optional_u64(args, "max_bytes")?
+                    .unwrap_or(crate::operations::read_file::DEFAULT_MAX_BYTES);
+                let binary = Self::parse_optional_bool(args, "binary")?.unwrap_or(false);
+                if self.guard.is_denied(path) {
+                    return Self::not_found_error("open file", path);
                 }
-                let path_refs: Vec<&str> = paths.iter().map(|s| s.as_str()).collect();
-                let user = args.get("user").and_then(|v| v.as_str());
-                let group = args.get("group").and_then(|v| v.as_str());
-
-                crate::operations::chown::chown(&path_refs, user, group)?;
+                let text = if binary {
+                    serde_json::to_string(&crate::operations::read_file::read_file_binary(
+                        path, max_bytes,
+                    )?)
+                } else {
+                    serde_json::to_string(&crate::operations::read_file::read_file(
+                        path, max_bytes,
+                    )?)
+                }
+                .map_err(crate::error::FileIoMcpError::Json)?;
 
                 Ok(serde_json::json!({
                     "content": [{
                         "type": "text",
-                        "text": "Ownership changed successfully"
+                        "text": text
                     }]
                 }))
             }
-            "fileio_get_current_directory" => {
-                let cwd = crate::operations::pwd::pwd()?;
+            "fileio_head" => {
+                let path = args.get("path").and_then(|v| v.as_str()).ok_or_else(|| {
+                    crate::error::McpError::InvalidToolParameters(
+                        "Missing required parameter: path".to_string(),
+                    )
+                })?;
+                let line_count = Self::parse_optional_u64(args, "line_count")?
+                    .unwrap_or(crate::operations::head::DEFAULT_HEAD_LINES);
+                if self.guard.is_denied(path) {
+                    return Self::not_found_error("open file", path);
+                }
+                let lines = crate::operations::head::head(path, line_count)?;
 
                 Ok(serde_json::json!({
                     "content": [{
                         "type": "text",
-                        "text": cwd
+                        "text": serde_json::to_string(&lines)
+                            .map_err(crate::error::FileIoMcpError::Json)?
                     }]
                 }))
             }
-            "fileio_count_lines" => {
-                let path_value = args.get("path").ok_or_else(|| {
+            "fileio_read_bytes" => {
+                let path = args.get("path").and_then(|v| v.as_str()).ok_or_else(|| {
                     crate::error::McpError::InvalidToolParameters(
                         "Missing required parameter: path".to_string(),
                     )
                 })?;
-                let paths = Self::parse_paths(path_value)?;
-                // Partial-denial oracle fix (issue #6): run count_lines only on
-                // allowed paths then re-merge sentinels for denied paths so the
-                // output array length always equals the input length.
-                let (allowed, denied_set) = self.partition_by_guard(&paths);
-                let allowed_refs: Vec<&str> = allowed.iter().map(|s| s.as_str()).collect();
-
-                let mut real_map: std::collections::HashMap<
-                    String,
-                    crate::operations::count_lines::LineCountResult,
-                > = crate::operations::count_lines::count_lines(&allowed_refs)?
-                    .into_iter()
-                    .map(|r| (r.path.clone(), r))
-                    .collect();
-
-                let counts: Vec<crate::operations::count_lines::LineCountResult> = paths
-                    .iter()
-                    .map(|p| {
-                        if denied_set.contains(p) {
-                            crate::operations::count_lines::LineCountResult {
-                                path: p.clone(),
-                                status: "ok".to_string(),
-                                lines: Some(0),
-                                exists: true,
-                            }
-                        } else {
-                            real_map.remove(p).unwrap_or_else(|| {
-                                crate::operations::count_lines::LineCountResult {
-                                    path: p.clone(),
-                                    status: "error: not found".to_string(),
-                                    lines: None,
-                                    exists: false,
-                                }
-                            })
-                        }
-                    })
-                    .collect();
-
-                let counts_json =
-                    serde_json::to_string(&counts).map_err(crate::error::FileIoMcpError::Json)?;
+                let offset = Self::parse_optional_u64(args, "offset")?.ok_or_else(|| {
+                    crate::error::McpError::InvalidToolParameters(
+                        "Missing required parameter: offset".to_string(),
+                    )
+                })?;
+                let length = Self::parse_optional_u64(args, "length")?.ok_or_else(|| {
+                    crate::error::McpError::InvalidToolParameters(
+                        "Missing required parameter: length".to_string(),
+                    )
+                })?;
+                if self.guard.is_denied(path) {
+                    return Self::not_found_error("open file", path);
+                }
+                let result = crate::operations::read_bytes::read_bytes(path, offset, length)?;
 
                 Ok(serde_json::json!({
                     "content": [{
                         "type": "text",
-                        "text": counts_json
+                        "text": serde_json::to_string(&result)
+                            .map_err(crate::error::FileIoMcpError::Json)?
                     }]
                 }))
             }
-            "fileio_count_words" => {
-                let path_value = args.get("path").ok_or_else(|| {
+            "fileio_truncate" => {
+                let path = args.get("path").and_then(|v| v.as_str()).ok_or_else(|| {
                     crate::error::McpError::InvalidToolParameters(
                         "Missing required parameter: path".to_string(),
                     )
                 })?;
-                let paths = Self::parse_paths(path_value)?;
-                // Partial-denial oracle fix (issue #6): same pattern as count_lines.
-                let (allowed, denied_set) = self.partition_by_guard(&paths);
-                let allowed_refs: Vec<&str> = allowed.iter().map(|s| s.as_str()).collect();
-
-                let mut real_map: std::collections::HashMap<
-                    String,
-                    crate::operations::count_words::WordCountResult,
-                > = crate::operations::count_words::count_words(&allowed_refs)?
-                    .into_iter()
-                    .map(|r| (r.path.clone(), r))
-                    .collect();
-
-                let counts: Vec<crate::operations::count_words::WordCountResult> = paths
-                    .iter()
-                    .map(|p| {
-                        if denied_set.contains(p) {
-                            crate::operations::count_words::WordCountResult {
-                                path: p.clone(),
-                                status: "ok".to_string(),
-                                words: Some(0),
-                                exists: true,
-                            }
-                        } else {
-                            real_map.remove(p).unwrap_or_else(|| {
-                                crate::operations::count_words::WordCountResult {
-                                    path: p.clone(),
-                                    status: "error: not found".to_string(),
-                                    words: None,
-                                    exists: false,
-                                }
-                            })
-                        }
-                    })
-                    .collect();
-
-                let counts_json =
-                    serde_json::to_string(&counts).map_err(crate::error::FileIoMcpError::Json)?;
+                let size = Self::parse_optional_u64(args, "size")?.ok_or_else(|| {
+                    crate::error::McpError::InvalidToolParameters(
+                        "Missing required parameter: size".to_string(),
+                    )
+                })?;
+                let result = if self.guard.is_denied(path) {
+                    crate::operations::truncate::TruncateResult {
+                        path: crate::operations::path_utils::resolve_path(path)
+                            .unwrap_or_else(|_| path.to_string()),
+                        previous_size: size,
+                        size,
+                    }
+                } else {
+                    crate::operations::truncate::truncate(path, size)?
+                };
 
                 Ok(serde_json::json!({
                     "content": [{
                         "type": "text",
-                        "text": counts_json
+                        "text": serde_json::to_string(&result)
+                            .map_err(crate::error::FileIoMcpError::Json)?
                     }]
                 }))
             }
@@ -1665,6 +4233,61 @@ mod tests {
     /// (`200.0`) or a decimal string (`"200"`) — as LLM tool-call plumbing
     /// frequently encodes it — must be accepted, not rejected with the
     /// contradictory "must be a non-negative integer".
+    #[tokio::test]
+    async fn read_lines_tail_reverse_and_line_numbers() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, "a\nb\nc").unwrap();
+        let path = file.path().to_str().unwrap();
+
+        let registry = ToolRegistry::new();
+        let args = serde_json::json!({"path": path, "tail": 2});
+        let res = registry
+            .execute_tool("fileio_read_lines", &args)
+            .await
+            .unwrap();
+        assert_eq!(res["content"][0]["text"], r#"["b","c"]"#);
+
+        let args = serde_json::json!({"path": path, "tail": 2, "reverse": true});
+        let res = registry
+            .execute_tool("fileio_read_lines", &args)
+            .await
+            .unwrap();
+        assert_eq!(res["content"][0]["text"], r#"["c","b"]"#);
+
+        let args =
+            serde_json::json!({"path": path, "start_line": 1, "line_count": 2, "reverse": true});
+        let res = registry
+            .execute_tool("fileio_read_lines", &args)
+            .await
+            .unwrap();
+        assert_eq!(res["content"][0]["text"], r#"["b","a"]"#);
+
+        let args = serde_json::json!({"path": path, "start_offset": 1, "with_line_numbers": true});
+        let res = registry
+            .execute_tool("fileio_read_lines", &args)
+            .await
+            .unwrap();
+        assert_eq!(
+            res["content"][0]["text"],
+            r#"[{"line_number":2,"content":"b"},{"line_number":3,"content":"c"}]"#
+        );
+
+        let args = serde_json::json!({"path": path, "tail": 1, "with_line_numbers": true});
+        let res = registry
+            .execute_tool("fileio_read_lines", &args)
+            .await
+            .unwrap();
+        assert_eq!(
+            res["content"][0]["text"],
+            r#"[{"line_number":3,"content":"c"}]"#
+        );
+
+        let args = serde_json::json!({"path": path, "tail": 2, "start_line": 1});
+        let res = registry.execute_tool("fileio_read_lines", &args).await;
+        let msg = format!("{}", res.err().unwrap());
+        assert!(msg.contains("tail"), "got: {msg}");
+    }
+
     #[tokio::test]
     async fn read_lines_accepts_float_and_string_start_line() {
         let mut file = NamedTempFile::new().unwrap();
@@ -1918,6 +4541,370 @@ mod tests {
         let _ = std::fs::remove_dir_all(&dir);
     }
 
+    /// Recursive chmod of an ancestor must not reach into a denied directory,
+    /// and a denied input must still get an entry (issue #6).
+    #[tokio::test]
+    async fn recursive_set_permissions_skips_denied_subtree() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = std::env::temp_dir().join("fileio_deny_chmod_tree_test");
+        let _ = std::fs::remove_dir_all(&dir);
+        let secret = dir.join("secret");
+        std::fs::create_dir_all(&secret).unwrap();
+        std::fs::write(secret.join("key"), "k").unwrap();
+        std::fs::write(dir.join("open.txt"), "o").unwrap();
+        for file in [secret.join("key"), dir.join("open.txt")] {
+            std::fs::set_permissions(&file, std::fs::Permissions::from_mode(0o644)).unwrap();
+        }
+
+        let registry = registry_blocking(secret.to_str().unwrap());
+        let args = serde_json::json!({
+            "path": [dir.to_str().unwrap(), secret.to_str().unwrap()],
+            "mode": "700",
+            "recursive": true,
+        });
+        let res = registry
+            .execute_tool("fileio_set_permissions", &args)
+            .await
+            .unwrap();
+        let body: serde_json::Value =
+            serde_json::from_str(res["content"][0]["text"].as_str().unwrap()).unwrap();
+        let paths: Vec<&str> = body["changes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| c["path"].as_str().unwrap())
+            .collect();
+
+        assert!(!paths.contains(&secret.join("key").to_str().unwrap()));
+        assert!(paths.contains(&secret.to_str().unwrap()));
+        assert!(paths.contains(&dir.join("open.txt").to_str().unwrap()));
+        let mode = |p: &std::path::Path| std::fs::metadata(p).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&secret.join("key")), 0o644);
+        assert_eq!(mode(&dir.join("open.txt")), 0o700);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    /// A denied write's synthetic result must be one a real write with the
+    /// same `if_exists` could produce.
+    #[tokio::test]
+    async fn denied_write_result_follows_if_exists() {
+        let dir = std::env::temp_dir().join("fileio_deny_write_shape_test");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let target = dir.join("f.txt");
+        let registry = registry_blocking(dir.to_str().unwrap());
+        let write = |if_exists: &str| {
+            let args = serde_json::json!({
+                "path": target.to_str().unwrap(),
+                "content": "abc",
+                "if_exists": if_exists,
+            });
+            let registry = &registry;
+            async move {
+                let res = registry
+                    .execute_tool("fileio_write_file", &args)
+                    .await
+                    .unwrap();
+                serde_json::from_str::<serde_json::Value>(
+                    res["content"][0]["text"].as_str().unwrap(),
+                )
+                .unwrap()
+            }
+        };
+
+        // 'error' only succeeds by creating the file.
+        let outcome = write("error").await;
+        assert_eq!(outcome["written"], true);
+        assert_eq!(outcome["created"], true);
+        assert_eq!(outcome["bytes_written"], 3);
+
+        // 'skip' succeeds without writing anything.
+        let outcome = write("skip").await;
+        assert_eq!(outcome["written"], false);
+        assert_eq!(outcome["created"], false);
+        assert_eq!(outcome["bytes_written"], 0);
+
+        // 'overwrite' replaces an existing file.
+        let outcome = write("overwrite").await;
+        assert_eq!(outcome["written"], true);
+        assert_eq!(outcome["created"], false);
+        assert_eq!(outcome["bytes_written"], 3);
+
+        assert!(!target.exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    /// A backup suffix that lands the backup on a denied file is handled
+    /// like a write to that file: nothing on disk changes.
+    #[tokio::test]
+    async fn write_with_denied_backup_target_is_synthesized() {
+        let dir = std::env::temp_dir().join("fileio_deny_backup_target_test");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let target = dir.join("confi");
+        let secret = dir.join("config");
+        std::fs::write(&target, "decoy").unwrap();
+        std::fs::write(&secret, "real secret").unwrap();
+
+        let guard = PathGuard::new(&[secret.to_str().unwrap().to_string()], None);
+        let registry = ToolRegistry::with_guard(guard);
+        let args = serde_json::json!({
+            "path": target.to_str().unwrap(),
+            "content": "new",
+            "backup_suffix": "g",
+        });
+        let res = registry
+            .execute_tool("fileio_write_file", &args)
+            .await
+            .unwrap();
+        let outcome: serde_json::Value =
+            serde_json::from_str(res["content"][0]["text"].as_str().unwrap()).unwrap();
+
+        assert_eq!(outcome["written"], true);
+        assert_eq!(outcome["backup"], secret.to_str().unwrap());
+        assert_eq!(std::fs::read_to_string(&secret).unwrap(), "real secret");
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "decoy");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    /// A verified copy into a denied destination reports the source's real
+    /// digest, which the caller can read for itself; directories get none.
+    #[tokio::test]
+    async fn denied_verified_copy_reports_real_source_digest() {
+        let dir = std::env::temp_dir().join("fileio_deny_copy_verify_test");
+        let _ = std::fs::remove_dir_all(&dir);
+        let denied = dir.join("denied");
+        std::fs::create_dir_all(&denied).unwrap();
+        let source = dir.join("src.txt");
+        std::fs::write(&source, "payload").unwrap();
+        let subdir = dir.join("tree");
+        std::fs::create_dir_all(&subdir).unwrap();
+
+        let registry = registry_blocking(denied.to_str().unwrap());
+        let args = serde_json::json!({
+            "source": [source.to_str().unwrap(), subdir.to_str().unwrap()],
+            "destination": denied.to_str().unwrap(),
+            "verify": true,
+            "recursive": true,
+        });
+        let res = registry.execute_tool("fileio_copy", &args).await.unwrap();
+        let body: serde_json::Value =
+            serde_json::from_str(res["content"][0]["text"].as_str().unwrap()).unwrap();
+
+        assert_eq!(
+            body[0]["sha256"],
+            crate::operations::checksum::sha256_hex(b"payload")
+        );
+        assert!(body[1].get("sha256").is_none());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    /// Every guarded read fails on a denied path with exactly the message a
+    /// missing path gives.
+    #[tokio::test]
+    async fn denied_read_error_matches_missing_file_error() {
+        let dir = std::env::temp_dir().join("fileio_deny_message_test");
+        let _ = std::fs::remove_dir_all(&dir);
+        let denied = dir.join("denied");
+        std::fs::create_dir_all(denied.join("tree")).unwrap();
+        std::fs::write(denied.join("secret.txt"), "real contents").unwrap();
+        let link = dir.join("link");
+        let link = link.to_str().unwrap();
+
+        type Args = fn(&str, &str) -> serde_json::Value;
+        let file_cases: &[(&str, Args)] = &[
+            ("fileio_read_lines", |p, _| serde_json::json!({ "path": p })),
+            ("fileio_read_file", |p, _| serde_json::json!({ "path": p })),
+            ("fileio_head", |p, _| serde_json::json!({ "path": p })),
+            (
+                "fileio_read_bytes",
+                |p, _| serde_json::json!({ "path": p, "offset": 0, "length": 4 }),
+            ),
+            ("fileio_magic", |p, _| serde_json::json!({ "path": p })),
+            ("fileio_bom", |p, _| serde_json::json!({ "path": p })),
+            (
+                "fileio_content_equals",
+                |p, _| serde_json::json!({ "path": p, "content": "x" }),
+            ),
+            ("fileio_validate", |p, _| serde_json::json!({ "path": p })),
+            (
+                "fileio_offset_to_position",
+                |p, _| serde_json::json!({ "path": p, "offset": 0 }),
+            ),
+            (
+                "fileio_position_to_offset",
+                |p, _| serde_json::json!({ "path": p, "line": 1, "column": 1 }),
+            ),
+            (
+                "fileio_line_ending_style",
+                |p, _| serde_json::json!({ "path": p }),
+            ),
+            (
+                "fileio_tail_follow",
+                |p, _| serde_json::json!({ "path": p }),
+            ),
+            (
+                "fileio_get_canonical_path",
+                |p, _| serde_json::json!({ "path": p }),
+            ),
+            (
+                "fileio_get_canonical_path",
+                |p, _| serde_json::json!({ "path": p, "max_symlink_depth": 8 }),
+            ),
+            (
+                "fileio_read_symbolic_link",
+                |p, _| serde_json::json!({ "path": p }),
+            ),
+            (
+                "fileio_create_hard_link",
+                |p, link| serde_json::json!({ "target": p, "link_path": link }),
+            ),
+        ];
+        let dir_cases: &[(&str, Args)] = &[
+            (
+                "fileio_find_files",
+                |p, _| serde_json::json!({ "root": p, "pattern": "*" }),
+            ),
+            (
+                "fileio_find_in_files",
+                |p, _| serde_json::json!({ "path": p, "pattern": "x" }),
+            ),
+            (
+                "fileio_rename_pattern",
+                |p, _| serde_json::json!({ "path": p, "pattern": "x", "replacement": "y" }),
+            ),
+            (
+                "fileio_dir_checksum",
+                |p, _| serde_json::json!({ "path": p }),
+            ),
+            ("fileio_watch_dir", |p, _| serde_json::json!({ "path": p })),
+            ("fileio_disk_usage", |p, _| serde_json::json!({ "path": p })),
+        ];
+
+        let registry = registry_blocking(denied.to_str().unwrap());
+        let cases = file_cases
+            .iter()
+            .map(|case| (case, "secret.txt"))
+            .chain(dir_cases.iter().map(|case| (case, "tree")));
+        for ((tool, args), name) in cases {
+            let mut messages = Vec::new();
+            for path in [denied.join(name), dir.join(name)] {
+                let path = path.to_str().unwrap();
+                let message = registry
+                    .execute_tool(tool, &args(path, link))
+                    .await
+                    .unwrap_err()
+                    .to_string();
+                messages.push(message.replace(path, "<path>"));
+            }
+            assert_eq!(messages[0], messages[1], "{tool}");
+        }
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    /// Moving into an allowed directory whose matching child is denied must
+    /// neither touch that child nor report whether it exists.
+    #[tokio::test]
+    async fn transfer_onto_denied_child_is_synthesized() {
+        let dir = std::env::temp_dir().join("fileio_deny_transfer_child_test");
+        let _ = std::fs::remove_dir_all(&dir);
+        let source = dir.join("src").join("secret");
+        let dest = dir.join("dest");
+        std::fs::create_dir_all(&source).unwrap();
+        std::fs::create_dir_all(dest.join("secret")).unwrap();
+
+        let registry = registry_blocking(dest.join("secret").to_str().unwrap());
+        let args = serde_json::json!({
+            "source": source.to_str().unwrap(),
+            "destination": dest.to_str().unwrap(),
+            "dry_run": true,
+            "report_conflicts": true,
+        });
+        let res = registry.execute_tool("fileio_move", &args).await.unwrap();
+        let body: serde_json::Value =
+            serde_json::from_str(res["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(body["conflicts"], serde_json::json!([]));
+
+        let args = serde_json::json!({
+            "source": source.to_str().unwrap(),
+            "destination": dest.to_str().unwrap(),
+        });
+        registry.execute_tool("fileio_move", &args).await.unwrap();
+        assert!(source.exists(), "denied target must not be moved onto");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    /// A batch rename in an allowed directory must neither move nor list a
+    /// denied entry, and must not say whether a denied target exists.
+    #[tokio::test]
+    async fn rename_pattern_leaves_denied_entries_alone() {
+        let dir = std::env::temp_dir().join("fileio_deny_rename_pattern_test");
+        let _ = std::fs::remove_dir_all(&dir);
+        let secret = dir.join("secret");
+        std::fs::create_dir_all(&secret).unwrap();
+        std::fs::write(dir.join("secret2"), "").unwrap();
+
+        let registry = registry_blocking(secret.to_str().unwrap());
+        let args = serde_json::json!({
+            "path": dir.to_str().unwrap(),
+            "pattern": "^secret$",
+            "replacement": "moved",
+        });
+        let res = registry
+            .execute_tool("fileio_rename_pattern", &args)
+            .await
+            .unwrap();
+        assert_eq!(res["content"][0]["text"], "[]");
+        assert!(secret.exists());
+        assert!(!dir.join("moved").exists());
+
+        let args = serde_json::json!({
+            "path": dir.to_str().unwrap(),
+            "pattern": "^secret2$",
+            "replacement": "secret",
+            "dry_run": true,
+        });
+        let res = registry
+            .execute_tool("fileio_rename_pattern", &args)
+            .await
+            .unwrap();
+        let body: serde_json::Value =
+            serde_json::from_str(res["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(body[0]["status"], "collision");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    /// Disk usage of an ancestor must not count a denied subtree anywhere.
+    #[tokio::test]
+    async fn disk_usage_leaves_denied_subtree_out_of_totals() {
+        let dir = std::env::temp_dir().join("fileio_deny_disk_usage_test");
+        let _ = std::fs::remove_dir_all(&dir);
+        let secret = dir.join("secret");
+        std::fs::create_dir_all(&secret).unwrap();
+        std::fs::write(secret.join("key"), vec![0u8; 400]).unwrap();
+        std::fs::write(dir.join("open.txt"), vec![0u8; 7]).unwrap();
+
+        let registry = registry_blocking(secret.to_str().unwrap());
+        let args = serde_json::json!({ "path": dir.to_str().unwrap() });
+        let res = registry
+            .execute_tool("fileio_disk_usage", &args)
+            .await
+            .unwrap();
+        let body: serde_json::Value =
+            serde_json::from_str(res["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(body["total_bytes"], 7);
+        assert_eq!(body["total_files"], 1);
+        assert_eq!(body["directories"].as_array().unwrap().len(), 1);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     /// Issue #3 regression: `fileio_edit_file` against a denied path must
     /// return the same JSON shape (`EditFileResult`) as a real edit, not a
     /// plain-text body. Otherwise a single no-op probe maps the deny-list.
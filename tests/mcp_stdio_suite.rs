@@ -17,11 +17,17 @@ struct McpStdioClient {
 
 impl McpStdioClient {
     fn start() -> Self {
+        Self::start_with_args(&[])
+    }
+
+    /// Spawn the server with extra `serve` flags appended.
+    fn start_with_args(extra: &[&str]) -> Self {
         let exe = env!("CARGO_BIN_EXE_fileio-mcp");
         let repo_root = Path::new(env!("CARGO_MANIFEST_DIR"));
 
         let mut child = Command::new(exe)
             .args(["serve", "--mode", "stdio"])
+            .args(extra)
             .current_dir(repo_root)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
@@ -262,7 +268,7 @@ fn fileio_write_file_overwrite() {
         let path = case.join("nested/out.txt");
         fs::create_dir_all(path.parent().unwrap()).unwrap();
 
-        client
+        let res = client
             .tool_call(
                 "fileio_write_file",
                 json!({"path": path.to_string_lossy(), "content":"hello\n", "append":false}),
@@ -270,6 +276,20 @@ fn fileio_write_file_overwrite() {
             .unwrap();
 
         assert_eq!(fs::read_to_string(&path).unwrap(), "hello\n");
+        let outcome = extract_value(&res);
+        assert_eq!(outcome["path"], json!(path.to_string_lossy()));
+        assert_eq!(outcome["bytes_written"], json!(6));
+        assert_eq!(outcome["created"], json!(true));
+
+        let res = client
+            .tool_call(
+                "fileio_write_file",
+                json!({"path": path.to_string_lossy(), "content":"bye\n"}),
+            )
+            .unwrap();
+        let outcome = extract_value(&res);
+        assert_eq!(outcome["bytes_written"], json!(4));
+        assert_eq!(outcome["created"], json!(false));
     });
 }
 
@@ -291,6 +311,101 @@ fn fileio_write_file_append() {
     });
 }
 
+#[test]
+fn fileio_write_file_prepend() {
+    run_case("fileio_write_file_prepend", |client, root| {
+        let case = case_dir(root, "fileio_write_file_prepend");
+        let path = case.join("main.rs");
+        fs::write(&path, "fn main() {}\n").unwrap();
+
+        client
+            .tool_call(
+                "fileio_write_file",
+                json!({"path": path.to_string_lossy(), "content": "// SPDX-License-Identifier: MIT\n", "prepend": true}),
+            )
+            .unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "// SPDX-License-Identifier: MIT\nfn main() {}\n"
+        );
+
+        expect_err_contains(
+            client.tool_call(
+                "fileio_write_file",
+                json!({"path": path.to_string_lossy(), "content": "x", "prepend": true, "append": true}),
+            ),
+            "prepend",
+        );
+        expect_err_contains(
+            client.tool_call(
+                "fileio_write_file",
+                json!({"path": path.to_string_lossy(), "content": "x", "prepend": true, "if_exists": "skip"}),
+            ),
+            "if_exists",
+        );
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "// SPDX-License-Identifier: MIT\nfn main() {}\n"
+        );
+    });
+}
+
+#[test]
+fn fileio_write_file_exclusive() {
+    run_case("fileio_write_file_exclusive", |client, root| {
+        let case = case_dir(root, "fileio_write_file_exclusive");
+        let path = case.join("nested/once.txt");
+
+        client
+            .tool_call(
+                "fileio_write_file",
+                json!({"path": path.to_string_lossy(), "content": "first", "exclusive": true}),
+            )
+            .unwrap();
+        expect_err_contains(
+            client.tool_call(
+                "fileio_write_file",
+                json!({"path": path.to_string_lossy(), "content": "second", "exclusive": true}),
+            ),
+            "already exists",
+        );
+        assert_eq!(fs::read_to_string(&path).unwrap(), "first");
+
+        expect_err_contains(
+            client.tool_call(
+                "fileio_write_file",
+                json!({"path": path.to_string_lossy(), "content": "x", "exclusive": true, "append": true}),
+            ),
+            "append",
+        );
+    });
+}
+
+#[test]
+fn fileio_write_file_base64() {
+    run_case("fileio_write_file_base64", |client, root| {
+        let case = case_dir(root, "fileio_write_file_base64");
+        let path = case.join("blob.bin");
+
+        client
+            .tool_call(
+                "fileio_write_file",
+                json!({"path": path.to_string_lossy(), "content": "/wCA", "content_encoding": "base64"}),
+            )
+            .unwrap();
+        assert_eq!(fs::read(&path).unwrap(), vec![0xFF, 0x00, 0x80]);
+
+        expect_err_contains(
+            client.tool_call(
+                "fileio_write_file",
+                json!({"path": path.to_string_lossy(), "content": "not base64!", "content_encoding": "base64"}),
+            ),
+            "base64",
+        );
+        assert_eq!(fs::read(&path).unwrap(), vec![0xFF, 0x00, 0x80]);
+    });
+}
+
 #[test]
 fn fileio_read_lines_ok() {
     run_case("fileio_read_lines_ok", |client, root| {
@@ -305,6 +420,59 @@ fn fileio_read_lines_ok() {
     });
 }
 
+#[test]
+fn fileio_read_file_round_trips_bytes() {
+    run_case("fileio_read_file_round_trips_bytes", |client, root| {
+        let case = case_dir(root, "fileio_read_file_round_trips_bytes");
+        for (name, content) in [
+            ("final_newline.txt", "a\nb\n"),
+            ("no_final_newline.txt", "a\nb"),
+            ("crlf.txt", "a\r\nb\r\n"),
+        ] {
+            let path = case.join(name);
+            fs::write(&path, content).unwrap();
+
+            let res = client
+                .tool_call("fileio_read_file", json!({"path": path.to_string_lossy()}))
+                .unwrap();
+            let text = res["content"][0]["text"].as_str().unwrap();
+            let read: String = serde_json::from_str(text).unwrap();
+            assert_eq!(read, content, "{name}");
+        }
+
+        let path = case.join("final_newline.txt");
+        expect_err_contains(
+            client.tool_call(
+                "fileio_read_file",
+                json!({"path": path.to_string_lossy(), "max_bytes": 2}),
+            ),
+            "too large",
+        );
+    });
+}
+
+#[test]
+fn fileio_head_ok() {
+    run_case("fileio_head_ok", |client, root| {
+        let case = case_dir(root, "fileio_head_ok");
+        let path = case.join("in.txt");
+        fs::write(&path, "a\nb\nc\n").unwrap();
+
+        let res = client
+            .tool_call(
+                "fileio_head",
+                json!({"path": path.to_string_lossy(), "line_count": 2}),
+            )
+            .unwrap();
+        assert_eq!(extract_value(&res), json!(["a", "b"]));
+
+        let res = client
+            .tool_call("fileio_head", json!({"path": path.to_string_lossy()}))
+            .unwrap();
+        assert_eq!(extract_value(&res), json!(["a", "b", "c"]));
+    });
+}
+
 #[test]
 fn fileio_read_lines_range() {
     run_case("fileio_read_lines_range", |client, root| {
@@ -1741,6 +1909,94 @@ fn fileio_get_current_directory() {
     });
 }
 
+#[test]
+fn fileio_get_current_directory_honours_working_dir() {
+    let dir = TempDir::new().expect("create temp root");
+    let dir_str = dir.path().to_string_lossy().to_string();
+    let mut client = McpStdioClient::start_with_args(&["--working-dir", &dir_str]);
+    client.initialize();
+
+    let res = client
+        .tool_call("fileio_get_current_directory", json!({}))
+        .unwrap();
+    let expected = fs::canonicalize(dir.path()).expect("canonicalize temp root");
+    assert_eq!(
+        extract_value(&res),
+        Value::String(expected.to_string_lossy().to_string())
+    );
+
+    // Relative paths now resolve against the pinned directory.
+    fs::write(dir.path().join("rel.txt"), "hello\n").unwrap();
+    let res = client
+        .tool_call("fileio_read_lines", json!({"path": "rel.txt"}))
+        .unwrap();
+    assert_eq!(extract_value(&res), json!(["hello"]));
+}
+
+#[test]
+fn fileio_stat_relative_path_under_working_dir() {
+    let dir = TempDir::new().expect("create temp root");
+    let dir_str = dir.path().to_string_lossy().to_string();
+    let mut client = McpStdioClient::start_with_args(&["--working-dir", &dir_str]);
+    client.initialize();
+    fs::write(dir.path().join("rel.txt"), "hello\n").unwrap();
+
+    let res = client
+        .tool_call("fileio_stat", json!({"path": ["rel.txt", "missing.txt"]}))
+        .unwrap();
+    let entries = extract_value(&res);
+    assert_eq!(entries[0]["exists"], json!(true));
+    assert_eq!(entries[0]["size"], json!(6));
+    assert_eq!(entries[1]["exists"], json!(false));
+}
+
+#[test]
+fn no_clobber_refuses_plain_overwrite() {
+    let dir = TempDir::new().expect("create temp root");
+    let path = dir.path().join("keep.txt");
+    fs::write(&path, "original").unwrap();
+    let mut client = McpStdioClient::start_with_args(&["--no-clobber"]);
+    client.initialize();
+
+    expect_err_contains(
+        client.tool_call(
+            "fileio_write_file",
+            json!({"path": path.to_string_lossy(), "content": "replaced"}),
+        ),
+        "already exists",
+    );
+    assert_eq!(fs::read_to_string(&path).unwrap(), "original");
+
+    // An explicit if_exists still overrides the default.
+    client
+        .tool_call(
+            "fileio_write_file",
+            json!({"path": path.to_string_lossy(), "content": "replaced", "if_exists": "overwrite"}),
+        )
+        .unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "replaced");
+}
+
+#[test]
+fn working_dir_must_exist() {
+    let dir = TempDir::new().expect("create temp root");
+    let missing = dir.path().join("missing");
+    let output = Command::new(env!("CARGO_BIN_EXE_fileio-mcp"))
+        .args(["serve", "--mode", "stdio", "--working-dir"])
+        .arg(&missing)
+        .stdin(Stdio::null())
+        .output()
+        .expect("spawn fileio-mcp");
+    assert!(
+        !output.status.success(),
+        "server must refuse a missing --working-dir"
+    );
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("--working-dir"),
+        "stderr should name the bad flag"
+    );
+}
+
 #[test]
 fn fileio_create_temporary_file() {
     run_case("fileio_create_temporary_file", |client, root| {